@@ -7,7 +7,7 @@ use std::{
 };
 
 use farmfe_core::{
-  cache::module_cache::CachedModule,
+  cache::module_cache::{CachedModule, ModuleCacheStatus},
   context::CompilationContext,
   error::{CompilationError, Result},
   farm_profile_function, farm_profile_scope,
@@ -44,7 +44,8 @@ use crate::{
 
 use self::module_cache::{
   get_content_hash_of_module, get_timestamp_of_module, handle_cached_modules,
-  try_get_module_cache_by_hash, try_get_module_cache_by_timestamp,
+  is_dependencies_resolve_result_changed, try_get_module_cache_by_hash,
+  try_get_module_cache_by_timestamp,
 };
 
 macro_rules! call_and_catch_error {
@@ -356,6 +357,31 @@ impl Compiler {
     Ok(deps.into_iter().map(|dep| (dep, None)).collect())
   }
 
+  /// Build the module again when its cache is marked as stale by plugins after restored from persistent cache
+  pub(crate) fn rebuild_stale_cached_module(
+    module_id: &ModuleId,
+    resolve_param: &PluginResolveHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<(
+    Module,
+    Vec<(PluginAnalyzeDepsHookResultEntry, Option<ModuleId>)>,
+  )> {
+    context
+      .cache_manager
+      .module_cache
+      .invalidate_cache(module_id);
+
+    let resolve_module_id_result = Self::resolve_module_id(resolve_param, context)?;
+    let mut module = Self::create_module_from_resolve_result(&resolve_module_id_result, context);
+    let deps = Self::build_module(
+      resolve_module_id_result.resolve_result,
+      &mut module,
+      context,
+    )?;
+
+    Ok((module, deps))
+  }
+
   fn build_module_after_transform(
     resolve_result: PluginResolveHookResult,
     load_module_type: ModuleType,
@@ -407,6 +433,14 @@ impl Compiler {
     module.package_name = package_info.name.unwrap_or("default".to_string());
     module.package_version = package_info.version.unwrap_or("0.0.0".to_string());
 
+    // sideEffects configured by the user takes precedence over sideEffects of package.json
+    if let Some(tree_shaking) = context.config.tree_shaking.as_obj()
+      && let Some(side_effects) =
+        tree_shaking.side_effects_override(&module.id.to_string(), &module.package_name)
+    {
+      module.side_effects = side_effects;
+    }
+
     // ================ Analyze Deps Start ===============
     let mut analyze_deps_result = call_and_catch_error!(analyze_deps, module, context);
     // ================ Analyze Deps End ===============
@@ -455,16 +489,31 @@ impl Compiler {
           farm_profile_scope!(format!("cache module {:?}", module_id));
           let mut cached_module = context.cache_manager.module_cache.get_cache(&module_id);
 
-          if let Err(e) = handle_cached_modules(&mut cached_module, &context) {
-            err_sender.send(e).unwrap();
-            return;
-          }
+          let (module, deps) = match handle_cached_modules(&mut cached_module, &context) {
+            Ok(false) => (
+              cached_module.module,
+              CachedModule::dep_sources(cached_module.dependencies),
+            ),
+            Ok(true) => {
+              match Self::rebuild_stale_cached_module(&module_id, &resolve_param, &context) {
+                Ok(r) => r,
+                Err(e) => {
+                  err_sender.send(e).unwrap();
+                  return;
+                }
+              }
+            }
+            Err(e) => {
+              err_sender.send(e).unwrap();
+              return;
+            }
+          };
 
           let params = HandleDependenciesParams {
-            module: cached_module.module,
+            module,
             resolve_param,
             order,
-            deps: CachedModule::dep_sources(cached_module.dependencies),
+            deps,
             thread_pool: c_thread_pool,
             err_sender,
             context,
@@ -661,13 +710,25 @@ fn resolve_module(
     "resolve_module from {:?} -> {:?}, cached: {:?}",
     resolve_param.importer, resolve_param.source, cached_dependency
   ));
-  let mut resolve_module_id_result = None;
-  let module_id = if let Some(cached_dependency) = &cached_dependency {
-    cached_dependency.clone()
+  let cached_module_id = if let Some(cached_dependency) = cached_dependency {
+    if context.module_graph.read().has_module(&cached_dependency) {
+      // the module has already been handled and it should not be handled twice
+      return Ok(ResolveModuleResult::Built(cached_dependency));
+    }
+
+    // revalidate the cache before locking the module graph, the revalidation calls resolve hooks of plugins
+    revalidate_cached_module(&cached_dependency, context)?.then_some(cached_dependency)
+  } else {
+    None
+  };
+
+  let resolve_module_id_result = if cached_module_id.is_none() {
+    Some(Compiler::resolve_module_id(resolve_param, context)?)
   } else {
-    resolve_module_id_result = Some(Compiler::resolve_module_id(resolve_param, context)?);
-    resolve_module_id_result.as_ref().unwrap().module_id.clone()
+    None
   };
+  let module_id = cached_module_id
+    .unwrap_or_else(|| resolve_module_id_result.as_ref().unwrap().module_id.clone());
 
   let mut module_graph = context.module_graph.write();
 
@@ -675,40 +736,60 @@ fn resolve_module(
     farm_profile_scope!(format!("module {:?} already exists", module_id));
     // the module has already been handled and it should not be handled twice
     ResolveModuleResult::Built(module_id)
-  } else {
-    if let Some(cached_dependency) = cached_dependency {
-      farm_profile_scope!(format!("cache module {:?} ", module_id));
-      let module_cache_manager = &context.cache_manager.module_cache;
-
-      if module_cache_manager.has_cache(&cached_dependency) {
-        let cached_module = module_cache_manager.get_cache_ref(&cached_dependency);
-        let should_invalidate_cached_module = context
-          .plugin_driver
-          .handle_persistent_cached_module(&cached_module.module, context)?
-          .unwrap_or(false);
-
-        if should_invalidate_cached_module {
-          module_cache_manager.invalidate_cache(&cached_dependency);
-        } else {
-          Compiler::insert_dummy_module(&cached_dependency, &mut module_graph);
-          return Ok(ResolveModuleResult::Cached(cached_dependency));
-        }
-      }
-    }
-
+  } else if let Some(resolve_module_id_result) = resolve_module_id_result {
     farm_profile_scope!(format!("new module {:?}", module_id));
-    let resolve_module_id_result = if let Some(result) = resolve_module_id_result {
-      result
-    } else {
-      Compiler::resolve_module_id(resolve_param, context)?
-    };
-
     Compiler::insert_dummy_module(&resolve_module_id_result.module_id, &mut module_graph);
     ResolveModuleResult::Success(Box::new(ResolvedModuleInfo {
       module: Compiler::create_module_from_resolve_result(&resolve_module_id_result, context),
       resolve_module_id_result,
     }))
+  } else {
+    farm_profile_scope!(format!("cache module {:?} ", module_id));
+    context
+      .stats
+      .record_module_cache_status(&module_id, ModuleCacheStatus::Hit);
+    Compiler::insert_dummy_module(&module_id, &mut module_graph);
+    ResolveModuleResult::Cached(module_id)
   };
 
   Ok(res)
 }
+
+/// Whether the cached module can be restored, the cache is invalidated with the reason if not.
+/// Must be called without locking the module graph, the resolve results of the dependencies are revalidated by resolve hooks
+fn revalidate_cached_module(
+  module_id: &ModuleId,
+  context: &Arc<CompilationContext>,
+) -> Result<bool> {
+  let module_cache_manager = &context.cache_manager.module_cache;
+
+  if !module_cache_manager.has_cache(module_id) {
+    return Ok(false);
+  }
+
+  let cached_module = module_cache_manager.get_cache_ref(module_id);
+  let invalidated_reason = if context
+    .plugin_driver
+    .handle_persistent_cached_module(&cached_module.module, context)?
+    .unwrap_or(false)
+  {
+    Some(ModuleCacheStatus::MissInvalidated)
+  } else if is_dependencies_resolve_result_changed(cached_module.value(), context) {
+    Some(ModuleCacheStatus::MissDepChanged)
+  } else {
+    None
+  };
+  drop(cached_module);
+
+  if let Some(reason) = invalidated_reason {
+    module_cache_manager.invalidate_cache_with_reason(module_id, reason);
+    return Ok(false);
+  }
+
+  Ok(true)
+}
+
+#[cfg(test)]
+mod test_module_cache;
+#[cfg(test)]
+mod test_resolve_module;
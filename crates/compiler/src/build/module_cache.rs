@@ -2,12 +2,14 @@ use std::{path::PathBuf, sync::Arc, time::SystemTime};
 
 use farmfe_core::{
   cache::module_cache::{
-    CachedModule, CachedModuleDependency, CachedWatchDependency, MetadataOption,
+    CachedModule, CachedModuleDependency, CachedResolveResult, CachedWatchDependency,
+    MetadataOption, ModuleCacheStatus,
   },
   context::{create_swc_source_map, CompilationContext},
   dashmap::DashMap,
   farm_profile_function,
   module::ModuleId,
+  plugin::{PluginHandlePersistentCachedCustomModuleHookParam, PluginResolveHookParam},
   rayon::prelude::*,
   resource::{Resource, ResourceOrigin},
   swc_common::Globals,
@@ -17,6 +19,8 @@ use farmfe_toolkit::script::{
   analyze_statement::analyze_statements, swc_try_with::resolve_module_mark,
 };
 
+use crate::Compiler;
+
 pub fn get_timestamp_of_module(module_id: &ModuleId, root: &str) -> u128 {
   farm_profile_function!(format!("get_timestamp_of_module: {:?}", module_id));
   let resolved_path = module_id.resolved_path(root);
@@ -70,42 +74,36 @@ pub fn try_get_module_cache_by_timestamp(
     "try_get_module_cache_by_timestamp: {:?}",
     module_id
   ));
-  let mut should_invalidate_cache = false;
 
-  if context.config.persistent_cache.timestamp_enabled()
-    && context.cache_manager.module_cache.has_cache(module_id)
-  {
-    let cached_module = context.cache_manager.module_cache.get_cache_ref(module_id);
-
-    if cached_module.value().module.last_update_timestamp == timestamp {
-      drop(cached_module);
-      let mut cached_module = context.cache_manager.module_cache.get_cache(module_id);
-      handle_cached_modules(&mut cached_module, context)?;
-
-      if cached_module.module.immutable
-        || !is_watch_dependencies_timestamp_changed(&cached_module, context)
-      {
-        let should_invalidate_cached_module = context
-          .plugin_driver
-          .handle_persistent_cached_module(&cached_module.module, context)?
-          .unwrap_or(false);
-
-        if !should_invalidate_cached_module {
-          return Ok(Some(cached_module));
-        } else {
-          should_invalidate_cache = true;
-        }
-      }
-    } else if !context.config.persistent_cache.hash_enabled() {
-      should_invalidate_cache = true;
-    }
+  if !context.config.persistent_cache.timestamp_enabled() {
+    return Ok(None);
   }
 
-  if should_invalidate_cache {
+  if !context.cache_manager.module_cache.has_cache(module_id) {
+    record_cache_miss(module_id, context);
+    return Ok(None);
+  }
+
+  let cached_module = context.cache_manager.module_cache.get_cache_ref(module_id);
+
+  if cached_module.value().module.last_update_timestamp == timestamp {
+    drop(cached_module);
+    let is_watch_dependencies_changed = |cached_module: &CachedModule| {
+      is_watch_dependencies_timestamp_changed(cached_module, context)
+    };
+
+    return try_restore_cached_module(module_id, is_watch_dependencies_changed, context);
+  }
+
+  drop(cached_module);
+
+  // the content hash may be unchanged, for example, the file is touched. Try to get the cache by hash later
+  if context.config.persistent_cache.hash_enabled() {
     context
-      .cache_manager
-      .module_cache
-      .invalidate_cache(module_id);
+      .stats
+      .record_module_cache_status(module_id, ModuleCacheStatus::MissContentChanged);
+  } else {
+    invalidate_module_cache(module_id, ModuleCacheStatus::MissContentChanged, context);
   }
 
   Ok(None)
@@ -118,48 +116,96 @@ pub fn try_get_module_cache_by_hash(
 ) -> farmfe_core::error::Result<Option<CachedModule>> {
   farm_profile_function!(format!("try_get_module_cache_by_hash: {:?}", module_id));
 
-  let mut should_invalidate_cache = false;
+  if !context.config.persistent_cache.hash_enabled() {
+    return Ok(None);
+  }
 
-  if context.config.persistent_cache.hash_enabled()
-    && context.cache_manager.module_cache.has_cache(module_id)
-  {
-    let cached_module = context.cache_manager.module_cache.get_cache_ref(module_id);
-
-    if cached_module.value().module.content_hash == content_hash {
-      drop(cached_module);
-      let mut cached_module = context.cache_manager.module_cache.get_cache(module_id);
-
-      handle_cached_modules(&mut cached_module, context)?;
-
-      if cached_module.module.immutable
-        || !is_watch_dependencies_content_hash_changed(&cached_module, context)
-      {
-        let should_invalidate_cached_module = context
-          .plugin_driver
-          .handle_persistent_cached_module(&cached_module.module, context)?
-          .unwrap_or(false);
-
-        if !should_invalidate_cached_module {
-          return Ok(Some(cached_module));
-        } else {
-          should_invalidate_cache = true;
-        }
-      }
-    } else {
-      should_invalidate_cache = true;
-    }
+  if !context.cache_manager.module_cache.has_cache(module_id) {
+    record_cache_miss(module_id, context);
+    return Ok(None);
   }
 
-  if should_invalidate_cache {
-    context
-      .cache_manager
-      .module_cache
-      .invalidate_cache(module_id);
+  let cached_module = context.cache_manager.module_cache.get_cache_ref(module_id);
+
+  if cached_module.value().module.content_hash == content_hash {
+    drop(cached_module);
+    let is_watch_dependencies_changed = |cached_module: &CachedModule| {
+      is_watch_dependencies_content_hash_changed(cached_module, context)
+    };
+
+    return try_restore_cached_module(module_id, is_watch_dependencies_changed, context);
   }
 
+  drop(cached_module);
+  invalidate_module_cache(module_id, ModuleCacheStatus::MissContentChanged, context);
+
   Ok(None)
 }
 
+/// The key of the cache matches, check the dependencies and plugins before restoring the cached module
+fn try_restore_cached_module(
+  module_id: &ModuleId,
+  is_watch_dependencies_changed: impl Fn(&CachedModule) -> bool,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<Option<CachedModule>> {
+  let mut cached_module = context.cache_manager.module_cache.get_cache(module_id);
+  let is_stale = handle_cached_modules(&mut cached_module, context)?;
+
+  if is_stale {
+    invalidate_module_cache(module_id, ModuleCacheStatus::MissInvalidated, context);
+    return Ok(None);
+  }
+
+  if (!cached_module.module.immutable && is_watch_dependencies_changed(&cached_module))
+    || is_dependencies_resolve_result_changed(&cached_module, context)
+  {
+    // keep the cache, the module may be restored by content hash later
+    context
+      .stats
+      .record_module_cache_status(module_id, ModuleCacheStatus::MissDepChanged);
+    return Ok(None);
+  }
+
+  let should_invalidate_cached_module = context
+    .plugin_driver
+    .handle_persistent_cached_module(&cached_module.module, context)?
+    .unwrap_or(false);
+
+  if should_invalidate_cached_module {
+    invalidate_module_cache(module_id, ModuleCacheStatus::MissInvalidated, context);
+    return Ok(None);
+  }
+
+  context
+    .stats
+    .record_module_cache_status(module_id, ModuleCacheStatus::Hit);
+
+  Ok(Some(cached_module))
+}
+
+fn invalidate_module_cache(
+  module_id: &ModuleId,
+  reason: ModuleCacheStatus,
+  context: &Arc<CompilationContext>,
+) {
+  context
+    .cache_manager
+    .module_cache
+    .invalidate_cache_with_reason(module_id, reason);
+  context.stats.record_module_cache_status(module_id, reason);
+}
+
+/// there is no cache of the module, it's new or the cache is invalidated earlier in this compilation
+fn record_cache_miss(module_id: &ModuleId, context: &Arc<CompilationContext>) {
+  let status = context
+    .cache_manager
+    .module_cache
+    .invalidated_reason(module_id)
+    .unwrap_or(ModuleCacheStatus::MissNew);
+
+  context.stats.record_module_cache_status(module_id, status);
+}
+
 pub fn set_module_graph_cache(module_ids: Vec<ModuleId>, context: &Arc<CompilationContext>) {
   farm_profile_function!("set_module_graph_cache".to_string());
   let module_graph = context.module_graph.read();
@@ -197,6 +243,20 @@ pub fn set_module_graph_cache(module_ids: Vec<ModuleId>, context: &Arc<Compilati
       .map(|(id, e)| CachedModuleDependency {
         dependency: id,
         edge_info: e.clone(),
+        resolve_results: e
+          .items()
+          .iter()
+          .filter_map(|item| {
+            let resolve_param = PluginResolveHookParam {
+              source: item.source.clone(),
+              importer: Some(module_id.clone()),
+              kind: item.kind.clone(),
+            };
+            context.get_resolve_cache(&resolve_param).map(|result| {
+              CachedResolveResult::new(item.source.clone(), item.kind.clone(), &result)
+            })
+          })
+          .collect(),
       })
       .collect();
     cached_dependency_map.insert(module_id.clone(), dependencies);
@@ -251,11 +311,11 @@ pub fn set_module_graph_cache(module_ids: Vec<ModuleId>, context: &Arc<Compilati
     });
 }
 
-/// recreate syntax context for the cached module
+/// recreate syntax context for the cached module, returns true if the cached module is marked as stale by plugins
 pub fn handle_cached_modules(
   cached_module: &mut CachedModule,
   context: &Arc<CompilationContext>,
-) -> farmfe_core::error::Result<()> {
+) -> farmfe_core::error::Result<bool> {
   // create a new sourcemap for the cached module cause the sourcemap of swc is not cacheable
   let (source_map, _) = create_swc_source_map(
     &cached_module.module.id,
@@ -284,7 +344,24 @@ pub fn handle_cached_modules(
     }
     box farmfe_core::module::ModuleMetaData::Css(_)
     | box farmfe_core::module::ModuleMetaData::Html(_) => { /* do nothing */ }
-    box farmfe_core::module::ModuleMetaData::Custom(_) => { /* TODO: add a hook for custom module */
+    box farmfe_core::module::ModuleMetaData::Custom(custom) => {
+      // let plugins rebuild the state of the custom module that can not be serialized
+      let is_stale = context
+        .plugin_driver
+        .handle_persistent_cached_custom_module(
+          &mut PluginHandlePersistentCachedCustomModuleHookParam {
+            module_id: &cached_module.module.id,
+            module_type: &cached_module.module.module_type,
+            content: &cached_module.module.content,
+            meta: custom,
+          },
+          context,
+        )?
+        .unwrap_or(false);
+
+      if is_stale {
+        return Ok(true);
+      }
     }
   };
 
@@ -310,8 +387,7 @@ pub fn handle_cached_modules(
     }
   }
 
-  // TODO: return of resolve hook should be treated as part of the cache key
-  Ok(())
+  Ok(false)
 }
 
 /// recreate the watch graph for the cached module
@@ -334,6 +410,38 @@ fn handle_relation_roots(
   Ok(())
 }
 
+/// Resolve the sources of the cached module again, returns true if any of them is resolved to a different module or fails to resolve.
+/// For example, a new file shadows the alias target or the package in node_modules is upgraded
+pub fn is_dependencies_resolve_result_changed(
+  cached_module: &CachedModule,
+  context: &Arc<CompilationContext>,
+) -> bool {
+  farm_profile_function!(format!(
+    "is_dependencies_resolve_result_changed: {:?}",
+    cached_module.module.id
+  ));
+
+  for cached_resolve_result in cached_module
+    .dependencies
+    .iter()
+    .flat_map(|dep| &dep.resolve_results)
+  {
+    let resolve_param = PluginResolveHookParam {
+      source: cached_resolve_result.source.clone(),
+      importer: Some(cached_module.module.id.clone()),
+      kind: cached_resolve_result.kind.clone(),
+    };
+
+    match Compiler::resolve_module_id(&resolve_param, context) {
+      Ok(result) if cached_resolve_result.is_same_resolution(&result.resolve_result) => {}
+      // rebuild the importer so that the resolve error is reported
+      _ => return true,
+    }
+  }
+
+  false
+}
+
 fn is_watch_dependencies_timestamp_changed(
   cached_module: &CachedModule,
   context: &Arc<CompilationContext>,
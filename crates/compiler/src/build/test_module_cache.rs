@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  cache::module_cache::{CachedModule, CachedModuleDependency, CachedResolveResult},
+  config::{
+    persistent_cache::{
+      PersistentCacheConfig, PersistentCacheConfigObj, PersistentModuleCacheKeyStrategy,
+    },
+    Config,
+  },
+  context::CompilationContext,
+  error::Result,
+  module::{Module, ModuleId},
+  plugin::{
+    Plugin, PluginHookContext, PluginResolveHookParam, PluginResolveHookResult, ResolveKind,
+  },
+  serde_json::{self, json, Value},
+};
+
+use super::module_cache::try_get_module_cache_by_hash;
+
+/// Resolve `./dep` to `dep_path`
+struct TestResolvePlugin {
+  dep_path: String,
+}
+
+impl Plugin for TestResolvePlugin {
+  fn name(&self) -> &str {
+    "TestResolvePlugin"
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    if param.source != "./dep" {
+      return Ok(None);
+    }
+
+    Ok(Some(PluginResolveHookResult {
+      resolved_path: self.dep_path.clone(),
+      ..Default::default()
+    }))
+  }
+}
+
+/// Create a context whose cache contains `a.ts` and `b.ts`, both of them import `./dep` which was resolved to `/project/dep.ts`
+fn create_context(dep_path: &str) -> Arc<CompilationContext> {
+  let config = Config {
+    root: "/project".to_string(),
+    persistent_cache: Box::new(PersistentCacheConfig::Obj(PersistentCacheConfigObj {
+      memory: true,
+      module_cache_key_strategy: PersistentModuleCacheKeyStrategy {
+        timestamp: false,
+        hash: true,
+      },
+      ..Default::default()
+    })),
+    ..Default::default()
+  };
+  let plugin = Arc::new(TestResolvePlugin {
+    dep_path: dep_path.to_string(),
+  }) as Arc<dyn Plugin>;
+  let context = Arc::new(CompilationContext::new(config, vec![plugin]).unwrap());
+
+  for module_id in ["a.ts", "b.ts"] {
+    let mut module = Module::new(module_id.into());
+    module.content_hash = format!("{module_id}-hash");
+
+    context.cache_manager.module_cache.set_cache(
+      module_id.into(),
+      CachedModule {
+        module,
+        dependencies: vec![CachedModuleDependency {
+          dependency: "dep.ts".into(),
+          edge_info: Default::default(),
+          resolve_results: vec![CachedResolveResult::new(
+            "./dep".to_string(),
+            ResolveKind::Import,
+            &PluginResolveHookResult {
+              resolved_path: "/project/dep.ts".to_string(),
+              ..Default::default()
+            },
+          )],
+        }],
+        watch_dependencies: vec![],
+        is_expired: false,
+      },
+    );
+  }
+
+  context
+}
+
+fn cache_stats(context: &Arc<CompilationContext>) -> Value {
+  serde_json::from_str(&context.stats.cache_stats_to_string()).unwrap()
+}
+
+#[test]
+fn test_module_cache_hit() {
+  let context = create_context("/project/dep.ts");
+
+  for module_id in ["a.ts", "b.ts"] {
+    let cached_module =
+      try_get_module_cache_by_hash(&module_id.into(), &format!("{module_id}-hash"), &context)
+        .unwrap();
+    assert!(cached_module.is_some());
+  }
+
+  let stats = cache_stats(&context);
+  assert_eq!(stats["modules"], json!({ "a.ts": "hit", "b.ts": "hit" }));
+  assert_eq!(stats["summary"], json!({ "hit": 2 }));
+}
+
+#[test]
+fn test_module_cache_miss_content_changed() {
+  let context = create_context("/project/dep.ts");
+  let module_id: ModuleId = "a.ts".into();
+
+  assert!(
+    try_get_module_cache_by_hash(&module_id, "changed-hash", &context)
+      .unwrap()
+      .is_none()
+  );
+  assert!(!context.cache_manager.module_cache.has_cache(&module_id));
+
+  // the reason is kept after the cache is invalidated
+  assert!(
+    try_get_module_cache_by_hash(&module_id, "changed-hash", &context)
+      .unwrap()
+      .is_none()
+  );
+  // modules that are never cached are new
+  assert!(
+    try_get_module_cache_by_hash(&"c.ts".into(), "c.ts-hash", &context)
+      .unwrap()
+      .is_none()
+  );
+
+  let stats = cache_stats(&context);
+  assert_eq!(
+    stats["modules"],
+    json!({ "a.ts": "missContentChanged", "c.ts": "missNew" })
+  );
+  assert_eq!(
+    stats["summary"],
+    json!({ "missContentChanged": 1, "missNew": 1 })
+  );
+}
+
+#[test]
+fn test_module_cache_miss_resolve_result_changed() {
+  let context = create_context("/project/dep-new.ts");
+  let module_id: ModuleId = "a.ts".into();
+
+  assert!(
+    try_get_module_cache_by_hash(&module_id, "a.ts-hash", &context)
+      .unwrap()
+      .is_none()
+  );
+  // the dependency may be resolved to the cached one again, for example, the new file is removed, so the cache is kept
+  assert!(context.cache_manager.module_cache.has_cache(&module_id));
+
+  let stats = cache_stats(&context);
+  assert_eq!(stats["modules"], json!({ "a.ts": "missDepChanged" }));
+  assert_eq!(stats["summary"], json!({ "missDepChanged": 1 }));
+}
@@ -0,0 +1,132 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  cache::module_cache::{
+    CachedModule, CachedModuleDependency, CachedResolveResult, ModuleCacheStatus,
+  },
+  config::Config,
+  context::CompilationContext,
+  error::Result,
+  module::{Module, ModuleId},
+  parking_lot::Mutex,
+  plugin::{
+    Plugin, PluginHookContext, PluginResolveHookParam, PluginResolveHookResult, ResolveKind,
+  },
+};
+
+use super::{resolve_module, ResolveModuleResult};
+
+/// Resolve `./a` to `/project/a.ts` and `./dep` to `dep_path`
+struct TestResolvePlugin {
+  dep_path: Mutex<String>,
+}
+
+impl Plugin for TestResolvePlugin {
+  fn name(&self) -> &str {
+    "TestResolvePlugin"
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    assert!(
+      context.module_graph.try_read().is_some(),
+      "resolve hooks should not be called while the module graph is locked"
+    );
+
+    let resolved_path = match param.source.as_str() {
+      "./a" => "/project/a.ts".to_string(),
+      "./dep" => self.dep_path.lock().clone(),
+      _ => return Ok(None),
+    };
+
+    Ok(Some(PluginResolveHookResult {
+      resolved_path,
+      ..Default::default()
+    }))
+  }
+}
+
+fn create_context(dep_path: &str) -> Arc<CompilationContext> {
+  let config = Config {
+    root: "/project".to_string(),
+    ..Default::default()
+  };
+  let plugin = Arc::new(TestResolvePlugin {
+    dep_path: Mutex::new(dep_path.to_string()),
+  }) as Arc<dyn Plugin>;
+  let context = Arc::new(CompilationContext::new(config, vec![plugin]).unwrap());
+
+  // the cache of a.ts is created when `./dep` is resolved to `/project/dep.ts`
+  context.cache_manager.module_cache.set_cache(
+    "a.ts".into(),
+    CachedModule {
+      module: Module::new("a.ts".into()),
+      dependencies: vec![CachedModuleDependency {
+        dependency: "dep.ts".into(),
+        edge_info: Default::default(),
+        resolve_results: vec![CachedResolveResult::new(
+          "./dep".to_string(),
+          ResolveKind::Import,
+          &PluginResolveHookResult {
+            resolved_path: "/project/dep.ts".to_string(),
+            ..Default::default()
+          },
+        )],
+      }],
+      watch_dependencies: vec![],
+      is_expired: false,
+    },
+  );
+
+  context
+}
+
+fn resolve_a(context: &Arc<CompilationContext>) -> ResolveModuleResult {
+  let resolve_param = PluginResolveHookParam {
+    source: "./a".to_string(),
+    importer: Some("index.ts".into()),
+    kind: ResolveKind::Import,
+  };
+
+  resolve_module(&resolve_param, Some("a.ts".into()), context).unwrap()
+}
+
+#[test]
+fn test_resolve_cached_module_unchanged() {
+  let context = create_context("/project/dep.ts");
+  let module_id: ModuleId = "a.ts".into();
+
+  assert!(matches!(resolve_a(&context), ResolveModuleResult::Cached(id) if id == module_id));
+  assert!(context.cache_manager.module_cache.has_cache(&module_id));
+  assert!(context.module_graph.read().has_module(&module_id));
+
+  // the module is already handled
+  assert!(matches!(resolve_a(&context), ResolveModuleResult::Built(id) if id == module_id));
+}
+
+#[test]
+fn test_resolve_cached_module_dependency_resolved_to_different_path() {
+  let context = create_context("/project/dep-new.ts");
+  let module_id: ModuleId = "a.ts".into();
+
+  let ResolveModuleResult::Success(info) = resolve_a(&context) else {
+    panic!("a.ts should be rebuilt");
+  };
+  assert_eq!(info.module.id, module_id);
+  assert_eq!(
+    info.resolve_module_id_result.resolve_result.resolved_path,
+    "/project/a.ts"
+  );
+
+  let module_cache = &context.cache_manager.module_cache;
+  assert!(!module_cache.has_cache(&module_id));
+  assert_eq!(
+    module_cache.invalidated_reason(&module_id),
+    Some(ModuleCacheStatus::MissDepChanged)
+  );
+  assert!(context.module_graph.read().has_module(&module_id));
+}
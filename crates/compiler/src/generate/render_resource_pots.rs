@@ -19,6 +19,8 @@ use farmfe_plugin_library::FARM_BUNDLE_PLACEHOLDER_PREFIX;
 
 use crate::generate::resource_cache::{set_resource_cache, try_get_resource_cache};
 
+const FORMAT_PLACEHOLDER: &str = "[format]";
+
 pub fn render_resource_pots_and_generate_resources(
   resource_pots: Vec<&mut ResourcePot>,
   context: &Arc<CompilationContext>,
@@ -32,9 +34,9 @@ pub fn render_resource_pots_and_generate_resources(
   let dynamic_entries = context.module_graph.read().dynamic_entries.clone();
 
   let is_library = context.config.output.target_env.is_library();
-  // Collect module_id -> JS resource filename mapping for library mode
+  // Collect (module_id, format) -> resource filename mapping for library mode
   // placeholder replacement (used to replace FARM_BUNDLE_PLACEHOLDER:: markers)
-  let module_to_resource: Mutex<HashMap<String, String>> = Mutex::new(HashMap::default());
+  let module_to_resource: Mutex<HashMap<(String, String), String>> = Mutex::new(HashMap::default());
 
   let mut resource_pots_need_render = vec![];
 
@@ -53,19 +55,11 @@ pub fn render_resource_pots_and_generate_resources(
 
       // For cached resource pots, also collect module -> resource mapping
       if is_library {
-        let js_name = cached_resources.resources.iter().find_map(|r| {
-          if matches!(r.resource.resource_type, ResourceType::Js) {
-            Some(r.resource.name.clone())
-          } else {
-            None
-          }
-        });
-        if let Some(js_name) = js_name {
-          let mut map = module_to_resource.lock();
-          for module_id in resource_pot.modules() {
-            map.insert(module_id.to_string(), js_name.clone());
-          }
-        }
+        collect_module_to_resource(
+          &mut module_to_resource.lock(),
+          resource_pot,
+          cached_resources.resources.iter().map(|r| &r.resource),
+        );
       }
 
       for cached_resource in cached_resources.resources {
@@ -162,21 +156,13 @@ pub fn render_resource_pots_and_generate_resources(
         }
       }
 
-      // Collect module_id -> JS resource filename mapping for library mode
+      // Collect module_id -> resource filename mapping for library mode
       if is_library {
-        let js_name = generated_resources.resources.iter().find_map(|r| {
-          if matches!(r.resource.resource_type, ResourceType::Js) {
-            Some(r.resource.name.clone())
-          } else {
-            None
-          }
-        });
-        if let Some(js_name) = js_name {
-          let mut map = module_to_resource.lock();
-          for module_id in resource_pot.modules() {
-            map.insert(module_id.to_string(), js_name.clone());
-          }
-        }
+        collect_module_to_resource(
+          &mut module_to_resource.lock(),
+          resource_pot,
+          generated_resources.resources.iter().map(|r| &r.resource),
+        );
       }
 
       // process generated resources after rendering
@@ -323,19 +309,55 @@ pub fn render_resource_pot_generate_resources(
   }
 }
 
+/// Map the modules of the resource pot to its JS and CSS resources.
+/// JS resources are keyed by their format so that each format imports resources of the same format,
+/// CSS resources are shared by all formats and keyed by an empty format.
+fn collect_module_to_resource<'a>(
+  module_to_resource: &mut HashMap<(String, String), String>,
+  resource_pot: &ResourcePot,
+  resources: impl Iterator<Item = &'a Resource>,
+) {
+  for resource in resources {
+    let format = match resource.resource_type {
+      ResourceType::Js => resource
+        .special_placeholders
+        .get(FORMAT_PLACEHOLDER)
+        .cloned()
+        .unwrap_or_default(),
+      ResourceType::Css => String::new(),
+      _ => continue,
+    };
+
+    for module_id in resource_pot.modules() {
+      module_to_resource
+        .entry((module_id.to_string(), format.clone()))
+        .or_insert_with(|| resource.name.clone());
+    }
+  }
+}
+
 /// Replace FARM_BUNDLE_PLACEHOLDER:: markers in JS resource bytes with actual
 /// relative paths to the target resource files.
 fn replace_bundle_placeholders(
   resource: &mut Resource,
-  module_to_resource: &HashMap<String, String>,
+  module_to_resource: &HashMap<(String, String), String>,
 ) {
   let content = String::from_utf8_lossy(&resource.bytes);
   if !content.contains(FARM_BUNDLE_PLACEHOLDER_PREFIX) {
     return;
   }
 
+  let format = resource
+    .special_placeholders
+    .get(FORMAT_PLACEHOLDER)
+    .cloned()
+    .unwrap_or_default();
   let mut new_content = content.to_string();
-  for (module_id, target_resource_name) in module_to_resource {
+  for ((module_id, target_format), target_resource_name) in module_to_resource {
+    if !target_format.is_empty() && *target_format != format {
+      continue;
+    }
+
     let placeholder = format!("{FARM_BUNDLE_PLACEHOLDER_PREFIX}{module_id}");
     if new_content.contains(&placeholder) {
       let relative = compute_relative_path(&resource.name, target_resource_name);
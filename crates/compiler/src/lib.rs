@@ -10,7 +10,7 @@ use std::sync::Arc;
 use farmfe_core::{
   config::{Config, Mode},
   context::CompilationContext,
-  error::Result,
+  error::{CompilationError, Result},
   farm_profile_function,
   module::ModuleId,
   parking_lot::Mutex,
@@ -31,11 +31,21 @@ pub mod write;
 pub struct Compiler {
   context: Arc<CompilationContext>,
   pub last_fail_module_ids: Mutex<Vec<ModuleId>>,
+  /// Compilers of the named environments in [Config::environments], sorted by name.
+  /// They share the plugins with this compiler, which compiles the top level config
+  environments: Vec<(String, Compiler)>,
 }
 
 impl Compiler {
   /// The params are [farmfe_core::config::Config] and dynamic load rust plugins and js plugins [farmfe_core::plugin::Plugin]
   pub fn new(config: Config, mut plugin_adapters: Vec<Arc<dyn Plugin>>) -> Result<Self> {
+    let mut environment_configs = config
+      .environments
+      .iter()
+      .map(|(name, env)| Ok((name.clone(), env.to_config(name, &config)?)))
+      .collect::<Result<Vec<_>>>()?;
+    environment_configs.sort_by(|a, b| a.0.cmp(&b.0));
+
     let render_plugin: Arc<dyn Plugin> = if config.output.target_env.is_library() {
       Arc::new(farmfe_plugin_library::FarmPluginLibrary::new(&config)) as _
     } else {
@@ -104,6 +114,14 @@ impl Compiler {
       }
     }
 
+    if config.html.csp.is_some() {
+      plugins.push(Arc::new(farmfe_plugin_html::FarmPluginHtmlCsp::new(&config)) as _);
+    }
+
+    if config.rsc.enabled() {
+      plugins.push(Arc::new(farmfe_plugin_rsc::FarmPluginRsc::new(&config)) as _);
+    }
+
     if config.preset_env.enabled() {
       plugins.push(Arc::new(farmfe_plugin_polyfill::FarmPluginPolyfill::new(&config)) as _);
     }
@@ -115,9 +133,23 @@ impl Compiler {
       plugins.push(Arc::new(farmfe_plugin_file_size::FarmPluginFileSize::new(&config)) as _);
     }
 
+    // plugins are shared by all environments
+    let environment_plugin_adapters = plugin_adapters.clone();
     plugins.append(&mut plugin_adapters);
 
-    Self::new_without_internal_plugins(config, plugins)
+    let mut compiler = Self::new_without_internal_plugins(config, plugins)?;
+
+    for (name, env_config) in environment_configs {
+      let mut env_compiler = Self::new(env_config, environment_plugin_adapters.clone())?;
+      let env_context = Arc::get_mut(&mut env_compiler.context)
+        .expect("the context of a new compiler should not be shared");
+      env_context
+        .shared_custom
+        .clone_from(&compiler.context.shared_custom);
+      compiler.environments.push((name, env_compiler));
+    }
+
+    Ok(compiler)
   }
 
   pub fn new_without_internal_plugins(
@@ -133,6 +165,7 @@ impl Compiler {
     Ok(Self {
       context: Arc::new(context),
       last_fail_module_ids: Mutex::new(vec![]),
+      environments: vec![],
     })
   }
 
@@ -159,6 +192,18 @@ impl Compiler {
 
   /// Compile the project using the configuration
   pub fn compile(&self) -> Result<()> {
+    // the client references found by the react server environments are added to the client environments, so they are compiled first
+    let (server_environments, environments): (Vec<_>, Vec<_>) = self
+      .environments
+      .iter()
+      .partition(|(_, environment)| environment.context.config.is_react_server());
+
+    for (name, environment) in server_environments {
+      environment
+        .compile()
+        .map_err(|e| environment_error(name, e))?;
+    }
+
     self.context.stats.set_start_time();
     if self.context.config.persistent_cache.enabled() {
       self
@@ -217,12 +262,47 @@ impl Compiler {
 
     self.context.stats.set_end_time();
 
+    // the other environments are compiled after the top level one to reuse its load results
+    for (name, environment) in environments {
+      environment
+        .compile()
+        .map_err(|e| environment_error(name, e))?;
+    }
+
     Ok(())
   }
 
   pub fn context(&self) -> &Arc<CompilationContext> {
     &self.context
   }
+
+  /// Names and compilers of the environments in [Config::environments]
+  pub fn environments(&self) -> &[(String, Compiler)] {
+    &self.environments
+  }
+
+  pub fn environment(&self, name: &str) -> Option<&Compiler> {
+    self
+      .environments
+      .iter()
+      .find(|(env_name, _)| env_name == name)
+      .map(|(_, compiler)| compiler)
+  }
+
+  /// Whether the file is a module or a watched file of this compiler
+  pub fn has_module(&self, resolved_path: &str) -> bool {
+    let module_graph = self.context.module_graph.read();
+    let watch_graph = self.context.watch_graph.read();
+    let module_id = ModuleId::new(resolved_path, "", &self.context.config.root);
+
+    module_graph.has_module(&module_id)
+      || watch_graph.has_module(&module_id)
+      || !module_graph.module_ids_by_file(&module_id).is_empty()
+  }
+}
+
+pub(crate) fn environment_error(name: &str, error: CompilationError) -> CompilationError {
+  CompilationError::GenericError(format!("[environment {name}] {error}"))
 }
 
 fn write_cache(context: Arc<CompilationContext>) {
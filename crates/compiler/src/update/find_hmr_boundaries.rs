@@ -1,60 +1,145 @@
 use std::sync::Arc;
 
 use farmfe_core::{
+  config::Mode,
   context::CompilationContext,
-  module::{module_graph::ModuleGraph, ModuleId},
+  module::{module_graph::ModuleGraph, ModuleId, ModuleMetaData, ModuleType},
+  plugin::{HmrFullReloadKind, HmrFullReloadReason, HmrModuleAcceptance},
   HashMap, HashSet,
 };
 
+#[derive(Debug, Default)]
+pub struct HmrBoundaries {
+  /// The key is the updated module, the value is the accepted import chains from the updated module to the boundaries
+  pub boundaries: HashMap<String, Vec<Vec<String>>>,
+  /// Non-script modules that apply the update themselves, the key is the module id and the value is the module type.
+  /// Contains the updated html modules and the non-script boundaries like plain css
+  pub non_script_updates: HashMap<String, String>,
+}
+
+/// Find the HMR boundaries of the updated modules. If any import chain of an updated module reaches an entry without being accepted,
+/// or an updated non-script module does not accept its own updates, the page has to be reloaded and the reason is returned.
+/// Updated html modules are not bubbled, they are applied by the client as a targeted html update.
 pub fn find_hmr_boundaries(
   update_module_ids: &Vec<ModuleId>,
   context: &Arc<CompilationContext>,
-) -> HashMap<String, Vec<Vec<String>>> {
-  let mut boundaries = HashMap::default();
+) -> Result<HmrBoundaries, HmrFullReloadReason> {
+  let mut result = HmrBoundaries::default();
   let module_graph = context.module_graph.read();
+  let mode = context.config.mode;
 
   for id in update_module_ids {
+    let module = module_graph.module(id).unwrap();
+
+    if matches!(module.module_type, ModuleType::Html) {
+      result
+        .non_script_updates
+        .insert(id.id(mode), module.module_type.to_string());
+      continue;
+    }
+
+    // non script modules can not be rendered as a script update, so they must accept their own updates
+    if !module.module_type.is_script() && !module.meta.hmr_self_accepted() {
+      return Err(full_reload_reason(
+        HmrFullReloadKind::NonScriptModule,
+        vec![id.clone()],
+        &module_graph,
+        mode,
+      ));
+    }
+
     let mut stack = vec![id.clone()];
     let mut visited = HashSet::default();
     let mut res = vec![];
-    let all_path_accepted =
-      find_hmr_accepted_recursively(id, &module_graph, &mut stack, &mut visited, &mut res);
     // if any of the path is not accepted, reload the whole page
-    if !all_path_accepted {
-      return HashMap::default();
+    if let Err(chain) =
+      find_hmr_accepted_recursively(id, &module_graph, &mut stack, &mut visited, &mut res)
+    {
+      return Err(full_reload_reason(
+        HmrFullReloadKind::Entry,
+        chain,
+        &module_graph,
+        mode,
+      ));
+    }
+
+    for boundary in res.iter().filter_map(|chain| chain.last()) {
+      let boundary_module = module_graph.module(boundary).unwrap();
+
+      if !boundary_module.module_type.is_script() {
+        result
+          .non_script_updates
+          .insert(boundary.id(mode), boundary_module.module_type.to_string());
+      }
     }
 
-    boundaries.insert(
-      id.id(context.config.mode),
+    result.boundaries.insert(
+      id.id(mode),
       res
         .into_iter()
-        .map(|v| v.into_iter().map(|id| id.id(context.config.mode)).collect())
+        .map(|v| v.into_iter().map(|id| id.id(mode)).collect())
         .collect(),
     );
   }
 
-  boundaries
+  Ok(result)
 }
 
+fn full_reload_reason(
+  kind: HmrFullReloadKind,
+  chain: Vec<ModuleId>,
+  module_graph: &ModuleGraph,
+  mode: Mode,
+) -> HmrFullReloadReason {
+  let modules = chain
+    .iter()
+    .map(|id| {
+      let meta = &module_graph.module(id).unwrap().meta;
+      let mut accepted_deps = match &**meta {
+        ModuleMetaData::Script(script) => script
+          .hmr_accepted_deps
+          .iter()
+          .map(|dep| dep.id(mode))
+          .collect::<Vec<_>>(),
+        _ => vec![],
+      };
+      accepted_deps.sort();
+
+      HmrModuleAcceptance {
+        id: id.id(mode),
+        self_accepted: meta.hmr_self_accepted(),
+        accepted_deps,
+      }
+    })
+    .collect();
+
+  HmrFullReloadReason {
+    kind,
+    chain: chain.into_iter().map(|id| id.id(mode)).collect(),
+    modules,
+  }
+}
+
+/// Returns the unaccepted import chain if there is a path from the module to an entry
 fn find_hmr_accepted_recursively(
   id: &ModuleId,
   module_graph: &ModuleGraph,
   stack: &mut Vec<ModuleId>,
   visited: &mut HashSet<ModuleId>,
   res: &mut Vec<Vec<ModuleId>>,
-) -> bool {
+) -> Result<(), Vec<ModuleId>> {
   let module = module_graph.module(id).unwrap();
 
   // There is a path from the module to the root that does not have HMR accepted
   if module_graph.entries.contains_key(id) {
-    return false;
+    return Err(stack.clone());
   }
 
-  // self accepted, non script modules are not self-acceptable for now
-  if module.module_type.is_script() && module.meta.as_script().hmr_self_accepted {
+  // self accepted, the acceptance is declared by the meta of each module type
+  if module.meta.hmr_self_accepted() {
     res.push(stack.clone());
 
-    return true;
+    return Ok(());
   }
 
   // check if any of the importers accepts the module
@@ -67,16 +152,8 @@ fn find_hmr_accepted_recursively(
       // check if the parent accepts the module
       let parent_module = module_graph.module(&parent).unwrap();
 
-      if !parent_module.module_type.is_script() {
-        return false;
-      }
       // if the importer accepts the module, push
-      if parent_module
-        .meta
-        .as_script()
-        .hmr_accepted_deps
-        .contains(id)
-      {
+      if parent_module.meta.hmr_accepts_dep(id) {
         let mut cloned_stack = stack.clone();
         cloned_stack.push(parent.clone());
         res.push(cloned_stack);
@@ -85,17 +162,14 @@ fn find_hmr_accepted_recursively(
       }
 
       stack.push(parent.clone());
-      let all_path_accepted =
-        find_hmr_accepted_recursively(&parent, module_graph, stack, visited, res);
+      let result = find_hmr_accepted_recursively(&parent, module_graph, stack, visited, res);
       stack.pop();
 
-      if !all_path_accepted {
-        return false;
-      }
+      result?;
     }
   }
 
-  true
+  Ok(())
 }
 
 #[cfg(test)]
@@ -106,10 +180,14 @@ mod tests {
     config::{Config, Mode},
     context::CompilationContext,
     module::{
-      meta_data::script::ScriptModuleMetaData, module_graph::ModuleGraph, ModuleMetaData,
-      ModuleType,
+      meta_data::{css::CssModuleMetaData, script::ScriptModuleMetaData, HMR_SELF_ACCEPTED_KEY},
+      module_graph::ModuleGraph,
+      CustomMetaDataMap, ModuleMetaData, ModuleType,
     },
     parking_lot::RwLock,
+    plugin::{HmrFullReloadKind, HmrModuleAcceptance},
+    swc_common::DUMMY_SP,
+    swc_css_ast::Stylesheet,
     HashMap, HashSet,
   };
   use farmfe_testing_helpers::construct_test_module_graph;
@@ -141,9 +219,13 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
+    let reason = find_hmr_boundaries(&vec!["F".into()], &context).unwrap_err();
 
-    assert_eq!(boundaries, HashMap::default());
+    // F is imported by C and D, the order of the importers is not guaranteed
+    assert_eq!(reason.kind, HmrFullReloadKind::Entry);
+    assert_eq!(reason.chain.first().unwrap(), "F");
+    assert!(["A", "B"].contains(&reason.chain.last().unwrap().as_str()));
+    assert_eq!(reason.chain.len(), reason.modules.len());
   }
 
   #[test]
@@ -164,7 +246,9 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
+    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context)
+      .unwrap()
+      .boundaries;
     // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
     assert_eq!(
       boundaries,
@@ -189,9 +273,13 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
-    // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
-    assert_eq!(boundaries, HashMap::default());
+    let reason = find_hmr_boundaries(&vec!["F".into()], &context).unwrap_err();
+
+    assert_eq!(reason.kind, HmrFullReloadKind::Entry);
+    assert_eq!(
+      reason.chain,
+      vec!["F".to_string(), "C".to_string(), "A".to_string()]
+    );
   }
 
   #[test]
@@ -214,7 +302,9 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
+    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context)
+      .unwrap()
+      .boundaries;
     // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
     assert_eq!(
       boundaries,
@@ -247,9 +337,30 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["F".into()], &context);
-    // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
-    assert_eq!(boundaries, HashMap::default());
+    let reason = find_hmr_boundaries(&vec!["F".into()], &context).unwrap_err();
+
+    assert_eq!(reason.kind, HmrFullReloadKind::Entry);
+    assert_eq!(
+      reason.chain,
+      vec!["F".to_string(), "C".to_string(), "A".to_string()]
+    );
+    assert_eq!(
+      reason.modules,
+      vec![
+        HmrModuleAcceptance {
+          id: "F".to_string(),
+          ..Default::default()
+        },
+        HmrModuleAcceptance {
+          id: "C".to_string(),
+          ..Default::default()
+        },
+        HmrModuleAcceptance {
+          id: "A".to_string(),
+          ..Default::default()
+        },
+      ]
+    );
   }
 
   #[test]
@@ -279,7 +390,9 @@ mod tests {
     }));
 
     let context = create_context(module_graph);
-    let boundaries = find_hmr_boundaries(&vec!["G".into()], &context);
+    let boundaries = find_hmr_boundaries(&vec!["G".into()], &context)
+      .unwrap()
+      .boundaries;
     // Be careful, the order of the paths may not be guaranteed. check the order if the test fails.
     assert_eq!(
       boundaries,
@@ -288,4 +401,99 @@ mod tests {
         .collect::<HashMap<_, _>>()
     );
   }
+
+  #[test]
+  fn find_hmr_boundaries_non_script_boundary() {
+    let mut module_graph = construct_test_module_graph();
+
+    let module_d = module_graph.module_mut(&"D".into()).unwrap();
+    module_d.module_type = ModuleType::Js;
+    *module_d.meta = ModuleMetaData::Script(Box::new(ScriptModuleMetaData {
+      hmr_accepted_deps: HashSet::from_iter(["F".into()]),
+      ..Default::default()
+    }));
+    let module_c = module_graph.module_mut(&"C".into()).unwrap();
+    module_c.module_type = ModuleType::Css;
+    *module_c.meta = ModuleMetaData::Css(Box::new(CssModuleMetaData {
+      ast: Stylesheet {
+        span: DUMMY_SP,
+        rules: vec![],
+      },
+      comments: Default::default(),
+      hmr_self_accepted: true,
+      custom: Default::default(),
+    }));
+
+    let context = create_context(module_graph);
+    let result = find_hmr_boundaries(&vec!["F".into()], &context).unwrap();
+    let mut chains = result.boundaries.get("F").unwrap().clone();
+    chains.sort();
+
+    assert_eq!(
+      chains,
+      vec![
+        vec!["F".to_string(), "C".to_string()],
+        vec!["F".to_string(), "D".to_string()]
+      ]
+    );
+    assert_eq!(
+      result.non_script_updates,
+      HashMap::from_iter([("C".to_string(), "css".to_string())])
+    );
+  }
+
+  #[test]
+  fn find_hmr_boundaries_non_script_module() {
+    let mut module_graph = construct_test_module_graph();
+
+    let module_d = module_graph.module_mut(&"D".into()).unwrap();
+    module_d.module_type = ModuleType::Js;
+    *module_d.meta = ModuleMetaData::Script(Box::new(ScriptModuleMetaData {
+      hmr_self_accepted: true,
+      ..Default::default()
+    }));
+    let module_g = module_graph.module_mut(&"G".into()).unwrap();
+    module_g.module_type = ModuleType::Custom("less".to_string());
+    *module_g.meta = ModuleMetaData::Custom(CustomMetaDataMap::default());
+
+    let context = create_context(module_graph);
+    let reason = find_hmr_boundaries(&vec!["G".into()], &context).unwrap_err();
+
+    assert_eq!(reason.kind, HmrFullReloadKind::NonScriptModule);
+    assert_eq!(reason.chain, vec!["G".to_string()]);
+
+    // custom modules declare self acceptance by the custom meta data
+    let mut module_graph = context.module_graph.write();
+    let module_g = module_graph.module_mut(&"G".into()).unwrap();
+    module_g.meta.write(HMR_SELF_ACCEPTED_KEY.to_string(), true);
+    drop(module_graph);
+
+    let result = find_hmr_boundaries(&vec!["G".into()], &context).unwrap();
+
+    assert_eq!(
+      result.boundaries,
+      HashMap::from_iter([("G".to_string(), vec![vec!["G".to_string()]])])
+    );
+    assert_eq!(
+      result.non_script_updates,
+      HashMap::from_iter([("G".to_string(), "less".to_string())])
+    );
+  }
+
+  #[test]
+  fn find_hmr_boundaries_html_entry() {
+    let mut module_graph = construct_test_module_graph();
+
+    let module_a = module_graph.module_mut(&"A".into()).unwrap();
+    module_a.module_type = ModuleType::Html;
+
+    let context = create_context(module_graph);
+    let result = find_hmr_boundaries(&vec!["A".into()], &context).unwrap();
+
+    assert!(result.boundaries.is_empty());
+    assert_eq!(
+      result.non_script_updates,
+      HashMap::from_iter([("A".to_string(), "html".to_string())])
+    );
+  }
 }
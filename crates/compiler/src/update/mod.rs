@@ -1,7 +1,8 @@
 use std::sync::Arc;
 
 use farmfe_core::{
-  cache::module_cache::CachedModule,
+  cache::module_cache::{CachedModule, ModuleCacheStatus},
+  config::Mode,
   context::CompilationContext,
   error::CompilationError,
   module::{
@@ -9,7 +10,9 @@ use farmfe_core::{
     module_group::{ModuleGroupId, ModuleGroupType},
     Module, ModuleId,
   },
-  plugin::{PluginResolveHookParam, ResolveKind, UpdateResult, UpdateType},
+  plugin::{
+    HmrFullReloadKind, NodeHmrUpdate, PluginResolveHookParam, ResolveKind, UpdateResult, UpdateType,
+  },
   resource::ResourceType,
   serde::Serialize,
   serde_json::{self, json},
@@ -27,6 +30,7 @@ use crate::{
     module_cache::handle_cached_modules, BuildModuleGraphThreadedParams, HandleDependenciesParams,
     ResolvedModuleInfo,
   },
+  environment_error,
   generate::finalize_resources::finalize_resources,
   Compiler,
 };
@@ -38,7 +42,8 @@ use self::{
   module_cache::set_updated_modules_cache,
   patch_module_group_graph::patch_module_group_graph,
   regenerate_resources::{
-    regenerate_resources_for_affected_module_groups, render_and_generate_update_resource,
+    regenerate_resources_for_affected_module_groups, render_and_generate_node_update_resource,
+    render_and_generate_update_resource,
   },
   update_context::UpdateContext,
 };
@@ -155,6 +160,14 @@ impl Compiler {
     self.context.stats.add_hmr_compilation_stats();
     self.context.stats.set_start_time();
 
+    // a path may belong to the environments only, e.g. the entry of a ssr environment, so only the
+    // paths of this compiler are updated here and each environment updates its own paths below
+    let (paths, environment_paths) = if self.environments.is_empty() {
+      (paths, vec![])
+    } else {
+      (self.filter_update_paths(&paths), paths)
+    };
+
     // mark the compilation as update
     // self.context.set_update();
     let (err_sender, err_receiver) = Self::create_thread_channel();
@@ -288,24 +301,65 @@ impl Compiler {
       }
     }
 
-    // If the module type is not script, we should skip render and generate update resource.
+    // find the boundaries, or the reason why the page has to be reloaded
+    let (hmr_boundaries, full_reload_reason) =
+      match find_hmr_boundaries::find_hmr_boundaries(&updated_module_ids, &self.context) {
+        Ok(hmr_boundaries) => (hmr_boundaries, None),
+        Err(reason) => (Default::default(), Some(reason)),
+      };
+
+    // If an updated module is not script and does not accept its own updates, we should skip render and generate update resource.
     // and just return `window.location.reload()`
-    let should_reload_page = updated_module_ids.iter().any(|id| {
-      let module_graph = self.context.module_graph.read();
-      let module = module_graph.module(id).unwrap();
-      !module.module_type.is_script()
-    });
+    let should_reload_page = full_reload_reason
+      .as_ref()
+      .is_some_and(|reason| reason.kind == HmrFullReloadKind::NonScriptModule);
 
-    let (immutable_resources, mutable_resources) = if should_reload_page {
+    let is_node = self.context.config.output.target_env.is_node();
+
+    let (immutable_resources, mutable_resources) = if should_reload_page && !is_node {
       ("window.location.reload()".to_string(), "{}".to_string())
-    } else if generate_update_resource {
-      render_and_generate_update_resource(&updated_module_ids, &diff_result, &self.context)?
+    } else if generate_update_resource && !is_node {
+      // non script modules are applied by the client themselves, only script modules are rendered
+      let updated_script_module_ids = {
+        let module_graph = self.context.module_graph.read();
+        updated_module_ids
+          .iter()
+          .filter(|id| module_graph.module(id).unwrap().module_type.is_script())
+          .cloned()
+          .collect::<Vec<_>>()
+      };
+      render_and_generate_update_resource(&updated_script_module_ids, &diff_result, &self.context)?
     } else {
       ("{}".to_string(), "{}".to_string())
     };
 
-    // find the boundaries.
-    let boundaries = find_hmr_boundaries::find_hmr_boundaries(&updated_module_ids, &self.context);
+    // node targets can not reload, the process should be restarted when the update is not accepted
+    let node_update = if !generate_update_resource || !is_node {
+      None
+    } else if full_reload_reason.is_some() {
+      Some(NodeHmrUpdate {
+        format: self.context.config.output.format.as_single(),
+        code: String::new(),
+        invalidated_module_ids: vec![],
+        restart: true,
+      })
+    } else {
+      Some(NodeHmrUpdate {
+        format: self.context.config.output.format.as_single(),
+        code: render_and_generate_node_update_resource(
+          &updated_module_ids,
+          &diff_result,
+          &self.context,
+        )?,
+        invalidated_module_ids: get_invalidated_module_ids(
+          &updated_module_ids,
+          &diff_result,
+          &hmr_boundaries.boundaries,
+          self.context.config.mode,
+        ),
+        restart: false,
+      })
+    };
 
     let dynamic_resources_map = self.regenerate_resources(
       affected_module_groups,
@@ -326,12 +380,42 @@ impl Compiler {
       .extend(diff_result.removed_modules);
     update_result.immutable_resources = immutable_resources;
     update_result.mutable_resources = mutable_resources;
-    update_result.boundaries = boundaries;
+    update_result.boundaries = hmr_boundaries.boundaries;
+    update_result.full_reload_reason = full_reload_reason;
+    update_result.non_script_updates = hmr_boundaries.non_script_updates;
     update_result.dynamic_resources_map = dynamic_resources_map;
+    update_result.node_update = node_update;
+
+    // the resources of the environments are regenerated synchronously, so they are ready when the update finishes
+    for (name, environment) in &self.environments {
+      let paths = environment.filter_update_paths(&environment_paths);
+
+      if paths.is_empty() {
+        continue;
+      }
+
+      let environment_result = environment
+        .update(paths, || {}, true, generate_update_resource)
+        .map_err(|e| environment_error(name, e))?;
+      update_result
+        .environments
+        .insert(name.clone(), environment_result);
+    }
 
     Ok(update_result)
   }
 
+  /// The updated paths that belong to this compiler, added paths are kept as they may be imported by any compiler
+  fn filter_update_paths(&self, paths: &[(String, UpdateType)]) -> Vec<(String, UpdateType)> {
+    paths
+      .iter()
+      .filter(|(path, update_type)| {
+        matches!(update_type, UpdateType::Added) || self.has_module(path)
+      })
+      .cloned()
+      .collect()
+  }
+
   /// Resolving, loading, transforming and parsing a module in a separate thread.
   /// This method is similar to the build_module_graph_threaded method in the build/mod.rs file,
   /// the difference is that this method is used for updating the module graph, only handles the updated and added module, and ignores the existing unchanged module,
@@ -371,16 +455,31 @@ impl Compiler {
         ResolveModuleResult::Cached(module_id) => {
           let mut cached_module = context.cache_manager.module_cache.get_cache(&module_id);
           // if the dependency is immutable, skip building
-          if let Err(e) = handle_cached_modules(&mut cached_module, &context) {
-            err_sender.send(e).unwrap();
-            return;
-          }
+          let (module, deps) = match handle_cached_modules(&mut cached_module, &context) {
+            Ok(false) => (
+              cached_module.module,
+              CachedModule::dep_sources(cached_module.dependencies),
+            ),
+            Ok(true) => {
+              match Self::rebuild_stale_cached_module(&module_id, &resolve_param, &context) {
+                Ok(r) => r,
+                Err(e) => {
+                  err_sender.send(e).unwrap();
+                  return;
+                }
+              }
+            }
+            Err(e) => {
+              err_sender.send(e).unwrap();
+              return;
+            }
+          };
 
           let handle_dependencies_params = HandleDependenciesParams {
-            module: cached_module.module,
+            module,
             resolve_param,
             order: order.unwrap_or(0),
-            deps: CachedModule::dep_sources(cached_module.dependencies),
+            deps,
             thread_pool: c_thread_pool,
             err_sender,
             context,
@@ -746,8 +845,12 @@ fn resolve_module(
         .unwrap_or(false);
 
       if should_invalidate_cached_module {
+        drop(cached_module);
         module_cache_manager.invalidate_cache(&cached_dependency);
       } else {
+        context
+          .stats
+          .record_module_cache_status(&cached_dependency, ModuleCacheStatus::Hit);
         Compiler::insert_dummy_module(&cached_dependency, &mut update_module_graph);
         return Ok(ResolveModuleResult::Cached(cached_dependency));
       }
@@ -768,6 +871,26 @@ fn resolve_module(
   })))
 }
 
+/// The modules whose cached exports are stale after the update, sorted
+fn get_invalidated_module_ids(
+  updated_module_ids: &[ModuleId],
+  diff_result: &DiffResult,
+  boundaries: &HashMap<String, Vec<Vec<String>>>,
+  mode: Mode,
+) -> Vec<String> {
+  let mut invalidated_module_ids = updated_module_ids
+    .iter()
+    .chain(&diff_result.removed_modules)
+    .map(|id| id.id(mode))
+    .chain(boundaries.values().flatten().flatten().cloned())
+    .collect::<HashSet<_>>()
+    .into_iter()
+    .collect::<Vec<_>>();
+  invalidated_module_ids.sort();
+
+  invalidated_module_ids
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase", crate = "farmfe_core::serde")]
 struct PrintedDiffAndPatchContext {
@@ -73,77 +73,112 @@ pub fn render_and_generate_update_resource(
     }
   }
 
-  let gen_resource_pot_code =
-    |resource_pot: &mut ResourcePot| -> farmfe_core::error::Result<String> {
-      if resource_pot.modules().is_empty() {
-        return Ok("".to_string());
-      }
+  drop(module_graph);
 
-      let hook_context = PluginHookContext::default();
-      let res = context
-        .plugin_driver
-        .render_resource_pot(resource_pot, context, &hook_context)?
-        .ok_or(CompilationError::GenerateResourcesError {
-          name: resource_pot.id.clone(),
-          ty: resource_pot.resource_pot_type.clone(),
-          source: None,
-        })?;
-      resource_pot.meta = res;
-      let (mut updated_result, _) =
-        render_resource_pot_generate_resources(resource_pot, context, &Default::default())?;
-      let update_resources = updated_result.resources.remove(0);
-
-      let sourcemap = update_resources
-        .source_map
-        .map(|map| SourceMap::from_slice(&map.bytes).unwrap());
-
-      let code = String::from_utf8(update_resources.resource.bytes).unwrap();
-      let global_this = get_farm_global_this(
-        &context.config.runtime.namespace,
-        &context.config.output.target_env,
-      );
-      let mut magic_string = MagicString::new(&code, None);
-
-      // force re-register the affected modules when hmr
-      magic_string.prepend(&format!("{global_this}.{FARM_MODULE_SYSTEM}._rg=true;"));
-      magic_string.append(&format!("{global_this}.{FARM_MODULE_SYSTEM}._rg=false;"));
-
-      let code = magic_string.to_string();
-
-      let map = if let Some(sourcemap) = sourcemap {
-        // the updated code will be executed in the browser using new Function(code), so we need to
-        // add extra (function anonymous() {})() to wrap the code to make sure the sourcemap works as expected
-        magic_string.prepend("(function anonymous(\n) {\n");
-        magic_string.append("\n})");
-
-        let map = magic_string
-          .generate_map(SourceMapOptions::default())
-          .unwrap();
-        let sourcemap =
-          collapse_sourcemap_chain(vec![sourcemap, map], CollapseSourcemapOptions::default());
-
-        let mut buf = vec![];
-        sourcemap.to_writer(&mut buf).unwrap();
-        let map_code = String::from_utf8(buf).unwrap();
-
-        // inline source map
-        format!(
-          "\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}",
-          base64_encode(map_code.as_bytes())
-        )
-      } else {
-        "".to_string()
-      };
-
-      Ok(format!("{code}{map}"))
-    };
-
-  let immutable_update_resource = gen_resource_pot_code(&mut immutable_update_resource_pot)?;
-  let mutable_update_resource = gen_resource_pot_code(&mut mutable_update_resource_pot)?;
+  let immutable_update_resource =
+    render_update_resource_pot(&mut immutable_update_resource_pot, true, context)?;
+  let mutable_update_resource =
+    render_update_resource_pot(&mut mutable_update_resource_pot, true, context)?;
 
   Ok((immutable_update_resource, mutable_update_resource))
 }
 
+/// Render the added and updated modules of node targets into a single update resource in the format of `output.format`.
+/// The long-running process should execute it as a module before applying the update with the runtime
+pub fn render_and_generate_node_update_resource(
+  updated_module_ids: &[ModuleId],
+  diff_result: &DiffResult,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<String> {
+  let mut update_resource_pot =
+    ResourcePot::new("__NODE_UPDATE_RESOURCE_POT__", "", ResourcePotType::Js);
+
+  {
+    let module_graph = context.module_graph.read();
+
+    for id in diff_result.added_modules.iter().chain(updated_module_ids) {
+      if !module_graph.module(id).unwrap().external {
+        update_resource_pot.add_module(id.clone());
+      }
+    }
+  }
+
+  render_update_resource_pot(&mut update_resource_pot, false, context)
+}
+
+/// Render the update resource pot to code that re-registers its modules.
+/// `wrapped_in_function` should be true if the code is executed by `new Function(code)` or `eval` in the browser
+fn render_update_resource_pot(
+  resource_pot: &mut ResourcePot,
+  wrapped_in_function: bool,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<String> {
+  if resource_pot.modules().is_empty() {
+    return Ok("".to_string());
+  }
+
+  let hook_context = PluginHookContext::default();
+  let res = context
+    .plugin_driver
+    .render_resource_pot(resource_pot, context, &hook_context)?
+    .ok_or(CompilationError::GenerateResourcesError {
+      name: resource_pot.id.clone(),
+      ty: resource_pot.resource_pot_type.clone(),
+      source: None,
+    })?;
+  resource_pot.meta = res;
+  let (mut updated_result, _) =
+    render_resource_pot_generate_resources(resource_pot, context, &Default::default())?;
+  let update_resources = updated_result.resources.remove(0);
+
+  let sourcemap = update_resources
+    .source_map
+    .map(|map| SourceMap::from_slice(&map.bytes).unwrap());
+
+  let code = String::from_utf8(update_resources.resource.bytes).unwrap();
+  let global_this = get_farm_global_this(
+    &context.config.runtime.namespace,
+    &context.config.output.target_env,
+  );
+  let mut magic_string = MagicString::new(&code, None);
+
+  // force re-register the affected modules when hmr
+  magic_string.prepend(&format!("{global_this}.{FARM_MODULE_SYSTEM}._rg=true;"));
+  magic_string.append(&format!("{global_this}.{FARM_MODULE_SYSTEM}._rg=false;"));
+
+  let code = magic_string.to_string();
+
+  let map = if let Some(sourcemap) = sourcemap {
+    // the updated code will be executed in the browser using new Function(code), so we need to
+    // add extra (function anonymous() {})() to wrap the code to make sure the sourcemap works as expected.
+    // node update resources are executed as modules, they are not wrapped
+    if wrapped_in_function {
+      magic_string.prepend("(function anonymous(\n) {\n");
+      magic_string.append("\n})");
+    }
+
+    let map = magic_string
+      .generate_map(SourceMapOptions::default())
+      .unwrap();
+    let sourcemap =
+      collapse_sourcemap_chain(vec![sourcemap, map], CollapseSourcemapOptions::default());
+
+    let mut buf = vec![];
+    sourcemap.to_writer(&mut buf).unwrap();
+    let map_code = String::from_utf8(buf).unwrap();
+
+    // inline source map
+    format!(
+      "\n//# sourceMappingURL=data:application/json;charset=utf-8;base64,{}",
+      base64_encode(map_code.as_bytes())
+    )
+  } else {
+    "".to_string()
+  };
+
+  Ok(format!("{code}{map}"))
+}
+
 pub fn regenerate_resources_for_affected_module_groups(
   affected_module_groups: HashSet<ModuleGroupId>,
   diff_result: DiffResult,
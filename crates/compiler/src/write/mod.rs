@@ -59,6 +59,10 @@ impl Compiler {
 
     self.copy_public_dir(output_dir).unwrap();
 
+    for (_, environment) in self.environments() {
+      environment.write_resources_to_disk()?;
+    }
+
     Ok(())
   }
 
@@ -0,0 +1,5 @@
+import { formatName } from '../lib/utils';
+
+export function Button(label: string): string {
+  return `<button>${formatName(label)}</button>`;
+}
@@ -0,0 +1,6 @@
+import { formatName } from './lib/utils';
+import { Button } from './components';
+
+export function render(name: string): string {
+  return Button(formatName(name));
+}
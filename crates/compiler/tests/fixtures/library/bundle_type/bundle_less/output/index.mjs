@@ -1,5 +1,5 @@
-import { formatName } from "./lib/utils.js";
-import { DEFAULT_PREFIX } from "./lib/constants.js";
+import { formatName } from "./lib/utils.mjs";
+import { DEFAULT_PREFIX } from "./lib/constants.mjs";
 function createMessage(name) {
     return DEFAULT_PREFIX + formatName(name);
 }
@@ -0,0 +1,5 @@
+import './button.css';
+
+export function createButton(text: string) {
+  return `<button class="farm-button">${text}</button>`;
+}
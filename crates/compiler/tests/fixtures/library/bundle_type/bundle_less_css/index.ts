@@ -0,0 +1 @@
+export { createButton } from './components/button';
@@ -0,0 +1,9 @@
+import { message } from './dep';
+
+export function render() {
+  return message;
+}
+
+if (import.meta.hot) {
+  import.meta.hot.accept('./dep');
+}
@@ -0,0 +1,3 @@
+import { render } from './app';
+
+console.log(render());
@@ -213,3 +213,47 @@ fn library_bundle_type_test() {
     test_bundle_type
   );
 }
+
+/// Relative imports are rewritten to the emitted file of the same format in bundle-less mode
+#[test]
+fn library_bundle_less_specifiers() {
+  use common::try_merge_config_file;
+  use farmfe_testing_helpers::fixture;
+
+  fixture!(
+    "tests/fixtures/library/bundle_less_specifiers/main.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap().to_path_buf();
+      let compiler = create_compiler_with_args(cwd.clone(), crate_path, |mut config, plugins| {
+        config.mode = Mode::Development;
+        config.minify = Box::new(BoolOrObj::Bool(false));
+        config.tree_shaking = Box::new(BoolOrObj::Bool(false));
+        config.input =
+          HashMap::from_iter([("main".to_string(), file.to_string_lossy().to_string())]);
+        config = try_merge_config_file(config, cwd.join("config.json"));
+
+        (config, plugins)
+      });
+
+      compiler.compile().unwrap();
+
+      let resources_map = compiler.context().resources_map.lock();
+      let code =
+        |name: &str| String::from_utf8_lossy(&resources_map.get(name).unwrap().bytes).to_string();
+
+      let main_esm = code("main.mjs");
+      assert!(main_esm.contains("\"./lib/utils.mjs\""));
+      // `./components` is a directory import of `components/index.tsx`
+      assert!(main_esm.contains("\"./components/index.mjs\""));
+      assert!(!main_esm.contains(".cjs\""));
+
+      let main_cjs = code("main.cjs");
+      assert!(main_cjs.contains("\"./lib/utils.cjs\""));
+      assert!(main_cjs.contains("\"./components/index.cjs\""));
+      assert!(!main_cjs.contains(".mjs\""));
+
+      assert!(code("components/index.mjs").contains("\"../lib/utils.mjs\""));
+      assert!(code("components/index.cjs").contains("\"../lib/utils.cjs\""));
+    }
+  );
+}
@@ -414,3 +414,50 @@ fn update_lazy_compilation_node() {
     }
   );
 }
+
+#[test]
+fn update_node() {
+  fixture!("tests/fixtures/update/node/index.ts", |file, crate_path| {
+    let cwd = file.parent().unwrap().to_path_buf();
+    let compiler = create_compiler_internal(
+      HashMap::from_iter([("index".to_string(), "./index.ts".to_string())]),
+      cwd.clone(),
+      crate_path,
+      false,
+      false,
+      TargetEnv::Node,
+    );
+
+    compiler.compile().unwrap();
+
+    // dep.ts is accepted by app.ts, so the update is applied inside the process
+    let update_file = cwd.join("dep.ts").to_string_lossy().to_string();
+    let result = compiler
+      .update(vec![(update_file, UpdateType::Updated)], || {}, true, true)
+      .unwrap();
+
+    assert_eq!(result.updated_module_ids, vec!["dep.ts".into()]);
+    assert!(result.full_reload_reason.is_none());
+    let node_update = result.node_update.unwrap();
+    assert!(!node_update.restart);
+    assert_eq!(
+      node_update.invalidated_module_ids,
+      vec!["app.ts".to_string(), "dep.ts".to_string()]
+    );
+    assert!(node_update.code.contains("\"dep.ts\""));
+    assert!(!node_update.code.contains("\"app.ts\""));
+    assert!(node_update.code.contains("._rg=true;"));
+
+    // index.ts is an entry that does not accept its updates, the process should be restarted
+    let update_file = cwd.join("index.ts").to_string_lossy().to_string();
+    let result = compiler
+      .update(vec![(update_file, UpdateType::Updated)], || {}, true, true)
+      .unwrap();
+
+    assert!(result.full_reload_reason.is_some());
+    let node_update = result.node_update.unwrap();
+    assert!(node_update.restart);
+    assert!(node_update.code.is_empty());
+    assert!(node_update.invalidated_module_ids.is_empty());
+  });
+}
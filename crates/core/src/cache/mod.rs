@@ -4,7 +4,7 @@ use parking_lot::Mutex;
 use store::{
   constant::{CacheStoreFactory, CacheStoreTrait},
   memory::MemoryCacheFactory,
-  DiskCacheFactory,
+  CacheStoreLimits, DiskCacheFactory,
 };
 
 use crate::config::Mode;
@@ -45,6 +45,7 @@ pub enum CacheType {
     cache_dir: String,
     namespace: String,
     mode: Mode,
+    limits: CacheStoreLimits,
   },
 }
 
@@ -56,7 +57,8 @@ impl CacheType {
         cache_dir,
         namespace,
         mode,
-      } => Box::new(DiskCacheFactory::new(cache_dir, namespace, *mode)),
+        limits,
+      } => Box::new(DiskCacheFactory::new(cache_dir, namespace, *mode, *limits)),
     }
   }
 }
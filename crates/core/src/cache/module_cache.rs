@@ -1,6 +1,8 @@
 use std::sync::Arc;
 
 use dashmap::mapref::one::{Ref, RefMut};
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 
 use farmfe_macro_cache_item::cache_item;
 pub use module_metadata::ModuleMetadataStore;
@@ -8,7 +10,7 @@ pub use module_metadata::ModuleMetadataStore;
 use crate::cache::scope::{CacheScopeStore, IdType};
 use crate::module::module_graph::ModuleGraphEdge;
 use crate::module::{Module, ModuleId};
-use crate::plugin::PluginAnalyzeDepsHookResultEntry;
+use crate::plugin::{PluginAnalyzeDepsHookResultEntry, PluginResolveHookResult, ResolveKind};
 use crate::Cacheable;
 
 use immutable_modules::ImmutableModulesMemoryStore;
@@ -28,6 +30,24 @@ pub struct ModuleCacheManager {
   pub immutable_modules_store: ImmutableModulesMemoryStore,
   context: Arc<CacheContext>,
   scope: CacheScopeStore,
+  /// Why the cache of the module is invalidated in this compilation, used to explain the cache miss
+  invalidated_reasons: DashMap<ModuleId, ModuleCacheStatus>,
+}
+
+/// Whether the module is restored from the persistent cache, and why not if it's missed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ModuleCacheStatus {
+  /// the module is restored from the cache
+  Hit,
+  /// there is no cache of the module
+  MissNew,
+  /// the timestamp or content hash of the module changed
+  MissContentChanged,
+  /// the watch dependencies of the module changed, or its imports are resolved to different modules
+  MissDepChanged,
+  /// the cache is invalidated by plugins or `compiler.invalidateModule`
+  MissInvalidated,
 }
 
 #[cache_item]
@@ -35,6 +55,37 @@ pub struct ModuleCacheManager {
 pub struct CachedModuleDependency {
   pub dependency: ModuleId,
   pub edge_info: ModuleGraphEdge,
+  /// results of the resolve hook for each source of the edge, used to check whether the dependency is still resolved to the same module
+  pub resolve_results: Vec<CachedResolveResult>,
+}
+
+#[cache_item]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedResolveResult {
+  pub source: String,
+  pub kind: ResolveKind,
+  pub resolved_path: String,
+  pub external: bool,
+  pub side_effects: bool,
+}
+
+impl CachedResolveResult {
+  pub fn new(source: String, kind: ResolveKind, result: &PluginResolveHookResult) -> Self {
+    Self {
+      source,
+      kind,
+      resolved_path: result.resolved_path.clone(),
+      external: result.external,
+      side_effects: result.side_effects,
+    }
+  }
+
+  /// true if `result` resolves the source to the same module as the cached one
+  pub fn is_same_resolution(&self, result: &PluginResolveHookResult) -> bool {
+    self.resolved_path == result.resolved_path
+      && self.external == result.external
+      && self.side_effects == result.side_effects
+  }
 }
 
 #[cache_item]
@@ -99,6 +150,7 @@ impl ModuleCacheManager {
       immutable_modules_store: ImmutableModulesMemoryStore::new(context.clone()),
       scope: CacheScopeStore::new(context.clone()),
       context,
+      invalidated_reasons: DashMap::new(),
     }
   }
 
@@ -115,6 +167,8 @@ impl ModuleCacheManager {
   }
 
   pub fn set_cache(&self, key: ModuleId, module: CachedModule) {
+    self.invalidated_reasons.remove(&key);
+
     if module.module.immutable {
       self.immutable_modules_store.set_cache(key, module);
     } else {
@@ -182,11 +236,24 @@ impl ModuleCacheManager {
   }
 
   pub fn invalidate_cache(&self, key: &ModuleId) {
+    self.invalidate_cache_with_reason(key, ModuleCacheStatus::MissInvalidated);
+  }
+
+  /// Invalidate the cache and record the reason, the reason is returned by [ModuleCacheManager::invalidated_reason] until the cache is set again
+  pub fn invalidate_cache_with_reason(&self, key: &ModuleId, reason: ModuleCacheStatus) {
+    if self.has_cache(key) {
+      self.invalidated_reasons.insert(key.clone(), reason);
+    }
+
     self.mutable_modules_store.invalidate_cache(key);
     self.immutable_modules_store.invalidate_cache(key);
     self.scope.remove_by_reference(&key.to_string());
   }
 
+  pub fn invalidated_reason(&self, key: &ModuleId) -> Option<ModuleCacheStatus> {
+    self.invalidated_reasons.get(key).map(|reason| *reason)
+  }
+
   pub fn cache_outdated(&self, key: &ModuleId) -> bool {
     self.mutable_modules_store.cache_outdated(key)
       || self.immutable_modules_store.cache_outdated(key)
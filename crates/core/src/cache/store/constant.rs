@@ -17,8 +17,10 @@ pub trait CacheStoreTrait: Send + Sync {
   fn shutdown(&self) {}
 }
 
-pub const FARM_CACHE_VERSION: &str = "0.9.1";
+pub const FARM_CACHE_VERSION: &str = "0.9.2";
 pub const FARM_CACHE_MANIFEST_FILE: &str = "farm-cache.json";
+/// last access time of the cache entries, used to prune the entries by `persistentCache.maxAge`
+pub const FARM_CACHE_ACCESS_FILE: &str = "farm-cache-access.json";
 
 pub trait CacheStoreFactory: Send + Sync {
   fn create_cache_store(&self, name: &str) -> Box<dyn CacheStoreTrait>;
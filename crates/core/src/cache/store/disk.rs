@@ -12,8 +12,12 @@ use parking_lot::{lock_api::ArcMutexGuard, Mutex};
 use rayon::iter::{IntoParallelIterator, ParallelBridge, ParallelIterator};
 
 use super::{
-  constant::{CacheStoreFactory, CacheStoreTrait, FARM_CACHE_MANIFEST_FILE, FARM_CACHE_VERSION},
+  constant::{
+    CacheStoreFactory, CacheStoreTrait, FARM_CACHE_ACCESS_FILE, FARM_CACHE_MANIFEST_FILE,
+    FARM_CACHE_VERSION,
+  },
   error::CacheError,
+  gc::{evict_cache_dirs, now_millis, CacheStoreLimits},
   namespace::NamespaceStore,
   CacheStoreKey,
 };
@@ -42,16 +46,23 @@ type CombineCacheData = HashMap<CacheStoreKey, Vec<u8>>;
 #[derive(Default)]
 pub struct CacheStore {
   cache_dir: PathBuf,
+  /// directory that contains the caches of all build dependencies and envs, e.g. `node_modules/.farm/cache`
+  cache_root: PathBuf,
+  /// cache directory of current build dependencies and envs under [Self::cache_root]
+  active_dir: PathBuf,
   /// name -> cache key manifest of this store.
   /// it will be stored in a separate file
   manifest: DashMap<String, String>,
   data: DashMap<String, Vec<u8>>,
   restored: DashSet<u8>,
   resource_lock: ResourceLock<String>,
+  limits: CacheStoreLimits,
+  /// name -> last time in milliseconds the cache is read or written, only tracked when `max_age` is set
+  last_access: DashMap<String, u64>,
 }
 
 impl CacheStore {
-  pub fn new(cache_dir_str: &str, namespace: &str, mode: Mode) -> Self {
+  pub fn new(cache_dir_str: &str, namespace: &str, mode: Mode, limits: CacheStoreLimits) -> Self {
     let mut cache_dir = Path::new(cache_dir_str).to_path_buf();
     let last = cache_dir
       .file_name()
@@ -59,8 +70,10 @@ impl CacheStore {
       .to_string_lossy()
       .to_string();
     cache_dir.pop();
+    let cache_root = cache_dir.clone();
 
     cache_dir.push(format!("{FARM_CACHE_VERSION}-{last}"));
+    let active_dir = cache_dir.clone();
 
     if !namespace.is_empty() {
       cache_dir.push(namespace);
@@ -84,13 +97,59 @@ impl CacheStore {
       DashMap::new()
     };
 
+    let last_access = DashMap::new();
+
+    if limits.max_age.is_some() {
+      let access_file_path = cache_dir.join(FARM_CACHE_ACCESS_FILE);
+      let mut access = std::fs::read_to_string(access_file_path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<HashMap<String, u64>>(&content).ok())
+        .unwrap_or_default();
+      let now = now_millis();
+
+      for item in manifest.iter() {
+        let time = access.remove(item.key()).unwrap_or(now);
+        last_access.insert(item.key().clone(), time);
+      }
+    }
+
     Self {
       cache_dir,
+      cache_root,
+      active_dir,
       manifest,
+      limits,
+      last_access,
       ..Default::default()
     }
   }
 
+  fn touch(&self, name: &str) {
+    if self.limits.max_age.is_some() {
+      self.last_access.insert(name.to_string(), now_millis());
+    }
+  }
+
+  /// Remove the entries that are not used in `max_age`, for example, caches of deleted modules
+  fn prune_expired_entries(&self) {
+    let Some(max_age) = self.limits.max_age else {
+      return;
+    };
+
+    let now = now_millis();
+    let expired_names = self
+      .last_access
+      .iter()
+      .filter(|item| now.saturating_sub(*item.value()) > max_age)
+      .map(|item| item.key().clone())
+      .collect::<Vec<_>>();
+
+    for name in expired_names {
+      self._remove_cache(&name, false);
+      self.last_access.remove(&name);
+    }
+  }
+
   fn hash_index_from_name(&self, name: &str) -> u8 {
     sha256(name.as_bytes(), 32)
       .chars()
@@ -151,6 +210,7 @@ impl CacheStore {
     }
 
     let manifest_item = self.manifest.get(name)?;
+    self.touch(name);
 
     self.data.get(manifest_item.value()).map(|v| v.map(|v| v))
   }
@@ -186,7 +246,9 @@ impl CacheStore {
         self.restore_cache_by_hash(hash, real_cache_path);
       });
 
-    self
+    self.prune_expired_entries();
+
+    let combine_data = self
       .manifest
       .iter()
       .par_bridge()
@@ -196,7 +258,8 @@ impl CacheStore {
           let name = item.key();
           let key = item.value();
 
-          let Some(value) = self.try_read_content(name) else {
+          // all shards are restored above, read the data directly so the last access time is not refreshed
+          let Some(value) = self.data.get(key).map(|v| v.value().clone()) else {
             return combine_data;
           };
 
@@ -216,7 +279,18 @@ impl CacheStore {
         }
 
         a
-      })
+      });
+
+    // shards that no longer contain any entry only hold stale caches
+    for hash in 0..16u8 {
+      let shard_path = self.join_hash(hash);
+
+      if !combine_data.contains_key(&hash) && shard_path.exists() {
+        let _ = std::fs::remove_file(shard_path);
+      }
+    }
+
+    combine_data
       .into_par_iter()
       .for_each(|(cache_file_path, data)| {
         let data = serialize!(&data);
@@ -249,6 +323,7 @@ impl CacheStore {
   fn insert_cache(&self, name: &str, key: &str, data: Vec<u8>) {
     self.manifest.insert(name.to_string(), key.to_string());
     self.data.insert(key.to_string(), data);
+    self.touch(name);
   }
 
   fn write_manifest(&self) {
@@ -264,6 +339,19 @@ impl CacheStore {
       serde_json::to_string(&manifest).unwrap(),
     )
     .unwrap();
+
+    if self.limits.max_age.is_some() {
+      let last_access = self
+        .last_access
+        .clone()
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+      std::fs::write(
+        self.cache_dir.join(FARM_CACHE_ACCESS_FILE),
+        serde_json::to_string(&last_access).unwrap(),
+      )
+      .unwrap();
+    }
   }
 }
 
@@ -311,6 +399,7 @@ impl CacheStoreTrait for CacheStore {
   fn shutdown(&self) {
     self.write_disk();
     self.write_manifest();
+    evict_cache_dirs(&self.cache_root, &self.active_dir, self.limits);
   }
 }
 
@@ -319,9 +408,10 @@ pub struct DiskCacheFactory {
 }
 
 impl DiskCacheFactory {
-  pub fn new(cache_dir: &str, namespace: &str, mode: Mode) -> Self {
-    let store: Arc<Box<dyn CacheStoreTrait>> =
-      Arc::new(Box::new(CacheStore::new(cache_dir, namespace, mode)));
+  pub fn new(cache_dir: &str, namespace: &str, mode: Mode, limits: CacheStoreLimits) -> Self {
+    let store: Arc<Box<dyn CacheStoreTrait>> = Arc::new(Box::new(CacheStore::new(
+      cache_dir, namespace, mode, limits,
+    )));
 
     Self { store }
   }
@@ -425,3 +515,52 @@ impl CacheStoreFactory for DiskCacheFactory {
     }
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use std::time::Duration;
+
+  use super::{CacheStore, CacheStoreLimits, CacheStoreTrait};
+  use crate::config::Mode;
+
+  #[test]
+  fn prune_expired_entries_across_writes() {
+    let root = std::env::temp_dir().join(format!("farm-cache-disk-max-age-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    let cache_dir = root.join("farm-cache");
+    let create_store = || {
+      CacheStore::new(
+        cache_dir.to_str().unwrap(),
+        "",
+        Mode::Development,
+        CacheStoreLimits {
+          max_size: None,
+          max_age: Some(300),
+        },
+      )
+    };
+
+    let store = create_store();
+    store
+      .write_single_cache(("a", "a1").into(), vec![1])
+      .unwrap();
+    store
+      .write_single_cache(("b", "b1").into(), vec![2])
+      .unwrap();
+    store.shutdown();
+
+    // `a` is not used by the following builds, writing the cache should not refresh its last access time
+    for _ in 0..2 {
+      std::thread::sleep(Duration::from_millis(200));
+      let store = create_store();
+      assert_eq!(store.read_cache("b"), Some(vec![2]));
+      store.shutdown();
+    }
+
+    let store = create_store();
+    assert!(!store.has_cache("a"));
+    assert_eq!(store.read_cache("b"), Some(vec![2]));
+
+    std::fs::remove_dir_all(&root).unwrap();
+  }
+}
@@ -0,0 +1,240 @@
+//! Garbage collection of the disk cache.
+//!
+//! Every change of build dependencies or envs creates a new cache directory like `node_modules/.farm/cache/0.9.2-<hash>`.
+//! Directories that are not used by current build are evicted by `persistentCache.maxAge` and `persistentCache.maxSize`
+//! in least recently used order.
+
+use std::{
+  fs,
+  path::{Path, PathBuf},
+  time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Limits of the disk cache, configured by `persistentCache.maxSize` and `persistentCache.maxAge`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStoreLimits {
+  /// max size in bytes of all cache directories
+  pub max_size: Option<u64>,
+  /// max age in milliseconds of cache directories and cache entries
+  pub max_age: Option<u64>,
+}
+
+impl CacheStoreLimits {
+  pub fn is_empty(&self) -> bool {
+    self.max_size.is_none() && self.max_age.is_none()
+  }
+}
+
+pub fn now_millis() -> u64 {
+  SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map(|d| d.as_millis() as u64)
+    .unwrap_or_default()
+}
+
+struct CacheDirInfo {
+  path: PathBuf,
+  size: u64,
+  last_used: u64,
+}
+
+/// Remove the cache directories under `cache_root` that exceed the limits, `active_dir` is used by current build and never removed.
+/// Returns the removed directories.
+pub fn evict_cache_dirs(
+  cache_root: &Path,
+  active_dir: &Path,
+  limits: CacheStoreLimits,
+) -> Vec<PathBuf> {
+  if limits.is_empty() {
+    return vec![];
+  }
+
+  let Ok(entries) = fs::read_dir(cache_root) else {
+    return vec![];
+  };
+
+  let mut active_size = 0;
+  let mut dirs = vec![];
+
+  for entry in entries.flatten() {
+    let path = entry.path();
+
+    if !path.is_dir() || !is_cache_dir_name(&entry.file_name().to_string_lossy()) {
+      continue;
+    }
+
+    let (size, last_used) = get_dir_size_and_last_used(&path);
+
+    if path == active_dir {
+      active_size = size;
+    } else {
+      dirs.push(CacheDirInfo {
+        path,
+        size,
+        last_used,
+      });
+    }
+  }
+
+  // least recently used first
+  dirs.sort_by_key(|dir| dir.last_used);
+
+  let now = now_millis();
+  let mut total_size = active_size + dirs.iter().map(|dir| dir.size).sum::<u64>();
+  let mut removed = vec![];
+
+  for dir in dirs {
+    let expired = limits
+      .max_age
+      .is_some_and(|max_age| now.saturating_sub(dir.last_used) > max_age);
+    let oversized = limits
+      .max_size
+      .is_some_and(|max_size| total_size > max_size);
+
+    if (expired || oversized) && fs::remove_dir_all(&dir.path).is_ok() {
+      total_size -= dir.size;
+      removed.push(dir.path);
+    }
+  }
+
+  removed
+}
+
+/// cache directories are named as `{FARM_CACHE_VERSION}-{hash}`, other files under the cache root are not touched
+fn is_cache_dir_name(name: &str) -> bool {
+  let Some((version, hash)) = name.split_once('-') else {
+    return false;
+  };
+
+  !hash.is_empty()
+    && version
+      .split('.')
+      .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// total size of the files in the directory and the latest modified time of them in milliseconds
+fn get_dir_size_and_last_used(dir: &Path) -> (u64, u64) {
+  let mut size = 0;
+  let mut last_used = 0;
+  let mut stack = vec![dir.to_path_buf()];
+
+  while let Some(dir) = stack.pop() {
+    let Ok(entries) = fs::read_dir(&dir) else {
+      continue;
+    };
+
+    for entry in entries.flatten() {
+      let Ok(meta) = entry.metadata() else {
+        continue;
+      };
+
+      if meta.is_dir() {
+        stack.push(entry.path());
+        continue;
+      }
+
+      size += meta.len();
+
+      if let Ok(modified) = meta.modified()
+        && let Ok(dur) = modified.duration_since(UNIX_EPOCH)
+      {
+        last_used = last_used.max(dur.as_millis() as u64);
+      }
+    }
+  }
+
+  (size, last_used)
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{fs, path::PathBuf, time::Duration};
+
+  use super::{evict_cache_dirs, is_cache_dir_name, CacheStoreLimits};
+
+  fn create_cache_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("farm-cache-gc-{name}-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&root);
+    fs::create_dir_all(&root).unwrap();
+    root
+  }
+
+  fn create_cache_dir(root: &PathBuf, name: &str, size: usize) -> PathBuf {
+    let dir = root.join(name).join("farm-cache").join("development");
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("cache-0"), vec![0u8; size]).unwrap();
+    root.join(name)
+  }
+
+  #[test]
+  fn cache_dir_name() {
+    assert!(is_cache_dir_name("0.9.2-1a2b3c"));
+    assert!(!is_cache_dir_name("0.9.2"));
+    assert!(!is_cache_dir_name("0.9.2-"));
+    assert!(!is_cache_dir_name("vite-1a2b3c"));
+  }
+
+  #[test]
+  fn evict_by_max_size() {
+    let root = create_cache_root("max-size");
+    let oldest = create_cache_dir(&root, "0.9.2-a", 100);
+    std::thread::sleep(Duration::from_millis(20));
+    let older = create_cache_dir(&root, "0.9.2-b", 100);
+    std::thread::sleep(Duration::from_millis(20));
+    let active = create_cache_dir(&root, "0.9.2-c", 100);
+    fs::write(root.join("other-file"), vec![0u8; 1000]).unwrap();
+
+    let removed = evict_cache_dirs(
+      &root,
+      &active,
+      CacheStoreLimits {
+        max_size: Some(250),
+        max_age: None,
+      },
+    );
+
+    assert_eq!(removed, vec![oldest.clone()]);
+    assert!(!oldest.exists());
+    assert!(older.exists());
+    assert!(active.exists());
+    assert!(root.join("other-file").exists());
+
+    // the active cache directory is never removed even if it exceeds the max size
+    evict_cache_dirs(
+      &root,
+      &active,
+      CacheStoreLimits {
+        max_size: Some(10),
+        max_age: None,
+      },
+    );
+    assert!(!older.exists());
+    assert!(active.exists());
+
+    fs::remove_dir_all(&root).unwrap();
+  }
+
+  #[test]
+  fn evict_by_max_age() {
+    let root = create_cache_root("max-age");
+    let expired = create_cache_dir(&root, "0.9.2-a", 10);
+    std::thread::sleep(Duration::from_millis(200));
+    let fresh = create_cache_dir(&root, "0.9.2-b", 10);
+    let active = create_cache_dir(&root, "0.9.2-c", 10);
+
+    let removed = evict_cache_dirs(
+      &root,
+      &active,
+      CacheStoreLimits {
+        max_size: None,
+        max_age: Some(100),
+      },
+    );
+
+    assert_eq!(removed, vec![expired]);
+    assert!(fresh.exists());
+    assert!(active.exists());
+
+    fs::remove_dir_all(&root).unwrap();
+  }
+}
@@ -3,10 +3,12 @@
 pub mod constant;
 mod disk;
 mod error;
+mod gc;
 pub mod memory;
 pub mod namespace;
 
 pub use disk::*;
+pub use gc::CacheStoreLimits;
 use farmfe_macro_cache_item::cache_item;
 
 /// Cache key of the store, it's a pair of (name, cache_key), a name should only be related to one cache key.
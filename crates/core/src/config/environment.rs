@@ -0,0 +1,124 @@
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+
+use super::{config_regex::ConfigRegex, persistent_cache::PersistentCacheConfig, Config};
+use crate::{
+  error::{CompilationError, Result},
+  HashMap,
+};
+
+/// Overrides of the top level config for a named environment, for example a `ssr` environment of a client app:
+///
+/// ```json
+/// {
+///   "input": { "server": "./src/entry-server.ts" },
+///   "output": { "targetEnv": "node", "path": "dist/server" },
+///   "resolve": { "conditions": ["node"] }
+/// }
+/// ```
+///
+/// `output` and `resolve` are merged deeply into the top level config, other fields replace the top level ones,
+/// except `define` which is merged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EnvironmentConfig {
+  pub input: Option<HashMap<String, String>>,
+  pub output: Option<Value>,
+  pub resolve: Option<Value>,
+  pub external: Option<Vec<ConfigRegex>>,
+  pub define: HashMap<String, Value>,
+}
+
+impl EnvironmentConfig {
+  /// Create the config of environment `name` from the top level `config`
+  pub fn to_config(&self, name: &str, config: &Config) -> Result<Config> {
+    let mut env_config = config.clone();
+    env_config.environments = HashMap::default();
+
+    if let Some(input) = &self.input {
+      env_config.input = input.clone();
+    }
+
+    if let Some(output) = &self.output {
+      env_config.output = Box::new(merge_value(name, &*config.output, output)?);
+    }
+
+    if let Some(resolve) = &self.resolve {
+      env_config.resolve = Box::new(merge_value(name, &*config.resolve, resolve)?);
+    }
+
+    if let Some(external) = &self.external {
+      env_config.external = external.clone();
+    }
+
+    env_config.define.extend(self.define.clone());
+
+    // environments compile the same modules differently, so their caches must not be shared
+    if config.persistent_cache.enabled() {
+      let mut cache_config = config.persistent_cache.as_obj(&config.root);
+      cache_config.namespace = format!("{}-{name}", cache_config.namespace);
+      env_config.persistent_cache = Box::new(PersistentCacheConfig::Obj(cache_config));
+    }
+
+    Ok(env_config)
+  }
+}
+
+fn merge_value<T: Serialize + DeserializeOwned>(
+  name: &str,
+  base: &T,
+  overrides: &Value,
+) -> Result<T> {
+  let to_error = |e: serde_json::Error| {
+    CompilationError::GenericError(format!("invalid config of environment `{name}`: {e}"))
+  };
+  let mut value = serde_json::to_value(base).map_err(to_error)?;
+  merge(&mut value, overrides);
+
+  serde_json::from_value(value).map_err(to_error)
+}
+
+fn merge(base: &mut Value, overrides: &Value) {
+  match (base, overrides) {
+    (Value::Object(base), Value::Object(overrides)) => {
+      for (key, value) in overrides {
+        merge(base.entry(key.clone()).or_insert(Value::Null), value);
+      }
+    }
+    (base, overrides) => *base = overrides.clone(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use serde_json::json;
+
+  use super::EnvironmentConfig;
+  use crate::config::{persistent_cache::PersistentCacheConfig, Config, TargetEnv};
+
+  #[test]
+  fn to_config() {
+    let config = Config {
+      persistent_cache: Box::new(PersistentCacheConfig::Bool(false)),
+      ..Default::default()
+    };
+    let env: EnvironmentConfig = serde_json::from_value(json!({
+      "input": { "server": "./src/entry-server.ts" },
+      "output": { "targetEnv": "node", "path": "dist/server" },
+      "resolve": { "conditions": ["node"] },
+      "define": { "SSR": true }
+    }))
+    .unwrap();
+
+    let env_config = env.to_config("ssr", &config).unwrap();
+
+    assert_eq!(env_config.input.len(), 1);
+    assert!(matches!(env_config.output.target_env, TargetEnv::Node));
+    assert_eq!(env_config.output.path, "dist/server");
+    // not overridden fields are kept
+    assert_eq!(env_config.output.public_path, config.output.public_path);
+    assert_eq!(env_config.resolve.conditions, vec!["node".to_string()]);
+    assert_eq!(env_config.resolve.extensions, config.resolve.extensions);
+    assert_eq!(env_config.define.get("SSR"), Some(&json!(true)));
+  }
+}
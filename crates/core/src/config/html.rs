@@ -1,7 +1,46 @@
 use serde::{Deserialize, Serialize};
 
+use crate::HashMap;
+
+pub const DEFAULT_CSP_NONCE_PLACEHOLDER: &str = "__FARM_CSP_NONCE__";
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase", default)]
 pub struct HtmlConfig {
   pub base: Option<String>,
+  /// Template data of each html input, key is the input name. For example, `{ index: { title: "Home" } }`
+  /// makes `%title%` of the `index` input replaced by `Home`.
+  /// Html templates are only rendered when it is set, `{}` renders the env and `define` only.
+  pub template_data: Option<HashMap<String, serde_json::Value>>,
+  /// Make the output html work under a strict Content-Security-Policy
+  pub csp: Option<HtmlCspConfig>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum HtmlCspConfig {
+  /// Add `nonce` attribute to all scripts and styles, the placeholder should be replaced by the server for each request
+  Nonce {
+    #[serde(default = "default_csp_nonce_placeholder")]
+    placeholder: String,
+  },
+  /// Compute sha256 hashes of all inline scripts and styles
+  Hash {
+    #[serde(default)]
+    output: HtmlCspHashOutput,
+  },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum HtmlCspHashOutput {
+  /// Inject `<meta http-equiv="Content-Security-Policy">` into the html
+  #[default]
+  Meta,
+  /// Emit a `[name].csp.json` file next to the html, the server should send it as headers
+  Json,
+}
+
+fn default_csp_nonce_placeholder() -> String {
+  DEFAULT_CSP_NONCE_PLACEHOLDER.to_string()
 }
@@ -6,8 +6,9 @@ use swc_ecma_parser::{EsSyntax as EsConfig, TsSyntax as TsConfig};
 use tree_shaking::TreeShakingConfig;
 
 use self::{
-  bool_or_obj::BoolOrObj, comments::CommentsConfig, config_regex::ConfigRegex, html::HtmlConfig,
-  partial_bundling::PartialBundlingConfig, preset_env::PresetEnvConfig, script::ScriptConfig,
+  bool_or_obj::BoolOrObj, comments::CommentsConfig, config_regex::ConfigRegex,
+  environment::EnvironmentConfig, html::HtmlConfig, partial_bundling::PartialBundlingConfig,
+  preset_env::PresetEnvConfig, rsc::RscConfig, script::ScriptConfig,
 };
 
 use crate::HashMap;
@@ -26,6 +27,7 @@ pub mod comments;
 pub mod config_regex;
 pub mod css;
 pub mod custom;
+pub mod environment;
 pub mod external;
 pub mod html;
 pub mod minify;
@@ -33,6 +35,7 @@ mod output;
 pub mod partial_bundling;
 pub mod persistent_cache;
 pub mod preset_env;
+pub mod rsc;
 pub mod script;
 pub mod tree_shaking;
 
@@ -88,6 +91,11 @@ pub struct Config {
   pub comments: Box<CommentsConfig>,
   /// preserved for future compatibility usage when there are more config options
   pub custom: Box<HashMap<String, String>>,
+  /// Named environments compiled together with the top level config, for example `ssr` for a client app.
+  /// Each environment is compiled by its own compiler with its own module graph and output, only the plugins are shared
+  pub environments: HashMap<String, EnvironmentConfig>,
+  /// React Server Components support, the environment with `react-server` in `resolve.conditions` is the server environment
+  pub rsc: Box<BoolOrObj<RscConfig>>,
 }
 
 impl Default for Config {
@@ -126,6 +134,8 @@ impl Default for Config {
       concatenate_modules: false,
       comments: Box::default(),
       custom: Box::<HashMap<String, String>>::default(),
+      environments: HashMap::default(),
+      rsc: Box::new(BoolOrObj::Bool(false)),
     }
   }
 }
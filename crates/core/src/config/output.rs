@@ -131,6 +131,36 @@ pub enum LibraryBundleType {
   BundleLess,
 }
 
+/// Generate the `exports`, `main`, `module` and `types` fields of `package.json` from the library output
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum PackageExportsConfig {
+  /// Emit a `package.json` to `output.path`, based on the `package.json` of root if exists
+  #[serde(rename = "emit")]
+  Emit,
+  /// Update the fields of the `package.json` of root in place
+  #[serde(rename = "patch")]
+  Patch,
+  /// `true` is the same as `emit`
+  #[serde(untagged)]
+  Bool(bool),
+}
+
+impl Default for PackageExportsConfig {
+  fn default() -> Self {
+    Self::Bool(false)
+  }
+}
+
+impl PackageExportsConfig {
+  pub fn enabled(&self) -> bool {
+    !matches!(self, Self::Bool(false))
+  }
+
+  pub fn is_patch(&self) -> bool {
+    matches!(self, Self::Patch)
+  }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase", default)]
 pub struct OutputConfig {
@@ -143,6 +173,12 @@ pub struct OutputConfig {
   pub format: ModuleFormatConfig,
   pub show_file_size: bool,
   pub library_bundle_type: LibraryBundleType,
+  /// the source root of bundle-less library output, each module is emitted to its path relative to `src_dir`.
+  /// If not set, the common parent dir of `input` will be used
+  pub src_dir: String,
+  /// generate `package.json` exports from the entries, formats and declarations of the library output.
+  /// Only works when `target_env` is `library`
+  pub package_exports: PackageExportsConfig,
   pub ascii_only: bool,
   /// external globals name, for example, if you set `external_globals: {"react": "React"}`,
   /// if you use `import * as React from 'react'`, you can access `React` from `window.React`
@@ -166,6 +202,8 @@ impl Default for OutputConfig {
       format: ModuleFormatConfig::default(),
       show_file_size: true,
       library_bundle_type: Default::default(),
+      src_dir: "".to_string(),
+      package_exports: Default::default(),
       ascii_only: false,
       external_globals: HashMap::default(),
       name: "__farm_global__".to_string(),
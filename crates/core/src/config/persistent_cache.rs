@@ -52,7 +52,10 @@ impl PersistentCacheConfig {
       // build dependencies are set by node side
       build_dependencies: vec![],
       envs: HashMap::default(),
+      max_size: None,
+      max_age: None,
       memory: false,
+      build_dependency_hash_inputs: vec![],
     })
   }
 
@@ -101,15 +104,23 @@ impl PersistentCacheConfig {
             let mut content = String::new();
 
             for dep in &cloned_obj.build_dependencies {
-              if !PathBuf::from(dep).exists()
-                || !PathBuf::from(dep).is_file()
-                || dep.ends_with(".farm")
-              {
-                content.push_str(dep);
+              let is_file = PathBuf::from(dep).exists()
+                && PathBuf::from(dep).is_file()
+                && !dep.ends_with(".farm");
+              let c = if is_file {
+                std::fs::read_to_string(dep).unwrap()
               } else {
-                let c = std::fs::read_to_string(dep).unwrap();
-                content.push_str(&c);
-              }
+                dep.clone()
+              };
+
+              content.push_str(&c);
+              cloned_obj
+                .build_dependency_hash_inputs
+                .push(PersistentCacheBuildDependencyInput {
+                  dependency: dep.clone(),
+                  is_file,
+                  hash: sha256(c.as_bytes(), 32),
+                });
             }
 
             let hash = sha256(content.as_bytes(), 32);
@@ -152,8 +163,27 @@ pub struct PersistentCacheConfigObj {
   /// Note that farm will resolve the config file dependencies from node side
   pub build_dependencies: Vec<String>,
   pub envs: HashMap<String, Value>,
+  /// Max size in bytes of the cache directory. When exceeded, caches created by other build dependencies or envs
+  /// are removed in least recently used order. Cache used by current build is never removed.
+  pub max_size: Option<u64>,
+  /// Max age in milliseconds of the caches. Caches created by other build dependencies or envs and cache entries
+  /// of current build(for example, entries of deleted modules) that are not used in this period are removed.
+  pub max_age: Option<u64>,
   #[serde(skip)]
   pub memory: bool,
+  /// The inputs of the hash that selects the cache directory, filled by [PersistentCacheConfig::as_obj]
+  #[serde(skip)]
+  pub build_dependency_hash_inputs: Vec<PersistentCacheBuildDependencyInput>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentCacheBuildDependencyInput {
+  /// path of the build dependency, or the value itself if it's not a file, for example the hash of `envs`
+  pub dependency: String,
+  pub is_file: bool,
+  /// hash of the file content or the value
+  pub hash: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use super::Config;
+
+/// Resolve condition of the react server environment, a compilation with this condition in `resolve.conditions`
+/// compiles the server components, otherwise it compiles the client components
+pub const REACT_SERVER_CONDITION: &str = "react-server";
+
+impl Config {
+  /// Whether this compilation is the react server environment of React Server Components
+  pub fn is_react_server(&self) -> bool {
+    self.rsc.enabled()
+      && self
+        .resolve
+        .conditions
+        .iter()
+        .any(|c| c == REACT_SERVER_CONDITION)
+  }
+}
+
+/// Config of React Server Components, enables `"use client"` and `"use server"` directives handling
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RscConfig {
+  /// the package that implements the server and client references, `{runtime}/server` and `{runtime}/client` are imported
+  pub runtime: String,
+  /// the module that exports `callServer`, which is passed to the server references created in the client environment.
+  /// If not set, `globalThis.__FARM_RSC_CALL_SERVER__` is called
+  pub call_server: Option<String>,
+}
+
+impl Default for RscConfig {
+  fn default() -> Self {
+    Self {
+      runtime: "react-server-dom-webpack".to_string(),
+      call_server: None,
+    }
+  }
+}
@@ -2,15 +2,70 @@ use serde::{Deserialize, Serialize};
 
 use super::config_regex::ConfigRegex;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct TreeShakingConfig {
-  /// exclude some side effects files
+  /// modules or packages that always have side effects, overrides `sideEffects` of package.json
   side_effects: Vec<ConfigRegex>,
+  /// modules or packages that are side effects free, overrides `sideEffects` of package.json
+  no_side_effects: Vec<ConfigRegex>,
+  /// modules to explain why their statements are preserved, the explanations are written to the stats
+  explain: Vec<ConfigRegex>,
 }
 
 impl TreeShakingConfig {
   pub fn is_match(&self, source: &str) -> bool {
     self.side_effects.iter().any(|i| i.is_match(source))
   }
+
+  /// Side effects of the module configured by the user, matched against both the module id and the package name.
+  /// [None] means the module is not configured and the `sideEffects` of package.json should be used.
+  /// If a module is matched by both `sideEffects` and `noSideEffects`, it's treated as side effects.
+  pub fn side_effects_override(&self, module_id: &str, package_name: &str) -> Option<bool> {
+    let is_match = |regexes: &Vec<ConfigRegex>| {
+      regexes
+        .iter()
+        .any(|r| r.is_match(module_id) || r.is_match(package_name))
+    };
+
+    if is_match(&self.side_effects) {
+      Some(true)
+    } else if is_match(&self.no_side_effects) {
+      Some(false)
+    } else {
+      None
+    }
+  }
+
+  pub fn should_explain(&self, module_id: &str) -> bool {
+    self.explain.iter().any(|r| r.is_match(module_id))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn side_effects_override() {
+    let config = TreeShakingConfig {
+      side_effects: vec![ConfigRegex::new("src/polyfill\\.ts$")],
+      no_side_effects: vec![ConfigRegex::new("^lodash-es$"), ConfigRegex::new("src/")],
+      explain: vec![],
+    };
+
+    assert_eq!(
+      config.side_effects_override("src/polyfill.ts", "default"),
+      Some(true)
+    );
+    assert_eq!(
+      config.side_effects_override("src/utils.ts", "default"),
+      Some(false)
+    );
+    assert_eq!(
+      config.side_effects_override("node_modules/lodash-es/map.js", "lodash-es"),
+      Some(false)
+    );
+    assert_eq!(config.side_effects_override("index.ts", "default"), None);
+  }
 }
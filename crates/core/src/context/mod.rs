@@ -11,7 +11,9 @@ use serde::{Deserialize, Serialize};
 use swc_common::{FileName, Globals, SourceFile, SourceMap};
 
 use crate::{
-  cache::{module_cache::MetadataOption, CacheManager, CacheOption, CacheType},
+  cache::{
+    module_cache::MetadataOption, store::CacheStoreLimits, CacheManager, CacheOption, CacheType,
+  },
   config::{persistent_cache::PersistentCacheConfig, Config},
   error::Result,
   module::{
@@ -22,7 +24,7 @@ use crate::{
     resource_pot::ResourcePotId, resource_pot_map::ResourcePotMap, Resource, ResourceOrigin,
     ResourceType,
   },
-  stats::Stats,
+  stats::{PersistentCacheStats, Stats},
   Cacheable, HashMap,
 };
 
@@ -49,6 +51,9 @@ pub fn farm_thread_nums() -> usize {
     .clamp(2.min(cpus), cpus)
 }
 
+/// Custom data shared by the environments of a compilation, see [Config::environments]
+pub type SharedCustomData = Mutex<HashMap<String, Box<dyn Any + Send + Sync>>>;
+
 /// Shared context through the whole compilation.
 pub struct CompilationContext {
   pub config: Box<Config>,
@@ -67,6 +72,9 @@ pub struct CompilationContext {
   pub log_store: Box<Mutex<LogStore>>,
   pub resolve_cache: Box<Mutex<HashMap<PluginResolveHookParam, PluginResolveHookResult>>>,
   pub custom: Box<Mutex<HashMap<String, Box<dyn Any + Send + Sync>>>>,
+  /// Custom data shared with the contexts of the other environments, used by plugins to pass data between the environments.
+  /// `None` if the compilation has only one environment
+  pub shared_custom: Option<Arc<SharedCustomData>>,
   // Keep plugin_driver last so plugin DLLs are unloaded only after all
   // context-owned data structures have been dropped.
   pub plugin_driver: Box<PluginDriver>,
@@ -76,6 +84,19 @@ impl CompilationContext {
   pub fn new(mut config: Config, plugins: Vec<Arc<dyn Plugin>>) -> Result<Self> {
     let cache_type = Self::create_persistent_cache_type(&mut config);
     let record = config.record;
+    let stats = Stats::new();
+
+    if let PersistentCacheConfig::Obj(obj) = &*config.persistent_cache
+      && !obj.memory
+    {
+      stats.set_persistent_cache_stats(PersistentCacheStats {
+        cache_dir: obj.cache_dir.clone(),
+        build_dependencies: obj.build_dependency_hash_inputs.clone(),
+      });
+    }
+
+    let shared_custom = (!config.environments.is_empty()).then(Default::default);
+
     let thread_pool = Arc::new(
       ThreadPoolBuilder::new()
         .num_threads(farm_thread_nums())
@@ -97,10 +118,11 @@ impl CompilationContext {
       thread_pool,
       config: Box::new(config),
       meta: Box::new(ContextMetaData::new()),
-      stats: Box::new(Stats::new()),
+      stats: Box::new(stats),
       log_store: Box::new(Mutex::new(LogStore::new())),
       resolve_cache: Box::new(Mutex::new(HashMap::default())),
       custom: Box::new(Mutex::default()),
+      shared_custom,
       plugin_driver: Box::new(Self::create_plugin_driver(plugins, record)),
     })
   }
@@ -124,12 +146,17 @@ impl CompilationContext {
           .expect("FarmDiskCache should have cache_dir filed, please check your config"),
         cache_config_obj.namespace.clone(),
       );
+      let limits = CacheStoreLimits {
+        max_size: cache_config_obj.max_size,
+        max_age: cache_config_obj.max_age,
+      };
       *config.persistent_cache = PersistentCacheConfig::Obj(cache_config_obj);
 
       CacheType::Disk {
         cache_dir,
         namespace,
         mode: config.mode,
+        limits,
       }
     } else {
       CacheType::Memory {}
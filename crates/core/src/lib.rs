@@ -24,9 +24,9 @@ pub use farmfe_macro_cache_item::cache_item;
 
 /// Version of this core crate, if the core data structures changed,
 /// and the changes will affect the memory layout,
-/// like adding or removing a field, this version should be bumped.
+/// like adding or removing a field or a trait method, this version should be bumped.
 /// So plugin loader can recognize compatibility of the dynamic library plugins and the core.
-pub const VERSION: &str = "0.5.1";
+pub const VERSION: &str = "0.6.0";
 
 // re-export common external crates
 pub use dashmap;
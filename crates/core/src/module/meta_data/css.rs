@@ -10,6 +10,8 @@ use super::{custom::CustomMetaDataMap, script::CommentsMetaData};
 pub struct CssModuleMetaData {
   pub ast: Stylesheet,
   pub comments: CommentsMetaData,
+  /// whether this stylesheet can be replaced as a whole when HMR, without reloading its importers
+  pub hmr_self_accepted: bool,
   pub custom: CustomMetaDataMap,
 }
 
@@ -54,7 +54,7 @@ impl InternalCustomMetaDataMap {
   }
 
   #[inline]
-  fn get_cache<T: Cacheable>(&mut self, key: &str) -> Option<Box<T>> {
+  fn get_cache<T: Cacheable>(&self, key: &str) -> Option<Box<T>> {
     if let Some(v) = self.map.get(key) {
       let bytes = v.serialize_bytes().ok()?;
       return T::deserialize_bytes(bytes).ok()?.downcast::<T>().ok();
@@ -127,7 +127,7 @@ impl CustomMetaDataMap {
     self.internal.get_mut::<T>(key)
   }
 
-  pub fn get_cache<T: Cacheable>(&mut self, key: &str) -> Option<Box<T>> {
+  pub fn get_cache<T: Cacheable>(&self, key: &str) -> Option<Box<T>> {
     self.internal.get_cache(key)
   }
 
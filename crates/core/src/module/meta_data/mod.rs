@@ -4,13 +4,17 @@ use farmfe_macro_cache_item::cache_item;
 use html::HtmlModuleMetaData;
 use script::ScriptModuleMetaData;
 
-use crate::Cacheable;
+use crate::{module::ModuleId, Cacheable};
 
 pub mod css;
 pub mod custom;
 pub mod html;
 pub mod script;
 
+/// Key of [ModuleMetaData::Custom] that declares whether a custom module accepts its own updates when HMR.
+/// The value is a `bool`, see [ModuleMetaData::hmr_self_accepted]
+pub const HMR_SELF_ACCEPTED_KEY: &str = "hmr_self_accepted";
+
 /// Module meta data shared by core plugins through the compilation
 /// Meta data which is not shared by core plugins should be stored in [ModuleMetaData::Custom]
 #[cache_item]
@@ -107,6 +111,31 @@ impl ModuleMetaData {
     }
   }
 
+  /// Whether the module accepts its own updates when HMR, the update stops bubbling to the importers if true.
+  /// * script modules declare it by `import.meta.hot.accept()`
+  /// * css modules declare it by [CssModuleMetaData::hmr_self_accepted]
+  /// * html modules never accept their own updates
+  /// * custom modules declare it by [HMR_SELF_ACCEPTED_KEY]
+  pub fn hmr_self_accepted(&self) -> bool {
+    match self {
+      Self::Script(script) => script.hmr_self_accepted,
+      Self::Css(css) => css.hmr_self_accepted,
+      Self::Html(_) => false,
+      Self::Custom(custom) => custom
+        .get_cache::<bool>(HMR_SELF_ACCEPTED_KEY)
+        .is_some_and(|accepted| *accepted),
+    }
+  }
+
+  /// Whether the module accepts the updates of its dependency `dep` when HMR.
+  /// Only script modules can accept dependencies, by `import.meta.hot.accept(deps)`
+  pub fn hmr_accepts_dep(&self, dep: &ModuleId) -> bool {
+    match self {
+      Self::Script(script) => script.hmr_accepted_deps.contains(dep),
+      _ => false,
+    }
+  }
+
   /// get custom meta data by key
   pub fn get_custom_mut<T: Cacheable + Default>(&mut self, key: &str) -> &mut T {
     if let Self::Custom(custom) = self {
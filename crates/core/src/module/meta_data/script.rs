@@ -58,6 +58,11 @@ pub struct ScriptModuleMetaData {
   pub all_deeply_declared_idents: HashSet<Atom>,
   pub is_async: bool,
   pub feature_flags: HashSet<FeatureFlag>,
+  /// top level functions annotated by `/*#__NO_SIDE_EFFECTS__*/`, calls of them are treated as side effects free.
+  /// [EXPORT_DEFAULT] means the default exported function is annotated
+  pub no_side_effects_fns: HashSet<SwcId>,
+  /// module level directives, for example `use client` and `use server`
+  pub directives: Vec<String>,
   // -- End
   /// Note: This field can be only accessed after(or in) optimize_module_graph hook
   /// Real export ident map, for example:
@@ -103,6 +108,8 @@ impl Default for ScriptModuleMetaData {
       all_deeply_declared_idents: Default::default(),
       is_async: false,
       feature_flags: Default::default(),
+      no_side_effects_fns: Default::default(),
+      directives: vec![],
       export_ident_map: Default::default(),
       reexport_ident_map: Default::default(),
       ambiguous_export_ident_map: Default::default(),
@@ -129,6 +136,8 @@ impl Clone for ScriptModuleMetaData {
       all_deeply_declared_idents: self.all_deeply_declared_idents.clone(),
       is_async: self.is_async,
       feature_flags: self.feature_flags.clone(),
+      no_side_effects_fns: self.no_side_effects_fns.clone(),
+      directives: self.directives.clone(),
       export_ident_map: self.export_ident_map.clone(),
       custom: self.custom.clone(),
       reexport_ident_map: self.reexport_ident_map.clone(),
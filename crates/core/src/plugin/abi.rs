@@ -0,0 +1,83 @@
+//! Abi version of the plugins that do not share memory layout with the core, like wasm plugins, see [super::wasm].
+//!
+//! Wasm plugins only exchange rkyv serialized messages with the core, so they are versioned by the message protocol
+//! instead of the core version, and compatible versions are negotiated with semver caret rules.
+//!
+//! Dynamic library rust plugins are not versioned by this module. They exchange rust types (`&Config`,
+//! `Arc<dyn Plugin>`) with the core that are not ffi-stable, so the loader requires them to be built against
+//! exactly the same [crate::VERSION].
+
+use std::fmt::Display;
+
+/// Version of the messages defined in [super::wasm]. Bump it when the messages change:
+/// * major(minor before 1.0): the archived layout of existing messages changed, including the core types embedded
+///   in them like [crate::module::ModuleType], or an existing hook is removed.
+/// * minor(patch before 1.0): new hooks or messages are added, plugins built against older versions keep working.
+/// * patch: the protocol is not changed.
+pub const PLUGIN_ABI_VERSION: PluginAbiVersion = PluginAbiVersion::new(1, 0, 0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PluginAbiVersion {
+  pub major: u32,
+  pub minor: u32,
+  pub patch: u32,
+}
+
+impl PluginAbiVersion {
+  pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+    Self {
+      major,
+      minor,
+      patch,
+    }
+  }
+
+  /// Whether a plugin built against `self` can be loaded by a core built against `host`, following semver caret rules:
+  /// the left-most non-zero components must be the same, and `host` must not be older than `self`, as the plugin may
+  /// use messages added in its version.
+  pub fn is_compatible_with(&self, host: &PluginAbiVersion) -> bool {
+    let same_breaking_component = if self.major != 0 {
+      self.major == host.major
+    } else if self.minor != 0 {
+      host.major == 0 && self.minor == host.minor
+    } else {
+      self == host
+    };
+
+    same_breaking_component && host >= self
+  }
+}
+
+impl Display for PluginAbiVersion {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn is_compatible_with() {
+    let host = PluginAbiVersion::new(1, 2, 3);
+    assert!(PluginAbiVersion::new(1, 2, 3).is_compatible_with(&host));
+    assert!(PluginAbiVersion::new(1, 0, 0).is_compatible_with(&host));
+    assert!(PluginAbiVersion::new(1, 2, 0).is_compatible_with(&host));
+    // plugins built against a newer protocol may use messages the host does not know
+    assert!(!PluginAbiVersion::new(1, 2, 4).is_compatible_with(&host));
+    assert!(!PluginAbiVersion::new(1, 3, 0).is_compatible_with(&host));
+    assert!(!PluginAbiVersion::new(2, 0, 0).is_compatible_with(&host));
+    assert!(!PluginAbiVersion::new(0, 2, 3).is_compatible_with(&host));
+
+    let host = PluginAbiVersion::new(0, 5, 3);
+    assert!(PluginAbiVersion::new(0, 5, 1).is_compatible_with(&host));
+    assert!(!PluginAbiVersion::new(0, 5, 4).is_compatible_with(&host));
+    assert!(!PluginAbiVersion::new(0, 4, 3).is_compatible_with(&host));
+    assert!(!PluginAbiVersion::new(1, 5, 3).is_compatible_with(&host));
+
+    let host = PluginAbiVersion::new(0, 0, 3);
+    assert!(PluginAbiVersion::new(0, 0, 3).is_compatible_with(&host));
+    assert!(!PluginAbiVersion::new(0, 0, 2).is_compatible_with(&host));
+  }
+}
@@ -0,0 +1,12 @@
+use std::sync::Arc;
+
+use crate::module::{meta_data::custom::CustomMetaDataMap, ModuleId, ModuleType};
+
+/// Parameter of `handle_persistent_cached_custom_module` hook, the module is restored from persistent cache
+/// and its meta data is [crate::module::ModuleMetaData::Custom]
+pub struct PluginHandlePersistentCachedCustomModuleHookParam<'a> {
+  pub module_id: &'a ModuleId,
+  pub module_type: &'a ModuleType,
+  pub content: &'a Arc<String>,
+  pub meta: &'a mut CustomMetaDataMap,
+}
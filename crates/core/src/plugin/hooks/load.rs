@@ -13,7 +13,7 @@ pub struct PluginLoadHookParam<'a> {
   pub meta: HashMap<String, String>,
 }
 
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PluginLoadHookResult {
   /// the source content of the module
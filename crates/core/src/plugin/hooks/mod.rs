@@ -4,6 +4,7 @@ pub mod finalize_resources;
 pub mod freeze_module;
 pub mod generate_resources;
 pub mod handle_entry_resource;
+pub mod handle_persistent_cached_custom_module;
 pub mod load;
 pub mod module_graph_updated;
 pub mod parse;
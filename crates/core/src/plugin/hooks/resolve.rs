@@ -33,6 +33,8 @@ pub enum ResolveKind {
   ScriptSrc,
   /// `<link href="index.css" />` of html
   LinkHref,
+  /// `<img src>`, `srcset`, `poster`, icons, `og:image` and web manifest of html
+  HtmlAsset,
   /// Hmr update
   HmrUpdate,
   /// Custom ResolveKind
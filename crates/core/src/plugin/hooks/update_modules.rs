@@ -1,4 +1,4 @@
-use crate::{module::ModuleId, resource::ResourceType, HashMap};
+use crate::{config::ModuleFormat, module::ModuleId, resource::ResourceType, HashMap};
 
 #[derive(Default, Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub struct WatchDiffResult {
@@ -18,8 +18,73 @@ pub struct UpdateResult {
   pub immutable_resources: String,
   pub mutable_resources: String,
   pub boundaries: HashMap<String, Vec<Vec<String>>>,
+  /// Why the update can not be applied by HMR, `None` if every updated module reaches a boundary
+  pub full_reload_reason: Option<HmrFullReloadReason>,
+  /// Non-script modules that apply the update themselves, the key is the module id and the value is the module type.
+  /// For example, updated html entries and plain css boundaries
+  pub non_script_updates: HashMap<String, String>,
   pub dynamic_resources_map: Option<HashMap<ModuleId, Vec<(String, ResourceType)>>>,
   pub extra_watch_result: WatchDiffResult,
+  /// The update of node targets, `immutable_resources` and `mutable_resources` are empty for node targets
+  pub node_update: Option<NodeHmrUpdate>,
+  /// Update results of the environments in [crate::config::Config::environments] that contain the updated files
+  pub environments: HashMap<String, UpdateResult>,
+}
+
+/// Why the update can not be applied by HMR
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HmrFullReloadKind {
+  /// The chain reaches an entry module without meeting a module that accepts the update
+  Entry,
+  /// The chain reaches a non-script module, which can not accept the update
+  NonScriptModule,
+}
+
+impl HmrFullReloadKind {
+  pub fn as_str(&self) -> &'static str {
+    match self {
+      Self::Entry => "entry",
+      Self::NonScriptModule => "nonScriptModule",
+    }
+  }
+}
+
+/// HMR acceptance declared by a module via `import.meta.hot.accept`
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HmrModuleAcceptance {
+  pub id: String,
+  pub self_accepted: bool,
+  /// Sorted ids of the dependencies accepted by this module
+  pub accepted_deps: Vec<String>,
+}
+
+/// The reason why an update falls back to a full reload
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HmrFullReloadReason {
+  pub kind: HmrFullReloadKind,
+  /// The first unaccepted import chain, starts with the updated module and ends with the entry or the non-script module
+  pub chain: Vec<String>,
+  /// HMR acceptance of every module in `chain`, in the same order
+  pub modules: Vec<HmrModuleAcceptance>,
+}
+
+/// The update of node targets, it's applied inside the long-running process:
+/// execute `code` as a module of `format`, then call `moduleSystem.h` of the runtime with the boundaries and `invalidated_module_ids`.
+/// If `restart` is true, the update can not be applied by HMR and the process should be restarted instead
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeHmrUpdate {
+  pub format: ModuleFormat,
+  /// Re-registers the factories of the added and updated modules, empty if `restart` is true
+  pub code: String,
+  /// Modules whose cached exports are stale: the removed and updated modules, and every module on the chains to the boundaries.
+  /// Empty if `restart` is true
+  pub invalidated_module_ids: Vec<String>,
+  /// An updated module does not reach a boundary, see [UpdateResult::full_reload_reason] for the reason
+  pub restart: bool,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
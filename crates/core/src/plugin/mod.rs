@@ -12,9 +12,11 @@ use crate::{
   HashMap,
 };
 
+pub mod abi;
 pub mod constants;
 pub mod hooks;
 pub mod plugin_driver;
+pub mod wasm;
 
 pub use hooks::{
   analyze_deps::{PluginAnalyzeDepsHookParam, PluginAnalyzeDepsHookResultEntry},
@@ -23,13 +25,17 @@ pub use hooks::{
   freeze_module::PluginFreezeModuleHookParam,
   generate_resources::{GeneratedResource, PluginGenerateResourcesHookResult},
   handle_entry_resource::PluginHandleEntryResourceHookParam,
+  handle_persistent_cached_custom_module::PluginHandlePersistentCachedCustomModuleHookParam,
   load::{PluginLoadHookParam, PluginLoadHookResult},
   module_graph_updated::PluginModuleGraphUpdatedHookParam,
   parse::PluginParseHookParam,
   process_module::PluginProcessModuleHookParam,
   resolve::{PluginResolveHookParam, PluginResolveHookResult, ResolveKind},
   transform::{PluginTransformHookParam, PluginTransformHookResult},
-  update_modules::{PluginUpdateModulesHookParam, UpdateResult, UpdateType},
+  update_modules::{
+    HmrFullReloadKind, HmrFullReloadReason, HmrModuleAcceptance, NodeHmrUpdate,
+    PluginUpdateModulesHookParam, UpdateResult, UpdateType,
+  },
 };
 
 pub const DEFAULT_PRIORITY: i32 = 100;
@@ -323,6 +329,16 @@ pub trait Plugin: Any + Send + Sync {
     Ok(None)
   }
 
+  /// Called when a module whose meta data is [ModuleMetaData::Custom] is restored from persistent cache.
+  /// Useful to rebuild the state that can not be serialized, return `Some(true)` to mark the cached module as stale and build it again
+  fn handle_persistent_cached_custom_module(
+    &self,
+    _param: &mut PluginHandlePersistentCachedCustomModuleHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<bool>> {
+    Ok(None)
+  }
+
   fn write_plugin_cache(&self, _context: &Arc<CompilationContext>) -> Result<Option<Vec<u8>>> {
     Ok(None)
   }
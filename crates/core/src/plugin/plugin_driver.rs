@@ -5,10 +5,11 @@ use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 use super::{
   hooks::freeze_module::PluginFreezeModuleHookParam, Plugin, PluginAnalyzeDepsHookParam,
   PluginFinalizeModuleHookParam, PluginFinalizeResourcesHookParam,
-  PluginGenerateResourcesHookResult, PluginHandleEntryResourceHookParam, PluginHookContext,
-  PluginLoadHookParam, PluginLoadHookResult, PluginModuleGraphUpdatedHookParam,
-  PluginParseHookParam, PluginProcessModuleHookParam, PluginResolveHookParam,
-  PluginResolveHookResult, PluginTransformHookParam, PluginUpdateModulesHookParam,
+  PluginGenerateResourcesHookResult, PluginHandleEntryResourceHookParam,
+  PluginHandlePersistentCachedCustomModuleHookParam, PluginHookContext, PluginLoadHookParam,
+  PluginLoadHookResult, PluginModuleGraphUpdatedHookParam, PluginParseHookParam,
+  PluginProcessModuleHookParam, PluginResolveHookParam, PluginResolveHookResult,
+  PluginTransformHookParam, PluginUpdateModulesHookParam,
 };
 use crate::{
   config::Config,
@@ -878,6 +879,60 @@ impl PluginDriver {
     context: &Arc<CompilationContext>
   );
 
+  /// Unlike [PluginDriver::handle_persistent_cached_module], all plugins are called in serial because each plugin
+  /// rebuilds its own state of the custom module. The module is stale if any plugin returns `Some(true)`
+  pub fn handle_persistent_cached_custom_module(
+    &self,
+    param: &mut PluginHandlePersistentCachedCustomModuleHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<bool>> {
+    let mut is_stale = None;
+
+    for plugin in &self.plugins {
+      let start_time = if self.record {
+        SystemTime::now()
+          .duration_since(UNIX_EPOCH)
+          .expect("Time went backwards")
+          .as_millis()
+      } else {
+        0
+      };
+
+      let Some(res) = plugin.handle_persistent_cached_custom_module(param, context)? else {
+        continue;
+      };
+
+      is_stale = Some(is_stale.unwrap_or(false) || res);
+
+      if self.record {
+        let end_time = SystemTime::now()
+          .duration_since(UNIX_EPOCH)
+          .expect("Time went backwards")
+          .as_millis();
+
+        context
+          .stats
+          .add_plugin_hook_stats(CompilationPluginHookStats {
+            plugin_name: plugin.name().to_string(),
+            hook_name: "handle_persistent_cached_custom_module".to_string(),
+            hook_context: None,
+            module_id: param.module_id.clone(),
+            input: serde_json::to_string(&vec![
+              param.module_id.to_string(),
+              param.module_type.to_string(),
+            ])
+            .unwrap(),
+            output: serde_json::to_string(&res).unwrap(),
+            duration: end_time - start_time,
+            start_time,
+            end_time,
+          });
+      }
+    }
+
+    Ok(is_stale)
+  }
+
   pub fn write_plugin_cache(&self, context: &Arc<CompilationContext>) -> Result<()> {
     for plugin in &self.plugins {
       let start_time = if context.config.record {
@@ -938,14 +993,17 @@ pub struct PluginDriverTransformHookResult {
 #[cfg(test)]
 mod tests {
   use crate::HashMap;
+  use parking_lot::Mutex;
   use std::sync::Arc;
 
   use crate::{
     config::Config,
     context::CompilationContext,
     error::Result,
+    module::{meta_data::custom::CustomMetaDataMap, ModuleId, ModuleType},
     plugin::{
-      Plugin, PluginHookContext, PluginResolveHookParam, PluginResolveHookResult, ResolveKind,
+      Plugin, PluginHandlePersistentCachedCustomModuleHookParam, PluginHookContext,
+      PluginResolveHookParam, PluginResolveHookResult, ResolveKind,
     },
   };
 
@@ -1048,6 +1106,109 @@ mod tests {
   #[test]
   fn hook_serial() {}
 
+  macro_rules! define_custom_module_plugin {
+    ($plugin_name:ident, $result:expr) => {
+      struct $plugin_name {
+        called: Arc<Mutex<Vec<&'static str>>>,
+      }
+
+      impl Plugin for $plugin_name {
+        fn name(&self) -> &str {
+          stringify!($plugin_name)
+        }
+
+        fn handle_persistent_cached_custom_module(
+          &self,
+          _param: &mut PluginHandlePersistentCachedCustomModuleHookParam,
+          _context: &Arc<CompilationContext>,
+        ) -> Result<Option<bool>> {
+          self.called.lock().push(stringify!($plugin_name));
+          Ok($result)
+        }
+      }
+    };
+  }
+
+  #[test]
+  fn handle_persistent_cached_custom_module() {
+    define_custom_module_plugin!(CustomModulePlugin1, Some(false));
+    define_custom_module_plugin!(CustomModulePlugin2, Some(true));
+    define_custom_module_plugin!(CustomModulePlugin3, None);
+
+    let context = Arc::new(CompilationContext::new(Config::default(), vec![]).unwrap());
+    let module_id: ModuleId = "a.vue".into();
+    let module_type = ModuleType::Custom("vue".to_string());
+    let content = Arc::new(String::new());
+    let called = Arc::new(Mutex::new(vec![]));
+
+    let call = |plugins: Vec<Arc<dyn Plugin>>| {
+      called.lock().clear();
+      let is_stale = PluginDriver::new(plugins, false)
+        .handle_persistent_cached_custom_module(
+          &mut PluginHandlePersistentCachedCustomModuleHookParam {
+            module_id: &module_id,
+            module_type: &module_type,
+            content: &content,
+            meta: &mut CustomMetaDataMap::default(),
+          },
+          &context,
+        )
+        .unwrap();
+
+      (is_stale, called.lock().clone())
+    };
+
+    // the stale flag of the second plugin is not ignored because the first one returns Some(false)
+    assert_eq!(
+      call(vec![
+        Arc::new(CustomModulePlugin1 {
+          called: called.clone()
+        }),
+        Arc::new(CustomModulePlugin2 {
+          called: called.clone()
+        }),
+        Arc::new(CustomModulePlugin3 {
+          called: called.clone()
+        }),
+      ]),
+      (
+        Some(true),
+        vec![
+          "CustomModulePlugin1",
+          "CustomModulePlugin2",
+          "CustomModulePlugin3"
+        ]
+      )
+    );
+    // plugins after the one that marks the module as stale are still called to rebuild their state
+    assert_eq!(
+      call(vec![
+        Arc::new(CustomModulePlugin2 {
+          called: called.clone()
+        }),
+        Arc::new(CustomModulePlugin1 {
+          called: called.clone()
+        }),
+      ]),
+      (
+        Some(true),
+        vec!["CustomModulePlugin2", "CustomModulePlugin1"]
+      )
+    );
+    assert_eq!(
+      call(vec![Arc::new(CustomModulePlugin1 {
+        called: called.clone()
+      })]),
+      (Some(false), vec!["CustomModulePlugin1"])
+    );
+    assert_eq!(
+      call(vec![Arc::new(CustomModulePlugin3 {
+        called: called.clone()
+      })]),
+      (None, vec!["CustomModulePlugin3"])
+    );
+  }
+
   #[test]
   fn hook_parallel() {}
 }
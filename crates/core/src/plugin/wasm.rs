@@ -0,0 +1,397 @@
+//! Protocol between the core and sandboxed rust plugins compiled to `wasm32-wasip1`.
+//!
+//! Unlike dynamic library plugins, wasm plugins never share memory layout with the core. Every hook call copies a
+//! rkyv serialized param into the guest memory through [WASM_PLUGIN_ALLOC_EXPORT], calls [WASM_PLUGIN_CALL_EXPORT]
+//! and copies back a serialized [WasmPluginHookResponse]. Messages are validated when decoded, so a misbehaving
+//! plugin can only fail its own hook call. Only module level hooks are supported for now, see [WasmPluginHook].
+//!
+//! Plugin authors implement [WasmPlugin] and mark the struct with `#[farm_wasm_plugin]`, which exports the entries below.
+
+use farmfe_macro_cache_item::cache_item;
+use rkyv::{
+  api::high::{HighDeserializer, HighValidator},
+  bytecheck::CheckBytes,
+  rancor::Error,
+  util::AlignedVec,
+};
+
+use crate::{
+  module::ModuleType,
+  plugin::{
+    abi::PluginAbiVersion, PluginLoadHookResult, PluginResolveHookResult,
+    PluginTransformHookResult, ResolveKind, DEFAULT_PRIORITY,
+  },
+  HashMap,
+};
+
+/// `fn() -> u64`, returns the abi version packed by [PluginAbiVersion::pack]
+pub const WASM_PLUGIN_ABI_VERSION_EXPORT: &str = "farm_plugin_abi_version";
+/// `fn(len: u32) -> u32`, allocates `len` bytes in the guest memory and returns the pointer
+pub const WASM_PLUGIN_ALLOC_EXPORT: &str = "farm_plugin_alloc";
+/// `fn(ptr: u32, len: u32)`, frees the memory returned by [WASM_PLUGIN_ALLOC_EXPORT] or [WASM_PLUGIN_CALL_EXPORT]
+pub const WASM_PLUGIN_DEALLOC_EXPORT: &str = "farm_plugin_dealloc";
+/// `fn(ptr: u32, len: u32) -> u64`, creates the plugin from [WasmPluginCreateParam] and returns [WasmPluginInfo]
+pub const WASM_PLUGIN_CREATE_EXPORT: &str = "farm_plugin_create";
+/// `fn(hook: u32, ptr: u32, len: u32) -> u64`, calls the hook and returns [WasmPluginHookResponse]
+pub const WASM_PLUGIN_CALL_EXPORT: &str = "farm_plugin_call";
+
+impl PluginAbiVersion {
+  /// Pack the version into a single wasm value, 16 bits for each component
+  pub fn pack(&self) -> u64 {
+    ((self.major as u64 & 0xffff) << 32)
+      | ((self.minor as u64 & 0xffff) << 16)
+      | (self.patch as u64 & 0xffff)
+  }
+
+  pub fn unpack(packed: u64) -> Self {
+    Self::new(
+      ((packed >> 32) & 0xffff) as u32,
+      ((packed >> 16) & 0xffff) as u32,
+      (packed & 0xffff) as u32,
+    )
+  }
+}
+
+/// Pack a guest `(ptr, len)` pair into a single wasm return value
+pub fn pack_ptr_len(ptr: u32, len: u32) -> u64 {
+  ((ptr as u64) << 32) | len as u64
+}
+
+pub fn unpack_ptr_len(packed: u64) -> (u32, u32) {
+  ((packed >> 32) as u32, packed as u32)
+}
+
+/// Hooks that can be implemented by wasm plugins
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WasmPluginHook {
+  Resolve = 0,
+  Load = 1,
+  Transform = 2,
+  ProcessModule = 3,
+}
+
+impl WasmPluginHook {
+  pub fn bit(&self) -> u32 {
+    1 << (*self as u32)
+  }
+
+  pub fn from_u32(hook: u32) -> Option<Self> {
+    match hook {
+      0 => Some(Self::Resolve),
+      1 => Some(Self::Load),
+      2 => Some(Self::Transform),
+      3 => Some(Self::ProcessModule),
+      _ => None,
+    }
+  }
+
+  /// Bit mask of the hooks, see [WasmPluginInfo::hooks]
+  pub fn mask(hooks: &[WasmPluginHook]) -> u32 {
+    hooks.iter().fold(0, |mask, hook| mask | hook.bit())
+  }
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginCreateParam {
+  /// json serialized [crate::config::Config]
+  pub config: String,
+  /// json serialized options of the plugin
+  pub options: String,
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginInfo {
+  pub name: String,
+  pub priority: i32,
+  /// bit mask of the implemented [WasmPluginHook]s, hooks not in the mask are never called
+  pub hooks: u32,
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub enum WasmPluginHookResponse {
+  /// the hook is skipped, same as returning `Ok(None)` in [crate::plugin::Plugin]
+  Skip,
+  /// rkyv serialized result of the hook
+  Done(Vec<u8>),
+  Error(String),
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginResolveHookParam {
+  pub source: String,
+  pub importer: Option<String>,
+  pub kind: ResolveKind,
+  /// caller of the hook, see [crate::plugin::PluginHookContext]
+  pub caller: Option<String>,
+  pub meta: HashMap<String, String>,
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginResolveHookResult {
+  pub resolved_path: String,
+  pub external: bool,
+  pub side_effects: bool,
+  pub query: Vec<(String, String)>,
+  pub meta: HashMap<String, String>,
+}
+
+impl From<WasmPluginResolveHookResult> for PluginResolveHookResult {
+  fn from(value: WasmPluginResolveHookResult) -> Self {
+    Self {
+      resolved_path: value.resolved_path,
+      external: value.external,
+      side_effects: value.side_effects,
+      query: value.query,
+      meta: value.meta,
+    }
+  }
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginLoadHookParam {
+  pub module_id: String,
+  pub resolved_path: String,
+  pub query: Vec<(String, String)>,
+  pub meta: HashMap<String, String>,
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginLoadHookResult {
+  pub content: String,
+  pub module_type: ModuleType,
+  pub source_map: Option<String>,
+}
+
+impl From<WasmPluginLoadHookResult> for PluginLoadHookResult {
+  fn from(value: WasmPluginLoadHookResult) -> Self {
+    Self {
+      content: value.content,
+      module_type: value.module_type,
+      source_map: value.source_map,
+    }
+  }
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginTransformHookParam {
+  pub module_id: String,
+  pub content: String,
+  pub module_type: ModuleType,
+  pub resolved_path: String,
+  pub query: Vec<(String, String)>,
+  pub meta: HashMap<String, String>,
+  pub source_map_chain: Vec<String>,
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginTransformHookResult {
+  pub content: String,
+  pub module_type: Option<ModuleType>,
+  pub source_map: Option<String>,
+  pub ignore_previous_source_map: bool,
+}
+
+impl From<WasmPluginTransformHookResult> for PluginTransformHookResult {
+  fn from(value: WasmPluginTransformHookResult) -> Self {
+    Self {
+      content: value.content,
+      module_type: value.module_type,
+      source_map: value.source_map,
+      ignore_previous_source_map: value.ignore_previous_source_map,
+    }
+  }
+}
+
+/// The ast can not cross the sandbox, so the module is printed to code before calling the plugin,
+/// and returned content is parsed again.
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginProcessModuleHookParam {
+  pub module_id: String,
+  pub module_type: ModuleType,
+  pub content: String,
+  pub source_map_chain: Vec<String>,
+}
+
+#[cache_item]
+#[derive(Debug, Clone)]
+pub struct WasmPluginProcessModuleHookResult {
+  pub content: String,
+  pub source_map: Option<String>,
+  pub ignore_previous_source_map: bool,
+}
+
+/// Serialize a message that is passed between the core and the plugin
+#[macro_export]
+macro_rules! encode_wasm_plugin_message {
+  ($t:expr) => {
+    $crate::rkyv::to_bytes::<$crate::rkyv::rancor::Error>($t)
+      .unwrap()
+      .to_vec()
+  };
+}
+
+/// Validate and deserialize a message that is passed between the core and the plugin.
+/// Bytes copied out of the wasm memory are not aligned, so they are copied to an aligned buffer first.
+pub fn decode_wasm_plugin_message<T>(bytes: &[u8]) -> std::result::Result<T, String>
+where
+  T: rkyv::Archive,
+  T::Archived:
+    for<'a> CheckBytes<HighValidator<'a, Error>> + rkyv::Deserialize<T, HighDeserializer<Error>>,
+{
+  let mut aligned = AlignedVec::<16>::with_capacity(bytes.len());
+  aligned.extend_from_slice(bytes);
+
+  rkyv::from_bytes::<T, Error>(&aligned).map_err(|e| format!("malformed wasm plugin message: {e}"))
+}
+
+/// Plugin that runs inside the wasm sandbox. Hooks receive owned params and return `Ok(None)` to skip,
+/// errors are reported as hook errors of the plugin by the core.
+pub trait WasmPlugin: Send + Sync {
+  fn name(&self) -> &str;
+
+  fn priority(&self) -> i32 {
+    DEFAULT_PRIORITY
+  }
+
+  /// Hooks implemented by this plugin, the core only calls these hooks
+  fn hooks(&self) -> Vec<WasmPluginHook>;
+
+  fn resolve(
+    &self,
+    _param: WasmPluginResolveHookParam,
+  ) -> std::result::Result<Option<WasmPluginResolveHookResult>, String> {
+    Ok(None)
+  }
+
+  fn load(
+    &self,
+    _param: WasmPluginLoadHookParam,
+  ) -> std::result::Result<Option<WasmPluginLoadHookResult>, String> {
+    Ok(None)
+  }
+
+  fn transform(
+    &self,
+    _param: WasmPluginTransformHookParam,
+  ) -> std::result::Result<Option<WasmPluginTransformHookResult>, String> {
+    Ok(None)
+  }
+
+  fn process_module(
+    &self,
+    _param: WasmPluginProcessModuleHookParam,
+  ) -> std::result::Result<Option<WasmPluginProcessModuleHookResult>, String> {
+    Ok(None)
+  }
+}
+
+/// Guest side dispatch used by `#[farm_wasm_plugin]`, returns serialized [WasmPluginHookResponse]
+pub fn call_wasm_plugin_hook(plugin: &dyn WasmPlugin, hook: u32, param: &[u8]) -> Vec<u8> {
+  macro_rules! dispatch {
+    ($method:ident, $param_ty:ty) => {
+      match decode_wasm_plugin_message::<$param_ty>(param).and_then(|param| plugin.$method(param)) {
+        Ok(Some(result)) => WasmPluginHookResponse::Done(encode_wasm_plugin_message!(&result)),
+        Ok(None) => WasmPluginHookResponse::Skip,
+        Err(e) => WasmPluginHookResponse::Error(e),
+      }
+    };
+  }
+
+  let response = match WasmPluginHook::from_u32(hook) {
+    Some(WasmPluginHook::Resolve) => dispatch!(resolve, WasmPluginResolveHookParam),
+    Some(WasmPluginHook::Load) => dispatch!(load, WasmPluginLoadHookParam),
+    Some(WasmPluginHook::Transform) => dispatch!(transform, WasmPluginTransformHookParam),
+    Some(WasmPluginHook::ProcessModule) => {
+      dispatch!(process_module, WasmPluginProcessModuleHookParam)
+    }
+    None => WasmPluginHookResponse::Error(format!("unknown hook {hook}")),
+  };
+
+  encode_wasm_plugin_message!(&response)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  struct TestPlugin;
+
+  impl WasmPlugin for TestPlugin {
+    fn name(&self) -> &str {
+      "test"
+    }
+
+    fn hooks(&self) -> Vec<WasmPluginHook> {
+      vec![WasmPluginHook::Load]
+    }
+
+    fn load(
+      &self,
+      param: WasmPluginLoadHookParam,
+    ) -> std::result::Result<Option<WasmPluginLoadHookResult>, String> {
+      if param.resolved_path.ends_with(".txt") {
+        return Ok(Some(WasmPluginLoadHookResult {
+          content: format!("export default {:?}", param.resolved_path),
+          module_type: ModuleType::Js,
+          source_map: None,
+        }));
+      }
+
+      Err("not a txt file".to_string())
+    }
+  }
+
+  fn call_load(resolved_path: &str) -> WasmPluginHookResponse {
+    let param = WasmPluginLoadHookParam {
+      module_id: resolved_path.to_string(),
+      resolved_path: resolved_path.to_string(),
+      query: vec![],
+      meta: Default::default(),
+    };
+    let bytes = call_wasm_plugin_hook(
+      &TestPlugin,
+      WasmPluginHook::Load as u32,
+      &encode_wasm_plugin_message!(&param),
+    );
+
+    decode_wasm_plugin_message(&bytes).unwrap()
+  }
+
+  #[test]
+  fn call_hook() {
+    let WasmPluginHookResponse::Done(result) = call_load("/a.txt") else {
+      panic!("load should be done");
+    };
+    let result = decode_wasm_plugin_message::<WasmPluginLoadHookResult>(&result).unwrap();
+    assert_eq!(result.content, "export default \"/a.txt\"");
+    assert_eq!(result.module_type, ModuleType::Js);
+
+    assert!(matches!(
+      call_load("/a.png"),
+      WasmPluginHookResponse::Error(e) if e == "not a txt file"
+    ));
+    assert!(decode_wasm_plugin_message::<WasmPluginLoadHookResult>(&[1, 2, 3]).is_err());
+  }
+
+  #[test]
+  fn pack() {
+    let version = PluginAbiVersion::new(0, 5, 1);
+    assert_eq!(PluginAbiVersion::unpack(version.pack()), version);
+    assert_eq!(unpack_ptr_len(pack_ptr_len(1024, 77)), (1024, 77));
+    assert_eq!(
+      WasmPluginHook::mask(&[WasmPluginHook::Resolve, WasmPluginHook::Transform]),
+      0b101
+    );
+  }
+}
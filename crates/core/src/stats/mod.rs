@@ -1,6 +1,8 @@
 use parking_lot::RwLock;
 
 use crate::{
+  cache::module_cache::ModuleCacheStatus,
+  config::persistent_cache::PersistentCacheBuildDependencyInput,
   module::{
     module_graph::{ModuleGraph, ModuleGraphEdge},
     ModuleId, ModuleType,
@@ -14,6 +16,8 @@ pub struct Stats {
   pub initial_compilation_flow_stats: RwLock<CompilationStats>,
   /// Most 10 recent compilation flow stats
   pub hmr_compilation_flow_stats: RwLock<Vec<CompilationStats>>,
+  /// Cache directory and the build dependencies that selected it
+  pub persistent_cache_stats: RwLock<PersistentCacheStats>,
 }
 
 macro_rules! handle_compilation_stats {
@@ -38,10 +42,13 @@ impl ToString for Stats {
     let hmr_compilation_flow_stats: &Vec<CompilationStats> =
       &self.hmr_compilation_flow_stats.read();
 
+    let persistent_cache_stats: &PersistentCacheStats = &self.persistent_cache_stats.read();
+
     format!(
-      "{{ \n  \"initialCompilationFlowStats\": {},\n  \"hmrCompilationFlowStats\": {}\n}}",
+      "{{ \n  \"initialCompilationFlowStats\": {},\n  \"hmrCompilationFlowStats\": {},\n  \"persistentCacheStats\": {}\n}}",
       serde_json::to_string(initial_compilation_flow_stats).unwrap(),
-      serde_json::to_string(hmr_compilation_flow_stats).unwrap()
+      serde_json::to_string(hmr_compilation_flow_stats).unwrap(),
+      serde_json::to_string(persistent_cache_stats).unwrap()
     )
   }
 }
@@ -51,6 +58,7 @@ impl Default for Stats {
     Self {
       initial_compilation_flow_stats: RwLock::new(CompilationStats::new()),
       hmr_compilation_flow_stats: RwLock::new(vec![]),
+      persistent_cache_stats: RwLock::new(PersistentCacheStats::default()),
     }
   }
 }
@@ -115,6 +123,56 @@ impl Stats {
       compilation_stats.entries = entries;
     })
   }
+
+  pub fn set_tree_shaking_explanations(&self, explanations: Vec<TreeShakingModuleExplanation>) {
+    handle_compilation_stats!(self, |compilation_stats: &mut CompilationStats| {
+      compilation_stats.tree_shaking_explanations = explanations;
+    })
+  }
+
+  pub fn set_persistent_cache_stats(&self, stats: PersistentCacheStats) {
+    *self.persistent_cache_stats.write() = stats;
+  }
+
+  /// Record whether the module is restored from the persistent cache, later records of the same module override the earlier ones
+  pub fn record_module_cache_status(&self, module_id: &ModuleId, status: ModuleCacheStatus) {
+    handle_compilation_stats!(self, |compilation_stats: &mut CompilationStats| {
+      compilation_stats
+        .module_cache_statuses
+        .insert(module_id.clone(), status);
+    })
+  }
+
+  /// Json string of the persistent cache stats and the module cache statuses of the latest compilation
+  pub fn cache_stats_to_string(&self) -> String {
+    let modules = handle_compilation_stats!(self, |compilation_stats: &mut CompilationStats| {
+      compilation_stats.module_cache_statuses.clone()
+    });
+    let mut summary = HashMap::<ModuleCacheStatus, usize>::default();
+
+    for status in modules.values() {
+      *summary.entry(*status).or_default() += 1;
+    }
+
+    let persistent_cache_stats = self.persistent_cache_stats.read();
+
+    serde_json::to_string(&serde_json::json!({
+      "cacheDir": persistent_cache_stats.cache_dir,
+      "buildDependencies": persistent_cache_stats.build_dependencies,
+      "summary": summary,
+      "modules": modules,
+    }))
+    .unwrap()
+  }
+}
+
+#[derive(serde::Serialize, serde::Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentCacheStats {
+  /// `None` if the persistent cache is disabled or in memory
+  pub cache_dir: Option<String>,
+  /// Inputs of the hash in the name of the cache directory, changing any of them makes the cache cold
+  pub build_dependencies: Vec<PersistentCacheBuildDependencyInput>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize, Default)]
@@ -127,6 +185,10 @@ pub struct CompilationStats {
   pub start_time: u128,
   pub build_end_time: u128,
   pub end_time: u128,
+  /// Explanations of modules matched by `treeShaking.explain`
+  pub tree_shaking_explanations: Vec<TreeShakingModuleExplanation>,
+  /// Whether the modules built in this compilation are restored from the persistent cache
+  pub module_cache_statuses: HashMap<ModuleId, ModuleCacheStatus>,
 }
 
 impl CompilationStats {
@@ -225,3 +287,57 @@ pub struct CompilationModuleStats {
   pub module_id: ModuleId,
   pub module_type: ModuleType,
 }
+
+/// Why tree shaking preserves a module and its statements, generated for modules matched by `treeShaking.explain`
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeShakingModuleExplanation {
+  pub module_id: ModuleId,
+  /// whether the module is removed from the module graph
+  pub removed: bool,
+  pub is_entry: bool,
+  /// side effects of the module, from package.json or `treeShaking.sideEffects/noSideEffects`
+  pub side_effects: bool,
+  /// exports that are used by other modules, `All` means all exports are used
+  pub used_exports: Vec<String>,
+  /// modules that import this module and the exports they use
+  pub importers: Vec<TreeShakingImporterExplanation>,
+  /// empty if the module is not esm, all statements of non esm modules are preserved
+  pub statements: Vec<TreeShakingStatementExplanation>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeShakingImporterExplanation {
+  pub importer: ModuleId,
+  pub used_exports: Vec<String>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TreeShakingStatementExplanation {
+  pub stmt_id: usize,
+  pub kept: bool,
+  pub reasons: Vec<TreeShakingKeptReason>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[serde(
+  tag = "type",
+  rename_all = "camelCase",
+  rename_all_fields = "camelCase"
+)]
+pub enum TreeShakingKeptReason {
+  /// the statement defines or re-exports exports used by other modules
+  UsedExports { idents: Vec<String> },
+  /// the statement has side effects
+  SideEffects { side_effects: String },
+  /// the statement defines idents used by another kept statement
+  UsedByStatement { stmt_id: usize, idents: Vec<String> },
+  /// the statement writes idents defined by another kept statement, e.g. `a.prototype.b = 1`
+  WriteUsedIdents { stmt_id: usize, idents: Vec<String> },
+  /// the statement defines imported idents that are written in this module and used by the imported module
+  WriteImportedIdents { idents: Vec<String> },
+  /// the statement imports a module that contains side effects
+  ImportSideEffectsModule { source: String },
+}
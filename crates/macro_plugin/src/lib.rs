@@ -23,3 +23,70 @@ pub fn farm_plugin(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
   ts.into()
 }
+
+/// Export the entries of a sandboxed plugin compiled to `wasm32-wasip1`, the struct should implement
+/// `farmfe_core::plugin::wasm::WasmPlugin` and `fn new(config: &Config, options: String) -> Self`.
+/// See `farmfe_core::plugin::wasm` for the protocol.
+#[proc_macro_attribute]
+pub fn farm_wasm_plugin(_attr: TokenStream, item: TokenStream) -> TokenStream {
+  let item_struct: ItemStruct = parse_macro_input!(item);
+  let struct_name = &item_struct.ident;
+
+  let ts = quote! {
+    static _FARM_WASM_PLUGIN: std::sync::OnceLock<#struct_name> = std::sync::OnceLock::new();
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn farm_plugin_abi_version() -> u64 {
+      farmfe_core::plugin::abi::PLUGIN_ABI_VERSION.pack()
+    }
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn farm_plugin_alloc(len: u32) -> u32 {
+      // boxed slice makes sure the capacity is exactly `len` when it's freed by `farm_plugin_dealloc`
+      let mut buf = std::mem::ManuallyDrop::new(std::vec![0u8; len as usize].into_boxed_slice());
+      buf.as_mut_ptr() as u32
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn farm_plugin_dealloc(ptr: u32, len: u32) {
+      unsafe { drop(std::vec::Vec::from_raw_parts(ptr as *mut u8, 0, len as usize)) };
+    }
+
+    fn _farm_wasm_plugin_return(bytes: std::vec::Vec<u8>) -> u64 {
+      let bytes = std::mem::ManuallyDrop::new(bytes.into_boxed_slice());
+      farmfe_core::plugin::wasm::pack_ptr_len(bytes.as_ptr() as u32, bytes.len() as u32)
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn farm_plugin_create(ptr: u32, len: u32) -> u64 {
+      use farmfe_core::plugin::wasm::{WasmPlugin, WasmPluginCreateParam, WasmPluginHook, WasmPluginInfo};
+
+      let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
+      let param: WasmPluginCreateParam = farmfe_core::plugin::wasm::decode_wasm_plugin_message(bytes)
+        .expect("invalid create param of wasm plugin");
+      let config: farmfe_core::config::Config = farmfe_core::serde_json::from_str(&param.config)
+        .expect("invalid config passed to wasm plugin");
+      let plugin = _FARM_WASM_PLUGIN.get_or_init(|| #struct_name::new(&config, param.options));
+
+      let info = WasmPluginInfo {
+        name: plugin.name().to_string(),
+        priority: plugin.priority(),
+        hooks: WasmPluginHook::mask(&plugin.hooks()),
+      };
+
+      _farm_wasm_plugin_return(farmfe_core::encode_wasm_plugin_message!(&info))
+    }
+
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn farm_plugin_call(hook: u32, ptr: u32, len: u32) -> u64 {
+      let bytes = unsafe { std::slice::from_raw_parts(ptr as *const u8, len as usize) };
+      let plugin = _FARM_WASM_PLUGIN.get().expect("wasm plugin is not created");
+
+      _farm_wasm_plugin_return(farmfe_core::plugin::wasm::call_wasm_plugin_hook(plugin, hook, bytes))
+    }
+
+    #item_struct
+  };
+
+  ts.into()
+}
@@ -20,10 +20,10 @@ pub mod profile_gui;
 
 use farmfe_core::{
   cache::module_cache::MetadataOption,
-  config::{Config, Mode},
+  config::{Config, Mode, ModuleFormat},
   context::CompilationContext,
   module::ModuleId,
-  plugin::ResolveKind,
+  plugin::{HmrFullReloadReason, NodeHmrUpdate, ResolveKind, UpdateResult},
   HashMap,
 };
 
@@ -85,6 +85,65 @@ impl From<TracedModuleGraph> for JsTracedModuleGraph {
   }
 }
 
+#[napi(object)]
+pub struct JsHmrModuleAcceptance {
+  pub id: String,
+  pub self_accepted: bool,
+  pub accepted_deps: Vec<String>,
+}
+
+#[napi(object)]
+pub struct JsHmrFullReloadReason {
+  /// `entry` or `nonScriptModule`
+  pub kind: String,
+  pub chain: Vec<String>,
+  pub modules: Vec<JsHmrModuleAcceptance>,
+}
+
+impl From<HmrFullReloadReason> for JsHmrFullReloadReason {
+  fn from(r: HmrFullReloadReason) -> Self {
+    Self {
+      kind: r.kind.as_str().to_string(),
+      chain: r.chain,
+      modules: r
+        .modules
+        .into_iter()
+        .map(|m| JsHmrModuleAcceptance {
+          id: m.id,
+          self_accepted: m.self_accepted,
+          accepted_deps: m.accepted_deps,
+        })
+        .collect(),
+    }
+  }
+}
+
+#[napi(object)]
+pub struct JsNodeHmrUpdate {
+  /// `esm` or `cjs`
+  pub format: String,
+  pub code: String,
+  pub invalidated_module_ids: Vec<String>,
+  /// the update can not be applied by HMR, the process should be restarted
+  pub restart: bool,
+}
+
+impl From<NodeHmrUpdate> for JsNodeHmrUpdate {
+  fn from(u: NodeHmrUpdate) -> Self {
+    Self {
+      // node targets only output esm or cjs
+      format: if matches!(u.format, ModuleFormat::CommonJs) {
+        "cjs".to_string()
+      } else {
+        "esm".to_string()
+      },
+      code: u.code,
+      invalidated_module_ids: u.invalidated_module_ids,
+      restart: u.restart,
+    }
+  }
+}
+
 #[napi(object)]
 pub struct JsUpdateResult {
   pub added: Vec<String>,
@@ -93,8 +152,99 @@ pub struct JsUpdateResult {
   pub immutable_modules: String,
   pub mutable_modules: String,
   pub boundaries: HashMap<String, Vec<Vec<String>>>,
+  pub full_reload_reason: Option<JsHmrFullReloadReason>,
+  pub non_script_updates: HashMap<String, String>,
   pub dynamic_resources_map: Option<HashMap<String, Vec<Vec<String>>>>,
   pub extra_watch_result: WatchDiffResult,
+  /// the update of node targets, applied by `moduleSystem.h` of the runtime
+  pub node_update: Option<JsNodeHmrUpdate>,
+  /// update results of the environments that contain the updated files, keyed by environment name
+  pub environments: HashMap<String, JsUpdateResult>,
+}
+
+impl JsUpdateResult {
+  fn from_update_result(res: UpdateResult, config: &Config) -> Self {
+    Self {
+      added: res
+        .added_module_ids
+        .into_iter()
+        .map(|id| id.id(Mode::Development))
+        .collect(),
+      changed: res
+        .updated_module_ids
+        .into_iter()
+        .map(|id| id.id(Mode::Development))
+        .collect(),
+      removed: res
+        .removed_module_ids
+        .into_iter()
+        .map(|id| id.id(Mode::Development))
+        .collect(),
+      immutable_modules: res.immutable_resources,
+      mutable_modules: res.mutable_resources,
+      boundaries: res.boundaries,
+      full_reload_reason: res.full_reload_reason.map(Into::into),
+      non_script_updates: res.non_script_updates,
+      dynamic_resources_map: res.dynamic_resources_map.map(|dynamic_resources_map| {
+        dynamic_resources_map
+          .into_iter()
+          .map(|(k, v)| {
+            (
+              k.id(config.mode),
+              v.into_iter()
+                .map(|(path, ty)| vec![path, ty.to_html_tag()])
+                .collect(),
+            )
+          })
+          .collect()
+      }),
+
+      extra_watch_result: WatchDiffResult {
+        add: res
+          .extra_watch_result
+          .add
+          .into_iter()
+          .map(|path| ModuleId::new(&path, "", &config.root).id(Mode::Development))
+          .collect(),
+        remove: res
+          .extra_watch_result
+          .remove
+          .into_iter()
+          .map(|path| ModuleId::new(&path, "", &config.root).id(Mode::Development))
+          .collect(),
+      },
+      node_update: res.node_update.map(Into::into),
+      environments: res
+        .environments
+        .into_iter()
+        .map(|(name, res)| (name, Self::from_update_result(res, config)))
+        .collect(),
+    }
+  }
+}
+
+#[cfg(feature = "wasm_plugin")]
+fn load_wasm_plugin(
+  plugin_path: &str,
+  config: &Config,
+  options: String,
+) -> napi::Result<Arc<dyn farmfe_core::plugin::Plugin>> {
+  Ok(Arc::new(
+    plugin_adapters::wasm_plugin_adapter::WasmPluginAdapter::new(plugin_path, config, options)
+      .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))?,
+  ))
+}
+
+#[cfg(not(feature = "wasm_plugin"))]
+fn load_wasm_plugin(
+  plugin_path: &str,
+  _config: &Config,
+  _options: String,
+) -> napi::Result<Arc<dyn farmfe_core::plugin::Plugin>> {
+  Err(napi::Error::new(
+    Status::GenericFailure,
+    format!("Can not load wasm plugin `{plugin_path}`: @farmfe/core is built without wasm plugin support"),
+  ))
 }
 
 #[napi(js_name = "Compiler")]
@@ -150,9 +300,19 @@ impl JsCompiler {
       let rust_plugin_path = rust_plugin[0].clone();
       let rust_plugin_options = rust_plugin[1].clone();
 
+      // rust plugins compiled to wasm32-wasip1 run in a sandbox instead of being loaded as dynamic libraries
+      if rust_plugin_path.ends_with(".wasm") {
+        plugins_adapters.push(load_wasm_plugin(
+          &rust_plugin_path,
+          &config,
+          rust_plugin_options,
+        )?);
+        continue;
+      }
+
       let rust_plugin = Arc::new(
         RustPluginAdapter::new(&rust_plugin_path, &config, rust_plugin_options)
-          .unwrap_or_else(|e| panic!("load rust plugin error: {e:?}")),
+          .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))?,
       ) as _;
       plugins_adapters.push(rust_plugin);
     }
@@ -292,54 +452,7 @@ impl JsCompiler {
         .map_err(|e| napi::Error::new(Status::GenericFailure, format!("{e}")))
       {
         Ok(res) => {
-          let js_update_result = JsUpdateResult {
-            added: res
-              .added_module_ids
-              .into_iter()
-              .map(|id| id.id(Mode::Development))
-              .collect(),
-            changed: res
-              .updated_module_ids
-              .into_iter()
-              .map(|id| id.id(Mode::Development))
-              .collect(),
-            removed: res
-              .removed_module_ids
-              .into_iter()
-              .map(|id| id.id(Mode::Development))
-              .collect(),
-            immutable_modules: res.immutable_resources,
-            mutable_modules: res.mutable_resources,
-            boundaries: res.boundaries,
-            dynamic_resources_map: res.dynamic_resources_map.map(|dynamic_resources_map| {
-              dynamic_resources_map
-                .into_iter()
-                .map(|(k, v)| {
-                  (
-                    k.id(context.config.mode),
-                    v.into_iter()
-                      .map(|(path, ty)| vec![path, ty.to_html_tag()])
-                      .collect(),
-                  )
-                })
-                .collect()
-            }),
-
-            extra_watch_result: WatchDiffResult {
-              add: res
-                .extra_watch_result
-                .add
-                .into_iter()
-                .map(|path| ModuleId::new(&path, "", &context.config.root).id(Mode::Development))
-                .collect(),
-              remove: res
-                .extra_watch_result
-                .remove
-                .into_iter()
-                .map(|path| ModuleId::new(&path, "", &context.config.root).id(Mode::Development))
-                .collect(),
-            },
-          };
+          let js_update_result = JsUpdateResult::from_update_result(res, &context.config);
 
           promise.resolve(Box::new(move |_| Ok(js_update_result)));
         }
@@ -370,15 +483,12 @@ impl JsCompiler {
 
   #[napi]
   pub fn has_module(&self, resolved_path: String) -> bool {
-    let context = self.compiler.context();
-    let module_graph = context.module_graph.read();
-    let watch_graph = context.watch_graph.read();
-    let module_id = ModuleId::new(&resolved_path, "", &context.config.root);
-    let module_ids_by_file = module_graph.module_ids_by_file(&module_id);
-
-    module_graph.has_module(&module_id)
-      || watch_graph.has_module(&module_id)
-      || !module_ids_by_file.is_empty()
+    self.compiler.has_module(&resolved_path)
+      || self
+        .compiler
+        .environments()
+        .iter()
+        .any(|(_, environment)| environment.has_module(&resolved_path))
   }
 
   #[napi]
@@ -424,6 +534,31 @@ impl JsCompiler {
     result
   }
 
+  /// Names of the environments in `compilation.environments`
+  #[napi]
+  pub fn environments(&self) -> Vec<String> {
+    self
+      .compiler
+      .environments()
+      .iter()
+      .map(|(name, _)| name.clone())
+      .collect()
+  }
+
+  #[napi]
+  pub fn environment_resources(&self, name: String) -> HashMap<String, Buffer> {
+    let Some(environment) = self.compiler.environment(&name) else {
+      return HashMap::default();
+    };
+    let resources = environment.context().resources_map.lock();
+
+    resources
+      .values()
+      .filter(|resource| !resource.emitted)
+      .map(|resource| (resource.name.clone(), resource.bytes.clone().into()))
+      .collect()
+  }
+
   #[napi]
   pub fn resources_map(&self, e: Env) -> HashMap<String, Unknown<'_>> {
     let context = self.compiler.context();
@@ -481,6 +616,14 @@ impl JsCompiler {
     context.stats.to_string()
   }
 
+  /// Json string of why the modules of the latest compilation are restored from the persistent cache or not,
+  /// and the build dependencies that selected the cache directory
+  #[napi]
+  pub fn cache_stats(&self) -> String {
+    let context = self.compiler.context();
+    context.stats.cache_stats_to_string()
+  }
+
   #[napi]
   pub fn invalidate_module(&self, module_id: String) {
     invalidate_module(self, module_id);
@@ -584,7 +727,10 @@ impl JsResolver {
     dynamic_extensions: Option<Vec<String>>,
   ) -> napi::Result<String> {
     let base_dir = PathBuf::from(base_dir);
-    let options = ResolveOptions { dynamic_extensions };
+    let options = ResolveOptions {
+      dynamic_extensions,
+      ..Default::default()
+    };
 
     let result = self
       .resolver
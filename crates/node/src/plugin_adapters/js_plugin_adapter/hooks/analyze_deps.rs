@@ -0,0 +1,80 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  module::{ModuleId, ModuleType},
+  plugin::{PluginAnalyzeDepsHookParam, PluginAnalyzeDepsHookResultEntry},
+  serde::{Deserialize, Serialize},
+};
+use napi::bindgen_prelude::FromNapiValue;
+
+use crate::{
+  new_js_plugin_hook,
+  plugin_adapters::js_plugin_adapter::module_hook_common::{
+    module_matches_filters, JsModuleHookFilters, ModuleHookFilters,
+  },
+  plugin_adapters::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct JsPluginAnalyzeDepsHookParams {
+  pub module_id: ModuleId,
+  pub module_type: ModuleType,
+  /// content of the module after load and transform
+  pub content: Arc<String>,
+  /// deps analyzed by previous plugins
+  pub deps: Vec<PluginAnalyzeDepsHookResultEntry>,
+}
+
+/// deps returned by the js plugin, they are appended to the deps analyzed by previous plugins
+pub type JsPluginAnalyzeDepsHookResult = Vec<PluginAnalyzeDepsHookResultEntry>;
+
+pub struct JsPluginAnalyzeDepsHook {
+  tsfn: ThreadSafeJsPluginHook,
+  pub(crate) filters: ModuleHookFilters,
+}
+
+impl JsPluginAnalyzeDepsHook {
+  new_js_plugin_hook!(
+    ModuleHookFilters,
+    JsModuleHookFilters,
+    JsPluginAnalyzeDepsHookParams,
+    JsPluginAnalyzeDepsHookResult
+  );
+
+  pub fn call(
+    &self,
+    param: &mut PluginAnalyzeDepsHookParam,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if !module_matches_filters(&param.module.id, &param.module.module_type, &self.filters) {
+      return Ok(None);
+    }
+
+    let Some(deps) = self
+      .tsfn
+      .call::<JsPluginAnalyzeDepsHookParams, JsPluginAnalyzeDepsHookResult>(
+        JsPluginAnalyzeDepsHookParams {
+          module_id: param.module.id.clone(),
+          module_type: param.module.module_type.clone(),
+          content: param.module.content.clone(),
+          deps: param.deps.clone(),
+        },
+        ctx,
+        None,
+      )?
+    else {
+      return Ok(None);
+    };
+
+    for dep in deps {
+      if !param.deps.contains(&dep) {
+        param.deps.push(dep);
+      }
+    }
+
+    Ok(Some(()))
+  }
+}
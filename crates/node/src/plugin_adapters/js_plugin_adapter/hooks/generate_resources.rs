@@ -0,0 +1,82 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  resource::{resource_pot::ResourcePot, Resource, ResourceOrigin, ResourceType},
+  serde::{Deserialize, Serialize},
+};
+use napi::bindgen_prelude::FromNapiValue;
+
+use crate::{
+  new_js_plugin_hook,
+  plugin_adapters::js_plugin_adapter::{
+    hooks::process_rendered_resource_pot::{
+      JsPluginProcessRenderedResourcePotHookFilters, JsResourcePot,
+      PluginProcessRenderedResourcePotHookFilters,
+    },
+    thread_safe_js_plugin_hook::ThreadSafeJsPluginHook,
+  },
+};
+
+/// Extra resource generated by js plugins for a resource pot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", crate = "farmfe_core::serde")]
+pub struct JsGeneratedResource {
+  pub name: String,
+  pub bytes: Vec<u8>,
+  pub resource_type: ResourceType,
+}
+
+pub type JsPluginGenerateResourcesHookResult = Vec<JsGeneratedResource>;
+
+pub struct JsPluginGenerateResourcesHook {
+  tsfn: ThreadSafeJsPluginHook,
+  filters: PluginProcessRenderedResourcePotHookFilters,
+}
+
+impl JsPluginGenerateResourcesHook {
+  new_js_plugin_hook!(
+    PluginProcessRenderedResourcePotHookFilters,
+    JsPluginProcessRenderedResourcePotHookFilters,
+    JsResourcePot,
+    JsPluginGenerateResourcesHookResult
+  );
+
+  /// Call the js hook with the rendered resource pot, the returned resources are emitted alongside the resources
+  /// generated by the builtin plugins. Return the emitted resources.
+  pub fn call(
+    &self,
+    resource_pot: &mut ResourcePot,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<Vec<Resource>>> {
+    if !self.filters.matches(resource_pot) {
+      return Ok(None);
+    }
+
+    let params = JsResourcePot::new(resource_pot, &ctx);
+    let Some(result) = self
+      .tsfn
+      .call::<JsResourcePot, JsPluginGenerateResourcesHookResult>(params, ctx, None)?
+    else {
+      return Ok(None);
+    };
+
+    Ok(Some(
+      result
+        .into_iter()
+        .map(|r| Resource {
+          name: r.name,
+          name_hash: resource_pot.modules_name_hash.clone(),
+          bytes: r.bytes,
+          emitted: false,
+          should_transform_output_filename: false,
+          resource_type: r.resource_type,
+          origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+          meta: Default::default(),
+          special_placeholders: Default::default(),
+        })
+        .collect(),
+    ))
+  }
+}
@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  config::config_regex::ConfigRegex,
+  context::CompilationContext,
+  error::Result,
+  module::ModuleId,
+  plugin::PluginHandleEntryResourceHookParam,
+  resource::{Resource, ResourceType},
+  serde::{Deserialize, Serialize},
+  HashMap,
+};
+use napi::bindgen_prelude::FromNapiValue;
+
+use crate::{
+  new_js_plugin_hook,
+  plugin_adapters::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook,
+};
+
+#[napi(object)]
+pub struct JsPluginHandleEntryResourceHookFilters {
+  pub module_ids: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct PluginHandleEntryResourceHookFilters {
+  pub module_ids: Vec<ConfigRegex>,
+}
+
+impl From<JsPluginHandleEntryResourceHookFilters> for PluginHandleEntryResourceHookFilters {
+  fn from(f: JsPluginHandleEntryResourceHookFilters) -> Self {
+    Self {
+      module_ids: f
+        .module_ids
+        .into_iter()
+        .map(|p| ConfigRegex::new(&p))
+        .collect(),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", crate = "farmfe_core::serde")]
+pub struct JsPluginHandleEntryResourceHookParams {
+  pub resource: Resource,
+  pub resource_sourcemap: Option<Resource>,
+  pub entry_module_id: ModuleId,
+  pub initial_resources: Vec<(String, ResourceType)>,
+  pub dynamic_resources: String,
+  pub dynamic_module_resources_map: String,
+  pub runtime_resource_name: String,
+  pub emit_runtime: bool,
+  pub additional_inject_resources: HashMap<String, Resource>,
+}
+
+impl From<&PluginHandleEntryResourceHookParam<'_>> for JsPluginHandleEntryResourceHookParams {
+  fn from(value: &PluginHandleEntryResourceHookParam) -> Self {
+    Self {
+      resource: value.resource.clone(),
+      resource_sourcemap: value.resource_sourcemap.clone(),
+      entry_module_id: value.entry_module_id.clone(),
+      initial_resources: value.initial_resources.clone(),
+      dynamic_resources: value.dynamic_resources.clone(),
+      dynamic_module_resources_map: value.dynamic_module_resources_map.clone(),
+      runtime_resource_name: value.runtime_resource_name.to_string(),
+      emit_runtime: value.emit_runtime,
+      additional_inject_resources: value.additional_inject_resources.clone(),
+    }
+  }
+}
+
+/// All fields are optional, only the returned fields are written back to [PluginHandleEntryResourceHookParam]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", crate = "farmfe_core::serde")]
+pub struct JsPluginHandleEntryResourceHookResult {
+  pub resource: Option<Resource>,
+  pub resource_sourcemap: Option<Resource>,
+  pub emit_runtime: Option<bool>,
+  pub additional_inject_resources: Option<HashMap<String, Resource>>,
+}
+
+pub struct JsPluginHandleEntryResourceHook {
+  tsfn: ThreadSafeJsPluginHook,
+  filters: PluginHandleEntryResourceHookFilters,
+}
+
+impl JsPluginHandleEntryResourceHook {
+  new_js_plugin_hook!(
+    PluginHandleEntryResourceHookFilters,
+    JsPluginHandleEntryResourceHookFilters,
+    JsPluginHandleEntryResourceHookParams,
+    JsPluginHandleEntryResourceHookResult
+  );
+
+  pub fn call(
+    &self,
+    param: &mut PluginHandleEntryResourceHookParam,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let entry_module_id = param.entry_module_id.to_string();
+
+    if !self
+      .filters
+      .module_ids
+      .iter()
+      .any(|f| f.is_match(&entry_module_id))
+    {
+      return Ok(None);
+    }
+
+    let Some(result) = self
+      .tsfn
+      .call::<JsPluginHandleEntryResourceHookParams, JsPluginHandleEntryResourceHookResult>(
+        (&*param).into(),
+        ctx,
+        None,
+      )?
+    else {
+      return Ok(None);
+    };
+
+    if let Some(resource) = result.resource {
+      param.resource = resource;
+    }
+
+    if let Some(resource_sourcemap) = result.resource_sourcemap {
+      param.resource_sourcemap = Some(resource_sourcemap);
+    }
+
+    if let Some(emit_runtime) = result.emit_runtime {
+      param.emit_runtime = emit_runtime;
+    }
+
+    if let Some(additional_inject_resources) = result.additional_inject_resources {
+      param
+        .additional_inject_resources
+        .extend(additional_inject_resources);
+    }
+
+    Ok(Some(()))
+  }
+}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  module::{ModuleId, ModuleType},
+  plugin::PluginHandlePersistentCachedCustomModuleHookParam,
+  serde::{Deserialize, Serialize},
+};
+use napi::bindgen_prelude::FromNapiValue;
+
+use crate::{
+  new_js_plugin_hook,
+  plugin_adapters::js_plugin_adapter::module_hook_common::{
+    module_matches_filters, JsModuleHookFilters, ModuleHookFilters,
+  },
+  plugin_adapters::js_plugin_adapter::thread_safe_js_plugin_hook::ThreadSafeJsPluginHook,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(crate = "farmfe_core::serde", rename_all = "camelCase")]
+pub struct JsPluginHandlePersistentCachedCustomModuleHookParams {
+  pub module_id: ModuleId,
+  pub module_type: ModuleType,
+  /// content of the module after load and transform
+  pub content: Arc<String>,
+}
+
+/// true if the cached module is stale and should be built again
+pub type JsPluginHandlePersistentCachedCustomModuleHookResult = bool;
+
+pub struct JsPluginHandlePersistentCachedCustomModuleHook {
+  tsfn: ThreadSafeJsPluginHook,
+  pub(crate) filters: ModuleHookFilters,
+}
+
+impl JsPluginHandlePersistentCachedCustomModuleHook {
+  new_js_plugin_hook!(
+    ModuleHookFilters,
+    JsModuleHookFilters,
+    JsPluginHandlePersistentCachedCustomModuleHookParams,
+    JsPluginHandlePersistentCachedCustomModuleHookResult
+  );
+
+  pub fn call(
+    &self,
+    param: &mut PluginHandlePersistentCachedCustomModuleHookParam,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<bool>> {
+    if !module_matches_filters(param.module_id, param.module_type, &self.filters) {
+      return Ok(None);
+    }
+
+    self.tsfn.call::<
+      JsPluginHandlePersistentCachedCustomModuleHookParams,
+      JsPluginHandlePersistentCachedCustomModuleHookResult,
+    >(
+      JsPluginHandlePersistentCachedCustomModuleHookParams {
+        module_id: param.module_id.clone(),
+        module_type: param.module_type.clone(),
+        content: param.content.clone(),
+      },
+      ctx,
+      None,
+    )
+  }
+}
@@ -1,13 +1,18 @@
+pub mod analyze_deps;
 pub mod augment_resource_hash;
 pub mod build_end;
 pub mod build_start;
 pub mod finalize_resources;
 pub mod finish;
 pub mod freeze_module;
+pub mod generate_resources;
+pub mod handle_entry_resource;
+pub mod handle_persistent_cached_custom_module;
 pub mod load;
 pub mod plugin_cache_loaded;
 pub mod process_module;
 pub mod process_rendered_resource_pot;
+pub mod render_resource_pot;
 pub mod render_start;
 pub mod resolve;
 pub mod transform;
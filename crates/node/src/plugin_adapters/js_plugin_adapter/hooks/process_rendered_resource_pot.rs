@@ -31,6 +31,19 @@ pub struct PluginProcessRenderedResourcePotHookFilters {
   pub module_ids: Vec<ConfigRegex>,
 }
 
+impl PluginProcessRenderedResourcePotHookFilters {
+  /// check the filters before the resource pot is converted to [JsResourcePot], as codegen is expensive
+  pub fn matches(&self, resource_pot: &ResourcePot) -> bool {
+    self.resource_pot_types.contains(&resource_pot.resource_pot_type)
+      || self.module_ids.iter().any(|f| {
+        resource_pot
+          .modules()
+          .iter()
+          .any(|id| f.is_match(&id.to_string()))
+      })
+  }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase", crate = "farmfe_core::serde")]
 pub struct JsResourcePot {
@@ -88,6 +101,25 @@ impl JsResourcePot {
       is_entry: resource_pot.entry_module.is_some(),
     }
   }
+
+  /// Create a [JsResourcePot] for a resource pot that is not rendered yet, `content` is empty
+  pub fn new_unrendered(resource_pot: &ResourcePot) -> Self {
+    Self {
+      id: resource_pot.id.clone(),
+      name: resource_pot.name.clone(),
+      resource_pot_type: resource_pot.resource_pot_type.clone(),
+      module_ids: resource_pot
+        .modules()
+        .iter()
+        .map(|m| m.to_string())
+        .collect(),
+      custom: Default::default(),
+      content: String::new(),
+      source_map_chain: vec![],
+      is_dynamic_entry: resource_pot.is_dynamic_entry,
+      is_entry: resource_pot.entry_module.is_some(),
+    }
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::Result,
+  resource::{
+    meta_data::{
+      css::CssResourcePotMetaData, html::HtmlResourcePotMetaData, js::JsResourcePotMetaData,
+      ResourcePotMetaData,
+    },
+    resource_pot::{ResourcePot, ResourcePotType},
+  },
+  serde::{Deserialize, Serialize},
+  swc_common::Globals,
+  swc_ecma_parser::Syntax,
+};
+use farmfe_toolkit::{
+  css::{parse_css_stylesheet, ParseCssModuleResult},
+  html::parse_html_document,
+  script::{parse_module, swc_try_with::resolve_module_mark, ParseScriptModuleResult},
+};
+use napi::bindgen_prelude::FromNapiValue;
+
+use crate::{
+  new_js_plugin_hook,
+  plugin_adapters::js_plugin_adapter::{
+    hooks::process_rendered_resource_pot::{
+      JsPluginProcessRenderedResourcePotHookFilters, JsResourcePot,
+      PluginProcessRenderedResourcePotHookFilters,
+    },
+    thread_safe_js_plugin_hook::ThreadSafeJsPluginHook,
+  },
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", crate = "farmfe_core::serde")]
+pub struct JsPluginRenderResourcePotHookResult {
+  pub content: String,
+}
+
+pub struct JsPluginRenderResourcePotHook {
+  tsfn: ThreadSafeJsPluginHook,
+  filters: PluginProcessRenderedResourcePotHookFilters,
+}
+
+impl JsPluginRenderResourcePotHook {
+  new_js_plugin_hook!(
+    PluginProcessRenderedResourcePotHookFilters,
+    JsPluginProcessRenderedResourcePotHookFilters,
+    JsResourcePot,
+    JsPluginRenderResourcePotHookResult
+  );
+
+  pub fn call(
+    &self,
+    resource_pot: &ResourcePot,
+    ctx: Arc<CompilationContext>,
+  ) -> Result<Option<ResourcePotMetaData>> {
+    if !self.filters.matches(resource_pot) {
+      return Ok(None);
+    }
+
+    let Some(result) = self
+      .tsfn
+      .call::<JsResourcePot, JsPluginRenderResourcePotHookResult>(
+        JsResourcePot::new_unrendered(resource_pot),
+        ctx.clone(),
+        None,
+      )?
+    else {
+      return Ok(None);
+    };
+
+    parse_rendered_content(resource_pot, result.content, &ctx).map(Some)
+  }
+}
+
+/// parse the content rendered by js plugins to [ResourcePotMetaData] so that following hooks can handle it like other resource pots
+fn parse_rendered_content(
+  resource_pot: &ResourcePot,
+  content: String,
+  context: &Arc<CompilationContext>,
+) -> Result<ResourcePotMetaData> {
+  Ok(match resource_pot.resource_pot_type {
+    ResourcePotType::DynamicEntryJs | ResourcePotType::Js => {
+      let ParseScriptModuleResult {
+        mut ast,
+        comments,
+        source_map,
+      } = parse_module(
+        &resource_pot.id.as_str().into(),
+        Arc::new(content),
+        Syntax::Es(Default::default()),
+        Default::default(),
+      )?;
+
+      let globals = Globals::new();
+      let (unresolved_mark, top_level_mark) = resolve_module_mark(&mut ast, false, &globals);
+
+      context
+        .meta
+        .set_resource_pot_source_map(&resource_pot.id, source_map);
+      context
+        .meta
+        .set_resource_pot_globals(&resource_pot.id, globals);
+
+      ResourcePotMetaData::Js(JsResourcePotMetaData {
+        ast,
+        comments: comments.into(),
+        top_level_mark: top_level_mark.as_u32(),
+        unresolved_mark: unresolved_mark.as_u32(),
+        rendered_modules: resource_pot.modules().into_iter().cloned().collect(),
+        ..Default::default()
+      })
+    }
+    ResourcePotType::Css => {
+      let ParseCssModuleResult {
+        ast,
+        comments,
+        source_map,
+      } = parse_css_stylesheet(&resource_pot.id, Arc::new(content))?;
+
+      context
+        .meta
+        .set_resource_pot_source_map(&resource_pot.id, source_map);
+
+      ResourcePotMetaData::Css(CssResourcePotMetaData {
+        ast,
+        comments: comments.into(),
+        custom: Default::default(),
+      })
+    }
+    ResourcePotType::Html => ResourcePotMetaData::Html(HtmlResourcePotMetaData {
+      ast: parse_html_document(&resource_pot.id, Arc::new(content))?,
+      custom: Default::default(),
+    }),
+    ResourcePotType::Custom(_) => {
+      unreachable!("custom resource pot type can not be handled by js plugins")
+    }
+  })
+}
@@ -5,14 +5,21 @@ use farmfe_core::{
   context::CompilationContext,
   error::{CompilationError, Result},
   module::ModuleType,
+  parking_lot::Mutex,
   plugin::{
-    EmptyPluginHookParam, Plugin, PluginFinalizeResourcesHookParam, PluginHookContext,
-    PluginLoadHookParam, PluginLoadHookResult, PluginResolveHookParam, PluginResolveHookResult,
+    EmptyPluginHookParam, GeneratedResource, Plugin, PluginAnalyzeDepsHookParam,
+    PluginFinalizeResourcesHookParam, PluginGenerateResourcesHookResult,
+    PluginHandleEntryResourceHookParam, PluginHookContext, PluginLoadHookParam,
+    PluginLoadHookResult, PluginResolveHookParam, PluginResolveHookResult,
     PluginTransformHookParam, PluginTransformHookResult, DEFAULT_PRIORITY,
   },
-  resource::{resource_pot::ResourcePotType, Resource, ResourceOrigin, ResourceType},
+  resource::{
+    meta_data::ResourcePotMetaData,
+    resource_pot::{ResourcePot, ResourcePotId, ResourcePotType},
+    Resource, ResourceOrigin, ResourceType,
+  },
   swc_ecma_parser::Syntax,
-  HashSet,
+  HashMap, HashSet,
 };
 use farmfe_toolkit::{
   css::{parse_css_stylesheet, ParseCssModuleResult},
@@ -30,16 +37,21 @@ use crate::{
 };
 
 use self::hooks::{
+  analyze_deps::JsPluginAnalyzeDepsHook,
   augment_resource_hash::JsPluginAugmentResourceHashHook,
   build_end::JsPluginBuildEndHook,
   build_start::JsPluginBuildStartHook,
   finalize_resources::JsPluginFinalizeResourcesHook,
   finish::JsPluginFinishHook,
   freeze_module::JsPluginFreezeModuleHook,
+  generate_resources::JsPluginGenerateResourcesHook,
+  handle_entry_resource::JsPluginHandleEntryResourceHook,
+  handle_persistent_cached_custom_module::JsPluginHandlePersistentCachedCustomModuleHook,
   load::JsPluginLoadHook,
   plugin_cache_loaded::JsPluginPluginCacheLoadedHook,
   process_module::JsPluginProcessModuleHook,
   process_rendered_resource_pot::JsPluginProcessRenderedResourcePotHook,
+  render_resource_pot::JsPluginRenderResourcePotHook,
   render_start::JsPluginRenderStartHook,
   resolve::JsPluginResolveHook,
   transform::JsPluginTransformHook,
@@ -54,7 +66,7 @@ use self::hooks::{
 pub mod context;
 mod context_methods;
 mod hooks;
-mod module_hook_common;
+pub(crate) mod module_hook_common;
 mod thread_safe_js_plugin_hook;
 
 pub struct JsPluginAdapter {
@@ -77,6 +89,15 @@ pub struct JsPluginAdapter {
   js_update_finished_hook: Option<JsPluginUpdateFinishedHook>,
   js_process_module_hook: Option<JsPluginProcessModuleHook>,
   js_freeze_module_hook: Option<JsPluginFreezeModuleHook>,
+  js_analyze_deps_hook: Option<JsPluginAnalyzeDepsHook>,
+  js_render_resource_pot_hook: Option<JsPluginRenderResourcePotHook>,
+  js_generate_resources_hook: Option<JsPluginGenerateResourcesHook>,
+  js_handle_entry_resource_hook: Option<JsPluginHandleEntryResourceHook>,
+  /// Resources returned by the `generateResources` hook of each resource pot, they are appended to the
+  /// generated resources in `process_generated_resources` so that they are cached with the resource pot
+  js_generated_resources: Mutex<HashMap<ResourcePotId, Vec<Resource>>>,
+  js_handle_persistent_cached_custom_module_hook:
+    Option<JsPluginHandlePersistentCachedCustomModuleHook>,
 }
 
 impl JsPluginAdapter {
@@ -113,6 +134,16 @@ impl JsPluginAdapter {
       get_named_property::<Object>(env, &js_plugin_object, "processModule").ok();
     let freeze_module_obj =
       get_named_property::<Object>(env, &js_plugin_object, "freezeModule").ok();
+    let analyze_deps_obj = get_named_property::<Object>(env, &js_plugin_object, "analyzeDeps").ok();
+    let render_resource_pot_obj =
+      get_named_property::<Object>(env, &js_plugin_object, "renderResourcePot").ok();
+    let generate_resources_obj =
+      get_named_property::<Object>(env, &js_plugin_object, "generateResources").ok();
+    let handle_entry_resource_obj =
+      get_named_property::<Object>(env, &js_plugin_object, "handleEntryResource").ok();
+    let handle_persistent_cached_custom_module_obj =
+      get_named_property::<Object>(env, &js_plugin_object, "handlePersistentCachedCustomModule")
+        .ok();
 
     Ok(Self {
       name,
@@ -143,6 +174,16 @@ impl JsPluginAdapter {
       js_process_module_hook: process_module_obj
         .map(|obj| JsPluginProcessModuleHook::new(env, obj)),
       js_freeze_module_hook: freeze_module_obj.map(|obj| JsPluginFreezeModuleHook::new(env, obj)),
+      js_analyze_deps_hook: analyze_deps_obj.map(|obj| JsPluginAnalyzeDepsHook::new(env, obj)),
+      js_render_resource_pot_hook: render_resource_pot_obj
+        .map(|obj| JsPluginRenderResourcePotHook::new(env, obj)),
+      js_generate_resources_hook: generate_resources_obj
+        .map(|obj| JsPluginGenerateResourcesHook::new(env, obj)),
+      js_handle_entry_resource_hook: handle_entry_resource_obj
+        .map(|obj| JsPluginHandleEntryResourceHook::new(env, obj)),
+      js_generated_resources: Mutex::new(HashMap::default()),
+      js_handle_persistent_cached_custom_module_hook: handle_persistent_cached_custom_module_obj
+        .map(|obj| JsPluginHandlePersistentCachedCustomModuleHook::new(env, obj)),
     })
   }
 
@@ -280,6 +321,23 @@ impl Plugin for JsPluginAdapter {
     Ok(None)
   }
 
+  fn analyze_deps(
+    &self,
+    param: &mut PluginAnalyzeDepsHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if self.is_internal_virtual_module(&param.module.id.to_string()) {
+      return Ok(None);
+    }
+
+    if let Some(ref js_analyze_deps_hook) = self.js_analyze_deps_hook {
+      check_module_filters!(self.name, "analyze_deps", &js_analyze_deps_hook.filters);
+      return js_analyze_deps_hook.call(param, context.clone());
+    }
+
+    Ok(None)
+  }
+
   fn freeze_module(
     &self,
     param: &mut farmfe_core::plugin::PluginFreezeModuleHookParam,
@@ -365,6 +423,23 @@ impl Plugin for JsPluginAdapter {
     }
   }
 
+  fn render_resource_pot(
+    &self,
+    resource_pot: &ResourcePot,
+    context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<ResourcePotMetaData>> {
+    if matches!(resource_pot.resource_pot_type, ResourcePotType::Custom(_)) {
+      return Ok(None);
+    }
+
+    if let Some(js_render_resource_pot_hook) = &self.js_render_resource_pot_hook {
+      js_render_resource_pot_hook.call(resource_pot, context.clone())
+    } else {
+      Ok(None)
+    }
+  }
+
   fn process_rendered_resource_pot(
     &self,
     resource_pot: &mut farmfe_core::resource::resource_pot::ResourcePot,
@@ -469,6 +544,68 @@ impl Plugin for JsPluginAdapter {
     }
   }
 
+  fn generate_resources(
+    &self,
+    resource_pot: &mut ResourcePot,
+    context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginGenerateResourcesHookResult>> {
+    if matches!(resource_pot.resource_pot_type, ResourcePotType::Custom(_)) {
+      return Ok(None);
+    }
+
+    if let Some(js_generate_resources_hook) = &self.js_generate_resources_hook
+      && let Some(resources) = js_generate_resources_hook.call(resource_pot, context.clone())?
+    {
+      // resources returned by js plugins are extra resources of the resource pot,
+      // return None so that the builtin plugins still generate the main resources
+      self
+        .js_generated_resources
+        .lock()
+        .insert(resource_pot.id.clone(), resources);
+    }
+
+    Ok(None)
+  }
+
+  fn process_generated_resources(
+    &self,
+    resources: &mut PluginGenerateResourcesHookResult,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let Some(ResourceOrigin::ResourcePot(resource_pot_id)) = resources
+      .resources
+      .first()
+      .map(|generated| &generated.resource.origin)
+    else {
+      return Ok(None);
+    };
+    let Some(js_resources) = self.js_generated_resources.lock().remove(resource_pot_id) else {
+      return Ok(None);
+    };
+
+    resources
+      .resources
+      .extend(js_resources.into_iter().map(|resource| GeneratedResource {
+        resource,
+        source_map: None,
+      }));
+
+    Ok(Some(()))
+  }
+
+  fn handle_entry_resource(
+    &self,
+    param: &mut PluginHandleEntryResourceHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if let Some(js_handle_entry_resource_hook) = &self.js_handle_entry_resource_hook {
+      js_handle_entry_resource_hook.call(param, context.clone())
+    } else {
+      Ok(None)
+    }
+  }
+
   fn finalize_resources(
     &self,
     params: &mut PluginFinalizeResourcesHookParam,
@@ -504,6 +641,23 @@ impl Plugin for JsPluginAdapter {
     Ok(Some(()))
   }
 
+  fn handle_persistent_cached_custom_module(
+    &self,
+    param: &mut farmfe_core::plugin::PluginHandlePersistentCachedCustomModuleHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<bool>> {
+    if let Some(ref js_hook) = self.js_handle_persistent_cached_custom_module_hook {
+      check_module_filters!(
+        self.name,
+        "handle_persistent_cached_custom_module",
+        &js_hook.filters
+      );
+      return js_hook.call(param, context.clone());
+    }
+
+    Ok(None)
+  }
+
   fn write_plugin_cache(&self, context: &Arc<CompilationContext>) -> Result<Option<Vec<u8>>> {
     if let Some(js_write_plugin_cache_hook) = &self.js_write_plugin_cache {
       js_write_plugin_cache_hook.call(context.clone())
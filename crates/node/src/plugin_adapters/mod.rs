@@ -1,2 +1,4 @@
 pub mod js_plugin_adapter;
 pub mod rust_plugin_adapter;
+#[cfg(feature = "wasm_plugin")]
+pub mod wasm_plugin_adapter;
@@ -3,7 +3,7 @@ use std::{mem::ManuallyDrop, sync::Arc};
 use farmfe_core::{
   config::Config,
   context::CompilationContext,
-  error::{CompilationError, Result},
+  error::Result,
   module::{module_graph::ModuleGraph, ModuleId, ModuleMetaData},
   plugin::{
     Plugin, PluginFinalizeResourcesHookParam, PluginGenerateResourcesHookResult, PluginHookContext,
@@ -34,11 +34,7 @@ pub struct RustPluginAdapter {
 
 impl RustPluginAdapter {
   pub fn new(plugin_path: &String, config: &Config, options: String) -> Result<Self> {
-    let (plugin, _lib) = unsafe {
-      load_rust_plugin(plugin_path, config, options).map_err(|e| {
-        CompilationError::GenericError(format!("Load rust plugin {plugin_path} failed. {e:?}"))
-      })?
-    };
+    let (plugin, _lib) = unsafe { load_rust_plugin(plugin_path, config, options)? };
 
     Ok(Self {
       plugin,
@@ -319,4 +315,14 @@ impl Plugin for RustPluginAdapter {
   ) -> Result<Option<bool>> {
     self.plugin.handle_persistent_cached_module(module, context)
   }
+
+  fn handle_persistent_cached_custom_module(
+    &self,
+    param: &mut farmfe_core::plugin::PluginHandlePersistentCachedCustomModuleHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<bool>> {
+    self
+      .plugin
+      .handle_persistent_cached_custom_module(param, context)
+  }
 }
@@ -1,39 +1,65 @@
-use core::panic;
-use std::{ffi::OsStr, sync::Arc};
+use std::{
+  ffi::OsStr,
+  panic::{catch_unwind, AssertUnwindSafe},
+  sync::Arc,
+};
 
-use farmfe_core::{config::Config, plugin::Plugin, VERSION};
+use farmfe_core::{
+  config::Config,
+  error::{CompilationError, Result},
+  plugin::Plugin,
+  VERSION,
+};
 
-use libloading::{Error, Library, Symbol};
+use libloading::{Library, Symbol};
 
 /// load rust plugin from the specified path
 ///
 /// # Safety
-/// The plugin is loaded as a dynamic library and it may be unsafe. We use core_version to control the compatibility.
+/// The plugin is loaded as a dynamic library and it may be unsafe. The plugin and the core exchange rust types
+/// that are not ffi-stable, so we require the plugin to be built against exactly the same core version.
 pub unsafe fn load_rust_plugin<P: AsRef<OsStr> + std::fmt::Display>(
   filename: P,
   config: &Config,
   options: String,
-) -> Result<(Arc<dyn Plugin>, Library), Error> {
+) -> Result<(Arc<dyn Plugin>, Library)> {
   type PluginCreate = unsafe fn(config: &Config, options: String) -> Arc<dyn Plugin>;
 
-  let lib = Library::new(filename.as_ref()).unwrap();
-  let core_version_fn: Symbol<unsafe fn() -> String> = lib.get(b"_core_version")?;
+  let lib = Library::new(filename.as_ref()).map_err(|e| {
+    CompilationError::GenericError(format!(
+      "Failed to load rust plugin `{filename}` as a dynamic library: {e}"
+    ))
+  })?;
+
+  let core_version_fn: Symbol<unsafe fn() -> String> = lib.get(b"_core_version").map_err(|e| {
+    CompilationError::GenericError(format!(
+      "Invalid rust plugin `{filename}`: `_core_version` is not exported, make sure the plugin is marked with #[farm_plugin]. {e}"
+    ))
+  })?;
   let core_version = core_version_fn();
 
   if core_version != VERSION {
-    panic!(
-      "\nIncompatible Rust Plugin: Current core's version({}) is not compatible with the plugin version(`{:?}`), plugin path: ({}).
-Please upgrade or downgrade the plugin or @farmfe/core to make the versions match. Version maps:
-  plugin core 0.4 -> @farmfe/core < 1.3.0
-  plugin core 0.5 -> @farmfe/core >= 1.3.0
-
-If you are plugin author, please build your plugin with rust toolchain `nightly-2024-10-07`\n",
-      VERSION, core_version, filename
-    );
+    return Err(CompilationError::GenericError(format!(
+      "\nIncompatible Rust Plugin: Current core's version({VERSION}) is not the same as the plugin core version(`{core_version}`), plugin path: ({filename}).
+Please upgrade or downgrade the plugin or @farmfe/core to make the versions the same.
+
+If you are plugin author, please rebuild your plugin against the latest farmfe_core with the rust toolchain in rust-toolchain.toml of farm\n"
+    )));
   }
 
-  let constructor: Symbol<PluginCreate> = lib.get(b"_plugin_create")?;
-  let plugin = constructor(config, options);
+  let constructor: Symbol<PluginCreate> = lib.get(b"_plugin_create").map_err(|e| {
+    CompilationError::GenericError(format!(
+      "Invalid rust plugin `{filename}`: `_plugin_create` is not exported, make sure the plugin is marked with #[farm_plugin]. {e}"
+    ))
+  })?;
+  let plugin = catch_unwind(AssertUnwindSafe(|| constructor(config, options))).map_err(|e| {
+    let msg = e
+      .downcast_ref::<String>()
+      .map(|s| s.as_str())
+      .or_else(|| e.downcast_ref::<&str>().copied())
+      .unwrap_or("unknown error");
+    CompilationError::GenericError(format!("Failed to create rust plugin `{filename}`: {msg}"))
+  })?;
 
   Ok((plugin, lib))
 }
@@ -0,0 +1,305 @@
+use std::sync::{Arc, Mutex};
+
+use farmfe_core::{
+  config::Config,
+  context::CompilationContext,
+  encode_wasm_plugin_message,
+  error::{CompilationError, Result},
+  plugin::{
+    wasm::{
+      decode_wasm_plugin_message, WasmPluginHook, WasmPluginLoadHookParam,
+      WasmPluginLoadHookResult, WasmPluginProcessModuleHookParam,
+      WasmPluginProcessModuleHookResult, WasmPluginResolveHookParam, WasmPluginResolveHookResult,
+      WasmPluginTransformHookParam, WasmPluginTransformHookResult,
+    },
+    Plugin, PluginHookContext, PluginLoadHookParam, PluginLoadHookResult,
+    PluginProcessModuleHookParam, PluginResolveHookParam, PluginResolveHookResult,
+    PluginTransformHookParam, PluginTransformHookResult,
+  },
+};
+
+use self::plugin_instance::WasmPluginInstance;
+use crate::plugin_adapters::js_plugin_adapter::module_hook_common::{
+  convert_code_to_metadata, format_module_metadata_to_code, ModuleHookResult,
+};
+
+pub mod plugin_instance;
+
+/// Adapter of rust plugins compiled to `wasm32-wasip1`, loaded when the plugin path ends with `.wasm`.
+/// The wasm instance is single threaded, so hook calls of the same plugin are serialized.
+pub struct WasmPluginAdapter {
+  name: String,
+  priority: i32,
+  hooks: u32,
+  instance: Mutex<WasmPluginInstance>,
+}
+
+impl WasmPluginAdapter {
+  pub fn new(plugin_path: &str, config: &Config, options: String) -> Result<Self> {
+    let (instance, info) = WasmPluginInstance::new(plugin_path, config, options)?;
+
+    Ok(Self {
+      name: info.name,
+      priority: info.priority,
+      hooks: info.hooks,
+      instance: Mutex::new(instance),
+    })
+  }
+
+  fn call_hook<T>(&self, hook: WasmPluginHook, param: Vec<u8>) -> Result<Option<T>>
+  where
+    T: farmfe_core::rkyv::Archive,
+    T::Archived: for<'a> farmfe_core::rkyv::bytecheck::CheckBytes<
+        farmfe_core::rkyv::api::high::HighValidator<'a, farmfe_core::rkyv::rancor::Error>,
+      > + farmfe_core::rkyv::Deserialize<
+        T,
+        farmfe_core::rkyv::api::high::HighDeserializer<farmfe_core::rkyv::rancor::Error>,
+      >,
+  {
+    let hook_error = |e: String| {
+      CompilationError::GenericError(format!("[{}] {hook:?} hook failed: {e}", self.name))
+    };
+    let result = self
+      .instance
+      .lock()
+      .map_err(|_| {
+        hook_error("the wasm instance is poisoned by a previous failed call".to_string())
+      })?
+      .call(hook, &param)
+      .map_err(|e| hook_error(e.to_string()))?;
+
+    result
+      .map(|bytes| decode_wasm_plugin_message::<T>(&bytes).map_err(hook_error))
+      .transpose()
+  }
+
+  fn has_hook(&self, hook: WasmPluginHook) -> bool {
+    self.hooks & hook.bit() != 0
+  }
+}
+
+impl Plugin for WasmPluginAdapter {
+  fn name(&self) -> &str {
+    &self.name
+  }
+
+  fn priority(&self) -> i32 {
+    self.priority
+  }
+
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    _context: &Arc<CompilationContext>,
+    hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginResolveHookResult>> {
+    if !self.has_hook(WasmPluginHook::Resolve) {
+      return Ok(None);
+    }
+
+    let param = WasmPluginResolveHookParam {
+      source: param.source.clone(),
+      importer: param.importer.as_ref().map(|i| i.to_string()),
+      kind: param.kind.clone(),
+      caller: hook_context.caller.clone(),
+      meta: hook_context.meta.clone(),
+    };
+
+    Ok(
+      self
+        .call_hook::<WasmPluginResolveHookResult>(
+          WasmPluginHook::Resolve,
+          encode_wasm_plugin_message!(&param),
+        )?
+        .map(Into::into),
+    )
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> Result<Option<PluginLoadHookResult>> {
+    if !self.has_hook(WasmPluginHook::Load) {
+      return Ok(None);
+    }
+
+    let param = WasmPluginLoadHookParam {
+      module_id: param.module_id.clone(),
+      resolved_path: param.resolved_path.to_string(),
+      query: param.query.clone(),
+      meta: param.meta.clone(),
+    };
+
+    Ok(
+      self
+        .call_hook::<WasmPluginLoadHookResult>(
+          WasmPluginHook::Load,
+          encode_wasm_plugin_message!(&param),
+        )?
+        .map(Into::into),
+    )
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginTransformHookResult>> {
+    if !self.has_hook(WasmPluginHook::Transform) {
+      return Ok(None);
+    }
+
+    let param = WasmPluginTransformHookParam {
+      module_id: param.module_id.clone(),
+      content: param.content.clone(),
+      module_type: param.module_type.clone(),
+      resolved_path: param.resolved_path.to_string(),
+      query: param.query.clone(),
+      meta: param.meta.clone(),
+      source_map_chain: param
+        .source_map_chain
+        .iter()
+        .map(|s| s.to_string())
+        .collect(),
+    };
+
+    Ok(
+      self
+        .call_hook::<WasmPluginTransformHookResult>(
+          WasmPluginHook::Transform,
+          encode_wasm_plugin_message!(&param),
+        )?
+        .map(Into::into),
+    )
+  }
+
+  fn process_module(
+    &self,
+    param: &mut PluginProcessModuleHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if !self.has_hook(WasmPluginHook::ProcessModule) {
+      return Ok(None);
+    }
+
+    let Some(content) =
+      format_module_metadata_to_code(param.meta, param.module_id, param.source_map_chain, context)?
+    else {
+      return Ok(None);
+    };
+
+    let wasm_param = WasmPluginProcessModuleHookParam {
+      module_id: param.module_id.to_string(),
+      module_type: param.module_type.clone(),
+      content,
+      source_map_chain: param
+        .source_map_chain
+        .iter()
+        .map(|s| s.to_string())
+        .collect(),
+    };
+
+    let Some(result) = self.call_hook::<WasmPluginProcessModuleHookResult>(
+      WasmPluginHook::ProcessModule,
+      encode_wasm_plugin_message!(&wasm_param),
+    )?
+    else {
+      return Ok(None);
+    };
+
+    convert_code_to_metadata(
+      param.module_id,
+      param.module_type,
+      param.meta,
+      ModuleHookResult {
+        content: result.content,
+        source_map: result.source_map,
+        ignore_previous_source_map: Some(result.ignore_previous_source_map),
+      },
+      param.source_map_chain,
+      context,
+    )?;
+
+    Ok(Some(()))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::{path::PathBuf, process::Command, sync::Arc};
+
+  use farmfe_core::{
+    config::Config,
+    context::CompilationContext,
+    module::ModuleType,
+    plugin::{Plugin, PluginHookContext, PluginLoadHookParam},
+  };
+
+  use super::WasmPluginAdapter;
+
+  /// Build `tests/fixtures/wasm_plugin` for `wasm32-wasip1`, the target should be installed by
+  /// `rustup target add wasm32-wasip1`
+  fn build_fixture_plugin(fixture: &PathBuf) -> PathBuf {
+    let status = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string()))
+      .args(["build", "--release", "--target", "wasm32-wasip1"])
+      .current_dir(fixture)
+      .status()
+      .expect("failed to run cargo");
+    assert!(
+      status.success(),
+      "failed to build the wasm plugin fixture, make sure the wasm32-wasip1 target is installed"
+    );
+
+    fixture.join("target/wasm32-wasip1/release/farmfe_wasm_plugin_fixture.wasm")
+  }
+
+  #[test]
+  fn load_wasm_plugin() {
+    let fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/wasm_plugin");
+    let plugin_path = build_fixture_plugin(&fixture);
+    let root = fixture.join("project");
+
+    let config = Config {
+      root: root.to_string_lossy().to_string(),
+      ..Default::default()
+    };
+    let plugin =
+      WasmPluginAdapter::new(plugin_path.to_str().unwrap(), &config, "{}".to_string()).unwrap();
+    assert_eq!(plugin.name(), "FarmPluginTxt");
+
+    let context = Arc::new(CompilationContext::new(config, vec![]).unwrap());
+    let load = |resolved_path: &str| {
+      plugin.load(
+        &PluginLoadHookParam {
+          module_id: resolved_path.to_string(),
+          resolved_path,
+          query: vec![],
+          meta: Default::default(),
+        },
+        &context,
+        &PluginHookContext::default(),
+      )
+    };
+
+    // files under the root are readable inside the sandbox
+    let hello = root.join("hello.txt");
+    let result = load(hello.to_str().unwrap()).unwrap().unwrap();
+    assert_eq!(
+      result.content,
+      "export default \"hello from wasm plugin\\n\";"
+    );
+    assert_eq!(result.module_type, ModuleType::Js);
+
+    // other files are skipped
+    assert!(load(root.join("index.ts").to_str().unwrap())
+      .unwrap()
+      .is_none());
+
+    // files outside of the root are not preopened, the error of the plugin is reported as a hook error
+    let outside = fixture.join("outside.txt");
+    let err = load(outside.to_str().unwrap()).unwrap_err().to_string();
+    assert!(err.contains("[FarmPluginTxt] Load hook failed"), "{err}");
+  }
+}
@@ -0,0 +1,200 @@
+use std::sync::LazyLock;
+
+use farmfe_core::{
+  config::Config,
+  encode_wasm_plugin_message,
+  error::{CompilationError, Result},
+  plugin::{
+    abi::{PluginAbiVersion, PLUGIN_ABI_VERSION},
+    wasm::{
+      decode_wasm_plugin_message, unpack_ptr_len, WasmPluginCreateParam, WasmPluginHook,
+      WasmPluginHookResponse, WasmPluginInfo, WASM_PLUGIN_ABI_VERSION_EXPORT,
+      WASM_PLUGIN_ALLOC_EXPORT, WASM_PLUGIN_CALL_EXPORT, WASM_PLUGIN_CREATE_EXPORT,
+      WASM_PLUGIN_DEALLOC_EXPORT,
+    },
+  },
+  serde_json,
+};
+use wasmer::{Instance, Memory, Module, Store, TypedFunction};
+use wasmer_wasix::{
+  virtual_fs::{host_fs, FileSystem},
+  WasiEnv,
+};
+
+/// The wasix environment needs a tokio context to drive its task manager, share one runtime across wasm plugins
+static WASM_PLUGIN_RT: LazyLock<tokio::runtime::Runtime> = LazyLock::new(|| {
+  tokio::runtime::Builder::new_multi_thread()
+    .worker_threads(1)
+    .thread_name("farm-wasm-plugin")
+    .enable_all()
+    .build()
+    .expect("failed to create farm wasm plugin tokio runtime")
+});
+
+/// A instantiated wasm plugin. The instance is sandboxed by wasi: only the project root is preopened, read-only and at
+/// the same path as the host, so the plugin can read the resolved paths passed through hook params. No env vars or args
+/// are exposed.
+pub struct WasmPluginInstance {
+  store: Store,
+  memory: Memory,
+  alloc: TypedFunction<u32, u32>,
+  dealloc: TypedFunction<(u32, u32), ()>,
+  call: TypedFunction<(u32, u32, u32), u64>,
+}
+
+impl WasmPluginInstance {
+  pub fn new(
+    plugin_path: &str,
+    config: &Config,
+    options: String,
+  ) -> Result<(Self, WasmPluginInfo)> {
+    let error = |msg: String| {
+      CompilationError::GenericError(format!("Failed to load wasm plugin `{plugin_path}`: {msg}"))
+    };
+    let _guard = WASM_PLUGIN_RT.enter();
+
+    let bytes = std::fs::read(plugin_path).map_err(|e| error(e.to_string()))?;
+    let mut store = Store::default();
+    let module = Module::new(&store, bytes).map_err(|e| error(e.to_string()))?;
+
+    let host_fs: Box<dyn FileSystem + Send + Sync> = Box::new(
+      host_fs::FileSystem::new(WASM_PLUGIN_RT.handle().clone(), "/")
+        .map_err(|e| error(e.to_string()))?,
+    );
+    let mut wasi_env = WasiEnv::builder("farm-wasm-plugin")
+      .fs(host_fs)
+      .preopen_build(|p| {
+        p.directory(&config.root)
+          .read(true)
+          .write(false)
+          .create(false)
+      })
+      .map_err(|e| error(e.to_string()))?
+      .finalize(&mut store)
+      .map_err(|e| error(e.to_string()))?;
+    let imports = wasi_env
+      .import_object(&mut store, &module)
+      .map_err(|e| error(e.to_string()))?;
+    let instance =
+      Instance::new(&mut store, &module, &imports).map_err(|e| error(e.to_string()))?;
+    wasi_env
+      .initialize(&mut store, instance.clone())
+      .map_err(|e| error(e.to_string()))?;
+
+    let abi_version = instance
+      .exports
+      .get_typed_function::<(), u64>(&store, WASM_PLUGIN_ABI_VERSION_EXPORT)
+      .map_err(|e| error(format!("`{WASM_PLUGIN_ABI_VERSION_EXPORT}` is not exported, make sure the plugin is marked with #[farm_wasm_plugin]. {e}")))?
+      .call(&mut store)
+      .map_err(|e| error(e.to_string()))?;
+    let abi_version = PluginAbiVersion::unpack(abi_version);
+
+    if !abi_version.is_compatible_with(&PLUGIN_ABI_VERSION) {
+      return Err(error(format!(
+        "Current core's abi version({PLUGIN_ABI_VERSION}) is not compatible with the plugin abi version({abi_version}), please rebuild the plugin against a compatible farmfe_core"
+      )));
+    }
+
+    macro_rules! export {
+      ($get:ident, $name:expr) => {
+        instance
+          .exports
+          .$get(&store, $name)
+          .map_err(|e| error(format!("`{}` is not exported. {e}", $name)))?
+      };
+    }
+
+    let memory = instance
+      .exports
+      .get_memory("memory")
+      .map_err(|e| error(e.to_string()))?
+      .clone();
+    let create: TypedFunction<(u32, u32), u64> =
+      export!(get_typed_function, WASM_PLUGIN_CREATE_EXPORT);
+    let mut plugin_instance = Self {
+      alloc: export!(get_typed_function, WASM_PLUGIN_ALLOC_EXPORT),
+      dealloc: export!(get_typed_function, WASM_PLUGIN_DEALLOC_EXPORT),
+      call: export!(get_typed_function, WASM_PLUGIN_CALL_EXPORT),
+      memory,
+      store,
+    };
+
+    let param = WasmPluginCreateParam {
+      config: serde_json::to_string(config).map_err(|e| error(e.to_string()))?,
+      options,
+    };
+    let (ptr, len) = plugin_instance.write(&encode_wasm_plugin_message!(&param))?;
+    let packed = create
+      .call(&mut plugin_instance.store, ptr, len)
+      .map_err(|e| error(e.to_string()))?;
+    plugin_instance.free(ptr, len)?;
+
+    let info = plugin_instance.take(packed)?;
+    let info = decode_wasm_plugin_message::<WasmPluginInfo>(&info).map_err(error)?;
+
+    Ok((plugin_instance, info))
+  }
+
+  /// Call the hook with serialized param, returns the serialized result or [None] if the hook is skipped
+  pub fn call(&mut self, hook: WasmPluginHook, param: &[u8]) -> Result<Option<Vec<u8>>> {
+    let _guard = WASM_PLUGIN_RT.enter();
+
+    let (ptr, len) = self.write(param)?;
+    let packed = self
+      .call
+      .call(&mut self.store, hook as u32, ptr, len)
+      .map_err(runtime_error)?;
+    self.free(ptr, len)?;
+
+    let response = self.take(packed)?;
+
+    match decode_wasm_plugin_message::<WasmPluginHookResponse>(&response)
+      .map_err(CompilationError::GenericError)?
+    {
+      WasmPluginHookResponse::Skip => Ok(None),
+      WasmPluginHookResponse::Done(result) => Ok(Some(result)),
+      WasmPluginHookResponse::Error(e) => Err(CompilationError::GenericError(e)),
+    }
+  }
+
+  /// Copy the bytes into the guest memory
+  fn write(&mut self, bytes: &[u8]) -> Result<(u32, u32)> {
+    let len = bytes.len() as u32;
+    let ptr = self
+      .alloc
+      .call(&mut self.store, len)
+      .map_err(runtime_error)?;
+    self
+      .memory
+      .view(&self.store)
+      .write(ptr as u64, bytes)
+      .map_err(|e| CompilationError::GenericError(e.to_string()))?;
+
+    Ok((ptr, len))
+  }
+
+  /// Copy the bytes returned by the guest out and free them
+  fn take(&mut self, packed: u64) -> Result<Vec<u8>> {
+    let (ptr, len) = unpack_ptr_len(packed);
+    let mut bytes = vec![0; len as usize];
+    self
+      .memory
+      .view(&self.store)
+      .read(ptr as u64, &mut bytes)
+      .map_err(|e| CompilationError::GenericError(e.to_string()))?;
+    self.free(ptr, len)?;
+
+    Ok(bytes)
+  }
+
+  fn free(&mut self, ptr: u32, len: u32) -> Result<()> {
+    self
+      .dealloc
+      .call(&mut self.store, ptr, len)
+      .map_err(runtime_error)
+  }
+}
+
+fn runtime_error(e: wasmer::RuntimeError) -> CompilationError {
+  CompilationError::GenericError(format!("wasm plugin trapped: {e}"))
+}
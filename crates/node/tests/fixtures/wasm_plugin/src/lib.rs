@@ -0,0 +1,48 @@
+use farmfe_core::{
+  config::Config,
+  module::ModuleType,
+  plugin::wasm::{WasmPlugin, WasmPluginHook, WasmPluginLoadHookParam, WasmPluginLoadHookResult},
+  serde_json,
+};
+use farmfe_macro_plugin::farm_wasm_plugin;
+
+/// Load `.txt` files as js modules, the file is read inside the sandbox
+#[farm_wasm_plugin]
+pub struct FarmPluginTxt {}
+
+impl FarmPluginTxt {
+  fn new(_config: &Config, _options: String) -> Self {
+    Self {}
+  }
+}
+
+impl WasmPlugin for FarmPluginTxt {
+  fn name(&self) -> &str {
+    "FarmPluginTxt"
+  }
+
+  fn hooks(&self) -> Vec<WasmPluginHook> {
+    vec![WasmPluginHook::Load]
+  }
+
+  fn load(
+    &self,
+    param: WasmPluginLoadHookParam,
+  ) -> Result<Option<WasmPluginLoadHookResult>, String> {
+    if !param.resolved_path.ends_with(".txt") {
+      return Ok(None);
+    }
+
+    let content = std::fs::read_to_string(&param.resolved_path)
+      .map_err(|e| format!("failed to read {}: {e}", param.resolved_path))?;
+
+    Ok(Some(WasmPluginLoadHookResult {
+      content: format!(
+        "export default {};",
+        serde_json::to_string(&content).map_err(|e| e.to_string())?
+      ),
+      module_type: ModuleType::Js,
+      source_map: None,
+    }))
+  }
+}
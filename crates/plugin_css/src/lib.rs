@@ -430,6 +430,8 @@ impl Plugin for FarmPluginCss {
       let meta = ModuleMetaData::Css(Box::new(CssModuleMetaData {
         ast: css_stylesheet,
         comments,
+        // plain css is applied as a whole stylesheet, including the css it imports
+        hmr_self_accepted: true,
         custom: Default::default(),
       }));
 
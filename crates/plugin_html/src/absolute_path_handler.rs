@@ -1,6 +1,8 @@
 use farmfe_core::swc_html_ast::{Document, Element};
 use farmfe_toolkit::swc_html_visit::{VisitMut, VisitMutWith};
 
+use crate::deps_analyzer::{get_asset_attr_kind, AssetAttrKind};
+
 pub struct AbsolutePathHandler {
   pub public_path: String,
 }
@@ -9,6 +11,7 @@ impl AbsolutePathHandler {
   /// Process the script and link with absolute paths manually added by users in HTML.
   /// Add the prefix of "publicPath" to its path.
   /// eg: <script src="/test.js"></script>  to  <script src="/publicPath/test.js"></script>
+  /// Assets served from `publicDir` like `<img src="/logo.png" />` are handled too.
   pub fn add_public_path_prefix(&mut self, html_ast: &mut Document) {
     html_ast.visit_mut_with(self)
   }
@@ -16,12 +19,21 @@ impl AbsolutePathHandler {
 
 impl VisitMut for AbsolutePathHandler {
   fn visit_mut_element(&mut self, element: &mut Element) {
-    if matches!(element.tag_name.to_lowercase().as_str(), "script" | "link") {
-      for attr in &mut element.attributes {
+    let is_script_or_link = matches!(element.tag_name.to_lowercase().as_str(), "script" | "link");
+    let asset_attrs = element
+      .attributes
+      .iter()
+      .map(|attr| get_asset_attr_kind(element, &attr.name) == Some(AssetAttrKind::Url))
+      .collect::<Vec<_>>();
+
+    if is_script_or_link || asset_attrs.contains(&true) {
+      for (attr, is_asset_attr) in element.attributes.iter_mut().zip(asset_attrs) {
         let value = attr.value.clone().unwrap_or_default();
         // determine if the path start with /.
-        if matches!(attr.name.to_lowercase().as_str(), "src" | "href")
+        if (is_asset_attr
+          || (is_script_or_link && matches!(attr.name.to_lowercase().as_str(), "src" | "href")))
           && !value.starts_with(&self.public_path)
+          && !value.starts_with("//")
           && value.starts_with("/")
         {
           let normalized_value = value.trim_start_matches("/");
@@ -0,0 +1,58 @@
+use farmfe_core::{swc_html_ast::Element, HashMap};
+use farmfe_toolkit::swc_html_visit::{VisitMut, VisitMutWith};
+
+use crate::deps_analyzer::{get_asset_attr_kind, parse_srcset, stringify_srcset, AssetAttrKind};
+
+/// Replace the sources of the assets referenced by html with the final urls of the generated resources.
+/// eg: `<img src="./logo.png" srcset="./logo@2x.png 2x" />` to `<img src="/assets/logo-1a2b3c4d.png" srcset="/assets/logo@2x-5e6f7a8b.png 2x" />`
+pub struct AssetsReplacer {
+  /// key: source of the asset, value: url of the asset resource
+  asset_urls: HashMap<String, String>,
+}
+
+impl AssetsReplacer {
+  pub fn new(asset_urls: HashMap<String, String>) -> Self {
+    Self { asset_urls }
+  }
+}
+
+impl VisitMut for AssetsReplacer {
+  fn visit_mut_element(&mut self, element: &mut Element) {
+    let attr_kinds = element
+      .attributes
+      .iter()
+      .map(|attr| get_asset_attr_kind(element, &attr.name))
+      .collect::<Vec<_>>();
+
+    for (attr, kind) in element.attributes.iter_mut().zip(attr_kinds) {
+      let Some(value) = &attr.value else {
+        continue;
+      };
+
+      let replaced = match kind {
+        Some(AssetAttrKind::Url) => self.asset_urls.get(value.trim()).cloned(),
+        Some(AssetAttrKind::SrcSet) => {
+          let mut candidates = parse_srcset(value);
+          let mut changed = false;
+
+          for (url, _) in &mut candidates {
+            if let Some(replaced_url) = self.asset_urls.get(url.as_str()) {
+              *url = replaced_url.clone();
+              changed = true;
+            }
+          }
+
+          // srcset descriptors are preserved
+          changed.then(|| stringify_srcset(&candidates))
+        }
+        None => None,
+      };
+
+      if let Some(replaced) = replaced {
+        attr.value = Some(replaced.into());
+      }
+    }
+
+    element.visit_mut_children_with(self);
+  }
+}
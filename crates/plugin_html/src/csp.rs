@@ -0,0 +1,140 @@
+use farmfe_core::{
+  serde_json,
+  swc_html_ast::{Child, Document, Element},
+};
+use farmfe_toolkit::{
+  hash::sha256_base64,
+  html::{create_attribute, create_element},
+  swc_html_visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+/// Add `nonce` attribute to all `<script>` and `<style>` of the html
+pub struct CspNonceInjector<'a> {
+  pub nonce: &'a str,
+}
+
+impl VisitMut for CspNonceInjector<'_> {
+  fn visit_mut_element(&mut self, element: &mut Element) {
+    if element.tag_name == "script" || element.tag_name == "style" {
+      element.attributes.retain(|attr| attr.name != "nonce");
+      element
+        .attributes
+        .push(create_attribute("nonce", Some(self.nonce)));
+    }
+
+    element.visit_mut_children_with(self);
+  }
+}
+
+/// Collect the sha256 hashes of inline `<script>` and `<style>` of the html, the hashes are in the format of `'sha256-xxx'`
+#[derive(Default)]
+pub struct CspHashCollector {
+  pub script_hashes: Vec<String>,
+  pub style_hashes: Vec<String>,
+}
+
+impl CspHashCollector {
+  pub fn collect(document: &Document) -> Self {
+    let mut collector = Self::default();
+    document.visit_with(&mut collector);
+
+    collector
+  }
+
+  /// the policy like `script-src 'self' 'sha256-xxx'; style-src 'self' 'sha256-xxx'`, `origin` of cdn is allowed too if `publicPath` is a url
+  pub fn policy(&self, public_path: &str) -> String {
+    let mut sources = vec!["'self'".to_string()];
+
+    if let Some(origin) = get_url_origin(public_path) {
+      sources.push(origin.to_string());
+    }
+
+    let directive = |name: &str, hashes: &[String]| {
+      sources
+        .iter()
+        .chain(hashes)
+        .fold(name.to_string(), |acc, source| format!("{acc} {source}"))
+    };
+
+    format!(
+      "{}; {}",
+      directive("script-src", &self.script_hashes),
+      directive("style-src", &self.style_hashes)
+    )
+  }
+
+  pub fn to_json(&self, public_path: &str) -> String {
+    serde_json::to_string_pretty(&serde_json::json!({
+      "policy": self.policy(public_path),
+      "script-src": self.script_hashes,
+      "style-src": self.style_hashes,
+    }))
+    .unwrap()
+  }
+}
+
+impl Visit for CspHashCollector {
+  fn visit_element(&mut self, element: &Element) {
+    let is_inline_script =
+      element.tag_name == "script" && !element.attributes.iter().any(|attr| attr.name == "src");
+
+    if is_inline_script || element.tag_name == "style" {
+      let code = element
+        .children
+        .iter()
+        .filter_map(|child| match child {
+          Child::Text(text) => Some(&*text.data),
+          _ => None,
+        })
+        .collect::<String>();
+
+      if !code.is_empty() {
+        let hash = format!("'sha256-{}'", sha256_base64(code.as_bytes()));
+        let hashes = if is_inline_script {
+          &mut self.script_hashes
+        } else {
+          &mut self.style_hashes
+        };
+
+        if !hashes.contains(&hash) {
+          hashes.push(hash);
+        }
+      }
+    }
+
+    element.visit_children_with(self);
+  }
+}
+
+/// Insert `<meta http-equiv="Content-Security-Policy" />` as the first child of `<head>`, so that it takes effect for all following scripts and styles
+pub struct CspMetaInjector {
+  pub policy: String,
+}
+
+impl VisitMut for CspMetaInjector {
+  fn visit_mut_element(&mut self, element: &mut Element) {
+    if element.tag_name == "head" {
+      element.children.insert(
+        0,
+        Child::Element(create_element(
+          "meta",
+          None,
+          vec![
+            ("http-equiv", "Content-Security-Policy"),
+            ("content", &self.policy),
+          ],
+        )),
+      );
+      return;
+    }
+
+    element.visit_mut_children_with(self);
+  }
+}
+
+fn get_url_origin(url: &str) -> Option<&str> {
+  let (_, rest) = url.split_once("://")?;
+  let host_len = rest.find('/').unwrap_or(rest.len());
+
+  Some(&url[..url.len() - rest.len() + host_len])
+}
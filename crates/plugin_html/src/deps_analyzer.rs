@@ -72,6 +72,13 @@ impl Visit for DepsAnalyzer {
       })
     }
 
+    for source in get_asset_sources(element) {
+      self.insert_dep(PluginAnalyzeDepsHookResultEntry {
+        kind: ResolveKind::HtmlAsset,
+        source,
+      })
+    }
+
     element.visit_children_with(self);
   }
 }
@@ -207,6 +214,148 @@ pub fn should_ignore_source(source: &str) -> bool {
     || source.starts_with("data:")
 }
 
+/// `rel` of `<link>` whose `href` references an asset
+const ASSET_LINK_RELS: [&str; 5] = [
+  "icon",
+  "apple-touch-icon",
+  "apple-touch-startup-image",
+  "mask-icon",
+  "manifest",
+];
+/// `property` or `name` of `<meta>` whose `content` references an asset
+const ASSET_META_PROPERTIES: [&str; 4] = [
+  "og:image",
+  "og:image:url",
+  "og:image:secure_url",
+  "twitter:image",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssetAttrKind {
+  /// the value is a single url, for example: `<img src="./logo.png" />`
+  Url,
+  /// the value is a list of urls with descriptors, for example: `<img srcset="./a.png 1x, ./b.png 2x" />`
+  SrcSet,
+}
+
+fn get_attr_value<'a>(element: &'a Element, name: &str) -> Option<&'a str> {
+  element
+    .attributes
+    .iter()
+    .find(|attr| attr.name == name)
+    .and_then(|attr| attr.value.as_deref())
+}
+
+/// Get the kind of the attribute if it references an asset:
+/// * `src` and `srcset` of `<img>` and `<source>`, `src` of `<video>` and `<audio>`, `poster` of `<video>`
+/// * `href` of `<link rel="icon" />`, `<link rel="apple-touch-icon" />`, `<link rel="manifest" />`, etc
+/// * `content` of `<meta property="og:image" />` and `<meta name="twitter:image" />`
+pub fn get_asset_attr_kind(element: &Element, attr_name: &str) -> Option<AssetAttrKind> {
+  match (&*element.tag_name, attr_name) {
+    ("img" | "source", "srcset") => Some(AssetAttrKind::SrcSet),
+    ("img" | "source" | "video" | "audio", "src") | ("video", "poster") => Some(AssetAttrKind::Url),
+    ("link", "href") => get_attr_value(element, "rel")
+      .is_some_and(|rel| {
+        rel
+          .split_ascii_whitespace()
+          .any(|r| ASSET_LINK_RELS.iter().any(|a| a.eq_ignore_ascii_case(r)))
+      })
+      .then_some(AssetAttrKind::Url),
+    ("meta", "content") => ["property", "name"]
+      .into_iter()
+      .any(|name| {
+        get_attr_value(element, name).is_some_and(|v| ASSET_META_PROPERTIES.contains(&v.trim()))
+      })
+      .then_some(AssetAttrKind::Url),
+    _ => None,
+  }
+}
+
+/// Get the sources of the assets referenced by the element, sources that should be ignored are filtered
+pub fn get_asset_sources(element: &Element) -> Vec<String> {
+  let mut sources = vec![];
+
+  for attr in &element.attributes {
+    let Some(value) = &attr.value else {
+      continue;
+    };
+
+    match get_asset_attr_kind(element, &attr.name) {
+      Some(AssetAttrKind::Url) => sources.push(value.trim().to_string()),
+      Some(AssetAttrKind::SrcSet) => {
+        sources.extend(parse_srcset(value).into_iter().map(|(url, _)| url))
+      }
+      None => {}
+    }
+  }
+
+  let mut result: Vec<String> = vec![];
+
+  for source in sources {
+    // `src` and `srcset` of the same element usually reference the same asset
+    if !should_ignore_asset_source(&source) && !result.contains(&source) {
+      result.push(source);
+    }
+  }
+
+  result
+}
+
+pub fn should_ignore_asset_source(source: &str) -> bool {
+  source.is_empty() || source.starts_with("//") || should_ignore_source(source)
+}
+
+/// Parse `srcset` to a list of `(url, descriptor)`, for example: `./a.png 1x, ./b.png 2x` is parsed to `[("./a.png", "1x"), ("./b.png", "2x")]`.
+/// Urls may contain commas(e.g. data url), so a url ends at whitespace rather than comma
+pub fn parse_srcset(value: &str) -> Vec<(String, String)> {
+  let mut candidates = vec![];
+  let mut rest = value;
+
+  loop {
+    rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == ',');
+
+    if rest.is_empty() {
+      break;
+    }
+
+    let url_end = rest
+      .find(|c: char| c.is_ascii_whitespace())
+      .unwrap_or(rest.len());
+    let (url, remaining) = rest.split_at(url_end);
+    let trimmed_url = url.trim_end_matches(',');
+
+    // a url that ends with comma has no descriptor, for example: `./a.png,./b.png 2x`
+    if trimmed_url.len() != url.len() {
+      candidates.push((trimmed_url.to_string(), String::new()));
+      rest = remaining;
+      continue;
+    }
+
+    let descriptor_end = remaining.find(',').unwrap_or(remaining.len());
+    candidates.push((
+      url.to_string(),
+      remaining[..descriptor_end].trim().to_string(),
+    ));
+    rest = &remaining[descriptor_end..];
+  }
+
+  candidates
+}
+
+pub fn stringify_srcset(candidates: &[(String, String)]) -> String {
+  candidates
+    .iter()
+    .map(|(url, descriptor)| {
+      if descriptor.is_empty() {
+        url.clone()
+      } else {
+        format!("{url} {descriptor}")
+      }
+    })
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
 #[cache_item(farmfe_core)]
 #[derive(Debug, Clone)]
 pub struct HtmlInlineModule {
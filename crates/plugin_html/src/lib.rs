@@ -1,11 +1,15 @@
 #![feature(box_patterns)]
 
-use std::{mem, sync::Arc};
+use std::{mem, path::Path, sync::Arc};
 
 use absolute_path_handler::AbsolutePathHandler;
+use assets_replacer::AssetsReplacer;
+use csp::{CspHashCollector, CspMetaInjector, CspNonceInjector};
 use deps_analyzer::{DepsAnalyzer, HtmlInlineModule, HTML_INLINE_ID_PREFIX};
 use farmfe_core::cache::module_cache::MetadataOption;
 use farmfe_core::module::meta_data::html::HtmlModuleMetaData;
+use farmfe_core::module::module_graph::ModuleGraph;
+use farmfe_core::module::{CustomMetaDataMap, Module};
 
 use farmfe_core::plugin::GeneratedResource;
 use farmfe_core::resource::meta_data::html::HtmlResourcePotMetaData;
@@ -13,15 +17,18 @@ use farmfe_core::resource::meta_data::ResourcePotMetaData;
 use farmfe_core::swc_common::Globals;
 use farmfe_core::{cache_item, HashMap};
 use farmfe_core::{
-  config::Config,
+  config::{
+    html::{HtmlCspConfig, HtmlCspHashOutput},
+    Config,
+  },
   context::CompilationContext,
   error::CompilationError,
   module::{ModuleId, ModuleMetaData, ModuleType},
   plugin::{
-    Plugin, PluginAnalyzeDepsHookParam, PluginFinalizeResourcesHookParam,
-    PluginGenerateResourcesHookResult, PluginHookContext, PluginLoadHookParam,
-    PluginLoadHookResult, PluginParseHookParam, PluginResolveHookParam, PluginResolveHookResult,
-    PluginTransformHookResult, ResolveKind,
+    Plugin, PluginAnalyzeDepsHookParam, PluginAnalyzeDepsHookResultEntry,
+    PluginFinalizeResourcesHookParam, PluginGenerateResourcesHookResult, PluginHookContext,
+    PluginLoadHookParam, PluginLoadHookResult, PluginParseHookParam, PluginResolveHookParam,
+    PluginResolveHookResult, PluginTransformHookResult, ResolveKind,
   },
   relative_path::RelativePath,
   resource::{
@@ -32,17 +39,29 @@ use farmfe_core::{
 use farmfe_toolkit::minify::minify_html_module;
 use farmfe_toolkit::plugin_utils::path_filter::PathFilter;
 use farmfe_toolkit::sourcemap::create_swc_source_map;
+use farmfe_toolkit::swc_html_visit::VisitMutWith;
 use farmfe_toolkit::{
-  fs::read_file_utf8,
+  fs::{read_file_utf8, transform_output_filename, TransformOutputFileNameParams},
   html::{codegen_html_document, parse_html_document},
   script::{module_type_from_id, swc_try_with::try_with},
 };
 use resources_injector::{ResourcesInjector, ResourcesInjectorOptions};
+use template::{is_html_template, HtmlTemplateRenderer};
+use web_manifest::{
+  get_web_manifest_sources, is_web_manifest_module, is_web_manifest_module_type,
+  is_web_manifest_source, parse_web_manifest, rewrite_web_manifest, RenderedWebManifest,
+  RenderedWebManifests, RENDERED_WEB_MANIFESTS_KEY, WEB_MANIFEST_META_KEY,
+  WEB_MANIFEST_MODULE_TYPE,
+};
 
 mod absolute_path_handler;
+mod assets_replacer;
+mod csp;
 mod deps_analyzer;
 mod resources_injector;
+mod template;
 mod utils;
+mod web_manifest;
 
 const BASE_HTML_CHILDREN_PLACEHOLDER: &str = "{{children}}";
 pub const UNRESOLVED_SLASH_MODULE: &str = "FARM_HTML_UNRESOLVED_SLASH_MODULE";
@@ -78,6 +97,10 @@ impl Plugin for FarmPluginHtml {
       }));
     }
 
+    if matches!(param.kind, ResolveKind::HtmlAsset) {
+      return self.resolve_html_asset(param, context, hook_context);
+    }
+
     // try resolve `/xxx` as `./xxx`, for example: `/src/index.ts` to `./src/index.ts`
     if matches!(param.kind, ResolveKind::ScriptSrc | ResolveKind::LinkHref)
       && param.source.starts_with("/")
@@ -126,6 +149,14 @@ impl Plugin for FarmPluginHtml {
       }
     }
 
+    if param.meta.contains_key(WEB_MANIFEST_META_KEY) {
+      return Ok(Some(PluginLoadHookResult {
+        content: read_file_utf8(param.resolved_path)?,
+        module_type: ModuleType::Custom(WEB_MANIFEST_MODULE_TYPE.to_string()),
+        source_map: None,
+      }));
+    }
+
     let module_type = module_type_from_id(param.resolved_path);
 
     if let Some(module_type) = module_type {
@@ -143,7 +174,7 @@ impl Plugin for FarmPluginHtml {
     }
   }
 
-  /// Inherit base html
+  /// Inherit base html and render html template
   fn transform(
     &self,
     param: &farmfe_core::plugin::PluginTransformHookParam,
@@ -153,6 +184,8 @@ impl Plugin for FarmPluginHtml {
       return Ok(None);
     }
 
+    let mut content = None;
+
     if let Some(base) = &context.config.html.base {
       let base_html = self
         .load(
@@ -177,17 +210,39 @@ impl Plugin for FarmPluginHtml {
           msg: format!("Load base html({base}) fail: Base html file does not exist"),
         })?;
 
-      return Ok(Some(PluginTransformHookResult {
-        content: base_html
+      content = Some(
+        base_html
           .content
           .replace(BASE_HTML_CHILDREN_PLACEHOLDER, &param.content),
-        module_type: None,
-        source_map: None,
-        ignore_previous_source_map: false,
-      }));
+      );
     }
 
-    Ok(None)
+    // render template before analyzing deps, so the injected tags are analyzed too
+    let html = content.as_deref().unwrap_or(&param.content);
+
+    if let Some(template_data) = &context.config.html.template_data
+      && is_html_template(html)
+    {
+      let data =
+        get_input_name(param.resolved_path, context).and_then(|name| template_data.get(name));
+      let rendered = HtmlTemplateRenderer::new(data, &context.config.define)
+        .render(html)
+        .map_err(|msg| CompilationError::TransformError {
+          resolved_path: param.resolved_path.to_string(),
+          msg,
+        })?;
+
+      if rendered != html {
+        content = Some(rendered);
+      }
+    }
+
+    Ok(content.map(|content| PluginTransformHookResult {
+      content,
+      module_type: None,
+      source_map: None,
+      ignore_previous_source_map: false,
+    }))
   }
 
   fn parse(
@@ -208,6 +263,11 @@ impl Plugin for FarmPluginHtml {
       }));
 
       Ok(Some(meta))
+    } else if is_web_manifest_module_type(&param.module_type) {
+      // the content of web manifest is kept in module.content, only validate it here
+      parse_web_manifest(&param.content, &param.resolved_path)?;
+
+      Ok(Some(ModuleMetaData::Custom(Default::default())))
     } else {
       Ok(None)
     }
@@ -232,6 +292,22 @@ impl Plugin for FarmPluginHtml {
         );
       }
 
+      Ok(Some(()))
+    } else if is_web_manifest_module(param.module) {
+      let sources = get_web_manifest_sources(
+        &param.module.content,
+        &param.module.id.resolved_path(&context.config.root),
+      )?;
+
+      param.deps.extend(
+        sources
+          .into_iter()
+          .map(|source| PluginAnalyzeDepsHookResultEntry {
+            source,
+            kind: ResolveKind::HtmlAsset,
+          }),
+      );
+
       Ok(Some(()))
     } else {
       Ok(None)
@@ -264,10 +340,27 @@ impl Plugin for FarmPluginHtml {
         });
       }
 
+      let mut ast = module.meta.as_html().ast.clone();
+      let (asset_urls, manifests) = render_html_assets(module, &module_graph, context)?;
+      ast.visit_mut_with(&mut AssetsReplacer::new(asset_urls));
+
+      let mut custom = CustomMetaDataMap::default();
+
+      if !manifests.is_empty() {
+        custom.insert(
+          RENDERED_WEB_MANIFESTS_KEY.to_string(),
+          Box::new(RenderedWebManifests { manifests }),
+        );
+      }
+
       return Ok(Some(ResourcePotMetaData::Html(HtmlResourcePotMetaData {
-        ast: module.meta.as_html().ast.clone(),
-        custom: Default::default(),
+        ast,
+        custom,
       })));
+    } else if matches!(&resource_pot.resource_pot_type, ResourcePotType::Custom(ty) if ty == WEB_MANIFEST_MODULE_TYPE)
+    {
+      // web manifests are rendered and emitted together with the html that references them
+      return Ok(Some(ResourcePotMetaData::Custom(Default::default())));
     }
 
     Ok(None)
@@ -280,6 +373,38 @@ impl Plugin for FarmPluginHtml {
     _hook_context: &PluginHookContext,
   ) -> farmfe_core::error::Result<Option<PluginGenerateResourcesHookResult>> {
     if matches!(resource_pot.resource_pot_type, ResourcePotType::Html) {
+      let manifests = resource_pot
+        .meta
+        .as_html_mut()
+        .custom
+        .get_ref::<RenderedWebManifests>(RENDERED_WEB_MANIFESTS_KEY)
+        .map(|m| m.manifests.clone())
+        .unwrap_or_default();
+
+      let manifest_resources = manifests.into_iter().map(|manifest| {
+        let ext = Path::new(&manifest.name)
+          .extension()
+          .and_then(|e| e.to_str())
+          .unwrap_or_default()
+          .to_string();
+
+        GeneratedResource {
+          resource: Resource {
+            name: manifest.name,
+            name_hash: "".to_string(),
+            bytes: manifest.content.into_bytes(),
+            emitted: false,
+            resource_type: ResourceType::Asset(ext),
+            origin: ResourceOrigin::ResourcePot(resource_pot.id.clone()),
+            // the name is already transformed by assets filename when rendering html
+            should_transform_output_filename: false,
+            meta: Default::default(),
+            special_placeholders: Default::default(),
+          },
+          source_map: None,
+        }
+      });
+
       Ok(Some(PluginGenerateResourcesHookResult {
         resources: vec![GeneratedResource {
           resource: Resource {
@@ -294,7 +419,15 @@ impl Plugin for FarmPluginHtml {
             special_placeholders: Default::default(),
           },
           source_map: None,
-        }],
+        }]
+        .into_iter()
+        .chain(manifest_resources)
+        .collect(),
+      }))
+    } else if matches!(&resource_pot.resource_pot_type, ResourcePotType::Custom(ty) if ty == WEB_MANIFEST_MODULE_TYPE)
+    {
+      Ok(Some(PluginGenerateResourcesHookResult {
+        resources: vec![],
       }))
     } else {
       Ok(None)
@@ -306,6 +439,176 @@ impl FarmPluginHtml {
   pub fn new(_: &Config) -> Self {
     Self {}
   }
+
+  /// Assets of html that start with `/` are kept as is if they exist in `publicDir`, otherwise they are resolved as `./xxx`.
+  /// Web manifests are marked so they are loaded and rendered by this plugin instead of being treated as json modules.
+  fn resolve_html_asset(
+    &self,
+    param: &PluginResolveHookParam,
+    context: &Arc<CompilationContext>,
+    hook_context: &PluginHookContext,
+  ) -> farmfe_core::error::Result<Option<PluginResolveHookResult>> {
+    let is_slash_source = param.source.starts_with('/');
+
+    if is_slash_source && let Some(public_dir) = &context.config.assets.public_dir {
+      let path = param
+        .source
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(&param.source);
+
+      if RelativePath::new(&path[1..])
+        .to_logical_path(public_dir)
+        .exists()
+      {
+        return Ok(Some(PluginResolveHookResult {
+          resolved_path: param.source.clone(),
+          external: true,
+          ..Default::default()
+        }));
+      }
+    }
+
+    let is_web_manifest = is_web_manifest_source(&param.source);
+
+    if !is_slash_source && !is_web_manifest {
+      return Ok(None);
+    }
+
+    let resolve_result = context.plugin_driver.resolve(
+      &PluginResolveHookParam {
+        source: if is_slash_source {
+          format!(".{}", param.source)
+        } else {
+          param.source.clone()
+        },
+        importer: param.importer.clone(),
+        kind: param.kind.clone(),
+      },
+      context,
+      &PluginHookContext {
+        caller: Some(self.name().to_string()),
+        meta: hook_context.meta.clone(),
+      },
+    )?;
+
+    let Some(mut resolve_result) = resolve_result else {
+      return Ok(is_slash_source.then(|| PluginResolveHookResult {
+        resolved_path: UNRESOLVED_SLASH_MODULE.to_string(),
+        external: true,
+        ..Default::default()
+      }));
+    };
+
+    if is_web_manifest && !resolve_result.external {
+      resolve_result
+        .meta
+        .insert(WEB_MANIFEST_META_KEY.to_string(), "true".to_string());
+    }
+
+    Ok(Some(resolve_result))
+  }
+}
+
+/// Get the name of the input whose path is the html
+fn get_input_name<'a>(
+  resolved_path: &str,
+  context: &'a Arc<CompilationContext>,
+) -> Option<&'a str> {
+  context
+    .config
+    .input
+    .iter()
+    .find(|(_, input)| {
+      let input_path = Path::new(input.as_str());
+
+      if input_path.is_absolute() {
+        input_path == Path::new(resolved_path)
+      } else {
+        RelativePath::new(input).to_logical_path(&context.config.root) == Path::new(resolved_path)
+      }
+    })
+    .map(|(name, _)| name.as_str())
+}
+
+/// Get the urls of the assets referenced by the html, key is the source of the asset.
+/// Web manifests are rewritten with the urls of their assets and named by `output.assetsFilename`.
+fn render_html_assets(
+  html_module: &Module,
+  module_graph: &ModuleGraph,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<(HashMap<String, String>, Vec<RenderedWebManifest>)> {
+  let resources_map = context.resources_map.lock();
+  let public_path = if context.config.output.public_path.is_empty() {
+    "/".to_string()
+  } else {
+    format!(
+      "{}/",
+      context.config.output.public_path.trim_end_matches('/')
+    )
+  };
+  let module_urls = resources_map
+    .values()
+    .filter_map(|resource| match &resource.origin {
+      ResourceOrigin::Module(module_id) => {
+        Some((module_id.clone(), format!("{public_path}{}", resource.name)))
+      }
+      _ => None,
+    })
+    .collect::<HashMap<_, _>>();
+
+  let mut asset_urls = HashMap::default();
+  let mut manifests = vec![];
+
+  for (dep_id, edge) in module_graph.dependencies(&html_module.id) {
+    let Some(dep_module) = module_graph.module(&dep_id) else {
+      continue;
+    };
+
+    if dep_module.external {
+      continue;
+    }
+
+    let url = if is_web_manifest_module(dep_module) {
+      let resolved_path = dep_id.resolved_path(&context.config.root);
+      let content = rewrite_web_manifest(&dep_module.content, &resolved_path, |source| {
+        module_graph
+          .get_dep_by_source_optional(&dep_id, source, Some(ResolveKind::HtmlAsset))
+          .and_then(|id| module_urls.get(&id).cloned())
+      })?;
+      let path = Path::new(&resolved_path);
+      let name = transform_output_filename(TransformOutputFileNameParams {
+        filename_config: context.config.output.assets_filename.clone(),
+        name: path
+          .file_prefix()
+          .and_then(|s| s.to_str())
+          .unwrap_or("manifest"),
+        name_hash: "",
+        bytes: content.as_bytes(),
+        ext: path
+          .extension()
+          .and_then(|s| s.to_str())
+          .unwrap_or("webmanifest"),
+        special_placeholders: &Default::default(),
+      });
+      let url = format!("{public_path}{name}");
+
+      manifests.push(RenderedWebManifest { name, content });
+      Some(url)
+    } else {
+      module_urls.get(&dep_id).cloned()
+    };
+
+    if let Some(url) = url {
+      for item in edge.items() {
+        if matches!(item.kind, ResolveKind::HtmlAsset) {
+          asset_urls.insert(item.source.clone(), url.clone());
+        }
+      }
+    }
+  }
+
+  Ok((asset_urls, manifests))
 }
 
 pub struct FarmPluginTransformHtml {
@@ -344,18 +647,27 @@ impl Plugin for FarmPluginTransformHtml {
     let html_resource = &mut param.resource;
 
     let module_graph = param.module_graph;
-    let current_html_id = param
+    let html_resource_pot = param
       .resource_pot_map
       .resource_pot(html_resource.origin.as_resource_pot())
-      .unwrap()
-      .modules()[0];
+      .unwrap();
+    let current_html_id = html_resource_pot.modules()[0];
     let script_entries = module_graph
       .dependencies(current_html_id)
       .into_iter()
       .filter_map(|dep| {
         let dep_module = module_graph.module(&dep.0).unwrap();
 
-        if dep_module.module_type.is_script() {
+        // assets like `<img src="./logo.svg" />` may be transformed to script modules, they should not be executed as entries.
+        // Dynamic imports added by plugins, e.g. the client references of React Server Components, are loaded on demand
+        if dep_module.module_type.is_script()
+          && dep.1.iter().any(|item| {
+            !matches!(
+              item.kind,
+              ResolveKind::HtmlAsset | ResolveKind::DynamicImport
+            )
+          })
+        {
           Some(dep.0.id(context.config.mode))
         } else {
           None
@@ -379,8 +691,17 @@ impl Plugin for FarmPluginTransformHtml {
       },
     );
 
-    let html_module = module_graph.module(current_html_id).unwrap();
-    let mut html_ast = html_module.meta.as_html().ast.clone();
+    // the ast of rendered html resource pot, in which the assets are replaced with the final urls
+    let mut html_ast = match &html_resource_pot.meta {
+      ResourcePotMetaData::Html(meta) => meta.ast.clone(),
+      _ => module_graph
+        .module(current_html_id)
+        .unwrap()
+        .meta
+        .as_html()
+        .ast
+        .clone(),
+    };
     resources_injector.inject(&mut html_ast);
 
     // set publicPath prefix
@@ -479,6 +800,85 @@ impl Plugin for FarmPluginMinifyHtml {
   }
 }
 
+pub struct FarmPluginHtmlCsp {}
+
+impl FarmPluginHtmlCsp {
+  pub fn new(_: &Config) -> Self {
+    Self {}
+  }
+}
+
+impl Plugin for FarmPluginHtmlCsp {
+  fn name(&self) -> &str {
+    "FarmPluginHtmlCsp"
+  }
+
+  /// Make sure the inline scripts and styles are hashed after they are minified
+  fn priority(&self) -> i32 {
+    -100
+  }
+
+  fn finalize_resources(
+    &self,
+    params: &mut PluginFinalizeResourcesHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<()>> {
+    let Some(csp) = &context.config.html.csp else {
+      return Ok(None);
+    };
+
+    let public_path = &context.config.output.public_path;
+    let mut csp_json_resources = vec![];
+
+    for resource in params.resources_map.values_mut() {
+      if !matches!(resource.resource_type, ResourceType::Html) {
+        continue;
+      }
+
+      let html_code = Arc::new(String::from_utf8(mem::take(&mut resource.bytes)).unwrap());
+      let mut html_ast = parse_html_document(&resource.name, html_code)?;
+
+      match csp {
+        HtmlCspConfig::Nonce { placeholder } => {
+          html_ast.visit_mut_with(&mut CspNonceInjector { nonce: placeholder });
+        }
+        HtmlCspConfig::Hash { output } => {
+          let hashes = CspHashCollector::collect(&html_ast);
+
+          match output {
+            HtmlCspHashOutput::Meta => html_ast.visit_mut_with(&mut CspMetaInjector {
+              policy: hashes.policy(public_path),
+            }),
+            HtmlCspHashOutput::Json => csp_json_resources.push(Resource {
+              name: format!("{}.csp.json", resource.name.trim_end_matches(".html")),
+              name_hash: "".to_string(),
+              bytes: hashes.to_json(public_path).into_bytes(),
+              emitted: false,
+              resource_type: ResourceType::Asset("json".to_string()),
+              origin: resource.origin.clone(),
+              should_transform_output_filename: false,
+              meta: Default::default(),
+              special_placeholders: Default::default(),
+            }),
+          }
+        }
+      }
+
+      resource.bytes = codegen_html_document(
+        &html_ast,
+        should_minify_html(&resource.name, &context.config),
+      )
+      .into_bytes();
+    }
+
+    for resource in csp_json_resources {
+      params.resources_map.insert(resource.name.clone(), resource);
+    }
+
+    Ok(None)
+  }
+}
+
 fn should_minify_html(name: &str, config: &Config) -> bool {
   let default_minify_options = Default::default();
   let minify_options = config.minify.as_obj().unwrap_or(&default_minify_options);
@@ -0,0 +1,358 @@
+//! A minimal template syntax for html, rendered before the deps of html are analyzed:
+//! ```html
+//! <title>%APP_TITLE%</title>
+//! {{#if ANALYTICS_ID}}
+//! <script src="https://analytics.com/%ANALYTICS_ID%.js"></script>
+//! {{else}}
+//! <!-- analytics disabled -->
+//! {{/if}}
+//! {{#each preloads}}
+//! <link rel="preload" href="%this.href%" as="%this.as%" />
+//! {{/each}}
+//! ```
+//! Variables are looked up in the template data of current input first, then the env and `define`.
+//! Unknown variables are kept as is. Contents of `<script>` and `<template>` are kept as is too, they may contain
+//! client side templates like `<script type="text/x-template">`.
+
+use farmfe_core::{
+  serde_json::{self, Value},
+  HashMap,
+};
+
+/// key of the env object in `define`, injected by the js side
+const FARM_PROCESS_ENV: &str = "FARM_PROCESS_ENV";
+const DEFINE_REGEX_PREFIX: &str = "$__farm_regex:";
+/// elements whose contents are not rendered
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "template"];
+
+enum TemplateNode {
+  Text(String),
+  /// contents of [RAW_TEXT_ELEMENTS]
+  Raw(String),
+  If {
+    condition: String,
+    consequent: Vec<TemplateNode>,
+    alternate: Vec<TemplateNode>,
+  },
+  Each {
+    path: String,
+    body: Vec<TemplateNode>,
+  },
+}
+
+enum TemplateTag {
+  If(String),
+  Each(String),
+  Else,
+  EndIf,
+  EndEach,
+}
+
+/// true if the content may contain variables or blocks, used to skip rendering quickly
+pub fn is_html_template(content: &str) -> bool {
+  content.contains('%') || content.contains("{{#")
+}
+
+pub struct HtmlTemplateRenderer<'a> {
+  data: Option<&'a Value>,
+  define: &'a HashMap<String, Value>,
+  /// item and index of the `each` blocks, innermost last
+  scopes: Vec<(Value, usize)>,
+}
+
+impl<'a> HtmlTemplateRenderer<'a> {
+  pub fn new(data: Option<&'a Value>, define: &'a HashMap<String, Value>) -> Self {
+    Self {
+      data,
+      define,
+      scopes: vec![],
+    }
+  }
+
+  pub fn render(&mut self, template: &str) -> Result<String, String> {
+    let mut rest = template;
+    let (nodes, end_tag) = parse_nodes(&mut rest)?;
+
+    if let Some(tag) = end_tag {
+      return Err(format!("Unexpected `{}` in html template", tag_str(&tag)));
+    }
+
+    let mut result = String::with_capacity(template.len());
+    self.render_nodes(&nodes, &mut result);
+
+    Ok(result)
+  }
+
+  fn render_nodes(&mut self, nodes: &[TemplateNode], result: &mut String) {
+    for node in nodes {
+      match node {
+        TemplateNode::Text(text) => self.render_text(text, result),
+        TemplateNode::Raw(text) => result.push_str(text),
+        TemplateNode::If {
+          condition,
+          consequent,
+          alternate,
+        } => {
+          if self.lookup(condition).is_some_and(|v| is_truthy(&v)) {
+            self.render_nodes(consequent, result);
+          } else {
+            self.render_nodes(alternate, result);
+          }
+        }
+        TemplateNode::Each { path, body } => {
+          let items = match self.lookup(path) {
+            Some(Value::Array(items)) => items,
+            Some(Value::Object(map)) => map.into_values().collect(),
+            _ => vec![],
+          };
+
+          for (index, item) in items.into_iter().enumerate() {
+            self.scopes.push((item, index));
+            self.render_nodes(body, result);
+            self.scopes.pop();
+          }
+        }
+      }
+    }
+  }
+
+  /// replace `%NAME%` in the text
+  fn render_text(&self, text: &str, result: &mut String) {
+    let mut rest = text;
+
+    while let Some(start) = rest.find('%') {
+      result.push_str(&rest[..start]);
+      rest = &rest[start + 1..];
+
+      let name_len = rest
+        .find(|c: char| !is_variable_char(c))
+        .unwrap_or(rest.len());
+
+      if name_len > 0
+        && rest[name_len..].starts_with('%')
+        && let Some(value) = self.lookup(&rest[..name_len])
+      {
+        result.push_str(&stringify_value(&value));
+        rest = &rest[name_len + 1..];
+      } else {
+        result.push('%');
+      }
+    }
+
+    result.push_str(rest);
+  }
+
+  fn lookup(&self, name: &str) -> Option<Value> {
+    let mut segments = name.split('.');
+    let first = segments.next()?;
+
+    if first == "this" {
+      let (item, _) = self.scopes.last()?;
+      return get_path(item, segments).cloned();
+    }
+
+    if name == "@index" {
+      let (_, index) = self.scopes.last()?;
+      return Some(Value::from(*index));
+    }
+
+    if let Some(value) = self.data.and_then(|data| get_path(data, name.split('.'))) {
+      return Some(value.clone());
+    }
+
+    if let Some(env) = self.define.get(FARM_PROCESS_ENV)
+      && let Some(value) = get_path(env, name.split('.'))
+    {
+      return Some(value.clone());
+    }
+
+    if name.starts_with(DEFINE_REGEX_PREFIX) {
+      return None;
+    }
+
+    self.define.get(name).map(|value| match value {
+      // values of define are usually JSON.stringify-ed, for example: `"\"Farm\""`
+      Value::String(s) => {
+        Value::String(serde_json::from_str::<String>(s).unwrap_or_else(|_| s.clone()))
+      }
+      _ => value.clone(),
+    })
+  }
+}
+
+fn get_path<'a>(value: &'a Value, segments: std::str::Split<'_, char>) -> Option<&'a Value> {
+  let mut current = value;
+
+  for segment in segments {
+    current = match current {
+      Value::Object(map) => map.get(segment)?,
+      Value::Array(items) => items.get(segment.parse::<usize>().ok()?)?,
+      _ => return None,
+    };
+  }
+
+  Some(current)
+}
+
+fn is_variable_char(c: char) -> bool {
+  c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '$' | '@')
+}
+
+fn is_truthy(value: &Value) -> bool {
+  match value {
+    Value::Null => false,
+    Value::Bool(b) => *b,
+    Value::Number(n) => n.as_f64().is_some_and(|n| n != 0.0),
+    Value::String(s) => !s.is_empty(),
+    Value::Array(items) => !items.is_empty(),
+    Value::Object(_) => true,
+  }
+}
+
+fn stringify_value(value: &Value) -> String {
+  match value {
+    Value::Null => String::new(),
+    Value::String(s) => s.clone(),
+    _ => value.to_string(),
+  }
+}
+
+fn tag_str(tag: &TemplateTag) -> &'static str {
+  match tag {
+    TemplateTag::If(_) => "{{#if}}",
+    TemplateTag::Each(_) => "{{#each}}",
+    TemplateTag::Else => "{{else}}",
+    TemplateTag::EndIf => "{{/if}}",
+    TemplateTag::EndEach => "{{/each}}",
+  }
+}
+
+/// find the next block tag, returns the start offset, the tag and the length of the tag
+fn next_tag(content: &str) -> Option<(usize, TemplateTag, usize)> {
+  let mut offset = 0;
+
+  while let Some(start) = content[offset..].find("{{") {
+    let start = offset + start;
+    let end = content[start..].find("}}")?;
+    let inner = content[start + 2..start + end].trim();
+    let tag = if let Some(condition) = inner.strip_prefix("#if ") {
+      Some(TemplateTag::If(condition.trim().to_string()))
+    } else if let Some(path) = inner.strip_prefix("#each ") {
+      Some(TemplateTag::Each(path.trim().to_string()))
+    } else {
+      match inner {
+        "else" => Some(TemplateTag::Else),
+        "/if" => Some(TemplateTag::EndIf),
+        "/each" => Some(TemplateTag::EndEach),
+        _ => None,
+      }
+    };
+
+    if let Some(tag) = tag {
+      return Some((start, tag, end + 2));
+    }
+
+    // not a block tag, for example `{{children}}` of base html
+    offset = start + 2;
+  }
+
+  None
+}
+
+/// find the contents of the next raw text element, returns the start and end offset of the contents.
+/// The end offset is the start of the closing tag, or the end of content if the element is not closed
+fn next_raw_text(content: &str) -> Option<(usize, usize)> {
+  // ascii lowercase keeps the byte offsets
+  let lower = content.to_ascii_lowercase();
+  let mut offset = 0;
+
+  while let Some(start) = lower[offset..].find('<') {
+    let start = offset + start;
+    offset = start + 1;
+
+    for name in RAW_TEXT_ELEMENTS {
+      let Some(after_name) = lower[start + 1..].strip_prefix(name) else {
+        continue;
+      };
+
+      if !after_name.starts_with(|c: char| c.is_ascii_whitespace() || c == '>' || c == '/') {
+        continue;
+      }
+
+      let contents_start = start + lower[start..].find('>')? + 1;
+      let contents_end = lower[contents_start..]
+        .find(&format!("</{name}"))
+        .map_or(content.len(), |end| contents_start + end);
+
+      return Some((contents_start, contents_end));
+    }
+  }
+
+  None
+}
+
+/// parse nodes until a closing tag(`{{else}}`, `{{/if}}` or `{{/each}}`) or the end of content
+fn parse_nodes(rest: &mut &str) -> Result<(Vec<TemplateNode>, Option<TemplateTag>), String> {
+  let mut nodes = vec![];
+
+  loop {
+    let next_tag = next_tag(rest);
+
+    // the raw text element starts before the next tag, keep its contents as is
+    if let Some((start, end)) = next_raw_text(rest)
+      && next_tag
+        .as_ref()
+        .is_none_or(|(tag_start, ..)| start <= *tag_start)
+    {
+      if start > 0 {
+        nodes.push(TemplateNode::Text(rest[..start].to_string()));
+      }
+      nodes.push(TemplateNode::Raw(rest[start..end].to_string()));
+      *rest = &rest[end..];
+      continue;
+    }
+
+    let Some((start, tag, len)) = next_tag else {
+      if !rest.is_empty() {
+        nodes.push(TemplateNode::Text(rest.to_string()));
+      }
+      *rest = "";
+      return Ok((nodes, None));
+    };
+
+    if start > 0 {
+      nodes.push(TemplateNode::Text(rest[..start].to_string()));
+    }
+    *rest = &rest[start + len..];
+
+    match tag {
+      TemplateTag::If(condition) => {
+        let unclosed = || format!("Unclosed `{{{{#if {condition}}}}}` in html template");
+        let (consequent, end_tag) = parse_nodes(rest)?;
+        let alternate = match end_tag {
+          Some(TemplateTag::Else) => match parse_nodes(rest)? {
+            (alternate, Some(TemplateTag::EndIf)) => alternate,
+            _ => return Err(unclosed()),
+          },
+          Some(TemplateTag::EndIf) => vec![],
+          _ => return Err(unclosed()),
+        };
+
+        nodes.push(TemplateNode::If {
+          condition,
+          consequent,
+          alternate,
+        });
+      }
+      TemplateTag::Each(path) => {
+        let body = match parse_nodes(rest)? {
+          (body, Some(TemplateTag::EndEach)) => body,
+          _ => return Err(format!("Unclosed `{{{{#each {path}}}}}` in html template")),
+        };
+
+        nodes.push(TemplateNode::Each { path, body });
+      }
+      tag => return Ok((nodes, Some(tag))),
+    }
+  }
+}
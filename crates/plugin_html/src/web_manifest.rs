@@ -0,0 +1,105 @@
+use farmfe_core::{
+  cache_item,
+  error::{CompilationError, Result},
+  module::{Module, ModuleType},
+  serde_json::{self, Value},
+};
+
+use crate::deps_analyzer::should_ignore_asset_source;
+
+/// module type of web manifests referenced by `<link rel="manifest" />`
+pub const WEB_MANIFEST_MODULE_TYPE: &str = "webmanifest";
+/// set in the meta of resolve result to mark the resolved module as a web manifest of html
+pub const WEB_MANIFEST_META_KEY: &str = "farm_html_web_manifest";
+/// key of [RenderedWebManifests] in the custom meta of html resource pot
+pub const RENDERED_WEB_MANIFESTS_KEY: &str = "rendered_web_manifests";
+
+#[cache_item(farmfe_core)]
+#[derive(Debug, Clone)]
+pub struct RenderedWebManifest {
+  pub name: String,
+  pub content: String,
+}
+
+/// Web manifests whose urls are rewritten when rendering html, they are emitted together with the html resource pot
+#[cache_item(farmfe_core)]
+#[derive(Debug, Clone, Default)]
+pub struct RenderedWebManifests {
+  pub manifests: Vec<RenderedWebManifest>,
+}
+
+pub fn is_web_manifest_source(source: &str) -> bool {
+  let path = source.split(['?', '#']).next().unwrap_or(source);
+
+  path.ends_with(".webmanifest") || path.ends_with(".json")
+}
+
+pub fn is_web_manifest_module_type(module_type: &ModuleType) -> bool {
+  matches!(module_type, ModuleType::Custom(ty) if ty == WEB_MANIFEST_MODULE_TYPE)
+}
+
+pub fn is_web_manifest_module(module: &Module) -> bool {
+  is_web_manifest_module_type(&module.module_type)
+}
+
+pub fn parse_web_manifest(content: &str, resolved_path: &str) -> Result<Value> {
+  serde_json::from_str(content).map_err(|e| CompilationError::ParseError {
+    resolved_path: resolved_path.to_string(),
+    msg: format!("Invalid web manifest: {e}"),
+  })
+}
+
+/// Get the sources of `icons`, `screenshots` and `shortcuts[].icons` of the web manifest
+pub fn get_web_manifest_sources(content: &str, resolved_path: &str) -> Result<Vec<String>> {
+  let mut manifest = parse_web_manifest(content, resolved_path)?;
+  let mut sources = vec![];
+
+  visit_manifest_srcs(&mut manifest, &mut |src| {
+    let src = src.trim();
+
+    if !should_ignore_asset_source(src) {
+      sources.push(src.to_string());
+    }
+  });
+
+  Ok(sources)
+}
+
+/// Rewrite the image sources of the web manifest to the urls returned by `get_url`, sources that `get_url` returns [None] are kept
+pub fn rewrite_web_manifest(
+  content: &str,
+  resolved_path: &str,
+  get_url: impl Fn(&str) -> Option<String>,
+) -> Result<String> {
+  let mut manifest = parse_web_manifest(content, resolved_path)?;
+
+  visit_manifest_srcs(&mut manifest, &mut |src| {
+    if let Some(url) = get_url(src.trim()) {
+      *src = url;
+    }
+  });
+
+  serde_json::to_string_pretty(&manifest)
+    .map_err(|e| CompilationError::GenericError(format!("Failed to serialize web manifest: {e}")))
+}
+
+fn visit_manifest_srcs(manifest: &mut Value, f: &mut impl FnMut(&mut String)) {
+  visit_image_srcs(manifest.get_mut("icons"), f);
+  visit_image_srcs(manifest.get_mut("screenshots"), f);
+
+  if let Some(Value::Array(shortcuts)) = manifest.get_mut("shortcuts") {
+    for shortcut in shortcuts {
+      visit_image_srcs(shortcut.get_mut("icons"), f);
+    }
+  }
+}
+
+fn visit_image_srcs(images: Option<&mut Value>, f: &mut impl FnMut(&mut String)) {
+  if let Some(Value::Array(images)) = images {
+    for image in images {
+      if let Some(Value::String(src)) = image.get_mut("src") {
+        f(src);
+      }
+    }
+  }
+}
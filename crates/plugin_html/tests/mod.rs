@@ -1,15 +1,21 @@
 use std::sync::Arc;
 
 use farmfe_core::{
+  config::{
+    html::{HtmlConfig, HtmlCspConfig, HtmlCspHashOutput},
+    Config,
+  },
   context::CompilationContext,
-  module::{Module, ModuleId},
+  module::{Module, ModuleId, ModuleType},
   plugin::{
-    Plugin, PluginAnalyzeDepsHookParam, PluginAnalyzeDepsHookResultEntry, PluginHookContext,
-    PluginLoadHookParam, PluginParseHookParam, ResolveKind,
+    Plugin, PluginAnalyzeDepsHookParam, PluginAnalyzeDepsHookResultEntry,
+    PluginFinalizeResourcesHookParam, PluginHookContext, PluginLoadHookParam, PluginParseHookParam,
+    PluginTransformHookParam, ResolveKind,
   },
-  HashMap,
+  resource::{Resource, ResourceType},
+  serde_json, HashMap,
 };
-use farmfe_plugin_html::FarmPluginHtml;
+use farmfe_plugin_html::{FarmPluginHtml, FarmPluginHtmlCsp};
 use farmfe_testing_helpers::fixture;
 
 #[test]
@@ -67,10 +73,184 @@ fn html_build_stage() {
 
     assert_eq!(
       analyze_deps_param.deps,
-      vec![PluginAnalyzeDepsHookResultEntry {
-        source: "./src/main.ts".to_string(),
-        kind: ResolveKind::ScriptSrc
-      }]
+      vec![
+        PluginAnalyzeDepsHookResultEntry {
+          source: "/favicon.ico".to_string(),
+          kind: ResolveKind::HtmlAsset
+        },
+        PluginAnalyzeDepsHookResultEntry {
+          source: "./assets/og.png".to_string(),
+          kind: ResolveKind::HtmlAsset
+        },
+        PluginAnalyzeDepsHookResultEntry {
+          source: "./assets/logo.png".to_string(),
+          kind: ResolveKind::HtmlAsset
+        },
+        PluginAnalyzeDepsHookResultEntry {
+          source: "./assets/logo@2x.png".to_string(),
+          kind: ResolveKind::HtmlAsset
+        },
+        PluginAnalyzeDepsHookResultEntry {
+          source: "./src/main.ts".to_string(),
+          kind: ResolveKind::ScriptSrc
+        }
+      ]
     );
   });
 }
+
+#[test]
+fn html_template() {
+  let config = Config {
+    input: HashMap::from_iter([("index".to_string(), "./index.html".to_string())]),
+    root: "/root".to_string(),
+    define: HashMap::from_iter([
+      (
+        "FARM_PROCESS_ENV".to_string(),
+        serde_json::json!({ "FARM_APP_TITLE": "Farm" }),
+      ),
+      ("VERSION".to_string(), serde_json::json!("\"1.0.0\"")),
+    ]),
+    html: Box::new(HtmlConfig {
+      template_data: Some(HashMap::from_iter([(
+        "index".to_string(),
+        serde_json::json!({
+          "lang": "en",
+          "analytics": false,
+          "preloads": [{ "href": "./a.woff2" }, { "href": "./b.woff2" }]
+        }),
+      )])),
+      ..Default::default()
+    }),
+    ..Default::default()
+  };
+  let context = Arc::new(CompilationContext::new(config, vec![]).unwrap());
+  let html_plugin = FarmPluginHtml::new(&context.config);
+
+  let content = r#"<html lang="%lang%">
+<head>
+  <title>%FARM_APP_TITLE% v%VERSION% %UNKNOWN% 100%</title>
+  {{#if analytics}}<script src="./analytics.ts"></script>{{else}}<!-- no analytics -->{{/if}}
+  {{#each preloads}}<link rel="preload" href="%this.href%" data-index="%@index%" />{{/each}}
+</head>
+<body>
+  <script type="text/x-template" id="%lang%">{{#if user}}<p>%lang% 50%</p></script>
+  <TEMPLATE>{{#each items}}%lang%</TEMPLATE>
+</body>
+</html>"#;
+
+  let result = html_plugin
+    .transform(
+      &PluginTransformHookParam {
+        module_id: "index.html".to_string(),
+        content: content.to_string(),
+        module_type: ModuleType::Html,
+        resolved_path: "/root/index.html",
+        query: vec![],
+        meta: HashMap::default(),
+        source_map_chain: vec![],
+      },
+      &context,
+    )
+    .unwrap()
+    .unwrap();
+
+  assert_eq!(
+    result.content,
+    r#"<html lang="en">
+<head>
+  <title>Farm v1.0.0 %UNKNOWN% 100%</title>
+  <!-- no analytics -->
+  <link rel="preload" href="./a.woff2" data-index="0" /><link rel="preload" href="./b.woff2" data-index="1" />
+</head>
+<body>
+  <script type="text/x-template" id="en">{{#if user}}<p>%lang% 50%</p></script>
+  <TEMPLATE>{{#each items}}%lang%</TEMPLATE>
+</body>
+</html>"#
+  );
+}
+
+#[test]
+fn html_template_is_opt_in() {
+  let context = Arc::new(CompilationContext::new(Config::default(), vec![]).unwrap());
+  let html_plugin = FarmPluginHtml::new(&context.config);
+
+  let result = html_plugin
+    .transform(
+      &PluginTransformHookParam {
+        module_id: "index.html".to_string(),
+        content: r#"<div>{{#if user}} 100%</div>"#.to_string(),
+        module_type: ModuleType::Html,
+        resolved_path: "/root/index.html",
+        query: vec![],
+        meta: HashMap::default(),
+        source_map_chain: vec![],
+      },
+      &context,
+    )
+    .unwrap();
+
+  assert!(result.is_none());
+}
+
+#[test]
+fn html_csp() {
+  let html = r#"<!DOCTYPE html><html><head><style>body{color:red}</style><script src="/index.js"></script></head><body><script>console.log(1)</script></body></html>"#;
+  let finalize_html = |csp: HtmlCspConfig| {
+    let config = Config {
+      html: Box::new(HtmlConfig {
+        csp: Some(csp),
+        ..Default::default()
+      }),
+      ..Default::default()
+    };
+    let context = Arc::new(CompilationContext::new(config, vec![]).unwrap());
+    let mut resources_map = HashMap::from_iter([(
+      "index.html".to_string(),
+      Resource {
+        name: "index.html".to_string(),
+        bytes: html.as_bytes().to_vec(),
+        resource_type: ResourceType::Html,
+        ..Default::default()
+      },
+    )]);
+
+    FarmPluginHtmlCsp::new(&context.config)
+      .finalize_resources(
+        &mut PluginFinalizeResourcesHookParam {
+          resources_map: &mut resources_map,
+          config: &context.config,
+        },
+        &context,
+      )
+      .unwrap();
+
+    resources_map
+  };
+
+  let resources_map = finalize_html(HtmlCspConfig::Nonce {
+    placeholder: "{{nonce}}".to_string(),
+  });
+  let code = String::from_utf8(resources_map["index.html"].bytes.clone()).unwrap();
+  assert_eq!(code.matches("{{nonce}}").count(), 3);
+
+  let resources_map = finalize_html(HtmlCspConfig::Hash {
+    output: HtmlCspHashOutput::Meta,
+  });
+  let code = String::from_utf8(resources_map["index.html"].bytes.clone()).unwrap();
+  assert!(code.contains("Content-Security-Policy"));
+  assert!(code.contains("script-src 'self' 'sha256-"));
+  assert!(code.contains("; style-src 'self' 'sha256-"));
+
+  let resources_map = finalize_html(HtmlCspConfig::Hash {
+    output: HtmlCspHashOutput::Json,
+  });
+  let csp_json = String::from_utf8(resources_map["index.csp.json"].bytes.clone()).unwrap();
+  assert!(csp_json.contains(r#""script-src": ["#));
+  assert!(
+    !String::from_utf8(resources_map["index.html"].bytes.clone())
+      .unwrap()
+      .contains("Content-Security-Policy")
+  );
+}
@@ -0,0 +1,228 @@
+use std::path::{Component, Path};
+
+use farmfe_core::{
+  config::Config,
+  module::{module_graph::ModuleGraph, ModuleId},
+  resource::resource_pot::{ResourcePot, ResourcePotType},
+  HashSet,
+};
+use farmfe_utils::relative;
+
+use crate::FARM_RUNTIME_PREFIX;
+
+/// Runtime modules are emitted under this dir in bundle-less mode
+const BUNDLE_LESS_RUNTIME_DIR: &str = "_farm_runtime";
+
+/// Create one resource pot per module for bundle-less library output.
+/// The resource pot is named by the module path relative to `output.src_dir` without extension,
+/// for example `src/lib/utils.ts` is emitted to `lib/utils.mjs` when `src_dir` is `src`.
+pub fn generate_bundle_less_resource_pots(
+  modules: &[ModuleId],
+  module_graph: &ModuleGraph,
+  config: &Config,
+) -> Vec<ResourcePot> {
+  let src_dir = get_bundle_less_src_dir(module_graph, config);
+  let mut used_names = HashSet::default();
+  let mut resource_pots = vec![];
+
+  for module_id in modules {
+    let module = module_graph.module(module_id).unwrap();
+    let resource_pot_type = ResourcePotType::from(module.module_type.clone());
+    let mut name = get_bundle_less_resource_name(module_id, &src_dir, false);
+
+    // a.ts and a.tsx are both named `a`, keep the extension of the latter to avoid conflicts
+    if !used_names.insert((name.clone(), resource_pot_type.clone())) {
+      name = get_bundle_less_resource_name(module_id, &src_dir, true);
+      used_names.insert((name.clone(), resource_pot_type.clone()));
+    }
+
+    let mut resource_pot = ResourcePot::new(&name, "", resource_pot_type);
+    resource_pot.add_module(module_id.clone());
+    resource_pots.push(resource_pot);
+  }
+
+  resource_pots
+}
+
+/// Get `output.src_dir` relative to root, fallback to the common parent dir of entries
+fn get_bundle_less_src_dir(module_graph: &ModuleGraph, config: &Config) -> String {
+  if !config.output.src_dir.is_empty() {
+    // relative to root in the same way as module ids, a dir outside root is like `../shared`
+    let src_dir = if Path::new(&config.output.src_dir).is_absolute() {
+      relative(&config.root, &config.output.src_dir)
+    } else {
+      config.output.src_dir.clone()
+    };
+
+    return normalize_dir(&src_dir);
+  }
+
+  let mut entries = module_graph
+    .entries
+    .keys()
+    .filter(|id| !id.relative_path().starts_with(FARM_RUNTIME_PREFIX))
+    .map(|id| {
+      Path::new(id.relative_path())
+        .parent()
+        .map(|p| normalize_dir(&p.to_string_lossy()))
+        .unwrap_or_default()
+    });
+
+  let Some(first) = entries.next() else {
+    return String::new();
+  };
+
+  entries.fold(first, |common, dir| {
+    common
+      .split('/')
+      .zip(dir.split('/'))
+      .take_while(|(a, b)| a == b)
+      .map(|(a, _)| a)
+      .collect::<Vec<_>>()
+      .join("/")
+  })
+}
+
+fn get_bundle_less_resource_name(module_id: &ModuleId, src_dir: &str, keep_ext: bool) -> String {
+  let relative_path = module_id.relative_path();
+
+  if let Some(runtime_path) = relative_path.strip_prefix(FARM_RUNTIME_PREFIX) {
+    let runtime_path = runtime_path.split('.').next().unwrap_or(runtime_path);
+    return format!("{BUNDLE_LESS_RUNTIME_DIR}/{runtime_path}");
+  }
+
+  let path = relative_path.replace('\\', "/");
+  let path = if src_dir.is_empty() {
+    path.as_str()
+  } else {
+    path
+      .strip_prefix(src_dir)
+      .and_then(|p| p.strip_prefix('/'))
+      .unwrap_or(path.as_str())
+  };
+  let path = Path::new(path);
+  let path = if keep_ext {
+    path.to_path_buf()
+  } else {
+    path.with_extension("")
+  };
+
+  // modules outside src_dir like `../shared/a.ts` are emitted to `_/shared/a`
+  path
+    .components()
+    .filter_map(|c| match c {
+      Component::Normal(c) => Some(c.to_string_lossy().to_string()),
+      Component::ParentDir => Some("_".to_string()),
+      _ => None,
+    })
+    .collect::<Vec<_>>()
+    .join("/")
+}
+
+fn normalize_dir(dir: &str) -> String {
+  let dir = dir.replace('\\', "/");
+  let dir = dir.trim_start_matches("./").trim_matches('/');
+
+  if dir == "." {
+    String::new()
+  } else {
+    dir.to_string()
+  }
+}
+
+/// Whether the resolved path is a package in node_modules, packages are kept external in bundle-less mode
+pub fn is_package_path(resolved_path: &str) -> bool {
+  resolved_path.contains("/node_modules/") || resolved_path.contains("\\node_modules\\")
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_core::config::OutputConfig;
+
+  use super::*;
+
+  fn config(root: &Path, src_dir: &str) -> Config {
+    Config {
+      root: root.to_string_lossy().to_string(),
+      output: Box::new(OutputConfig {
+        src_dir: src_dir.to_string(),
+        ..Default::default()
+      }),
+      ..Default::default()
+    }
+  }
+
+  #[test]
+  fn bundle_less_src_dir() {
+    let cwd = std::env::current_dir().unwrap();
+    let root = cwd.join("project");
+    let mut module_graph = ModuleGraph::new();
+    module_graph
+      .entries
+      .insert("src/index.ts".into(), "index".to_string());
+    module_graph
+      .entries
+      .insert("src/cli/main.ts".into(), "cli".to_string());
+
+    // the common parent dir of the entries
+    assert_eq!(
+      get_bundle_less_src_dir(&module_graph, &config(&root, "")),
+      "src"
+    );
+    assert_eq!(
+      get_bundle_less_src_dir(&module_graph, &config(&root, "./lib/")),
+      "lib"
+    );
+    let src_dir = root.join("src").to_string_lossy().to_string();
+    assert_eq!(
+      get_bundle_less_src_dir(&module_graph, &config(&root, &src_dir)),
+      "src"
+    );
+    // src dir outside root
+    let src_dir = cwd.join("shared").to_string_lossy().to_string();
+    assert_eq!(
+      get_bundle_less_src_dir(&module_graph, &config(&root, &src_dir)),
+      "../shared"
+    );
+    assert_eq!(
+      get_bundle_less_src_dir(&module_graph, &config(&root, "../shared")),
+      "../shared"
+    );
+  }
+
+  #[test]
+  fn bundle_less_resource_name() {
+    // `./lib/utils` is emitted to `lib/utils.mjs` or `lib/utils.cjs` by the ext of the format
+    assert_eq!(
+      get_bundle_less_resource_name(&"src/lib/utils.ts".into(), "src", false),
+      "lib/utils"
+    );
+    // a directory import `./components` is resolved to its index module
+    assert_eq!(
+      get_bundle_less_resource_name(&"src/components/index.tsx".into(), "src", false),
+      "components/index"
+    );
+    assert_eq!(
+      get_bundle_less_resource_name(&"src/lib/utils.ts".into(), "", false),
+      "src/lib/utils"
+    );
+    assert_eq!(
+      get_bundle_less_resource_name(&"src/lib/utils.tsx".into(), "src", true),
+      "lib/utils.tsx"
+    );
+    // modules outside src dir
+    assert_eq!(
+      get_bundle_less_resource_name(&"shared/a.ts".into(), "src", false),
+      "shared/a"
+    );
+    assert_eq!(
+      get_bundle_less_resource_name(&"../shared/a.ts".into(), "src", false),
+      "_/shared/a"
+    );
+    // src dir outside root
+    assert_eq!(
+      get_bundle_less_resource_name(&"../shared/lib/a.ts".into(), "../shared", false),
+      "lib/a"
+    );
+  }
+}
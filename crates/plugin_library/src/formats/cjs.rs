@@ -43,7 +43,7 @@ pub fn emit_cjs_resources(
   );
 
   let mut resources = emit_resource_pot(resource_pot, context, hook_context)?;
-  add_format_to_generated_resources(&mut resources, "cjs");
+  add_format_to_generated_resources(&mut resources, "cjs", context);
 
   if options.should_add_farm_node_require {
     // revert ast
@@ -63,7 +63,7 @@ pub fn emit_esm_resources(
 
   let mut resources = emit_resource_pot(resource_pot, context, hook_context)?;
 
-  add_format_to_generated_resources(&mut resources, "esm");
+  add_format_to_generated_resources(&mut resources, "esm", context);
 
   if options.should_add_farm_node_require {
     // revert ast
@@ -79,7 +79,7 @@ pub fn emit_umd_resources(
   .unwrap();
 
   let mut resources = emit_resource_pot(resource_pot, context, hook_context)?;
-  add_format_to_generated_resources(&mut resources, "umd");
+  add_format_to_generated_resources(&mut resources, "umd", context);
 
   Ok(resources)
 }
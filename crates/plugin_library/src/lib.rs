@@ -15,14 +15,14 @@ use farmfe_core::{
   },
   parking_lot::Mutex,
   plugin::{
-    Plugin, PluginAnalyzeDepsHookResultEntry, PluginGenerateResourcesHookResult,
+    Plugin, PluginAnalyzeDepsHookResultEntry, PluginGenerateResourcesHookResult, PluginHookContext,
     PluginResolveHookResult, ResolveKind,
   },
   rayon::iter::{IntoParallelRefMutIterator, ParallelIterator},
   relative_path::RelativePath,
   resource::{
     meta_data::{js::JsResourcePotMetaData, ResourcePotMetaData},
-    resource_pot::ResourcePotType,
+    resource_pot::{ResourcePot, ResourcePotType},
   },
   swc_common::DUMMY_SP,
   swc_ecma_ast::{Module, ModuleDecl, ModuleItem, Str},
@@ -44,10 +44,16 @@ use farmfe_toolkit::{
   swc_ecma_visit::VisitMutWith,
 };
 
-use crate::formats::{generate_library_format_resources, GenerateLibraryFormatResourcesOptions};
+use crate::{
+  bundle_less::{generate_bundle_less_resource_pots, is_package_path},
+  formats::{generate_library_format_resources, GenerateLibraryFormatResourcesOptions},
+  package_exports::generate_package_exports,
+};
 
+mod bundle_less;
 mod formats;
 mod import_meta_visitor;
+mod package_exports;
 mod utils;
 
 /// Placeholder prefix used to mark import sources that need to be replaced
@@ -105,8 +111,12 @@ impl Plugin for FarmPluginLibrary {
         // so that dynamic imports can create separate resource pots.
       }
       LibraryBundleType::BundleLess => {
-        config.partial_bundling.target_concurrent_requests = usize::MAX;
-        config.partial_bundling.target_min_size = 0;
+        // each module is emitted to its own resource pot named by the source path, see `partial_bundling`,
+        // so entries follow the source layout too
+        config.output.entry_filename = config
+          .output
+          .entry_filename
+          .replace("[entryName]", "[resourceName]");
       }
     }
 
@@ -116,8 +126,11 @@ impl Plugin for FarmPluginLibrary {
       FARM_RUNTIME_MODULE_HELPER_ID.to_string(),
     );
 
-    // add [format] place holder if there are multiple formats
-    if matches!(config.output.format, ModuleFormatConfig::Multiple(_)) {
+    // add [format] place holder if there are multiple formats.
+    // bundle-less output is distinguished by ext(mjs/cjs) so that css files can be shared by all formats
+    if matches!(config.output.format, ModuleFormatConfig::Multiple(_))
+      && config.output.library_bundle_type != LibraryBundleType::BundleLess
+    {
       if !config.output.filename.contains("[format]") {
         config.output.filename = format!("[format]/{}", config.output.filename);
       }
@@ -143,8 +156,8 @@ impl Plugin for FarmPluginLibrary {
   fn resolve(
     &self,
     param: &farmfe_core::plugin::PluginResolveHookParam,
-    _context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
-    _hook_context: &farmfe_core::plugin::PluginHookContext,
+    context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
+    hook_context: &farmfe_core::plugin::PluginHookContext,
   ) -> farmfe_core::error::Result<Option<PluginResolveHookResult>> {
     if param.source.starts_with(FARM_RUNTIME_PREFIX) {
       return Ok(Some(PluginResolveHookResult {
@@ -156,6 +169,29 @@ impl Plugin for FarmPluginLibrary {
       }));
     }
 
+    // keep packages unbundled in bundle-less mode
+    if context.config.output.library_bundle_type == LibraryBundleType::BundleLess
+      && !matches!(param.kind, ResolveKind::Entry(_))
+      && !hook_context.contain_caller(PLUGIN_NAME)
+    {
+      let hook_context = hook_context.clone_and_append_caller(PLUGIN_NAME);
+
+      if let Some(result) = context
+        .plugin_driver
+        .resolve(param, context, &hook_context)?
+      {
+        if !result.external && is_package_path(&result.resolved_path) {
+          return Ok(Some(PluginResolveHookResult {
+            resolved_path: param.source.clone(),
+            external: true,
+            ..Default::default()
+          }));
+        }
+
+        return Ok(Some(result));
+      }
+    }
+
     Ok(None)
   }
 
@@ -229,6 +265,26 @@ impl Plugin for FarmPluginLibrary {
     Ok(None)
   }
 
+  /// Emit one resource pot per module in bundle-less mode
+  fn partial_bundling(
+    &self,
+    modules: &Vec<ModuleId>,
+    context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> farmfe_core::error::Result<Option<Vec<ResourcePot>>> {
+    if context.config.output.library_bundle_type != LibraryBundleType::BundleLess {
+      return Ok(None);
+    }
+
+    let module_graph = context.module_graph.read();
+
+    Ok(Some(generate_bundle_less_resource_pots(
+      modules,
+      &module_graph,
+      &context.config,
+    )))
+  }
+
   // 2. Update ResolveKind to Import for cjs library module
   fn module_graph_build_end(
     &self,
@@ -372,7 +428,12 @@ impl Plugin for FarmPluginLibrary {
     // Replace import sources for internal modules (modules in other resource pots,
     // not truly external packages) with placeholders. These will be replaced with
     // actual relative paths to the output resource files after resources are generated.
-    replace_internal_import_sources_with_placeholders(&mut ast, &external_modules, &module_graph);
+    replace_internal_import_sources_with_placeholders(
+      &mut ast,
+      &external_modules,
+      &module_graph,
+      context.config.output.library_bundle_type == LibraryBundleType::BundleLess,
+    );
 
     context
       .meta
@@ -428,25 +489,45 @@ impl Plugin for FarmPluginLibrary {
 
     Ok(Some(result))
   }
+
+  fn finalize_resources(
+    &self,
+    param: &mut farmfe_core::plugin::PluginFinalizeResourcesHookParam,
+    context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<()>> {
+    if !context.config.output.package_exports.enabled() {
+      return Ok(None);
+    }
+
+    generate_package_exports(param.resources_map, context)?;
+
+    Ok(Some(()))
+  }
 }
 
 /// Replace import/export-from sources in the AST for modules that are NOT truly
 /// external (i.e., modules that exist in other resource pots within the compilation).
 /// These sources are replaced with placeholders like `FARM_BUNDLE_PLACEHOLDER::<module_id>`
 /// which will be resolved to actual relative paths after resource filenames are determined.
+/// In bundle-less mode, css modules are emitted alongside the script modules, so their sources are replaced too.
 fn replace_internal_import_sources_with_placeholders(
   ast: &mut Module,
   external_modules: &HashMap<(String, ResolveKind), ModuleId>,
   module_graph: &ModuleGraph,
+  is_bundle_less: bool,
 ) {
   // Build a mapping from source string -> module_id for non-truly-external modules
-  // that are script types (JS/TS). CSS and other module types should not be replaced.
+  // that are script types (JS/TS). Other module types should not be replaced except css in bundle-less mode.
   let source_to_internal_module: HashMap<String, &ModuleId> = external_modules
     .iter()
     .filter(|((_, _), module_id)| {
       module_graph
         .module(module_id)
-        .map(|m| !m.external && m.module_type.is_script())
+        .map(|m| {
+          !m.external
+            && (m.module_type.is_script()
+              || (is_bundle_less && matches!(m.module_type, ModuleType::Css)))
+        })
         .unwrap_or(false)
     })
     .map(|((source, _), module_id)| (source.clone(), module_id))
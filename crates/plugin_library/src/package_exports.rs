@@ -0,0 +1,358 @@
+use std::{path::Path, sync::Arc};
+
+use farmfe_core::{
+  context::CompilationContext,
+  error::CompilationError,
+  module::ModuleId,
+  resource::{Resource, ResourceOrigin, ResourceType},
+  serde_json::{self, Map, Value},
+  HashMap,
+};
+
+const PACKAGE_JSON: &str = "package.json";
+const DTS_RESOURCE_TYPE: &str = "d.ts";
+
+/// Output files of an entry
+#[derive(Default)]
+struct EntryOutputs {
+  esm: Option<String>,
+  cjs: Option<String>,
+  umd: Option<String>,
+  types: Option<String>,
+}
+
+/// Generate `exports`, `main`, `module` and `types` of `package.json` from the emitted entry resources.
+/// The `package.json` is emitted to `output.path`, or the `package.json` of root is updated in place
+/// when `output.package_exports` is `patch`
+pub fn generate_package_exports(
+  resources_map: &mut HashMap<String, Resource>,
+  context: &Arc<CompilationContext>,
+) -> farmfe_core::error::Result<()> {
+  let config = &context.config;
+  let is_patch = config.output.package_exports.is_patch();
+  let entries = context.module_graph.read().entries.clone();
+
+  let mut entry_outputs: HashMap<ModuleId, EntryOutputs> = HashMap::default();
+  let resource_pot_map = context.resource_pot_map.read();
+
+  for resource in resources_map.values() {
+    if resource.emitted {
+      continue;
+    }
+
+    match (&resource.resource_type, &resource.origin) {
+      (ResourceType::Js, ResourceOrigin::ResourcePot(resource_pot_id)) => {
+        let Some(entry) = resource_pot_map
+          .resource_pot(resource_pot_id)
+          .and_then(|resource_pot| resource_pot.entry_module.as_ref())
+          .filter(|entry| entries.contains_key(*entry))
+        else {
+          continue;
+        };
+
+        let outputs = entry_outputs.entry(entry.clone()).or_default();
+        let name = Some(resource.name.clone());
+
+        match resource
+          .special_placeholders
+          .get("[format]")
+          .map(|f| f.as_str())
+        {
+          Some("esm") => outputs.esm = name,
+          Some("cjs") => outputs.cjs = name,
+          Some("umd") => outputs.umd = name,
+          _ => {}
+        }
+      }
+      (ResourceType::Custom(ty), ResourceOrigin::Module(module_id)) if ty == DTS_RESOURCE_TYPE => {
+        // declarations are emitted with the module they are generated from as origin,
+        // for bundled declarations it's the entry module
+        if entries.contains_key(module_id) {
+          entry_outputs.entry(module_id.clone()).or_default().types = Some(resource.name.clone());
+        }
+      }
+      _ => {}
+    }
+  }
+
+  drop(resource_pot_map);
+
+  if entry_outputs.is_empty() {
+    return Ok(());
+  }
+
+  // paths in package.json of root should contain output dir
+  let prefix = if is_patch {
+    let output_dir = Path::new(&config.output.path);
+    let output_dir = output_dir
+      .strip_prefix(&config.root)
+      .unwrap_or(output_dir)
+      .to_string_lossy()
+      .replace('\\', "/");
+    let output_dir = output_dir.trim_start_matches("./").trim_matches('/');
+
+    if output_dir.is_empty() {
+      "./".to_string()
+    } else {
+      format!("./{output_dir}/")
+    }
+  } else {
+    "./".to_string()
+  };
+  let to_package_path = |name: &String| Value::String(format!("{prefix}{name}"));
+
+  let mut sorted_entries = entries
+    .iter()
+    .filter(|(id, _)| entry_outputs.contains_key(*id))
+    .collect::<Vec<_>>();
+  sorted_entries.sort_by_key(|(_, name)| (name.as_str() != "index", name.to_string()));
+
+  let is_single_entry = sorted_entries.len() == 1;
+  let mut exports = Map::new();
+  let mut root_outputs = None;
+
+  for (entry, name) in sorted_entries {
+    let outputs = &entry_outputs[entry];
+    let subpath = if is_single_entry || name == "index" {
+      root_outputs = Some(outputs);
+      ".".to_string()
+    } else {
+      format!("./{name}")
+    };
+
+    exports.insert(subpath, Value::Object(entry_conditions(outputs, &prefix)));
+  }
+
+  let package_json_path = Path::new(&config.root).join(PACKAGE_JSON);
+  let original = std::fs::read_to_string(&package_json_path).ok();
+  let mut package_json = match &original {
+    Some(content) => serde_json::from_str::<Map<String, Value>>(content).map_err(|e| {
+      CompilationError::GenericError(format!(
+        "Failed to parse {package_json_path:?} when generating package exports: {e}"
+      ))
+    })?,
+    None => Map::new(),
+  };
+
+  if let Some(outputs) = root_outputs {
+    let main = outputs
+      .cjs
+      .as_ref()
+      .or(outputs.umd.as_ref())
+      .or(outputs.esm.as_ref());
+
+    if let Some(main) = main {
+      package_json.insert("main".to_string(), to_package_path(main));
+    }
+    if let Some(esm) = &outputs.esm {
+      package_json.insert("module".to_string(), to_package_path(esm));
+    }
+    if let Some(types) = &outputs.types {
+      package_json.insert("types".to_string(), to_package_path(types));
+    }
+  }
+
+  let exports = if is_patch {
+    merge_exports(package_json.remove("exports"), exports)
+  } else {
+    exports
+  };
+  package_json.insert("exports".to_string(), Value::Object(exports));
+
+  let content = format!("{}\n", serde_json::to_string_pretty(&package_json).unwrap());
+
+  if is_patch {
+    // only write when changed, avoid triggering file watchers on every build
+    if original.as_ref() != Some(&content) {
+      std::fs::write(&package_json_path, content).map_err(|e| {
+        CompilationError::GenericError(format!(
+          "Failed to write {package_json_path:?} when generating package exports: {e}"
+        ))
+      })?;
+    }
+  } else {
+    resources_map.insert(
+      PACKAGE_JSON.to_string(),
+      Resource {
+        name: PACKAGE_JSON.to_string(),
+        name_hash: "".to_string(),
+        bytes: content.into_bytes(),
+        emitted: false,
+        should_transform_output_filename: false,
+        resource_type: ResourceType::Asset("json".to_string()),
+        origin: ResourceOrigin::Module(PACKAGE_JSON.into()),
+        meta: Default::default(),
+        special_placeholders: Default::default(),
+      },
+    );
+  }
+
+  Ok(())
+}
+
+/// The conditions of an entry subpath, only the emitted formats are included
+fn entry_conditions(outputs: &EntryOutputs, prefix: &str) -> Map<String, Value> {
+  let to_package_path = |name: &String| Value::String(format!("{prefix}{name}"));
+  // types should always be the first condition
+  let mut conditions = Map::new();
+
+  if let Some(types) = &outputs.types {
+    conditions.insert("types".to_string(), to_package_path(types));
+  }
+  if let Some(esm) = &outputs.esm {
+    conditions.insert("import".to_string(), to_package_path(esm));
+  }
+  if let Some(cjs) = &outputs.cjs {
+    conditions.insert("require".to_string(), to_package_path(cjs));
+  }
+  if let Some(umd) = &outputs.umd {
+    conditions.insert("default".to_string(), to_package_path(umd));
+  }
+
+  conditions
+}
+
+/// Merge the generated subpaths into the `exports` of the user's `package.json`. Only the subpaths of the entries
+/// are owned by the plugin and overwritten, other subpaths like `./package.json` are kept in their original order
+fn merge_exports(existing: Option<Value>, generated: Map<String, Value>) -> Map<String, Value> {
+  let mut exports = match existing {
+    Some(Value::Object(map)) if map.keys().all(|key| key.starts_with('.')) => map,
+    // `"exports": "./index.js"` and an object of conditions are the shorthands of the `.` subpath
+    Some(value @ (Value::String(_) | Value::Object(_) | Value::Array(_))) => {
+      Map::from_iter([(".".to_string(), value)])
+    }
+    _ => Map::new(),
+  };
+
+  for (subpath, conditions) in generated {
+    exports.insert(subpath, conditions);
+  }
+
+  exports
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_core::serde_json::json;
+
+  use super::*;
+
+  fn outputs(
+    esm: Option<&str>,
+    cjs: Option<&str>,
+    umd: Option<&str>,
+    types: Option<&str>,
+  ) -> EntryOutputs {
+    EntryOutputs {
+      esm: esm.map(String::from),
+      cjs: cjs.map(String::from),
+      umd: umd.map(String::from),
+      types: types.map(String::from),
+    }
+  }
+
+  #[test]
+  fn entry_conditions_of_formats() {
+    assert_eq!(
+      Value::Object(entry_conditions(
+        &outputs(
+          Some("index.mjs"),
+          Some("index.cjs"),
+          None,
+          Some("index.d.ts")
+        ),
+        "./dist/"
+      )),
+      json!({
+        "types": "./dist/index.d.ts",
+        "import": "./dist/index.mjs",
+        "require": "./dist/index.cjs"
+      })
+    );
+    // single format
+    assert_eq!(
+      Value::Object(entry_conditions(
+        &outputs(Some("index.js"), None, None, None),
+        "./"
+      )),
+      json!({ "import": "./index.js" })
+    );
+    assert_eq!(
+      Value::Object(entry_conditions(
+        &outputs(None, None, Some("index.js"), None),
+        "./"
+      )),
+      json!({ "default": "./index.js" })
+    );
+    // an entry that only emits declarations
+    assert_eq!(
+      Value::Object(entry_conditions(
+        &outputs(None, None, None, Some("types.d.ts")),
+        "./"
+      )),
+      json!({ "types": "./types.d.ts" })
+    );
+    // types is the first condition
+    assert_eq!(
+      entry_conditions(&outputs(Some("a.mjs"), None, None, Some("a.d.ts")), "./")
+        .keys()
+        .next()
+        .unwrap(),
+      "types"
+    );
+  }
+
+  #[test]
+  fn merge_exports_keeps_user_exports() {
+    let generated = Map::from_iter([
+      (".".to_string(), json!({ "import": "./dist/index.mjs" })),
+      (
+        "./types".to_string(),
+        json!({ "types": "./dist/types.d.ts" }),
+      ),
+    ]);
+
+    let merged = merge_exports(
+      Some(json!({
+        "./package.json": "./package.json",
+        ".": "./old/index.js",
+        "./styles/*": "./styles/*.css"
+      })),
+      generated.clone(),
+    );
+    assert_eq!(
+      Value::Object(merged.clone()),
+      json!({
+        "./package.json": "./package.json",
+        ".": { "import": "./dist/index.mjs" },
+        "./styles/*": "./styles/*.css",
+        "./types": { "types": "./dist/types.d.ts" }
+      })
+    );
+    // the user's subpaths keep their order
+    assert_eq!(
+      merged.keys().collect::<Vec<_>>(),
+      vec!["./package.json", ".", "./styles/*", "./types"]
+    );
+
+    // shorthands of the `.` subpath are replaced
+    assert_eq!(
+      Value::Object(merge_exports(
+        Some(json!("./old/index.js")),
+        generated.clone()
+      )),
+      Value::Object(generated.clone())
+    );
+    assert_eq!(
+      Value::Object(merge_exports(
+        Some(json!({ "import": "./old/index.mjs" })),
+        generated.clone()
+      )),
+      Value::Object(generated.clone())
+    );
+    assert_eq!(
+      Value::Object(merge_exports(None, generated.clone())),
+      Value::Object(generated)
+    );
+  }
+}
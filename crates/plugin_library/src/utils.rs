@@ -1,6 +1,7 @@
 use std::{collections::VecDeque, sync::Arc};
 
 use farmfe_core::{
+  config::LibraryBundleType,
   context::CompilationContext,
   module::meta_data::script::{
     statement::{Statement, StatementId, SwcId},
@@ -190,7 +191,13 @@ impl Visit for UsedDefinedIdentsAnalyzer {
   }
 }
 
-pub fn add_format_to_generated_resources(resources: &mut [GeneratedResource], format: &str) {
+pub fn add_format_to_generated_resources(
+  resources: &mut [GeneratedResource],
+  format: &str,
+  context: &Arc<CompilationContext>,
+) {
+  let is_bundle_less = context.config.output.library_bundle_type == LibraryBundleType::BundleLess;
+
   resources.iter_mut().for_each(|resource| {
     let both_placeholder_map = HashMap::from_iter([("[format]".to_string(), format.to_string())]);
 
@@ -221,6 +228,13 @@ pub fn add_format_to_generated_resources(resources: &mut [GeneratedResource], fo
         })
     });
 
+    // bundle-less output of all formats are emitted to the same dir, always use mjs/cjs to distinguish them
+    let ext = match format {
+      "cjs" if is_bundle_less => Some("cjs".to_string()),
+      "esm" if is_bundle_less => Some("mjs".to_string()),
+      _ => ext,
+    };
+
     // override internal ext
     if let Some(ext) = ext {
       resource
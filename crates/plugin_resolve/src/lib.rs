@@ -11,7 +11,7 @@ use farmfe_core::{
   serde_json, HashMap, HashSet,
 };
 
-use farmfe_toolkit::resolve::DYNAMIC_EXTENSION_PRIORITY;
+use farmfe_toolkit::resolve::{ADDITIONAL_RESOLVE_CONDITIONS, DYNAMIC_EXTENSION_PRIORITY};
 use farmfe_utils::parse_query;
 use once_cell::sync::OnceCell;
 use resolver::{parse_package_source, ResolveOptions, Resolver};
@@ -100,7 +100,15 @@ impl Plugin for FarmPluginResolve {
       } else {
         None
       };
-    let resolve_options = ResolveOptions { dynamic_extensions };
+    let additional_conditions = hook_context
+      .meta
+      .get(ADDITIONAL_RESOLVE_CONDITIONS)
+      .map(|conditions| serde_json::from_str::<Vec<String>>(conditions).unwrap_or_default())
+      .unwrap_or_default();
+    let resolve_options = ResolveOptions {
+      dynamic_extensions,
+      additional_conditions,
+    };
 
     let resolver = &self.resolver;
     let result = resolver.resolve(
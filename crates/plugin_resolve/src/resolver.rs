@@ -47,6 +47,8 @@ pub struct ResolveCacheKey {
 #[derive(Debug, Clone, Hash, PartialEq, Eq, Default)]
 pub struct ResolveOptions {
   pub dynamic_extensions: Option<Vec<String>>,
+  /// conditions used together with `resolve.conditions`, e.g. `worker` for the modules imported by web workers
+  pub additional_conditions: Vec<String>,
 }
 
 pub struct Resolver {
@@ -134,7 +136,7 @@ impl Resolver {
     farm_profile_function!("resolver::resolve".to_string());
 
     // 1. try `imports` field(https://nodejs.org/api/packages.html#subpath-imports).
-    let resolved_imports = self.try_imports(source, base_dir.clone(), kind, context);
+    let resolved_imports = self.try_imports(source, base_dir.clone(), kind, options, context);
     let (source, base_dir) = if let Some((resolved_imports, package_dir)) = resolved_imports {
       (resolved_imports, PathBuf::from(&package_dir))
     } else {
@@ -674,23 +676,30 @@ impl Resolver {
     );
 
     let relative_path = if let Ok(package_json_info) = package_json_info {
-      resolve_exports_or_imports(&package_json_info, subpath, "exports", kind, context)
-        .into_resolved(context.config.resolve.strict_exports)
-        .or_else(|| {
-          if context.config.output.target_env.is_browser() {
-            try_browser_map(
-              &package_json_info,
-              BrowserMapType::Source(subpath.to_string()),
-            )
-            .map(|browser_map_result| match browser_map_result {
-              BrowserMapResult::Str(mapped_value) => mapped_value,
-              BrowserMapResult::External => BROWSER_SUBPATH_EXTERNAL_ID.to_string(),
-            })
-          } else {
-            None
-          }
-        })
-        .unwrap_or(subpath.to_string())
+      resolve_exports_or_imports(
+        &package_json_info,
+        subpath,
+        "exports",
+        kind,
+        options,
+        context,
+      )
+      .into_resolved(context.config.resolve.strict_exports)
+      .or_else(|| {
+        if context.config.output.target_env.is_browser() {
+          try_browser_map(
+            &package_json_info,
+            BrowserMapType::Source(subpath.to_string()),
+          )
+          .map(|browser_map_result| match browser_map_result {
+            BrowserMapResult::Str(mapped_value) => mapped_value,
+            BrowserMapResult::External => BROWSER_SUBPATH_EXTERNAL_ID.to_string(),
+          })
+        } else {
+          None
+        }
+      })
+      .unwrap_or(subpath.to_string())
     } else {
       subpath.to_string()
     };
@@ -732,6 +741,7 @@ impl Resolver {
           ".",
           HIGHEST_PRIORITY_FIELD,
           kind,
+          options,
           context,
         )
         .into_resolved(context.config.resolve.strict_exports)
@@ -824,6 +834,7 @@ impl Resolver {
     source: &str,
     base_dir: PathBuf,
     kind: &ResolveKind,
+    options: &ResolveOptions,
     context: &Arc<CompilationContext>,
   ) -> Option<(String, String)> {
     farm_profile_function!("try_imports".to_string());
@@ -840,9 +851,16 @@ impl Resolver {
     )
     .ok()?;
 
-    resolve_exports_or_imports(&package_json_info, source, "imports", kind, context)
-      .into_resolved(context.config.resolve.strict_exports)
-      .map(|imports_path| (imports_path, package_json_info.dir().to_string()))
+    resolve_exports_or_imports(
+      &package_json_info,
+      source,
+      "imports",
+      kind,
+      options,
+      context,
+    )
+    .into_resolved(context.config.resolve.strict_exports)
+    .map(|imports_path| (imports_path, package_json_info.dir().to_string()))
   }
 
   fn is_module_side_effects(
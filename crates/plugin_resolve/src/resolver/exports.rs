@@ -12,7 +12,7 @@ use farmfe_core::{
   HashSet,
 };
 
-use super::utils::get_field_value_from_package_json_info;
+use super::{utils::get_field_value_from_package_json_info, ResolveOptions};
 
 #[derive(Debug, Eq, PartialEq, Hash)]
 enum Condition {
@@ -93,11 +93,18 @@ pub fn resolve_exports_or_imports(
   key: &str,
   field_type: &str,
   kind: &ResolveKind,
+  options: &ResolveOptions,
   context: &Arc<CompilationContext>,
 ) -> ResolveExportsOrImportsResult {
   farm_profile_function!("resolve_exports_or_imports".to_string());
-  let mut additional_conditions: HashSet<String> =
-    context.config.resolve.conditions.iter().cloned().collect();
+  let mut additional_conditions: HashSet<String> = context
+    .config
+    .resolve
+    .conditions
+    .iter()
+    .chain(options.additional_conditions.iter())
+    .cloned()
+    .collect();
 
   if !additional_conditions.contains(&String::from("production"))
     && !additional_conditions.contains(&String::from("development"))
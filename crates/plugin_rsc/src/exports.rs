@@ -0,0 +1,147 @@
+use farmfe_core::swc_ecma_ast::{
+  Decl, DefaultDecl, ExportSpecifier, Expr, ModuleDecl, ModuleExportName, ModuleItem, Pat,
+};
+
+/// An export of a module with directive
+#[derive(Debug, PartialEq, Eq)]
+pub struct DirectiveExport {
+  /// the exported name, `default` for default export
+  pub name: String,
+  /// the local ident of the export, [None] if the export can not be referenced in the module,
+  /// for example `export default async () => {}` and `export { foo } from './foo'`
+  pub local: Option<String>,
+}
+
+/// Collect the exports of a `use client` or `use server` module.
+/// `export * from` can not be analyzed statically and is ignored
+pub fn collect_directive_exports(items: &[ModuleItem]) -> Vec<DirectiveExport> {
+  let mut exports = vec![];
+  let mut add_export = |name: String, local: Option<String>| {
+    exports.push(DirectiveExport { name, local });
+  };
+
+  for item in items {
+    let ModuleItem::ModuleDecl(module_decl) = item else {
+      continue;
+    };
+
+    match module_decl {
+      ModuleDecl::ExportDecl(export_decl) => match &export_decl.decl {
+        Decl::Fn(fn_decl) => {
+          let name = fn_decl.ident.sym.to_string();
+          add_export(name.clone(), Some(name));
+        }
+        Decl::Class(class_decl) => {
+          let name = class_decl.ident.sym.to_string();
+          add_export(name.clone(), Some(name));
+        }
+        Decl::Var(var_decl) => {
+          for decl in &var_decl.decls {
+            if let Pat::Ident(ident) = &decl.name {
+              let name = ident.sym.to_string();
+              add_export(name.clone(), Some(name));
+            }
+          }
+        }
+        _ => {}
+      },
+      ModuleDecl::ExportNamed(named_export) if !named_export.type_only => {
+        for specifier in &named_export.specifiers {
+          match specifier {
+            ExportSpecifier::Named(named) if !named.is_type_only => {
+              let local = export_name(&named.orig);
+              let name = named
+                .exported
+                .as_ref()
+                .map(export_name)
+                .unwrap_or(local.clone());
+              let local = named_export.src.is_none().then_some(local);
+              add_export(name, local);
+            }
+            ExportSpecifier::Namespace(namespace) => {
+              add_export(export_name(&namespace.name), None);
+            }
+            ExportSpecifier::Default(default) => {
+              add_export(default.exported.sym.to_string(), None);
+            }
+            _ => {}
+          }
+        }
+      }
+      ModuleDecl::ExportDefaultDecl(export_default_decl) => {
+        let local = match &export_default_decl.decl {
+          DefaultDecl::Fn(fn_expr) => fn_expr.ident.as_ref().map(|i| i.sym.to_string()),
+          DefaultDecl::Class(class_expr) => class_expr.ident.as_ref().map(|i| i.sym.to_string()),
+          DefaultDecl::TsInterfaceDecl(_) => continue,
+        };
+        add_export("default".to_string(), local);
+      }
+      ModuleDecl::ExportDefaultExpr(export_default_expr) => {
+        let local = match &*export_default_expr.expr {
+          Expr::Ident(ident) => Some(ident.sym.to_string()),
+          _ => None,
+        };
+        add_export("default".to_string(), local);
+      }
+      _ => {}
+    }
+  }
+
+  exports
+}
+
+fn export_name(name: &ModuleExportName) -> String {
+  match name {
+    ModuleExportName::Ident(ident) => ident.sym.to_string(),
+    ModuleExportName::Str(str) => str.value.to_string_lossy().into_owned(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use farmfe_core::{swc_ecma_ast::EsVersion, swc_ecma_parser::Syntax};
+  use farmfe_toolkit::script::{parse_module, ParseScriptModuleResult};
+
+  use super::{collect_directive_exports, DirectiveExport};
+
+  #[test]
+  fn collect_exports() {
+    let code = r#"
+'use server';
+export async function like() {}
+export const a = 1, b = 2;
+const c = 3;
+export { c as d };
+export { e } from './e';
+export type { F } from './f';
+export default async () => {};
+"#;
+
+    let ParseScriptModuleResult { ast, .. } = parse_module(
+      &"any".into(),
+      Arc::new(code.to_string()),
+      Syntax::Typescript(Default::default()),
+      EsVersion::EsNext,
+    )
+    .unwrap();
+
+    let export = |name: &str, local: Option<&str>| DirectiveExport {
+      name: name.to_string(),
+      local: local.map(|l| l.to_string()),
+    };
+
+    assert_eq!(
+      collect_directive_exports(&ast.body),
+      vec![
+        export("like", Some("like")),
+        export("a", Some("a")),
+        export("b", Some("b")),
+        export("d", Some("c")),
+        export("e", None),
+        export("default", None),
+      ]
+    );
+  }
+}
@@ -0,0 +1,445 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  config::{rsc::RscConfig, Config},
+  context::CompilationContext,
+  error::Result,
+  module::{meta_data::ModuleMetaData, module_graph::ModuleGraph, Module, ModuleType},
+  plugin::{
+    Plugin, PluginAnalyzeDepsHookParam, PluginAnalyzeDepsHookResultEntry,
+    PluginFinalizeResourcesHookParam, PluginTransformHookParam, PluginTransformHookResult,
+    ResolveKind,
+  },
+  resource::{resource_pot_map::ResourcePotMap, Resource, ResourceOrigin, ResourceType},
+  serde_json::{self, json, Map, Value},
+  swc_ecma_ast::EsVersion,
+  HashMap,
+};
+use farmfe_plugin_script_meta::{analyze_directives, USE_CLIENT, USE_SERVER};
+use farmfe_toolkit::script::{parse_module, syntax_from_module_type, ParseScriptModuleResult};
+
+use exports::collect_directive_exports;
+use references::{
+  client_reference_proxy, server_reference_id, server_reference_proxy,
+  server_reference_registrations,
+};
+
+mod exports;
+mod references;
+
+pub const CLIENT_REFERENCE_MANIFEST: &str = "client-reference-manifest.json";
+pub const SERVER_REFERENCE_MANIFEST: &str = "server-reference-manifest.json";
+/// Key of [CompilationContext::shared_custom], the resolved paths of the `use client` modules found by the server environment
+const CLIENT_REFERENCES_KEY: &str = "rsc_client_references";
+
+/// Handle `"use client"` and `"use server"` directives of React Server Components.
+/// * In the server environment(`react-server` in `resolve.conditions`), `use client` modules are replaced by client references,
+///   and the exports of `use server` modules are registered as server references.
+/// * In the client environment, `use server` modules are replaced by server references that call the server by `callServer`,
+///   and the `use client` modules found by the server environment are dynamically imported by the entries, so that each of
+///   them is split into chunks that are loaded on demand when the server renders it.
+///
+/// The client and server reference manifests are emitted so that the rsc runtime can map the references to the chunks.
+pub struct FarmPluginRsc {
+  is_server: bool,
+  config: RscConfig,
+}
+
+impl FarmPluginRsc {
+  pub fn new(config: &Config) -> Self {
+    Self {
+      is_server: config.is_react_server(),
+      config: config.rsc.as_obj().cloned().unwrap_or_default(),
+    }
+  }
+}
+
+impl Plugin for FarmPluginRsc {
+  fn name(&self) -> &str {
+    "FarmPluginRsc"
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<PluginTransformHookResult>> {
+    if !param.module_type.is_script()
+      || !(param.content.contains(USE_CLIENT) || param.content.contains(USE_SERVER))
+    {
+      return Ok(None);
+    }
+
+    let Some(syntax) =
+      syntax_from_module_type(&param.module_type, context.config.script.parser.clone())
+    else {
+      return Ok(None);
+    };
+
+    // syntax errors are reported by the script plugin when parsing the module
+    let Ok(ParseScriptModuleResult { ast, .. }) = parse_module(
+      &param.module_id.as_str().into(),
+      Arc::new(param.content.clone()),
+      syntax,
+      EsVersion::EsNext,
+    ) else {
+      return Ok(None);
+    };
+
+    let directives = analyze_directives(&ast);
+    let has_directive = |directive: &str| directives.iter().any(|d| d == directive);
+    let exports = collect_directive_exports(&ast.body);
+
+    let content = if self.is_server && has_directive(USE_CLIENT) {
+      client_reference_proxy(&param.module_id, &exports, &self.config)
+    } else if self.is_server && has_directive(USE_SERVER) {
+      // keep the module as is, the registrations are appended so the source map is still valid
+      return Ok(Some(PluginTransformHookResult {
+        content: format!(
+          "{}{}",
+          param.content,
+          server_reference_registrations(&param.module_id, &exports, &self.config)
+        ),
+        ..Default::default()
+      }));
+    } else if !self.is_server && has_directive(USE_SERVER) {
+      server_reference_proxy(&param.module_id, &exports, &self.config)
+    } else {
+      return Ok(None);
+    };
+
+    Ok(Some(PluginTransformHookResult {
+      content,
+      module_type: Some(ModuleType::Js),
+      source_map: None,
+      ignore_previous_source_map: true,
+    }))
+  }
+
+  fn analyze_deps(
+    &self,
+    param: &mut PluginAnalyzeDepsHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if self.is_server || !param.module.is_entry {
+      return Ok(None);
+    }
+
+    let Some(shared_custom) = &context.shared_custom else {
+      return Ok(None);
+    };
+    let shared_custom = shared_custom.lock();
+    let Some(client_references) = shared_custom
+      .get(CLIENT_REFERENCES_KEY)
+      .and_then(|references| references.downcast_ref::<Vec<String>>())
+    else {
+      return Ok(None);
+    };
+
+    for resolved_path in client_references {
+      if !param.deps.iter().any(|dep| &dep.source == resolved_path) {
+        param.deps.push(PluginAnalyzeDepsHookResultEntry {
+          source: resolved_path.clone(),
+          kind: ResolveKind::DynamicImport,
+        });
+      }
+    }
+
+    Ok(Some(()))
+  }
+
+  fn module_graph_build_end(
+    &self,
+    module_graph: &mut ModuleGraph,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    if !self.is_server {
+      return Ok(None);
+    }
+
+    let Some(shared_custom) = &context.shared_custom else {
+      return Ok(None);
+    };
+
+    // the client reference proxies keep the `use client` directive
+    let mut client_references = module_graph
+      .modules()
+      .into_iter()
+      .filter(|module| has_directive(module, USE_CLIENT))
+      .map(|module| module.id.resolved_path(&context.config.root))
+      .collect::<Vec<_>>();
+    client_references.sort();
+
+    shared_custom.lock().insert(
+      CLIENT_REFERENCES_KEY.to_string(),
+      Box::new(client_references),
+    );
+
+    Ok(Some(()))
+  }
+
+  fn finalize_resources(
+    &self,
+    param: &mut PluginFinalizeResourcesHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>> {
+    let module_graph = context.module_graph.read();
+    let resource_pot_map = context.resource_pot_map.read();
+    let manifest = self.reference_manifest(&module_graph, &resource_pot_map, param.resources_map);
+
+    let name = if self.is_server {
+      SERVER_REFERENCE_MANIFEST
+    } else {
+      CLIENT_REFERENCE_MANIFEST
+    };
+
+    param.resources_map.insert(
+      name.to_string(),
+      Resource {
+        name: name.to_string(),
+        name_hash: "".to_string(),
+        bytes: serde_json::to_string_pretty(&Value::Object(manifest))
+          .unwrap()
+          .into_bytes(),
+        emitted: false,
+        should_transform_output_filename: false,
+        resource_type: ResourceType::Asset("json".to_string()),
+        origin: ResourceOrigin::Module(name.into()),
+        meta: Default::default(),
+        special_placeholders: Default::default(),
+      },
+    );
+
+    Ok(Some(()))
+  }
+}
+
+impl FarmPluginRsc {
+  /// The server reference manifest in the server environment, or the client reference manifest in the client environment
+  fn reference_manifest(
+    &self,
+    module_graph: &ModuleGraph,
+    resource_pot_map: &ResourcePotMap,
+    resources_map: &HashMap<String, Resource>,
+  ) -> Map<String, Value> {
+    // client references are rendered in the client environment, server references are executed in the server environment
+    let directive = if self.is_server {
+      USE_SERVER
+    } else {
+      USE_CLIENT
+    };
+    let mut manifest = Map::new();
+
+    for module in module_graph.modules() {
+      let ModuleMetaData::Script(meta) = module.meta.as_ref() else {
+        continue;
+      };
+
+      if !meta.directives.iter().any(|d| d == directive) {
+        continue;
+      }
+
+      let id = module.id.to_string();
+      let chunks = module_chunks(module, resource_pot_map, resources_map);
+
+      if self.is_server {
+        for export in collect_directive_exports(&meta.ast.body) {
+          if export.local.is_none() {
+            continue;
+          }
+
+          manifest.insert(
+            server_reference_id(&id, &export.name),
+            json!({ "id": id, "name": export.name, "chunks": chunks }),
+          );
+        }
+      } else {
+        manifest.insert(
+          id.clone(),
+          json!({ "id": id, "chunks": chunks, "name": "*" }),
+        );
+      }
+    }
+
+    manifest
+  }
+}
+
+fn has_directive(module: &Module, directive: &str) -> bool {
+  match module.meta.as_ref() {
+    ModuleMetaData::Script(meta) => meta.directives.iter().any(|d| d == directive),
+    _ => false,
+  }
+}
+
+/// The js and css resources of the resource pots that contain the module
+fn module_chunks(
+  module: &Module,
+  resource_pot_map: &ResourcePotMap,
+  resources_map: &HashMap<String, Resource>,
+) -> Vec<String> {
+  let mut chunks = module
+    .resource_pots
+    .iter()
+    .filter_map(|id| resource_pot_map.resource_pot(id))
+    .flat_map(|resource_pot| resource_pot.resources())
+    .filter(|name| {
+      resources_map.get(*name).is_some_and(|resource| {
+        matches!(resource.resource_type, ResourceType::Js | ResourceType::Css)
+      })
+    })
+    .cloned()
+    .collect::<Vec<_>>();
+  chunks.sort();
+
+  chunks
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use farmfe_core::{
+    config::{bool_or_obj::BoolOrObj, rsc::REACT_SERVER_CONDITION, Config},
+    module::{
+      meta_data::script::ScriptModuleMetaData, module_graph::ModuleGraph, Module, ModuleMetaData,
+    },
+    resource::{
+      resource_pot::{ResourcePot, ResourcePotType},
+      resource_pot_map::ResourcePotMap,
+      Resource, ResourceType,
+    },
+    serde_json::{json, Value},
+    swc_ecma_ast::EsVersion,
+    swc_ecma_parser::Syntax,
+    HashMap,
+  };
+  use farmfe_plugin_script_meta::analyze_directives;
+  use farmfe_toolkit::script::{parse_module, ParseScriptModuleResult};
+
+  use super::FarmPluginRsc;
+
+  fn plugin(is_server: bool) -> FarmPluginRsc {
+    let mut config = Config {
+      rsc: Box::new(BoolOrObj::Bool(true)),
+      ..Default::default()
+    };
+
+    if is_server {
+      config
+        .resolve
+        .conditions
+        .push(REACT_SERVER_CONDITION.to_string());
+    }
+
+    FarmPluginRsc::new(&config)
+  }
+
+  /// add the module of `code`, which is contained by the resource pot `chunk`
+  fn add_module(
+    module_graph: &mut ModuleGraph,
+    resource_pot_map: &mut ResourcePotMap,
+    id: &str,
+    code: &str,
+    chunk: &str,
+  ) {
+    let ParseScriptModuleResult { ast, .. } = parse_module(
+      &id.into(),
+      Arc::new(code.to_string()),
+      Syntax::Typescript(Default::default()),
+      EsVersion::EsNext,
+    )
+    .unwrap();
+    let directives = analyze_directives(&ast);
+
+    let mut module = Module::new(id.into());
+    module.meta = Box::new(ModuleMetaData::Script(Box::new(ScriptModuleMetaData {
+      ast,
+      directives,
+      ..Default::default()
+    })));
+
+    let mut resource_pot = ResourcePot::new(chunk, "", ResourcePotType::Js);
+    resource_pot.add_module(id.into());
+    resource_pot.add_resource(format!("{chunk}.js"));
+    resource_pot.add_resource(format!("{chunk}.js.map"));
+    module.resource_pots.insert(resource_pot.id.clone());
+
+    resource_pot_map.add_resource_pot(resource_pot);
+    module_graph.add_module(module);
+  }
+
+  fn manifest(is_server: bool) -> Value {
+    let mut module_graph = ModuleGraph::new();
+    let mut resource_pot_map = ResourcePotMap::new();
+    let mut resources_map = HashMap::default();
+
+    add_module(
+      &mut module_graph,
+      &mut resource_pot_map,
+      "src/Button.tsx",
+      "'use client';\nexport function Button() {}",
+      "button",
+    );
+    add_module(
+      &mut module_graph,
+      &mut resource_pot_map,
+      "src/actions.ts",
+      "'use server';\nexport async function like() {}\nexport { view } from './view';",
+      "actions",
+    );
+    add_module(
+      &mut module_graph,
+      &mut resource_pot_map,
+      "src/index.tsx",
+      "export const a = 1;",
+      "index",
+    );
+
+    for chunk in ["button", "actions", "index"] {
+      for (name, resource_type) in [
+        (format!("{chunk}.js"), ResourceType::Js),
+        (
+          format!("{chunk}.js.map"),
+          ResourceType::SourceMap(format!("{chunk}.js")),
+        ),
+      ] {
+        resources_map.insert(
+          name.clone(),
+          Resource {
+            name,
+            resource_type,
+            ..Default::default()
+          },
+        );
+      }
+    }
+
+    Value::Object(plugin(is_server).reference_manifest(
+      &module_graph,
+      &resource_pot_map,
+      &resources_map,
+    ))
+  }
+
+  #[test]
+  fn client_reference_manifest() {
+    assert_eq!(
+      manifest(false),
+      json!({
+        "src/Button.tsx": { "id": "src/Button.tsx", "chunks": ["button.js"], "name": "*" }
+      })
+    );
+  }
+
+  #[test]
+  fn server_reference_manifest() {
+    // re-exports can not be registered, they are not in the manifest
+    assert_eq!(
+      manifest(true),
+      json!({
+        "src/actions.ts#like": { "id": "src/actions.ts", "name": "like", "chunks": ["actions.js"] }
+      })
+    );
+  }
+}
@@ -0,0 +1,220 @@
+use farmfe_core::{config::rsc::RscConfig, serde_json};
+use farmfe_plugin_script_meta::USE_CLIENT;
+
+use crate::exports::DirectiveExport;
+
+/// Global function that is called by the server references when `rsc.callServer` is not set
+pub const CALL_SERVER_GLOBAL: &str = "__FARM_RSC_CALL_SERVER__";
+
+/// The id of a server reference, the same as the key of the server reference manifest
+pub fn server_reference_id(module_id: &str, name: &str) -> String {
+  format!("{module_id}#{name}")
+}
+
+/// Replace a `use client` module in the server environment, each export is a client reference
+/// that throws when it's called on the server. The directive is kept so the module is still known as a client reference:
+/// ```js
+/// "use client";
+/// import { registerClientReference } from 'react-server-dom-webpack/server';
+/// export const Button = registerClientReference(function () { throw ... }, "src/Button.tsx", "Button");
+/// ```
+pub fn client_reference_proxy(
+  module_id: &str,
+  exports: &[DirectiveExport],
+  config: &RscConfig,
+) -> String {
+  let mut code = format!(
+    "{};\nimport {{ registerClientReference }} from {};\n",
+    quote(USE_CLIENT),
+    quote(&format!("{}/server", config.runtime))
+  );
+
+  for export in exports {
+    let message = format!(
+      "Attempted to call {}() of {module_id} from the server, but it's on the client. It can only be rendered as a component or passed to client components.",
+      export.name
+    );
+    let reference = format!(
+      "registerClientReference(function () {{ throw new Error({}); }}, {}, {})",
+      quote(&message),
+      quote(module_id),
+      quote(&export.name)
+    );
+
+    code.push_str(&export_statement(&export.name, &reference));
+  }
+
+  code
+}
+
+/// Register the exported functions of a `use server` module in the server environment,
+/// the registrations are appended to the module:
+/// ```js
+/// import { registerServerReference as $$registerServerReference } from 'react-server-dom-webpack/server';
+/// $$registerServerReference(like, "src/actions.ts", "like");
+/// ```
+pub fn server_reference_registrations(
+  module_id: &str,
+  exports: &[DirectiveExport],
+  config: &RscConfig,
+) -> String {
+  let mut code = format!(
+    "\nimport {{ registerServerReference as $$registerServerReference }} from {};\n",
+    quote(&format!("{}/server", config.runtime))
+  );
+
+  for export in exports {
+    if let Some(local) = &export.local {
+      code.push_str(&format!(
+        "$$registerServerReference({local}, {}, {});\n",
+        quote(module_id),
+        quote(&export.name)
+      ));
+    }
+  }
+
+  code
+}
+
+/// Replace a `use server` module in the client environment, each export is a server reference
+/// that calls the server action by `callServer`:
+/// ```js
+/// import { createServerReference } from 'react-server-dom-webpack/client';
+/// export const like = createServerReference("src/actions.ts#like", callServer);
+/// ```
+pub fn server_reference_proxy(
+  module_id: &str,
+  exports: &[DirectiveExport],
+  config: &RscConfig,
+) -> String {
+  let mut code = format!(
+    "import {{ createServerReference }} from {};\n",
+    quote(&format!("{}/client", config.runtime))
+  );
+
+  match &config.call_server {
+    Some(call_server) => code.push_str(&format!(
+      "import {{ callServer }} from {};\n",
+      quote(call_server)
+    )),
+    None => code.push_str(&format!(
+      "const callServer = (...args) => globalThis.{CALL_SERVER_GLOBAL}(...args);\n"
+    )),
+  }
+
+  for export in exports {
+    let reference = format!(
+      "createServerReference({}, callServer)",
+      quote(&server_reference_id(module_id, &export.name))
+    );
+
+    code.push_str(&export_statement(&export.name, &reference));
+  }
+
+  code
+}
+
+fn export_statement(name: &str, expr: &str) -> String {
+  if name == "default" {
+    format!("export default {expr};\n")
+  } else if is_ident(name) {
+    format!("export const {name} = {expr};\n")
+  } else {
+    // string export names like `export { a as 'a-b' }` are rarely used, they are not supported
+    String::new()
+  }
+}
+
+fn is_ident(name: &str) -> bool {
+  let mut chars = name.chars();
+
+  chars
+    .next()
+    .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+    && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+fn quote(str: &str) -> String {
+  serde_json::to_string(str).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_core::config::rsc::RscConfig;
+
+  use super::{client_reference_proxy, server_reference_proxy, server_reference_registrations};
+  use crate::exports::DirectiveExport;
+
+  fn exports() -> Vec<DirectiveExport> {
+    vec![
+      DirectiveExport {
+        name: "like".to_string(),
+        local: Some("like".to_string()),
+      },
+      DirectiveExport {
+        name: "default".to_string(),
+        local: None,
+      },
+      DirectiveExport {
+        name: "a-b".to_string(),
+        local: Some("c".to_string()),
+      },
+    ]
+  }
+
+  #[test]
+  fn client_reference_proxy_output() {
+    let code = client_reference_proxy("src/Button.tsx", &exports(), &RscConfig::default());
+
+    assert_eq!(
+      code,
+      r#""use client";
+import { registerClientReference } from "react-server-dom-webpack/server";
+export const like = registerClientReference(function () { throw new Error("Attempted to call like() of src/Button.tsx from the server, but it's on the client. It can only be rendered as a component or passed to client components."); }, "src/Button.tsx", "like");
+export default registerClientReference(function () { throw new Error("Attempted to call default() of src/Button.tsx from the server, but it's on the client. It can only be rendered as a component or passed to client components."); }, "src/Button.tsx", "default");
+"#
+    );
+  }
+
+  #[test]
+  fn server_reference_registrations_output() {
+    let code = server_reference_registrations("src/actions.ts", &exports(), &RscConfig::default());
+
+    assert_eq!(
+      code,
+      r#"
+import { registerServerReference as $$registerServerReference } from "react-server-dom-webpack/server";
+$$registerServerReference(like, "src/actions.ts", "like");
+$$registerServerReference(c, "src/actions.ts", "a-b");
+"#
+    );
+  }
+
+  #[test]
+  fn server_reference_proxy_output() {
+    let code = server_reference_proxy("src/actions.ts", &exports(), &RscConfig::default());
+
+    assert_eq!(
+      code,
+      r#"import { createServerReference } from "react-server-dom-webpack/client";
+const callServer = (...args) => globalThis.__FARM_RSC_CALL_SERVER__(...args);
+export const like = createServerReference("src/actions.ts#like", callServer);
+export default createServerReference("src/actions.ts#default", callServer);
+"#
+    );
+
+    let config = RscConfig {
+      call_server: Some("/src/call-server.ts".to_string()),
+      ..Default::default()
+    };
+    let code = server_reference_proxy("src/actions.ts", &exports()[..1], &config);
+
+    assert_eq!(
+      code,
+      r#"import { createServerReference } from "react-server-dom-webpack/client";
+import { callServer } from "/src/call-server.ts";
+export const like = createServerReference("src/actions.ts#like", callServer);
+"#
+    );
+  }
+}
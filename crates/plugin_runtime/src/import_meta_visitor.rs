@@ -16,30 +16,49 @@ use farmfe_toolkit::swc_ecma_visit::{VisitMut, VisitMutWith};
 use farmfe_utils::stringify_query;
 
 /// transform `import.meta.xxx` to `module.meta.xxx`
-pub struct ImportMetaVisitor {}
+pub struct ImportMetaVisitor {
+  hot_only: bool,
+}
 
 impl ImportMetaVisitor {
   pub fn new() -> Self {
-    Self {}
+    Self { hot_only: false }
+  }
+
+  /// Only transform `import.meta.hot`, other properties like `import.meta.url` are kept for native esm
+  pub fn hot_only() -> Self {
+    Self { hot_only: true }
   }
 }
 
+fn module_meta_expr() -> Expr {
+  Expr::Member(MemberExpr {
+    span: DUMMY_SP,
+    obj: Box::new(Expr::Ident(Ident::new(
+      FARM_MODULE.into(),
+      DUMMY_SP,
+      SyntaxContext::empty(),
+    ))),
+    prop: MemberProp::Ident(IdentName::new("meta".into(), DUMMY_SP)),
+  })
+}
+
+fn is_import_meta(expr: &Expr) -> bool {
+  matches!(expr, Expr::MetaProp(meta_prop) if matches!(meta_prop.kind, MetaPropKind::ImportMeta))
+}
+
 impl VisitMut for ImportMetaVisitor {
   fn visit_mut_expr(&mut self, expr: &mut Expr) {
     match expr {
-      Expr::MetaProp(meta_prop) => {
-        if matches!(meta_prop.kind, MetaPropKind::ImportMeta) {
-          // check if it's hmr accepted
-          *expr = Expr::Member(MemberExpr {
-            span: DUMMY_SP,
-            obj: Box::new(Expr::Ident(Ident::new(
-              FARM_MODULE.into(),
-              DUMMY_SP,
-              SyntaxContext::empty(),
-            ))),
-            prop: MemberProp::Ident(IdentName::new("meta".into(), DUMMY_SP)),
-          });
-        }
+      Expr::MetaProp(_) if !self.hot_only && is_import_meta(expr) => {
+        *expr = module_meta_expr();
+      }
+      Expr::Member(MemberExpr {
+        obj,
+        prop: MemberProp::Ident(IdentName { sym, .. }),
+        ..
+      }) if self.hot_only && sym == "hot" && is_import_meta(obj) => {
+        **obj = module_meta_expr();
       }
       _ => {
         expr.visit_mut_children_with(self);
@@ -241,9 +241,16 @@ impl Plugin for FarmPluginRuntime {
       let ast = &mut param.module.meta.as_script_mut().ast;
       let mut import_meta_v = ImportMetaVisitor::new();
       ast.visit_mut_with(&mut import_meta_v);
+    } else if target_env.is_node() && context.config.mode.is_dev() {
+      // `import.meta.hot` is provided by the runtime for node hmr
+      let ast = &mut param.module.meta.as_script_mut().ast;
+      ast.visit_mut_with(&mut ImportMetaVisitor::hot_only());
     }
 
-    if matches!(target_env, TargetEnv::Browser) {
+    // node targets support hmr in the long-running process, see `moduleSystem.h` of the runtime
+    if matches!(target_env, TargetEnv::Browser)
+      || (target_env.is_node() && context.config.mode.is_dev())
+    {
       let ast = &mut param.module.meta.as_script_mut().ast;
       let mut hmr_accepted_v =
         import_meta_visitor::HmrAcceptedVisitor::new(param.module.id.clone(), context.clone());
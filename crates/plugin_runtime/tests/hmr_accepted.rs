@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use farmfe_core::{
-  config::Config,
+  config::{Config, OutputConfig, TargetEnv},
   context::CompilationContext,
   module::{meta_data::script::ScriptModuleMetaData, Module},
   plugin::{Plugin, PluginFinalizeModuleHookParam, PluginLoadHookResult},
@@ -12,10 +12,9 @@ use farmfe_toolkit::{
   script::{parse_module, syntax_from_module_type},
 };
 
-#[test]
-fn hmr_accepted() {
+fn assert_hmr_accepted(config: Config) {
   fixture!("tests/fixtures/hmr_accepted/*.ts", |file, _| {
-    let config = Config::default();
+    let config = config.clone();
     let plugin_script = farmfe_plugin_runtime::FarmPluginRuntime::new(&config);
     let context = Arc::new(CompilationContext::new(config, vec![]).unwrap());
     let id = file.to_string_lossy().to_string();
@@ -58,3 +57,20 @@ fn hmr_accepted() {
     assert!(module.meta.as_script().hmr_self_accepted);
   });
 }
+
+#[test]
+fn hmr_accepted() {
+  assert_hmr_accepted(Config::default());
+}
+
+#[test]
+fn hmr_accepted_node() {
+  // `import.meta` is kept for native esm, only `import.meta.hot` is transformed
+  assert_hmr_accepted(Config {
+    output: Box::new(OutputConfig {
+      target_env: TargetEnv::Node,
+      ..Default::default()
+    }),
+    ..Default::default()
+  });
+}
@@ -165,6 +165,8 @@ impl Plugin for FarmPluginScript {
           unresolved_idents: Default::default(),
           all_deeply_declared_idents: Default::default(),
           feature_flags: Default::default(),
+          no_side_effects_fns: Default::default(),
+          directives: vec![],
           export_ident_map: Default::default(),
           reexport_ident_map: Default::default(),
           ambiguous_export_ident_map: Default::default(),
@@ -3,7 +3,10 @@ use std::sync::Arc;
 use farmfe_core::{config::Config, context::CompilationContext, plugin::Plugin};
 /// A set of Plugins that are used to fill module.meta for the script module.
 pub use plugin_exports::FarmPluginScriptMetaExports;
-pub use plugin_features::FarmPluginScriptMetaFeatures;
+pub use plugin_features::{
+  directives::{analyze_directives, USE_CLIENT, USE_SERVER},
+  FarmPluginScriptMetaFeatures,
+};
 
 /// Each module exports a specific Farm Plugin
 mod plugin_exports;
@@ -4,8 +4,10 @@ use farmfe_toolkit::script::set_module_system_for_module_meta;
 use features_analyzer::FeaturesAnalyzer;
 use statements::analyze_statements;
 
+pub mod directives;
 mod features_analyzer;
 mod idents;
+mod no_side_effects_fns;
 mod statements;
 
 /// In finalize_module hook, fill the es module features like `meta.feature_flags`, `meta.module_system`, `meta.hmr_accepted`, etc.
@@ -59,6 +61,12 @@ impl Plugin for FarmPluginScriptMetaFeatures {
     let features_analyzer = FeaturesAnalyzer::new(param.deps, &meta.statements);
     meta.feature_flags = features_analyzer.analyze();
 
+    // functions annotated by /*#__NO_SIDE_EFFECTS__*/
+    meta.no_side_effects_fns = no_side_effects_fns::analyze_no_side_effects_fns(meta);
+
+    // 'use client', 'use server', etc.
+    meta.directives = directives::analyze_directives(&meta.ast);
+
     Ok(None)
   }
 }
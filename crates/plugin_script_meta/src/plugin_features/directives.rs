@@ -0,0 +1,62 @@
+use farmfe_core::swc_ecma_ast::{Expr, Lit, Module as SwcModule, ModuleItem, Stmt};
+
+pub const USE_CLIENT: &str = "use client";
+pub const USE_SERVER: &str = "use server";
+
+/// Collect the module level directives, which are the string literal statements at the top of the module, for example:
+/// ```js
+/// 'use client';
+/// 'use strict';
+/// import React from 'react';
+/// ```
+pub fn analyze_directives(ast: &SwcModule) -> Vec<String> {
+  ast
+    .body
+    .iter()
+    .map_while(|item| match item {
+      ModuleItem::Stmt(Stmt::Expr(expr_stmt)) => match &*expr_stmt.expr {
+        Expr::Lit(Lit::Str(str)) => Some(str.value.to_string_lossy().into_owned()),
+        _ => None,
+      },
+      _ => None,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use farmfe_core::{
+    swc_common::{Globals, GLOBALS},
+    swc_ecma_ast::EsVersion,
+    swc_ecma_parser::Syntax,
+  };
+  use farmfe_toolkit::script::{parse_module, ParseScriptModuleResult};
+
+  #[test]
+  fn analyze_directives() {
+    let code = r#"
+// comments are allowed before directives
+'use client';
+"use strict";
+import React from 'react';
+'use server';
+"#;
+
+    GLOBALS.set(&Globals::new(), || {
+      let ParseScriptModuleResult { ast, .. } = parse_module(
+        &"any".into(),
+        Arc::new(code.to_string()),
+        Syntax::Es(Default::default()),
+        EsVersion::Es2022,
+      )
+      .unwrap();
+
+      assert_eq!(
+        super::analyze_directives(&ast),
+        vec!["use client".to_string(), "use strict".to_string()]
+      );
+    });
+  }
+}
@@ -0,0 +1,161 @@
+use farmfe_core::{
+  module::meta_data::script::{statement::SwcId, ScriptModuleMetaData, EXPORT_DEFAULT},
+  swc_common::{
+    comments::{Comment, CommentKind},
+    BytePos, Spanned,
+  },
+  swc_ecma_ast::{Decl, DefaultDecl, Expr, ModuleDecl, ModuleItem, Pat, Stmt},
+  HashSet,
+};
+
+/// Collect top level functions annotated by `/*#__NO_SIDE_EFFECTS__*/` or `/*@__NO_SIDE_EFFECTS__*/`, for example:
+/// ```js
+/// /*#__NO_SIDE_EFFECTS__*/ function foo() {}
+/// /*#__NO_SIDE_EFFECTS__*/ export function bar() {}
+/// export const zoo = /*#__NO_SIDE_EFFECTS__*/ () => {};
+/// export default /*#__NO_SIDE_EFFECTS__*/ function () {}
+/// ```
+pub fn analyze_no_side_effects_fns(meta: &ScriptModuleMetaData) -> HashSet<SwcId> {
+  let annotated_pos = meta
+    .comments
+    .leading
+    .iter()
+    .filter(|item| item.comment.iter().any(is_no_side_effects_comment))
+    .map(|item| item.byte_pos)
+    .collect::<HashSet<_>>();
+
+  let mut no_side_effects_fns = HashSet::default();
+
+  if annotated_pos.is_empty() {
+    return no_side_effects_fns;
+  }
+
+  let is_annotated = |pos: BytePos| annotated_pos.contains(&pos);
+
+  for item in &meta.ast.body {
+    match item {
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => collect_decl(
+        &export_decl.decl,
+        is_annotated(export_decl.span.lo),
+        &is_annotated,
+        &mut no_side_effects_fns,
+      ),
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default_decl)) => {
+        if let DefaultDecl::Fn(fn_expr) = &export_default_decl.decl
+          && (is_annotated(export_default_decl.span.lo) || is_annotated(fn_expr.function.span.lo))
+        {
+          no_side_effects_fns.insert(EXPORT_DEFAULT.into());
+
+          if let Some(ident) = &fn_expr.ident {
+            no_side_effects_fns.insert(ident.to_id().into());
+          }
+        }
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default_expr)) => {
+        if is_fn_expr(&export_default_expr.expr)
+          && (is_annotated(export_default_expr.span.lo)
+            || is_annotated(export_default_expr.expr.span_lo()))
+        {
+          no_side_effects_fns.insert(EXPORT_DEFAULT.into());
+        }
+      }
+      ModuleItem::Stmt(Stmt::Decl(decl)) => {
+        collect_decl(decl, false, &is_annotated, &mut no_side_effects_fns)
+      }
+      _ => {}
+    }
+  }
+
+  no_side_effects_fns
+}
+
+fn collect_decl(
+  decl: &Decl,
+  outer_annotated: bool,
+  is_annotated: &impl Fn(BytePos) -> bool,
+  no_side_effects_fns: &mut HashSet<SwcId>,
+) {
+  match decl {
+    Decl::Fn(fn_decl) => {
+      if outer_annotated || is_annotated(fn_decl.function.span.lo) {
+        no_side_effects_fns.insert(fn_decl.ident.to_id().into());
+      }
+    }
+    // const foo = /*#__NO_SIDE_EFFECTS__*/ () => {} or /*#__NO_SIDE_EFFECTS__*/ const foo = () => {}
+    Decl::Var(var_decl) => {
+      let var_annotated = outer_annotated || is_annotated(var_decl.span.lo);
+
+      for decl in &var_decl.decls {
+        if let Pat::Ident(ident) = &decl.name
+          && let Some(init) = &decl.init
+          && is_fn_expr(init)
+          && (var_annotated || is_annotated(init.span_lo()))
+        {
+          no_side_effects_fns.insert(ident.id.to_id().into());
+        }
+      }
+    }
+    _ => {}
+  }
+}
+
+fn is_fn_expr(expr: &Expr) -> bool {
+  matches!(expr, Expr::Fn(_) | Expr::Arrow(_))
+}
+
+fn is_no_side_effects_comment(comment: &Comment) -> bool {
+  comment.kind == CommentKind::Block
+    && matches!(
+      comment.text.trim(),
+      "#__NO_SIDE_EFFECTS__" | "@__NO_SIDE_EFFECTS__"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use farmfe_core::{
+    module::meta_data::script::{ScriptModuleMetaData, EXPORT_DEFAULT},
+    swc_common::{Globals, GLOBALS},
+    swc_ecma_ast::EsVersion,
+    swc_ecma_parser::Syntax,
+  };
+  use farmfe_toolkit::script::{parse_module, ParseScriptModuleResult};
+
+  #[test]
+  fn analyze_no_side_effects_fns() {
+    let code = r#"
+/*#__NO_SIDE_EFFECTS__*/ function a() {}
+function b() {}
+/*#__NO_SIDE_EFFECTS__*/ export function c() {}
+export const d = /*#__NO_SIDE_EFFECTS__*/ () => {};
+/*@__NO_SIDE_EFFECTS__*/ const e = function () {};
+/*#__NO_SIDE_EFFECTS__*/ const f = 1;
+export default /*#__NO_SIDE_EFFECTS__*/ function () {}
+"#;
+
+    GLOBALS.set(&Globals::new(), || {
+      let ParseScriptModuleResult { ast, comments, .. } = parse_module(
+        &"any".into(),
+        Arc::new(code.to_string()),
+        Syntax::Es(Default::default()),
+        EsVersion::Es2022,
+      )
+      .unwrap();
+      let meta = ScriptModuleMetaData {
+        ast,
+        comments: comments.into(),
+        ..Default::default()
+      };
+
+      let mut fns = super::analyze_no_side_effects_fns(&meta)
+        .into_iter()
+        .map(|i| i.sym.to_string())
+        .collect::<Vec<_>>();
+      fns.sort();
+
+      assert_eq!(fns, vec!["a", "c", "d", EXPORT_DEFAULT, "e"]);
+    });
+  }
+}
@@ -0,0 +1,70 @@
+use farmfe_core::{
+  module::{module_graph::ModuleGraph, ModuleId},
+  stats::{
+    TreeShakingImporterExplanation, TreeShakingModuleExplanation, TreeShakingStatementExplanation,
+  },
+  HashMap, HashSet,
+};
+
+use crate::module::TreeShakeModule;
+
+/// Collect why modules matched by `treeShaking.explain` and their statements are preserved.
+/// Should be called after the module graph is tree shaken but before the unused modules are removed.
+pub fn explain_tree_shake_modules(
+  module_graph: &ModuleGraph,
+  tree_shake_modules_map: &HashMap<ModuleId, TreeShakeModule>,
+  modules_to_remove: &[ModuleId],
+) -> Vec<TreeShakingModuleExplanation> {
+  let modules_to_remove = modules_to_remove.iter().collect::<HashSet<_>>();
+  let mut explanations = vec![];
+
+  for tree_shake_module in tree_shake_modules_map.values() {
+    let Some(explain_importers) = &tree_shake_module.explain_importers else {
+      continue;
+    };
+    let module_id = &tree_shake_module.module_id;
+
+    let mut importers = explain_importers
+      .iter()
+      .map(|(importer, used_exports)| {
+        let mut used_exports = used_exports.iter().cloned().collect::<Vec<_>>();
+        used_exports.sort();
+
+        TreeShakingImporterExplanation {
+          importer: importer.clone(),
+          used_exports,
+        }
+      })
+      .collect::<Vec<_>>();
+    importers.sort_by(|a, b| a.importer.cmp(&b.importer));
+
+    let stmt_graph = &tree_shake_module.stmt_graph;
+    let mut statements = stmt_graph
+      .stmt_ids()
+      .into_iter()
+      .map(|stmt_id| TreeShakingStatementExplanation {
+        stmt_id,
+        kept: stmt_graph.used_stmts().contains(&stmt_id),
+        reasons: stmt_graph.kept_reasons(&stmt_id),
+      })
+      .collect::<Vec<_>>();
+    statements.sort_by_key(|s| s.stmt_id);
+
+    let mut used_exports = tree_shake_module.handled_used_exports.to_string_vec();
+    used_exports.sort();
+
+    explanations.push(TreeShakingModuleExplanation {
+      module_id: module_id.clone(),
+      removed: modules_to_remove.contains(module_id),
+      is_entry: module_graph.entries.contains_key(module_id)
+        || module_graph.dynamic_entries.contains_key(module_id),
+      side_effects: tree_shake_module.side_effects,
+      used_exports,
+      importers,
+      statements,
+    });
+  }
+
+  explanations.sort_by(|a, b| a.module_id.cmp(&b.module_id));
+  explanations
+}
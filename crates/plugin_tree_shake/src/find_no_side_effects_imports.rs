@@ -0,0 +1,193 @@
+use farmfe_core::{
+  module::{
+    meta_data::script::{
+      statement::{ExportSpecifierInfo, ImportSpecifierInfo, SwcId},
+      EXPORT_DEFAULT,
+    },
+    module_graph::ModuleGraph,
+    ModuleId, ModuleSystem,
+  },
+  plugin::ResolveKind,
+  HashMap, HashSet,
+};
+
+/// Find the imported idents of each module that refer to functions annotated by `/*#__NO_SIDE_EFFECTS__*/` in the imported modules,
+/// so calls of these functions can be treated as no side effects. For example:
+/// ```js
+/// // dep.js
+/// /*#__NO_SIDE_EFFECTS__*/ export function defineComponent() {}
+/// // index.js
+/// import { defineComponent as dc } from './dep';
+/// const comp = dc({}); // dc is a no side effects import of index.js
+/// ```
+/// Re-exports like `export { foo } from './foo'` and `export * from './foo'` are traced.
+pub fn find_no_side_effects_imports(
+  module_graph: &ModuleGraph,
+) -> HashMap<ModuleId, HashSet<SwcId>> {
+  let mut result = HashMap::default();
+
+  // skip when none of the modules contains annotated functions, which is the most common case
+  if !module_graph
+    .modules()
+    .into_iter()
+    .any(|m| is_esm(m) && !m.meta.as_script().no_side_effects_fns.is_empty())
+  {
+    return result;
+  }
+
+  for module in module_graph.modules() {
+    if !is_esm(module) {
+      continue;
+    }
+
+    let mut no_side_effects_imports = HashSet::default();
+
+    for stmt in &module.meta.as_script().statements {
+      let Some(import_info) = &stmt.import_info else {
+        continue;
+      };
+
+      for specifier in &import_info.specifiers {
+        let (local, imported) = match specifier {
+          ImportSpecifierInfo::Named { local, imported } => {
+            (local, imported.as_ref().unwrap_or(local).sym.to_string())
+          }
+          ImportSpecifierInfo::Default(local) => (local, EXPORT_DEFAULT.to_string()),
+          ImportSpecifierInfo::Namespace(_) => continue,
+        };
+
+        let Some(dep_id) = module_graph.get_dep_by_source_optional(
+          &module.id,
+          &import_info.source,
+          Some(ResolveKind::Import),
+        ) else {
+          continue;
+        };
+
+        if is_no_side_effects_export(module_graph, &dep_id, &imported, &mut HashSet::default()) {
+          no_side_effects_imports.insert(local.clone());
+        }
+      }
+    }
+
+    if !no_side_effects_imports.is_empty() {
+      result.insert(module.id.clone(), no_side_effects_imports);
+    }
+  }
+
+  result
+}
+
+fn is_esm(module: &farmfe_core::module::Module) -> bool {
+  module.module_type.is_script()
+    && !module.external
+    && module.meta.as_script().module_system == ModuleSystem::EsModule
+}
+
+fn is_no_side_effects_export(
+  module_graph: &ModuleGraph,
+  module_id: &ModuleId,
+  export_name: &str,
+  visited: &mut HashSet<(ModuleId, String)>,
+) -> bool {
+  if !visited.insert((module_id.clone(), export_name.to_string())) {
+    return false;
+  }
+
+  let Some(module) = module_graph.module(module_id) else {
+    return false;
+  };
+
+  if !is_esm(module) {
+    return false;
+  }
+
+  let meta = module.meta.as_script();
+  let mut export_all_sources = vec![];
+
+  for stmt in &meta.statements {
+    let Some(export_info) = &stmt.export_info else {
+      continue;
+    };
+
+    for specifier in &export_info.specifiers {
+      match specifier {
+        ExportSpecifierInfo::Default => {
+          if export_name == EXPORT_DEFAULT {
+            return meta.no_side_effects_fns.contains(&EXPORT_DEFAULT.into());
+          }
+        }
+        ExportSpecifierInfo::Named { local, exported } => {
+          if &*exported.as_ref().unwrap_or(local).sym != export_name {
+            continue;
+          }
+
+          return match &export_info.source {
+            // export { foo as bar } from './foo';
+            Some(source) => module_graph
+              .get_dep_by_source_optional(module_id, source, Some(ResolveKind::ExportFrom))
+              .is_some_and(|dep_id| {
+                is_no_side_effects_export(module_graph, &dep_id, &local.sym, visited)
+              }),
+            None => is_no_side_effects_ident(module_graph, module_id, local, visited),
+          };
+        }
+        ExportSpecifierInfo::All => {
+          if let Some(source) = &export_info.source {
+            export_all_sources.push(source);
+          }
+        }
+        ExportSpecifierInfo::Namespace(_) => {}
+      }
+    }
+  }
+
+  // export * does not re-export the default export
+  if export_name == EXPORT_DEFAULT {
+    return false;
+  }
+
+  export_all_sources.into_iter().any(|source| {
+    module_graph
+      .get_dep_by_source_optional(module_id, source, Some(ResolveKind::ExportFrom))
+      .is_some_and(|dep_id| is_no_side_effects_export(module_graph, &dep_id, export_name, visited))
+  })
+}
+
+/// the ident is annotated in current module, or imported from another module and annotated there
+fn is_no_side_effects_ident(
+  module_graph: &ModuleGraph,
+  module_id: &ModuleId,
+  ident: &SwcId,
+  visited: &mut HashSet<(ModuleId, String)>,
+) -> bool {
+  let meta = module_graph.module(module_id).unwrap().meta.as_script();
+
+  if meta.no_side_effects_fns.contains(ident) {
+    return true;
+  }
+
+  for stmt in &meta.statements {
+    let Some(import_info) = &stmt.import_info else {
+      continue;
+    };
+
+    for specifier in &import_info.specifiers {
+      let imported = match specifier {
+        ImportSpecifierInfo::Named { local, imported } if local == ident => {
+          imported.as_ref().unwrap_or(local).sym.to_string()
+        }
+        ImportSpecifierInfo::Default(local) if local == ident => EXPORT_DEFAULT.to_string(),
+        _ => continue,
+      };
+
+      return module_graph
+        .get_dep_by_source_optional(module_id, &import_info.source, Some(ResolveKind::Import))
+        .is_some_and(|dep_id| {
+          is_no_side_effects_export(module_graph, &dep_id, &imported, visited)
+        });
+    }
+  }
+
+  false
+}
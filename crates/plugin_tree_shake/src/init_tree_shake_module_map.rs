@@ -3,16 +3,19 @@ use farmfe_core::{
   parking_lot::Mutex,
   rayon::iter::{IntoParallelIterator, ParallelIterator},
   swc_common::GLOBALS,
-  HashMap,
+  HashMap, HashSet,
 };
 
-use crate::module::TreeShakeModule;
+use crate::{find_no_side_effects_imports::find_no_side_effects_imports, module::TreeShakeModule};
 
 pub fn init_tree_shake_module_map(
   module_graph: &mut ModuleGraph,
   context: &std::sync::Arc<farmfe_core::context::CompilationContext>,
 ) -> HashMap<ModuleId, TreeShakeModule> {
   let tree_shake_modules_map = Mutex::new(HashMap::<ModuleId, TreeShakeModule>::default());
+  let no_side_effects_imports = find_no_side_effects_imports(module_graph);
+  let empty_no_side_effects_imports = HashSet::default();
+
   module_graph
     .modules_mut()
     .into_par_iter()
@@ -23,7 +26,24 @@ pub fn init_tree_shake_module_map(
       let globals = context.meta.get_globals(&module.id);
 
       GLOBALS.set(globals.value(), || {
-        let tree_shake_module = TreeShakeModule::new(module);
+        let imported_no_side_effects_fns = no_side_effects_imports
+          .get(&module.id)
+          .unwrap_or(&empty_no_side_effects_imports);
+        let mut tree_shake_module =
+          TreeShakeModule::with_imported_no_side_effects_fns(module, imported_no_side_effects_fns);
+
+        if let Some(tree_shaking) = context.config.tree_shaking.as_obj() {
+          let module_id = module.id.to_string();
+          // modules configured as side effects are always preserved when they are imported
+          if tree_shaking.side_effects_override(&module_id, &module.package_name) == Some(true) {
+            tree_shake_module.contains_self_executed_stmt = true;
+          }
+
+          if tree_shaking.should_explain(&module_id) {
+            tree_shake_module.enable_explain();
+          }
+        }
+
         tree_shake_modules_map
           .lock()
           .insert(module.id.clone(), tree_shake_module);
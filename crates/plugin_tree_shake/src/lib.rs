@@ -7,6 +7,8 @@ use farmfe_core::{
 
 use crate::remove_hot_update::remove_useless_hot_update_stmts;
 
+pub mod explain;
+pub mod find_no_side_effects_imports;
 pub mod init_tree_shake_module_map;
 pub mod mark_initial_side_effects;
 pub mod module;
@@ -67,17 +69,28 @@ impl Plugin for FarmPluginTreeShake {
       }
     }
 
-    // 5. remove the unused modules
+    // 5. explain why modules are preserved if treeShaking.explain is configured
+    let explanations = explain::explain_tree_shake_modules(
+      module_graph,
+      &tree_shake_modules_map,
+      &modules_to_remove,
+    );
+
+    if !explanations.is_empty() {
+      context.stats.set_tree_shaking_explanations(explanations);
+    }
+
+    // 6. remove the unused modules
     for module_id in modules_to_remove {
       module_graph.remove_module(&module_id);
     }
 
-    // 6. remove useless hot update statements if production
+    // 7. remove useless hot update statements if production
     if matches!(context.config.mode, Mode::Production) {
       remove_useless_hot_update_stmts(module_graph);
     }
 
-    // 7. remove export idents
+    // 8. remove export idents
     tree_shake_modules::remove_export_idents::remove_export_idents(module_graph);
 
     Ok(Some(()))
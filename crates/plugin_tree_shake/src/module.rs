@@ -1,7 +1,7 @@
 use std::{fmt, mem};
 
 use farmfe_core::{
-  module::{Module, ModuleId, ModuleSystem},
+  module::{meta_data::script::statement::SwcId, Module, ModuleId, ModuleSystem},
   swc_common::comments::SingleThreadedComments,
   HashMap, HashSet,
 };
@@ -148,10 +148,21 @@ pub struct TreeShakeModule {
   /// pending used exports will be used to analyze the used exports of the module
   pub pending_used_exports: UsedExports,
   pub module_system: ModuleSystem,
+  /// importers and the exports they use, only recorded for modules matched by `treeShaking.explain`
+  pub explain_importers: Option<HashMap<ModuleId, HashSet<String>>>,
 }
 
 impl TreeShakeModule {
   pub fn new(module: &mut Module) -> Self {
+    Self::with_imported_no_side_effects_fns(module, &HashSet::default())
+  }
+
+  /// Same as [TreeShakeModule::new], calls of `imported_no_side_effects_fns` are treated as no side effects.
+  /// See [crate::find_no_side_effects_imports]
+  pub fn with_imported_no_side_effects_fns(
+    module: &mut Module,
+    imported_no_side_effects_fns: &HashSet<SwcId>,
+  ) -> Self {
     farmfe_core::farm_profile_function!(format!(
       "TreeShakeModule::new {:?}",
       module.id.to_string()
@@ -163,7 +174,7 @@ impl TreeShakeModule {
     let ast = &module.meta.as_script().ast;
     let comments = SingleThreadedComments::from(comments_meta);
     let stmt_graph = if module_system == ModuleSystem::EsModule {
-      StatementGraph::new(module, ast, &comments)
+      StatementGraph::new(module, ast, &comments, imported_no_side_effects_fns)
     } else {
       StatementGraph::empty()
     };
@@ -183,6 +194,22 @@ impl TreeShakeModule {
       handled_used_exports,
       side_effects: module.side_effects,
       module_system,
+      explain_importers: None,
+    }
+  }
+
+  /// Record why the module and its statements are preserved, see [crate::explain]
+  pub fn enable_explain(&mut self) {
+    self.explain_importers = Some(HashMap::default());
+    self.stmt_graph.enable_explain();
+  }
+
+  pub fn add_explain_importer(&mut self, importer: &ModuleId, used_exports: &UsedExports) {
+    if let Some(explain_importers) = &mut self.explain_importers {
+      explain_importers
+        .entry(importer.clone())
+        .or_default()
+        .extend(used_exports.to_string_vec());
     }
   }
 
@@ -8,6 +8,7 @@ pub use farmfe_core::module::meta_data::script::statement::{
 use farmfe_core::module::meta_data::script::statement::{Statement, SwcId, WriteTopLevelVar};
 use farmfe_core::module::Module;
 use farmfe_core::petgraph::Direction;
+use farmfe_core::stats::TreeShakingKeptReason;
 use farmfe_core::swc_common::comments::SingleThreadedComments;
 use farmfe_core::swc_common::Mark;
 use farmfe_core::swc_ecma_ast::ModuleDecl;
@@ -49,6 +50,14 @@ impl UsedStatementIdent {
   pub fn is_ident_matched(&self, ident: &SwcId) -> bool {
     matches!(self, Self::SwcIdent(id) if id == ident)
   }
+
+  /// Same as [fmt::Display] but without syntax context, used for explanations
+  pub fn name(&self) -> String {
+    match self {
+      UsedStatementIdent::SwcIdent(id) => id.sym.to_string(),
+      _ => self.to_string(),
+    }
+  }
 }
 
 impl fmt::Display for UsedStatementIdent {
@@ -117,15 +126,31 @@ pub struct StatementGraph {
   pub reverse_defined_idents_map: HashMap<SwcId, usize>,
   /// written_imported_idents is the idents that are defined in import statement, and are written at the top level of the module
   pub written_imported_idents: HashSet<WriteTopLevelVar>,
+  /// why the statements are marked as used, only recorded for modules matched by `treeShaking.explain`
+  kept_reasons: Option<HashMap<StatementId, Vec<TreeShakingKeptReason>>>,
 }
 
 impl StatementGraph {
-  pub fn new(module: &Module, ast: &SwcModule, comments: &SingleThreadedComments) -> Self {
+  /// `imported_no_side_effects_fns` are the imported idents that refer to functions annotated by `/*#__NO_SIDE_EFFECTS__*/` in the imported modules
+  pub fn new(
+    module: &Module,
+    ast: &SwcModule,
+    comments: &SingleThreadedComments,
+    imported_no_side_effects_fns: &HashSet<SwcId>,
+  ) -> Self {
     let mut g = petgraph::graph::Graph::new();
     let mut id_index_map = HashMap::default();
 
     let unresolved_mark = Mark::from_u32(module.meta.as_script().unresolved_mark);
     let top_level_mark = Mark::from_u32(module.meta.as_script().top_level_mark);
+    let no_side_effects_fns = module
+      .meta
+      .as_script()
+      .no_side_effects_fns
+      .iter()
+      .chain(imported_no_side_effects_fns)
+      .cloned()
+      .collect::<HashSet<_>>();
 
     let mut reverse_defined_idents_map = HashMap::default();
     // 1. analyze all defined idents of each statement
@@ -137,6 +162,7 @@ impl StatementGraph {
         unresolved_mark,
         top_level_mark,
         comments,
+        &no_side_effects_fns,
       );
       stmt.side_effects = side_effects;
 
@@ -157,6 +183,7 @@ impl StatementGraph {
       used_stmts: HashSet::default(),
       reverse_defined_idents_map: HashMap::default(),
       written_imported_idents: HashSet::default(),
+      kept_reasons: None,
     };
 
     for (index, item) in ast.body.iter().enumerate() {
@@ -191,6 +218,7 @@ impl StatementGraph {
       used_stmts: HashSet::default(),
       reverse_defined_idents_map: HashMap::default(),
       written_imported_idents: HashSet::default(),
+      kept_reasons: None,
     }
   }
 
@@ -198,6 +226,29 @@ impl StatementGraph {
     &self.used_stmts
   }
 
+  /// Start recording why statements are marked as used, see [StatementGraph::kept_reasons]
+  pub fn enable_explain(&mut self) {
+    self.kept_reasons.get_or_insert_with(HashMap::default);
+  }
+
+  pub fn add_kept_reason(&mut self, stmt_id: StatementId, reason: TreeShakingKeptReason) {
+    if let Some(kept_reasons) = &mut self.kept_reasons {
+      let reasons = kept_reasons.entry(stmt_id).or_default();
+
+      if !reasons.contains(&reason) {
+        reasons.push(reason);
+      }
+    }
+  }
+
+  pub fn kept_reasons(&self, stmt_id: &StatementId) -> Vec<TreeShakingKeptReason> {
+    self
+      .kept_reasons
+      .as_ref()
+      .and_then(|kept_reasons| kept_reasons.get(stmt_id).cloned())
+      .unwrap_or_default()
+  }
+
   pub fn preserved_side_effects_stmts(&self) -> Vec<StatementId> {
     let mut preserved_statements = self
       .g
@@ -400,10 +451,25 @@ impl StatementGraph {
   ) -> Vec<TracedUsedImportStatement> {
     farmfe_core::farm_profile_function!("trace_and_mark_used_statements".to_string());
 
+    if self.kept_reasons.is_some() {
+      for (stmt_id, used_idents) in &used_stmts_exports {
+        let idents = used_idents.iter().map(|i| i.name()).collect();
+        // see the doc comment above for the difference of the two entries
+        let reason = if all_used_import_all_fields.is_none() {
+          TreeShakingKeptReason::UsedExports { idents }
+        } else {
+          TreeShakingKeptReason::WriteImportedIdents { idents }
+        };
+        self.add_kept_reason(*stmt_id, reason);
+      }
+    }
+
     let mut used_statements_map = used_stmts_exports;
 
     // 1. preserve all statements that have side effects
     for stmt_id in self.preserved_side_effects_stmts() {
+      let side_effects = format!("{:?}", self.stmt(&stmt_id).side_effects);
+      self.add_kept_reason(stmt_id, TreeShakingKeptReason::SideEffects { side_effects });
       let stmt = self.stmt(&stmt_id);
       used_statements_map.insert(
         stmt_id,
@@ -473,6 +539,8 @@ impl StatementGraph {
       stmt.used_defined_idents.extend(stmt_used_defined_idents);
 
       // 3.3 visit dependencies of the used statement
+      let mut dep_kept_reasons = vec![];
+
       for (dep_stmt, edge) in self.dependencies(&stmt_id) {
         // find all used defined idents of the dependency statement
         let all_used_dep_defined_idents =
@@ -489,6 +557,19 @@ impl StatementGraph {
           .collect::<HashSet<_>>();
 
         if !unhandled_used_dep_defined_idents.is_empty() {
+          if self.kept_reasons.is_some() {
+            dep_kept_reasons.push((
+              dep_stmt.id,
+              TreeShakingKeptReason::UsedByStatement {
+                stmt_id,
+                idents: unhandled_used_dep_defined_idents
+                  .iter()
+                  .map(|i| i.sym.to_string())
+                  .collect(),
+              },
+            ));
+          }
+
           stmts.push_back((
             dep_stmt.id,
             unhandled_used_dep_defined_idents
@@ -504,6 +585,16 @@ impl StatementGraph {
       for (dept_id, dept_used_idents, used_import_all_fields) in
         self.trace_dependents_side_effects(stmt_id, &all_used_import_all_fields)
       {
+        if self.kept_reasons.is_some() {
+          dep_kept_reasons.push((
+            dept_id,
+            TreeShakingKeptReason::WriteUsedIdents {
+              stmt_id,
+              idents: dept_used_idents.iter().map(|i| i.sym.to_string()).collect(),
+            },
+          ));
+        }
+
         stmts.push_back((
           dept_id,
           dept_used_idents
@@ -514,6 +605,10 @@ impl StatementGraph {
         ));
       }
 
+      for (dep_stmt_id, reason) in dep_kept_reasons {
+        self.add_kept_reason(dep_stmt_id, reason);
+      }
+
       // 3.5 collect all used `import/export from` statements and push them into result
       if let Some(import_info) = &self.stmt(&stmt_id).import_info {
         result.push(TracedUsedImportStatement::from_import_info_and_used_idents(
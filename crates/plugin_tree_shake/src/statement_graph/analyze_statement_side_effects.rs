@@ -6,7 +6,7 @@ use farmfe_core::{
     comments::{Comments, SingleThreadedComments},
     Mark, Spanned,
   },
-  swc_ecma_ast::{Expr, ModuleItem},
+  swc_ecma_ast::{CallExpr, Callee, Expr, ModuleItem},
   HashMap, HashSet,
 };
 use farmfe_toolkit::swc_ecma_visit::{Visit, VisitWith};
@@ -15,18 +15,26 @@ use super::StatementSideEffects;
 
 /// Analyze the side effects of a statement. See [StatementSideEffects] for more details.
 /// If there are more side effects detection rules, add them here.
+///
+/// `no_side_effects_fns` are the local or imported functions annotated by `/*#__NO_SIDE_EFFECTS__*/`, calls of them are treated as no side effects.
 pub fn analyze_statement_side_effects(
   item: &ModuleItem,
   unresolved_mark: Mark,
   top_level_mark: Mark,
   comments: &SingleThreadedComments,
+  no_side_effects_fns: &HashSet<SwcId>,
 ) -> StatementSideEffects {
   match item {
     ModuleItem::ModuleDecl(module_decl) => match module_decl {
       farmfe_core::swc_ecma_ast::ModuleDecl::Import(_) => StatementSideEffects::NoSideEffects,
       farmfe_core::swc_ecma_ast::ModuleDecl::ExportDecl(export_decl) => match &export_decl.decl {
         farmfe_core::swc_ecma_ast::Decl::Var(var_decl) => {
-          let mut analyzer = SideEffectsAnalyzer::new(unresolved_mark, top_level_mark, comments);
+          let mut analyzer = SideEffectsAnalyzer::new(
+            unresolved_mark,
+            top_level_mark,
+            comments,
+            no_side_effects_fns,
+          );
           analyzer.set_in_top_level(true);
           analyzer.analyze(|this| var_decl.visit_children_with(this));
 
@@ -39,7 +47,12 @@ pub fn analyze_statement_side_effects(
         StatementSideEffects::NoSideEffects
       }
       farmfe_core::swc_ecma_ast::ModuleDecl::ExportDefaultExpr(default_expr) => {
-        let mut analyzer = SideEffectsAnalyzer::new(unresolved_mark, top_level_mark, comments);
+        let mut analyzer = SideEffectsAnalyzer::new(
+          unresolved_mark,
+          top_level_mark,
+          comments,
+          no_side_effects_fns,
+        );
         analyzer.set_in_top_level(true);
         analyzer.analyze(|this| default_expr.expr.visit_with(this));
         analyzer.side_effects
@@ -48,7 +61,12 @@ pub fn analyze_statement_side_effects(
       _ => StatementSideEffects::NoSideEffects,
     },
     ModuleItem::Stmt(stmt) => {
-      let mut analyzer = SideEffectsAnalyzer::new(unresolved_mark, top_level_mark, comments);
+      let mut analyzer = SideEffectsAnalyzer::new(
+        unresolved_mark,
+        top_level_mark,
+        comments,
+        no_side_effects_fns,
+      );
       analyzer.set_in_top_level(true);
       analyzer.analyze(|this| stmt.visit_with(this));
 
@@ -106,6 +124,7 @@ struct SideEffectsAnalyzer<'a> {
   top_level_mark: Mark,
   side_effects: StatementSideEffects,
   comments: &'a SingleThreadedComments,
+  no_side_effects_fns: &'a HashSet<SwcId>,
 
   in_assign_left: Option<HashSet<SwcId>>,
   in_assign_right: Option<Option<HashSet<SwcId>>>,
@@ -132,12 +151,14 @@ impl<'a> SideEffectsAnalyzer<'a> {
     unresolved_mark: Mark,
     top_level_mark: Mark,
     comments: &'a SingleThreadedComments,
+    no_side_effects_fns: &'a HashSet<SwcId>,
   ) -> Self {
     Self {
       unresolved_mark,
       top_level_mark,
       side_effects: StatementSideEffects::NoSideEffects,
       comments,
+      no_side_effects_fns,
       in_assign_left: None,
       in_top_level: false,
       in_call: false,
@@ -154,6 +175,19 @@ impl<'a> SideEffectsAnalyzer<'a> {
     self.in_top_level
   }
 
+  /// the callee is annotated by `/*#__NO_SIDE_EFFECTS__*/`, e.g. `/*#__NO_SIDE_EFFECTS__*/ function foo() {}; foo()`
+  fn is_no_side_effects_call(&self, call_expr: &CallExpr) -> bool {
+    if let Callee::Expr(callee) = &call_expr.callee
+      && let Expr::Ident(ident) = &**callee
+    {
+      return self
+        .no_side_effects_fns
+        .contains(&SwcId::from(ident.to_id()));
+    }
+
+    false
+  }
+
   pub fn with_assign_right<F: FnOnce(&mut Self)>(&mut self, id: Option<HashSet<SwcId>>, f: F) {
     let prev = self.in_assign_right.take();
     self.in_assign_right = Some(id);
@@ -310,6 +344,16 @@ impl<'a> Visit for SideEffectsAnalyzer<'a> {
         .merge_side_effects(StatementSideEffects::NoSideEffects);
       return;
     }
+    // for calls of functions annotated by /*#__NO_SIDE_EFFECTS__*/, only the arguments may have side effects
+    if let Expr::Call(call_expr) = expr
+      && self.is_no_side_effects_call(call_expr)
+    {
+      self
+        .side_effects
+        .merge_side_effects(StatementSideEffects::NoSideEffects);
+      call_expr.args.visit_with(self);
+      return;
+    }
 
     match expr {
       Expr::Fn(_) | Expr::Class(_) | Expr::Lit(_) | Expr::Arrow(_) => self
@@ -1,9 +1,11 @@
 use std::sync::Arc;
 
 use farmfe_core::{
-  swc_common::{comments::SingleThreadedComments, Globals, Mark, GLOBALS},
+  module::meta_data::script::statement::SwcId,
+  swc_common::{comments::SingleThreadedComments, Globals, Mark, SyntaxContext, GLOBALS},
   swc_ecma_ast::{EsVersion, Module},
   swc_ecma_parser::Syntax,
+  HashSet,
 };
 use farmfe_toolkit::{
   script::ParseScriptModuleResult, swc_ecma_transforms::resolver, swc_ecma_visit::VisitMutWith,
@@ -65,6 +67,7 @@ fn no_side_effects() {
         unresolved_mark,
         top_level_mark,
         &SingleThreadedComments::default(),
+        &HashSet::default(),
       );
 
       if i == 2 || i == 6 {
@@ -97,6 +100,7 @@ fn write_top_level_var() {
       unresolved_mark,
       top_level_mark,
       &SingleThreadedComments::default(),
+      &HashSet::default(),
     );
     println!("{side_effects:?}");
     assert!(matches!(
@@ -122,6 +126,7 @@ fn write_top_level_var() {
       unresolved_mark,
       top_level_mark,
       &SingleThreadedComments::default(),
+      &HashSet::default(),
     );
     assert!(matches!(
       side_effects,
@@ -160,6 +165,7 @@ fn access_global_var() {
       unresolved_mark,
       top_level_mark,
       &SingleThreadedComments::default(),
+      &HashSet::default(),
     );
     println!("{side_effects:?}");
     assert!(matches!(
@@ -173,6 +179,7 @@ fn access_global_var() {
       unresolved_mark,
       top_level_mark,
       &SingleThreadedComments::default(),
+      &HashSet::default(),
     );
     assert!(matches!(
       side_effects,
@@ -197,6 +204,7 @@ fn commonjs_module_exports() {
       unresolved_mark,
       top_level_mark,
       &SingleThreadedComments::default(),
+      &HashSet::default(),
     );
     assert!(matches!(
       side_effects,
@@ -226,6 +234,7 @@ console.log(a, b);
       unresolved_mark,
       top_level_mark,
       &SingleThreadedComments::default(),
+      &HashSet::default(),
     );
 
     assert!(matches!(
@@ -255,32 +264,52 @@ export default Steps;
     let (module, comments, unresolved_mark, top_level_mark) = parse_module_comments(code);
 
     let item_2 = &module.body[1];
-    let side_effects =
-      super::analyze_statement_side_effects(item_2, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_2,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     assert!(matches!(
       side_effects,
       super::StatementSideEffects::WriteTopLevelVar(_)
     ));
 
     let item_3 = &module.body[2];
-    let side_effects =
-      super::analyze_statement_side_effects(item_3, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_3,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     assert!(matches!(
       side_effects,
       super::StatementSideEffects::WriteTopLevelVar(_)
     ));
 
     let item_4 = &module.body[3];
-    let side_effects =
-      super::analyze_statement_side_effects(item_4, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_4,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     assert!(matches!(
       side_effects,
       super::StatementSideEffects::WriteTopLevelVar(_)
     ));
 
     let item_5 = &module.body[4];
-    let side_effects =
-      super::analyze_statement_side_effects(item_5, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_5,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     assert!(matches!(
       side_effects,
       super::StatementSideEffects::WriteOrCallGlobalVar
@@ -299,8 +328,13 @@ comp();
     let (module, comments, unresolved_mark, top_level_mark) = parse_module_comments(code);
 
     let item_2 = &module.body[1];
-    let side_effects =
-      super::analyze_statement_side_effects(item_2, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_2,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     println!("{side_effects:?}");
     assert!(matches!(
       side_effects,
@@ -308,8 +342,13 @@ comp();
     ));
 
     let item_3 = &module.body[2];
-    let side_effects =
-      super::analyze_statement_side_effects(item_3, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_3,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     println!("{side_effects:?}");
     assert!(matches!(
       side_effects,
@@ -330,8 +369,13 @@ var _c;
     let (module, comments, unresolved_mark, top_level_mark) = parse_module_comments(code);
 
     let item_2 = &module.body[1];
-    let side_effects =
-      super::analyze_statement_side_effects(item_2, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_2,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     println!("{side_effects:?}");
     assert!(matches!(
       side_effects,
@@ -339,8 +383,13 @@ var _c;
     ));
 
     let item_3 = &module.body[2];
-    let side_effects =
-      super::analyze_statement_side_effects(item_3, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_3,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     println!("{side_effects:?}");
     assert!(matches!(
       side_effects,
@@ -363,8 +412,13 @@ fn object_lit_assign() {
     let (module, comments, unresolved_mark, top_level_mark) = parse_module_comments(code);
 
     let item_2 = &module.body[1];
-    let side_effects =
-      super::analyze_statement_side_effects(item_2, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_2,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     println!("{side_effects:?}");
     assert!(matches!(
       side_effects,
@@ -372,8 +426,13 @@ fn object_lit_assign() {
     ));
 
     let item_3 = &module.body[2];
-    let side_effects =
-      super::analyze_statement_side_effects(item_3, unresolved_mark, top_level_mark, &comments);
+    let side_effects = super::analyze_statement_side_effects(
+      item_3,
+      unresolved_mark,
+      top_level_mark,
+      &comments,
+      &HashSet::default(),
+    );
     println!("{side_effects:?}");
     assert!(matches!(
       side_effects,
@@ -381,3 +440,37 @@ fn object_lit_assign() {
     ));
   })
 }
+
+#[test]
+fn no_side_effects_fn_calls() {
+  GLOBALS.set(&Globals::new(), || {
+    let code = r#"
+import { defineComponent } from 'vue';
+function foo() { console.log('foo'); }
+const a = foo();
+const b = defineComponent({ setup: bar() });
+const c = defineComponent({});
+"#;
+    let (module, comments, unresolved_mark, top_level_mark) = parse_module_comments(code);
+    let ctxt = SyntaxContext::empty().apply_mark(top_level_mark).as_u32();
+    let no_side_effects_fns = HashSet::from_iter([
+      SwcId::new("foo".into(), ctxt),
+      SwcId::new("defineComponent".into(), ctxt),
+    ]);
+
+    let analyze = |index: usize| {
+      super::analyze_statement_side_effects(
+        &module.body[index],
+        unresolved_mark,
+        top_level_mark,
+        &comments,
+        &no_side_effects_fns,
+      )
+    };
+
+    assert_eq!(analyze(2), super::StatementSideEffects::NoSideEffects);
+    // arguments of the call still have side effects
+    assert_ne!(analyze(3), super::StatementSideEffects::NoSideEffects);
+    assert_eq!(analyze(4), super::StatementSideEffects::NoSideEffects);
+  })
+}
@@ -5,6 +5,7 @@ use farmfe_core::{
     meta_data::script::statement::SwcId, module_graph::ModuleGraph, ModuleId, ModuleSystem,
   },
   plugin::ResolveKind,
+  stats::TreeShakingKeptReason,
   HashMap, HashSet,
 };
 
@@ -85,11 +86,14 @@ fn traverse_tree_shake_modules(
   let mut tree_shake_module_ids_queue = VecDeque::from(entry_module_ids);
 
   let set_dep_used_export_all =
-    |dep_id: &ModuleId, tree_shake_modules_map: &mut HashMap<ModuleId, TreeShakeModule>| {
+    |importer: &ModuleId,
+     dep_id: &ModuleId,
+     tree_shake_modules_map: &mut HashMap<ModuleId, TreeShakeModule>| {
       let dep_tree_shake_module = tree_shake_modules_map.get_mut(dep_id);
 
       if let Some(dep_tree_shake_module) = dep_tree_shake_module {
         dep_tree_shake_module.pending_used_exports.set_export_all();
+        dep_tree_shake_module.add_explain_importer(importer, &UsedExports::All);
       }
     };
 
@@ -99,7 +103,7 @@ fn traverse_tree_shake_modules(
       if !visited_modules.contains(&tree_shake_module_id) {
         // make sure all non tree shakeable modules are handled
         for (dep_id, _) in module_graph.dependencies(&tree_shake_module_id) {
-          set_dep_used_export_all(&dep_id, tree_shake_modules_map);
+          set_dep_used_export_all(&tree_shake_module_id, &dep_id, tree_shake_modules_map);
           tree_shake_module_ids_queue.push_back(dep_id);
         }
         visited_modules.insert(tree_shake_module_id);
@@ -136,7 +140,7 @@ fn traverse_tree_shake_modules(
           }
         }
 
-        set_dep_used_export_all(&dep_id, tree_shake_modules_map);
+        set_dep_used_export_all(&tree_shake_module_id, &dep_id, tree_shake_modules_map);
         tree_shake_module_ids_queue.push_back(dep_id);
       }
     } else {
@@ -177,6 +181,8 @@ fn set_pending_used_exports(
     let dep_id = module_graph.get_dep_by_source(tree_shake_module_id, &source, Some(kind));
 
     if let Some(dep_tree_shake_module) = tree_shake_modules_map.get_mut(&dep_id) {
+      dep_tree_shake_module.add_explain_importer(tree_shake_module_id, &used_stmt_idents);
+
       match used_stmt_idents {
         UsedExports::All => {
           dep_tree_shake_module.pending_used_exports.set_export_all();
@@ -268,7 +274,7 @@ fn trace_and_mark_used_statements(
             );
           }
 
-          extra_used_import_export_from_stmts.push(stmt.id);
+          extra_used_import_export_from_stmts.push((stmt.id, source.to_string()));
         }
       }
     }
@@ -278,8 +284,12 @@ fn trace_and_mark_used_statements(
     .get_mut(tree_shake_module_id)
     .unwrap();
 
-  for stmt_id in extra_used_import_export_from_stmts {
+  for (stmt_id, source) in extra_used_import_export_from_stmts {
     tree_shake_module.stmt_graph.mark_used_statements(stmt_id);
+    tree_shake_module.stmt_graph.add_kept_reason(
+      stmt_id,
+      TreeShakingKeptReason::ImportSideEffectsModule { source },
+    );
   }
 
   // for dependency kind other than import and export from, always trace the dependency
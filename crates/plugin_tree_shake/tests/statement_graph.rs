@@ -27,7 +27,7 @@ fn create_test_statement_graph(code: &str) -> StatementGraph {
     top_level_mark: top_level_mark.as_u32(),
     ..Default::default()
   }));
-  StatementGraph::new(&module, &ast, &comment)
+  StatementGraph::new(&module, &ast, &comment, &HashSet::default())
 }
 
 #[test]
@@ -1,10 +1,11 @@
+use farmfe_core::stats::TreeShakingKeptReason;
 use farmfe_core::swc_common::{Globals, GLOBALS};
 use farmfe_core::HashSet;
 use farmfe_plugin_tree_shake::module::UsedExportsIdent;
 
 use farmfe_plugin_tree_shake::module::{TreeShakeModule, UsedExports};
 
-use common::{create_module, create_module_with_globals};
+use common::{create_module, create_module_with_comments, create_module_with_globals};
 
 use crate::common::print_id;
 
@@ -449,3 +450,45 @@ export * from './bar';
     assert_stmt!(2, Vec::<&str>::new());
   });
 }
+
+#[test]
+fn explain_kept_statements() {
+  let code = r#"
+const b = 1;
+export const a = b + 1;
+console.log('side effect');
+export const c = 2;
+  "#;
+
+  GLOBALS.set(&Globals::new(), || {
+    let mut module = create_module_with_comments(code);
+    let mut tree_shake_module = TreeShakeModule::new(&mut module);
+    tree_shake_module.enable_explain();
+    tree_shake_module.pending_used_exports =
+      UsedExports::Partial(HashSet::from_iter([UsedExportsIdent::SwcIdent(
+        "a".to_string(),
+      )]));
+    tree_shake_module.trace_and_mark_used_statements();
+
+    let stmt_graph = &tree_shake_module.stmt_graph;
+    assert_eq!(
+      stmt_graph.kept_reasons(&0),
+      vec![TreeShakingKeptReason::UsedByStatement {
+        stmt_id: 1,
+        idents: vec!["b".to_string()]
+      }]
+    );
+    assert_eq!(
+      stmt_graph.kept_reasons(&1),
+      vec![TreeShakingKeptReason::UsedExports {
+        idents: vec!["a".to_string()]
+      }]
+    );
+    assert!(matches!(
+      stmt_graph.kept_reasons(&2).as_slice(),
+      [TreeShakingKeptReason::SideEffects { .. }]
+    ));
+    assert!(stmt_graph.kept_reasons(&3).is_empty());
+    assert!(!stmt_graph.used_stmts().contains(&3));
+  });
+}
@@ -1,3 +1,4 @@
 pub use farmfe_utils::hash::base64_decode;
 pub use farmfe_utils::hash::base64_encode;
 pub use farmfe_utils::hash::sha256;
+pub use farmfe_utils::hash::sha256_base64;
@@ -32,6 +32,9 @@ lazy_static! {
 }
 
 pub const DYNAMIC_EXTENSION_PRIORITY: &str = "DYNAMIC_EXTENSION_PRIORITY";
+/// Conditions that are used together with `resolve.conditions` when resolving `exports` and `imports` of package.json,
+/// the value is a json array of strings, e.g. `["worker"]`
+pub const ADDITIONAL_RESOLVE_CONDITIONS: &str = "ADDITIONAL_RESOLVE_CONDITIONS";
 
 /// Load closest package.json start from the specified path, return [farmfe_core::error::Result<Value>].
 pub fn load_package_json(
@@ -11,6 +11,16 @@ pub fn sha256(bytes: &[u8], len: usize) -> String {
   format!("{hash:x}")[..len].to_string()
 }
 
+/// base64 encoded sha256 digest of the bytes, for example, used as `'sha256-xxx'` source of Content-Security-Policy
+pub fn sha256_base64(bytes: &[u8]) -> String {
+  let mut hasher = Sha256::new();
+
+  hasher.update(bytes);
+  let hash = hasher.finalize();
+
+  general_purpose::STANDARD.encode(hash)
+}
+
 pub fn base64_encode(bytes: &[u8]) -> String {
   general_purpose::STANDARD.encode(bytes)
 }
@@ -41,6 +51,14 @@ mod tests {
     assert_eq!(super::sha256(b"hello world", 8), "b94d27b9".to_string());
   }
 
+  #[test]
+  fn test_sha256_base64() {
+    assert_eq!(
+      super::sha256_base64(b"hello world"),
+      "uU0nuZNNPgilLlLX2n2r+sSE7+N6U4DukIj3rOLvzek=".to_string()
+    );
+  }
+
   #[test]
   fn test_base64_encode() {
     assert_eq!(super::base64_encode(b"hello world"), "aGVsbG8gd29ybGQ=");
@@ -14,12 +14,20 @@ export declare class Compiler {
   hasModule(resolvedPath: string): boolean
   getParentFiles(resolvedPath: string): Array<string>
   resources(): Record<string, Buffer>
+  /** Names of the environments in `compilation.environments` */
+  environments(): Array<string>
+  environmentResources(name: string): Record<string, Buffer>
   resourcesMap(): Record<string, unknown>
   writeResourcesToDisk(): void
   watchModules(): Array<string>
   relativeModulePaths(): Array<string>
   resource(name: string): Buffer | null
   stats(): string
+  /**
+   * Json string of why the modules of the latest compilation are restored from the persistent cache or not,
+   * and the build dependencies that selected the cache directory
+   */
+  cacheStats(): string
   invalidateModule(moduleId: string): void
   /** Write cache with name and data */
   writeMetadata(name: string, data: string, options?: JsApiMetadata | undefined | null): void
@@ -43,11 +51,33 @@ export interface JsApiMetadata {
   refer?: Array<string>
 }
 
+export interface JsHmrFullReloadReason {
+  /** `entry` or `nonScriptModule` */
+  kind: string
+  chain: Array<string>
+  modules: Array<JsHmrModuleAcceptance>
+}
+
+export interface JsHmrModuleAcceptance {
+  id: string
+  selfAccepted: boolean
+  acceptedDeps: Array<string>
+}
+
 export interface JsModuleHookFilters {
   moduleTypes?: Array<string>
   resolvedPaths?: Array<string>
 }
 
+export interface JsNodeHmrUpdate {
+  /** `esm` or `cjs` */
+  format: string
+  code: string
+  invalidatedModuleIds: Array<string>
+  /** the update can not be applied by HMR, the process should be restarted */
+  restart: boolean
+}
+
 export interface JsPluginAugmentResourceHashHookFilters {
   resourcePotTypes: Array<string>
   moduleIds: Array<string>
@@ -95,8 +125,14 @@ export interface JsUpdateResult {
   immutableModules: string
   mutableModules: string
   boundaries: Record<string, Array<Array<string>>>
+  fullReloadReason?: JsHmrFullReloadReason
+  nonScriptUpdates: Record<string, string>
   dynamicResourcesMap?: Record<string, Array<Array<string>>>
   extraWatchResult: WatchDiffResult
+  /** the update of node targets, applied by `moduleSystem.h` of the runtime */
+  nodeUpdate?: JsNodeHmrUpdate
+  /** update results of the environments that contain the updated files, keyed by environment name */
+  environments: Record<string, JsUpdateResult>
 }
 
 export interface WatchDiffResult {
@@ -39,6 +39,36 @@ export interface TracedModuleGraph {
   reverseEdges: Record<string, string[]>;
 }
 
+export type ModuleCacheStatus =
+  | 'hit'
+  | 'missNew'
+  | 'missContentChanged'
+  | 'missDepChanged'
+  | 'missInvalidated';
+
+export interface CacheStats {
+  /**
+   * Cache directory selected by the hash of `buildDependencies`, `null` if the persistent cache is disabled or in memory
+   */
+  cacheDir: string | null;
+  /**
+   * Inputs of the hash of the cache directory, changing any of them makes the cache cold
+   */
+  buildDependencies: Array<{
+    dependency: string;
+    isFile: boolean;
+    hash: string;
+  }>;
+  /**
+   * Count of modules by cache status
+   */
+  summary: Partial<Record<ModuleCacheStatus, number>>;
+  /**
+   * Cache status of each module built in the latest compilation
+   */
+  modules: Record<string, ModuleCacheStatus>;
+}
+
 export class Compiler {
   private _bindingCompiler: BindingCompiler;
   private _updateQueue: UpdateQueueItem[] = [];
@@ -168,6 +198,20 @@ export class Compiler {
     return this._bindingCompiler.resources();
   }
 
+  /**
+   * Names of the environments configured by `compilation.environments`
+   */
+  environments(): string[] {
+    return this._bindingCompiler.environments();
+  }
+
+  /**
+   * Resources of environment `name`, the resources of the top level config are returned by `resources()`
+   */
+  environmentResources(name: string): Record<string, Buffer> {
+    return this._bindingCompiler.environmentResources(name);
+  }
+
   resource(path: string): Buffer | null {
     return (
       this._bindingCompiler.resource(path) ||
@@ -239,6 +283,13 @@ export class Compiler {
     return this._bindingCompiler.stats();
   }
 
+  /**
+   * Why the modules of the latest compilation are restored from the persistent cache or not
+   */
+  cacheStats(): CacheStats {
+    return JSON.parse(this._bindingCompiler.cacheStats());
+  }
+
   async waitForCompileFinish() {
     if (this.compiling && this._compileFinishPromise) {
       await this._compileFinishPromise;
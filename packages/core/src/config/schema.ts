@@ -71,6 +71,13 @@ const outputSchema = z
       .union([moduleFormatSchema, z.array(moduleFormatSchema)])
       .optional(),
     showFileSize: z.boolean().optional(),
+    libraryBundleType: z
+      .enum(['single-bundle', 'multiple-bundle', 'bundle-less'])
+      .optional(),
+    srcDir: z.string().optional(),
+    packageExports: z
+      .union([z.boolean(), z.literal('emit'), z.literal('patch')])
+      .optional(),
     asciiOnly: z.boolean().optional(),
     externalGlobals: z.record(z.string()).optional(),
     name: z.string().optional()
@@ -240,6 +247,31 @@ const compilationConfigSchema = z
     externalNodeBuiltins: z
       .union([z.boolean(), z.array(z.string())])
       .optional(),
+    environments: z
+      .record(
+        z.string(),
+        z
+          .object({
+            input: z.record(z.string()).optional(),
+            output: outputSchema,
+            resolve: z
+              .object({
+                extensions: z.array(z.string()).optional(),
+                mainFields: z.array(z.string()).optional(),
+                conditions: z.array(z.string()).optional(),
+                symlinks: z.boolean().optional(),
+                strictExports: z.boolean().optional(),
+                autoExternalFailedResolve: z.boolean().optional(),
+                dedupe: z.array(z.string()).optional()
+              })
+              .strict()
+              .optional(),
+            define: z.record(z.any()).optional(),
+            external: z.array(z.string()).optional()
+          })
+          .strict()
+      )
+      .optional(),
     mode: z.string().optional(),
 
     coreLibPath: z.string().optional(),
@@ -357,7 +389,18 @@ const compilationConfigSchema = z
       .strict()
       .optional(),
     lazyCompilation: z.boolean().optional(),
-    treeShaking: z.boolean().optional(),
+    treeShaking: z
+      .union([
+        z.boolean(),
+        z
+          .object({
+            sideEffects: z.array(z.string()).optional(),
+            noSideEffects: z.array(z.string()).optional(),
+            explain: z.array(z.string()).optional()
+          })
+          .strict()
+      ])
+      .optional(),
     minify: z
       .union([
         z.boolean(),
@@ -410,7 +453,24 @@ const compilationConfigSchema = z
           .optional()
       })
       .optional(),
-    html: z.object({ base: z.string().optional() }).optional(),
+    html: z
+      .object({
+        base: z.string().optional(),
+        templateData: z.record(z.string(), z.record(z.string(), z.any())).optional(),
+        csp: z
+          .discriminatedUnion('mode', [
+            z.object({
+              mode: z.literal('nonce'),
+              placeholder: z.string().optional()
+            }),
+            z.object({
+              mode: z.literal('hash'),
+              output: z.enum(['meta', 'json']).optional()
+            })
+          ])
+          .optional()
+      })
+      .optional(),
     persistentCache: z.union([
       z.boolean(),
       z
@@ -425,6 +485,8 @@ const compilationConfigSchema = z
             })
             .optional(),
           envs: z.record(z.string(), z.string()).optional(),
+          maxSize: z.number().positive().optional(),
+          maxAge: z.number().positive().optional(),
           globalBuiltinCacheKeyStrategy: z
             .object({
               env: z.boolean().optional(),
@@ -438,6 +500,15 @@ const compilationConfigSchema = z
         .optional()
     ]),
     comments: z.union([z.boolean(), z.literal('license')]).optional(),
+    rsc: z
+      .union([
+        z.boolean(),
+        z.object({
+          runtime: z.string().optional(),
+          callServer: z.string().optional()
+        })
+      ])
+      .optional(),
     custom: z.record(z.string(), z.string()).optional(),
     concatenateModules: z.boolean().optional()
   })
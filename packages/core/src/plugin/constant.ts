@@ -5,15 +5,20 @@ export const JS_PLUGIN_BRIDGE_HOOKS = new Set([
   'transform',
   'processModule',
   'freezeModule',
+  'analyzeDeps',
   'buildEnd',
   'renderStart',
+  'renderResourcePot',
   'processRenderedResourcePot',
   'augmentResourceHash',
+  'generateResources',
+  'handleEntryResource',
   'finalizeResources',
   'transformHtml',
   'writeResources',
   'pluginCacheLoaded',
   'writePluginCache',
+  'handlePersistentCachedCustomModule',
   'finish',
   'updateFinished',
   'updateModules'
@@ -141,7 +141,14 @@ export async function resolveConfigResolvedHook(
 
       if (
         typeof hook !== 'object' ||
-        !['load', 'transform', 'processModule', 'freezeModule'].includes(key)
+        ![
+          'load',
+          'transform',
+          'processModule',
+          'freezeModule',
+          'analyzeDeps',
+          'handlePersistentCachedCustomModule'
+        ].includes(key)
       ) {
         continue;
       }
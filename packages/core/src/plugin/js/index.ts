@@ -7,6 +7,7 @@ import { resolveAsyncPlugins } from '../index.js';
 import { cssPluginUnwrap, cssPluginWrap } from './adapter-plugins/css.js';
 import { defaultLoadPlugin } from './adapter-plugins/default-load.js';
 import {
+  createAnalyzeDepsSchema,
   createAugmentResourceHashSchema,
   createBuildEndSchema,
   createBuildStartSchema,
@@ -17,6 +18,9 @@ import {
   createFinalizeResourcesSchema,
   createFinishSchema,
   createFreezeModuleSchema,
+  createGenerateResourcesSchema,
+  createHandleEntryResourceSchema,
+  createHandlePersistentCachedCustomModuleSchema,
   createLoadSchema,
   createNameSchema,
   createPluginCacheLoadedSchema,
@@ -129,15 +133,23 @@ schemaRegistry
   .register('transform', createTransformSchema)
   .register('processModule', createProcessModuleSchema)
   .register('freezeModule', createFreezeModuleSchema)
+  .register('analyzeDeps', createAnalyzeDepsSchema)
   .register('buildEnd', createBuildEndSchema)
   .register('renderStart', createRenderStartSchema)
+  .register('renderResourcePot', createRenderResourcePotSchema)
   .register('processRenderedResourcePot', createRenderResourcePotSchema)
   .register('augmentResourceHash', createAugmentResourceHashSchema)
+  .register('generateResources', createGenerateResourcesSchema)
+  .register('handleEntryResource', createHandleEntryResourceSchema)
   .register('finalizeResources', createFinalizeResourcesSchema)
   .register('transformHtml', createTransformHtmlSchema)
   .register('writeResources', createWriteResourcesSchema)
   .register('pluginCacheLoaded', createPluginCacheLoadedSchema)
   .register('writePluginCache', createWritePluginCacheSchema)
+  .register(
+    'handlePersistentCachedCustomModule',
+    createHandlePersistentCachedCustomModuleSchema
+  )
   .register('finish', createFinishSchema)
   .register('updateFinished', createUpdateFinishedSchema)
   .register('updateModules', createUpdateModulesSchema);
@@ -109,6 +109,14 @@ export const augmentResourceHashSchema = z
     moduleIds: data.moduleIds ?? []
   }));
 
+export const handleEntryResourceFilterSchema = z
+  .object({
+    moduleIds: z.array(z.string()).optional().default([])
+  })
+  .transform((data) => ({
+    moduleIds: data.moduleIds ?? []
+  }));
+
 export const createNameSchema = (name: string) => {
   return z
     .string()
@@ -329,6 +337,46 @@ export const createFreezeModuleSchema = (name: string) => {
   });
 };
 
+export const createAnalyzeDepsSchema = (name: string) => {
+  return z.object({
+    filters: transformFilterSchema
+      .refine(
+        (data) => {
+          return data.moduleTypes.length > 0 || data.resolvedPaths.length > 0;
+        },
+        {
+          message: `\n 'analyzeDeps' hook of plugin '${name}' must have at least one filter(like resolvedPaths or moduleTypes)`
+        }
+      )
+      .default({
+        moduleTypes: [],
+        resolvedPaths: []
+      }),
+    executor: z.function()
+  });
+};
+
+export const createHandlePersistentCachedCustomModuleSchema = (
+  name: string
+) => {
+  return z.object({
+    filters: transformFilterSchema
+      .refine(
+        (data) => {
+          return data.moduleTypes.length > 0 || data.resolvedPaths.length > 0;
+        },
+        {
+          message: `\n 'handlePersistentCachedCustomModule' hook of plugin '${name}' must have at least one filter(like resolvedPaths or moduleTypes)`
+        }
+      )
+      .default({
+        moduleTypes: [],
+        resolvedPaths: []
+      }),
+    executor: z.function()
+  });
+};
+
 export const createRenderStartSchema = (name: string) => {
   return z
     .object({
@@ -383,6 +431,43 @@ export const createAugmentResourceHashSchema = (name: string) => {
   });
 };
 
+export const createGenerateResourcesSchema = (name: string) => {
+  return z.object({
+    filters: renderResourcePotSchema
+      .refine(
+        (data) => {
+          return data.resourcePotTypes.length > 0 || data.moduleIds.length > 0;
+        },
+        {
+          message: `\n 'generateResources' hook of plugin '${name}' must have at least one filter(like moduleIds or resourcePotTypes)`
+        }
+      )
+      .default({
+        resourcePotTypes: [],
+        moduleIds: []
+      }),
+    executor: z.function()
+  });
+};
+
+export const createHandleEntryResourceSchema = (name: string) => {
+  return z.object({
+    filters: handleEntryResourceFilterSchema
+      .refine(
+        (data) => {
+          return data.moduleIds.length > 0;
+        },
+        {
+          message: `\n 'handleEntryResource' hook of plugin '${name}' must have at least one filter(like moduleIds)`
+        }
+      )
+      .default({
+        moduleIds: []
+      }),
+    executor: z.function()
+  });
+};
+
 export const createFinalizeResourcesSchema = (name: string) => {
   return z
     .object({
@@ -42,8 +42,9 @@ export async function rustPluginResolver(
     pluginPath = require.resolve(pluginPath);
   }
 
-  // a rust plugin' entry can be a .farm file or a .js file that exports the path to the binary
-  if (!pluginPath.endsWith('.farm')) {
+  // a rust plugin' entry can be a .farm file, a .wasm file(sandboxed plugin compiled to wasm32-wasip1)
+  // or a .js file that exports the path to the binary
+  if (!pluginPath.endsWith('.farm') && !pluginPath.endsWith('.wasm')) {
     // rust plugin should export a default string representing the path to the binary
     if (process.platform === 'win32') {
       pluginPath = (await import(pathToFileURL(pluginPath).toString())).default;
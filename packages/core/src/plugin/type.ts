@@ -110,6 +110,57 @@ export interface Resource {
   origin: { type: 'ResourcePot' | 'Module'; value: string };
 }
 
+export interface PluginAnalyzeDepsHookEntry {
+  source: string;
+  /** e.g. 'import', 'dynamicImport', 'cssUrl' or { custom: 'xxx' } */
+  kind: string | Record<string, unknown>;
+}
+
+export interface PluginAnalyzeDepsHookParams {
+  moduleId: string;
+  moduleType: ModuleType;
+  content: string;
+  deps: PluginAnalyzeDepsHookEntry[];
+}
+
+export interface PluginHandlePersistentCachedCustomModuleHookParams {
+  moduleId: string;
+  moduleType: ModuleType;
+  content: string;
+}
+
+export interface PluginRenderResourcePotHookResult {
+  content: string;
+}
+
+export interface PluginGeneratedResource {
+  name: string;
+  bytes: number[];
+  resourceType: string;
+}
+
+export interface PluginHandleEntryResourceHookParams {
+  resource: Resource;
+  resourceSourcemap?: Resource;
+  entryModuleId: string;
+  initialResources: [string, string][];
+  dynamicResources: string;
+  dynamicModuleResourcesMap: string;
+  runtimeResourceName: string;
+  emitRuntime: boolean;
+  additionalInjectResources: Record<string, Resource>;
+}
+
+export type PluginHandleEntryResourceHookResult = Partial<
+  Pick<
+    PluginHandleEntryResourceHookParams,
+    | 'resource'
+    | 'resourceSourcemap'
+    | 'emitRuntime'
+    | 'additionalInjectResources'
+  >
+>;
+
 export type PluginFinalizeResourcesHookParams = {
   resourcesMap: Record<string, Resource>;
   config: Config['config'];
@@ -198,12 +249,33 @@ export interface JsPlugin {
     PluginProcessModuleResult
   >;
 
+  /**
+   * Add dependencies to the module, returned deps are appended to the deps analyzed by previous plugins
+   */
+  analyzeDeps?: JsPluginHook<
+    NormalizeFilterParams,
+    PluginAnalyzeDepsHookParams,
+    PluginAnalyzeDepsHookEntry[]
+  >;
+
   buildEnd?: { executor: Callback<Record<string, never>, void> };
 
   renderStart?: {
     executor: Callback<Config['config'], void>;
   };
 
+  /**
+   * Render the resource pot, `content` of the param is always empty as the resource pot is not rendered yet
+   */
+  renderResourcePot?: JsPluginHook<
+    {
+      resourcePotTypes?: ResourcePotType[];
+      moduleIds?: string[];
+    },
+    JsResourcePot,
+    PluginRenderResourcePotHookResult
+  >;
+
   processRenderedResourcePot?: JsPluginHook<
     {
       resourcePotTypes?: ResourcePotType[];
@@ -222,6 +294,24 @@ export interface JsPlugin {
     string
   >;
 
+  /**
+   * Emit extra resources for the rendered resource pot, the builtin resources are still generated
+   */
+  generateResources?: JsPluginHook<
+    {
+      resourcePotTypes?: ResourcePotType[];
+      moduleIds?: string[];
+    },
+    JsResourcePot,
+    PluginGeneratedResource[]
+  >;
+
+  handleEntryResource?: JsPluginHook<
+    { moduleIds: string[] },
+    PluginHandleEntryResourceHookParams,
+    PluginHandleEntryResourceHookResult
+  >;
+
   finalizeResources?: {
     executor: Callback<
       PluginFinalizeResourcesHookParams,
@@ -241,6 +331,16 @@ export interface JsPlugin {
     ) => void | Promise<void>;
   };
 
+  /**
+   * Called when a module of custom module type is restored from persistent cache, useful to rebuild the state of the module.
+   * Return `true` to mark the cached module as stale and build it again
+   */
+  handlePersistentCachedCustomModule?: JsPluginHook<
+    NormalizeFilterParams,
+    PluginHandlePersistentCachedCustomModuleHookParams,
+    boolean | undefined | null | void
+  >;
+
   pluginCacheLoaded?: {
     executor: Callback<number[], undefined | null | void>;
   };
@@ -102,8 +102,17 @@ export class HmrEngine {
       removed,
       immutableModules,
       mutableModules,
-      boundaries
+      boundaries,
+      fullReloadReason,
+      nonScriptUpdates
     } = result;
+
+    if (fullReloadReason) {
+      logger.warn(
+        `full reload: ${fullReloadReason.chain.join(' -> ')} is not accepted by HMR`
+      );
+    }
+
     const resultStr = `{
         added: [${formatHmrResult(added)}],
         changed: [${formatHmrResult(changed)}],
@@ -111,6 +120,8 @@ export class HmrEngine {
         immutableModules: ${JSON.stringify(immutableModules.trim())},
         mutableModules: ${JSON.stringify(mutableModules.trim())},
         boundaries: ${JSON.stringify(boundaries)},
+        fullReloadReason: ${JSON.stringify(fullReloadReason ?? null)},
+        nonScriptUpdates: ${JSON.stringify(nonScriptUpdates)},
         dynamicResourcesMap: ${JSON.stringify(dynamicResourcesMap)}
       }`;
 
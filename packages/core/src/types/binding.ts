@@ -22,6 +22,7 @@ export type ResolveKind =
       | 'cssUrl'
       | 'scriptSrc'
       | 'linkHref'
+      | 'htmlAsset'
       | 'hmrUpdate'
     >;
 
@@ -176,6 +177,26 @@ export interface OutputConfig {
    */
   showFileSize?: boolean;
 
+  /**
+   * bundle strategy when `targetEnv` is `library`
+   * @default 'single-bundle'
+   */
+  libraryBundleType?: 'single-bundle' | 'multiple-bundle' | 'bundle-less';
+
+  /**
+   * source root of `bundle-less` output, each module is emitted to its path relative to `srcDir`.
+   * If not set, the common parent dir of `input` will be used
+   */
+  srcDir?: string;
+
+  /**
+   * generate `exports`, `main`, `module` and `types` of `package.json` from the library output.
+   * `true` or `'emit'` emits a `package.json` to `output.path`, `'patch'` updates the `package.json` of root in place.
+   * only works when `targetEnv` is `library`
+   * @default false
+   */
+  packageExports?: boolean | 'emit' | 'patch';
+
   /**
    * output ascii only
    * @default false
@@ -381,6 +402,21 @@ export interface GlobalBuiltinCacheKeyStrategy {
   env?: boolean;
 }
 
+export interface TreeShakingConfig {
+  /**
+   * Regex of module ids or package names that always have side effects, overrides `sideEffects` of package.json
+   */
+  sideEffects?: string[];
+  /**
+   * Regex of module ids or package names that are side effects free, overrides `sideEffects` of package.json
+   */
+  noSideEffects?: string[];
+  /**
+   * Regex of module ids to explain why their statements are preserved, the explanations are written to `treeShakingExplanations` of the stats
+   */
+  explain?: string[];
+}
+
 export interface PersistentCacheConfig {
   namespace?: string;
   cacheDir?: string;
@@ -390,6 +426,14 @@ export interface PersistentCacheConfig {
     hash?: boolean;
   };
   envs?: Record<string, string>;
+  /**
+   * Max size in bytes of the cache directory. When exceeded, caches of other build dependencies or envs are removed in least recently used order
+   */
+  maxSize?: number;
+  /**
+   * Max age in milliseconds of the caches. Caches of other build dependencies or envs and cache entries(like caches of deleted modules) not used in this period are removed
+   */
+  maxAge?: number;
   /**
    * Whether to ignore the built-in keys of the cache, such as define, buildDependencies, lockfile, etc.
    * If these keys are not ignored, the cache will be fully invalidated when these keys change.
@@ -459,6 +503,27 @@ export interface PresetEnvConfig {
   assumptions?: any;
 }
 
+export interface RscConfig {
+  /**
+   * The package that implements the client and server references, `${runtime}/server` and `${runtime}/client` are imported
+   * @default 'react-server-dom-webpack'
+   */
+  runtime?: string;
+  /**
+   * The module that exports `callServer`, which is passed to the server references in the client environment.
+   * `globalThis.__FARM_RSC_CALL_SERVER__` is called if not set
+   */
+  callServer?: string;
+}
+
+export interface EnvironmentConfig {
+  input?: Record<string, string>;
+  output?: OutputConfig;
+  resolve?: Omit<ResolveConfig, 'alias'>;
+  define?: Record<string, any>;
+  external?: string[];
+}
+
 export interface Config {
   config?: {
     coreLibPath?: string;
@@ -482,6 +547,14 @@ export interface Config {
      */
     external?: (string | Record<string, string>)[];
     externalNodeBuiltins?: boolean | string[];
+    /**
+     * Named environments compiled together with the top level config, e.g. a `ssr` environment of a client app. Each environment is a separate compilation that shares the plugins of the top level config.
+     * `output` and `resolve` are merged deeply into the top level config, `define` is merged, other fields replace the top level ones.
+     * Sources loaded by one environment are reused by the others.
+     *
+     * tip: overrides of an environment are passed to the compiler as is, so `output.targetEnv` must be `node` or `browser`
+     */
+    environments?: Record<string, EnvironmentConfig>;
     mode?: 'development' | 'production';
     root?: string;
     runtime?: RuntimeConfig;
@@ -495,6 +568,20 @@ export interface Config {
     css?: CssConfig;
     html?: {
       base?: string;
+      /**
+       * Template data of each html input, key is the input name. Html templates are only rendered when it is set.
+       * `%NAME%` in html is replaced by the data of the input, then the env and `define`.
+       * `{{#if NAME}}...{{else}}...{{/if}}` and `{{#each NAME}}...{{/each}}` blocks are supported too.
+       */
+      templateData?: Record<string, Record<string, any>>;
+      /**
+       * Make the output html work under a strict Content-Security-Policy.
+       * - `nonce`: add `nonce="<placeholder>"` to all scripts and styles, the server should replace the placeholder for each request. Default placeholder is `__FARM_CSP_NONCE__`
+       * - `hash`: compute sha256 hashes of all inline scripts and styles, and emit them as `<meta http-equiv="Content-Security-Policy">`(default) or a `[name].csp.json` file next to the html
+       */
+      csp?:
+        | { mode: 'nonce'; placeholder?: string }
+        | { mode: 'hash'; output?: 'meta' | 'json' };
     };
     /**
      * Configure whether to enable sourcemap, optional configuration items and descriptions are as follows:
@@ -517,13 +604,19 @@ export interface Config {
     /**
      * Whether to enable tree shake, set to false to disable. See https://farmfe.org/docs/features/tree-shake
      */
-    treeShaking?: boolean;
+    treeShaking?: boolean | TreeShakingConfig;
     minify?: boolean | JsMinifyOptions;
     record?: boolean;
     progress?: boolean;
     presetEnv?: boolean | PresetEnvConfig;
     persistentCache?: boolean | PersistentCacheConfig;
     comments?: boolean | 'license';
+    /**
+     * Handle `"use client"` and `"use server"` directives of React Server Components.
+     * The environment with `react-server` in `resolve.conditions` is the server environment, see https://farmfe.org/docs/config/compilation-options#rsc
+     * @default false
+     */
+    rsc?: boolean | RscConfig;
     custom?: Record<string, any>;
     concatenateModules?: boolean;
   };
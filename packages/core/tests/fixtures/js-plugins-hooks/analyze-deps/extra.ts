@@ -0,0 +1 @@
+export default 'analyze-deps-extra';
@@ -0,0 +1 @@
+export default 'generate-resources';
@@ -0,0 +1 @@
+export default 'handle-entry-resource';
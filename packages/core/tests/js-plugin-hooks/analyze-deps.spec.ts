@@ -0,0 +1,37 @@
+import path from 'path';
+import { expect, test } from 'vitest';
+import { getCompiler, getJsPluginsFixturesDir } from './common.js';
+
+test('Js Plugin Execution - analyzeDeps', async () => {
+  const hookName = 'analyze-deps';
+  const root = getJsPluginsFixturesDir(hookName);
+  const calledModules: string[] = [];
+  const compiler = await getCompiler(
+    '',
+    [
+      {
+        name: `test-${hookName}`,
+        priority: 1000,
+        analyzeDeps: {
+          filters: {
+            resolvedPaths: ['index.ts']
+          },
+          executor: async (param) => {
+            expect(param.content).toContain(hookName);
+            expect(param.deps).toEqual([]);
+            calledModules.push(param.moduleId);
+
+            return [{ source: './extra', kind: 'import' }];
+          }
+        }
+      }
+    ],
+    hookName
+  );
+
+  await compiler.compile();
+
+  expect(calledModules).toEqual(['index.ts?foo=bar']);
+  // the dep added by the hook is resolved and built like the deps in the code
+  expect(compiler.hasModule(path.join(root, 'extra.ts'))).toBe(true);
+});
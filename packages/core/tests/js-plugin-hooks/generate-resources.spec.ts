@@ -0,0 +1,60 @@
+import fs from 'fs';
+import path from 'path';
+import { expect, test } from 'vitest';
+import { JsPlugin } from '../../src/index.js';
+import { getCompiler, getJsPluginsFixturesDir } from './common.js';
+
+test('Js Plugin Execution - generateResources', async () => {
+  const hookName = 'generate-resources';
+  const root = getJsPluginsFixturesDir(hookName);
+  const cacheDir = path.join(root, 'node_modules', '.farm', 'cache');
+  fs.rmSync(cacheDir, { recursive: true, force: true });
+
+  const calledHooks: string[] = [];
+  const plugin: JsPlugin = {
+    name: `test-${hookName}`,
+    priority: 1000,
+    generateResources: {
+      filters: {
+        moduleIds: ['^index.ts\\?foo=bar$'],
+        resourcePotTypes: ['js']
+      },
+      executor: async (param) => {
+        calledHooks.push('generateResources');
+
+        return [
+          {
+            name: 'index.modules.json',
+            bytes: [...Buffer.from(JSON.stringify(param.moduleIds))],
+            resourceType: 'json'
+          }
+        ];
+      }
+    }
+  };
+
+  for (let i = 0; i < 2; i++) {
+    const compiler = await getCompiler(
+      '',
+      [plugin],
+      hookName,
+      undefined,
+      undefined,
+      {
+        persistentCache: { cacheDir }
+      }
+    );
+    await compiler.compile();
+
+    const resources = compiler.resources();
+    // the builtin resources are still generated
+    expect(resources['index.mjs']).toBeTruthy();
+    // the extra resources are restored with the cached resource pot
+    expect(resources['index.modules.json']?.toString()).toBe(
+      '["index.ts?foo=bar"]'
+    );
+  }
+
+  // the second compilation reuses the cached resource pot
+  expect(calledHooks).toEqual(['generateResources']);
+});
@@ -0,0 +1,53 @@
+import { expect, test } from 'vitest';
+import { getCompiler } from './common.js';
+
+test('Js Plugin Execution - handleEntryResource', async () => {
+  const hookName = 'handle-entry-resource';
+  const calledEntries: string[] = [];
+  const compiler = await getCompiler(
+    '',
+    [
+      {
+        name: `test-${hookName}`,
+        priority: 1000,
+        handleEntryResource: {
+          filters: {
+            moduleIds: ['^index.ts\\?foo=bar$']
+          },
+          executor: async (param) => {
+            expect(param.resource.name).toBe('index.mjs');
+            calledEntries.push(param.entryModuleId);
+
+            const banner = '/* handle-entry-resource */\n';
+
+            return {
+              resource: {
+                ...param.resource,
+                bytes: [...Buffer.from(banner), ...param.resource.bytes]
+              },
+              additionalInjectResources: {
+                'entry-info.json': {
+                  ...param.resource,
+                  name: 'entry-info.json',
+                  bytes: [...Buffer.from(param.entryModuleId)],
+                  resourceType: 'json'
+                }
+              }
+            };
+          }
+        }
+      }
+    ],
+    hookName
+  );
+
+  await compiler.compile();
+
+  expect(calledEntries).toEqual(['index.ts?foo=bar']);
+
+  const resources = compiler.resources();
+  expect(resources['index.mjs'].toString()).toContain(
+    '/* handle-entry-resource */'
+  );
+  expect(resources['entry-info.json'].toString()).toBe('index.ts?foo=bar');
+});
@@ -0,0 +1,43 @@
+import { expect, test } from 'vitest';
+import { getOutputResult } from '../common.js';
+import { getCompiler, getOutputFilePath } from './common.js';
+
+test('Js Plugin Execution - renderResourcePot', async () => {
+  const hookName = 'render-resource-pot';
+  const calledHooks: string[] = [];
+  const compiler = await getCompiler(
+    'render',
+    [
+      {
+        name: `test-${hookName}`,
+        priority: 1000,
+        renderResourcePot: {
+          filters: {
+            moduleIds: ['^index.ts\\?foo=bar$'],
+            resourcePotTypes: ['js']
+          },
+          executor: async (param) => {
+            // the resource pot is not rendered yet
+            expect(param.content).toBe('');
+            expect(param.moduleIds).toEqual(['index.ts?foo=bar']);
+            calledHooks.push('renderResourcePot');
+
+            return {
+              content: "export default 'rendered-by-js-plugin';"
+            };
+          }
+        }
+      }
+    ],
+    hookName
+  );
+
+  await compiler.compile();
+  compiler.writeResourcesToDisk();
+
+  expect(calledHooks).toEqual(['renderResourcePot']);
+
+  const outputFilePath = getOutputFilePath('render', hookName);
+  const result = await getOutputResult(outputFilePath);
+  expect(result.default).toBe('rendered-by-js-plugin');
+});
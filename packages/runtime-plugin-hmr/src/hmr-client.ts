@@ -4,6 +4,7 @@ import { logger } from './logger.js';
 import { ErrorOverlay, overlayId } from './overlay.js';
 import type {
   HMRPayload,
+  HmrFullReloadReason,
   HmrUpdateResult,
   RawHmrUpdateResult
 } from './types.js';
@@ -115,11 +116,35 @@ export class HmrClient {
       moduleSystem.a(id);
     }
 
-    for (const id of result.changed) {
-      if (!result.boundaries[id]) {
-        // do not found boundary module, reload the window
-        location.reload();
+    if (
+      result.changed.some(
+        (id) => !result.boundaries[id] && !result.nonScriptUpdates[id]
+      )
+    ) {
+      // do not found boundary module, reload the window
+      if (result.fullReloadReason) {
+        logger.warn(formatFullReloadReason(result.fullReloadReason));
       }
+      location.reload();
+    }
+
+    let stylesheetsUpdated = false;
+
+    for (const [id, moduleType] of Object.entries(result.nonScriptUpdates)) {
+      if (moduleType === 'html') {
+        // skip the reload only when the page is rendered from another html for sure
+        if (mayRenderPage(id)) {
+          location.reload();
+        }
+      } else if (moduleType === 'css') {
+        stylesheetsUpdated = true;
+      }
+
+      this.notifyListeners('farm:nonScriptUpdate', { id, moduleType });
+    }
+
+    if (stylesheetsUpdated) {
+      reloadStylesheets();
     }
 
     if (result.dynamicResources && result) {
@@ -266,6 +291,8 @@ export class HmrClient {
         changed: result.changed,
         removed: result.removed,
         boundaries: result.boundaries,
+        fullReloadReason: result.fullReloadReason,
+        nonScriptUpdates: result.nonScriptUpdates ?? {},
         // modules,
         dynamicResources: result.dynamicResources,
         dynamicModuleResourcesMap: result.dynamicModuleResourcesMap
@@ -275,6 +302,55 @@ export class HmrClient {
   }
 }
 
+export function formatFullReloadReason(reason: HmrFullReloadReason) {
+  if (reason.kind === 'nonScriptModule') {
+    return `full reload, ${reason.chain[0]} is not a script module and does not accept its own updates`;
+  }
+
+  const modules = reason.modules.map(
+    (m) =>
+      `  ${m.id}: selfAccepted: ${m.selfAccepted}, acceptedDeps: [${m.acceptedDeps.join(', ')}]`
+  );
+
+  return [
+    `full reload, the update reaches an entry module without being accepted: ${reason.chain.join(' -> ')}`,
+    'call `import.meta.hot.accept()` in one of the modules below to apply the update without reloading:',
+    ...modules
+  ].join('\n');
+}
+
+// the html module id is relative to the root. Only a path ending with `.html`, or with `/` for the index.html of
+// the directory, can be matched to the html file. Other paths, like the deep routes of a SPA that fall back to an
+// html, may be rendered from any html
+function mayRenderPage(htmlId: string) {
+  const pagePath = decodeURI(location.pathname);
+  const htmlPath = `/${htmlId.split('?')[0]}`;
+
+  if (pagePath.endsWith('.html')) {
+    return pagePath.endsWith(htmlPath);
+  }
+
+  if (pagePath.endsWith('/')) {
+    return `${pagePath}index.html`.endsWith(htmlPath);
+  }
+
+  return true;
+}
+
+// css that is not transformed to script is loaded by <link>, fetch it again to apply the update
+function reloadStylesheets() {
+  document
+    .querySelectorAll<HTMLLinkElement>('link[rel="stylesheet"]')
+    .forEach((link) => {
+      const url = new URL(link.href, location.href);
+
+      if (url.origin !== location.origin) return;
+
+      url.searchParams.set('t', Date.now().toString());
+      link.href = url.toString();
+    });
+}
+
 export function createOverlay(err: any) {
   clearOverlay();
   document.body.appendChild(new ErrorOverlay(err));
@@ -12,6 +12,21 @@ import type { Resource } from '@farmfe/runtime';
 //     dynamicRequire: (id: string) => Promise<any>
 //   ) => void
 // >;
+export interface HmrModuleAcceptance {
+  id: string;
+  selfAccepted: boolean;
+  acceptedDeps: string[];
+}
+
+// why the update falls back to a full reload
+export interface HmrFullReloadReason {
+  kind: 'entry' | 'nonScriptModule';
+  // the first unaccepted import chain, from the updated module to the entry or the non-script module
+  chain: string[];
+  // hmr acceptance of every module in the chain
+  modules: HmrModuleAcceptance[];
+}
+
 export interface HmrUpdateResult {
   added: string[];
   changed: string[];
@@ -19,6 +34,9 @@ export interface HmrUpdateResult {
 
   // closest boundary modules which are related to added or changed
   boundaries: Record<string, string[][]>;
+  fullReloadReason: HmrFullReloadReason | null;
+  // non-script modules that apply the update themselves, module id -> module type
+  nonScriptUpdates: Record<string, string>;
   // // modules which are added or changed
   // modules: ModuleMap;
   dynamicResources: Resource[] | null;
@@ -30,6 +48,8 @@ export interface RawHmrUpdateResult {
   changed: string[];
   removed: string[];
   boundaries: Record<string, string[][]>;
+  fullReloadReason: HmrFullReloadReason | null;
+  nonScriptUpdates: Record<string, string>;
   immutableModules: string;
   mutableModules: string;
   dynamicResources: Resource[] | null;
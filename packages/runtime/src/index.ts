@@ -4,4 +4,5 @@
  */
 export type { Module, ModuleSystem } from './module-system.js';
 export type { Resource } from './modules/dynamic-import.js';
+export type { HotContext, HotUpdate } from './modules/module-system-helper.js';
 export type { FarmRuntimePlugin, FarmRuntimePluginContainer } from './modules/plugin.js';
\ No newline at end of file
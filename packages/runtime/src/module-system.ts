@@ -1,4 +1,5 @@
 import type { Resource } from './modules/dynamic-import.js';
+import type { HotContext, HotUpdate } from './modules/module-system-helper.js';
 import type { FarmRuntimePluginContainer } from './modules/plugin.js';
 
 // if statement will be removed during compile time when referencing following variables
@@ -68,6 +69,12 @@ export interface ModuleSystem {
     dynamicResources: Resource[],
     dynamicModuleResourcesMap: Record<string, number[]>
   ): void;
+  /** createHotContext
+   * Only available in development when target env is node, the hot context is set to `module.meta.hot` */
+  hc?(id: string): HotContext;
+  /** applyHotUpdate
+   * Only available in development when target env is node, returns false if the process should be restarted */
+  h?(update: HotUpdate): Promise<boolean>;
   /** setPublicPaths
    * The public paths are injected during compile time */
   sp(publicPaths: string[]): void;
@@ -203,6 +210,9 @@ export function farmRequire(id: string): any {
     require: farmRequire
   } as Module;
 
+  if (__FARM_RUNTIME_TARGET_ENV__ === 'node')
+    module.meta.hot = __farm_internal_module_system__.hc?.(id);
+
   if (__FARM_ENABLE_RUNTIME_PLUGIN__)
     __farm_internal_module_system__.p?.s('moduleCreated', module); // call the module created hook
 
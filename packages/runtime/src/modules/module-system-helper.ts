@@ -1,5 +1,8 @@
 import type { ModuleInitialization, ModuleSystem } from "../module-system.js";
 
+// if statement will be removed during compile time when referencing following variables
+declare const __FARM_RUNTIME_TARGET_ENV__: 'browser' | 'node' | 'library';
+
 let moduleSystem: ModuleSystem;
 
 export function initModuleSystem(ms: ModuleSystem) {
@@ -7,6 +10,12 @@ export function initModuleSystem(ms: ModuleSystem) {
   moduleSystem.u = updateModule;
   moduleSystem.e = deleteModule;
   moduleSystem.a = clearCache;
+
+  // the browser hmr is handled by the hmr runtime plugin, node hmr is applied inside the long-running process
+  if (__FARM_RUNTIME_TARGET_ENV__ === 'node') {
+    moduleSystem.hc = createHotContext;
+    moduleSystem.h = applyHotUpdate;
+  }
 }
 
 function updateModule(moduleId: string, init: ModuleInitialization): void {
@@ -34,4 +43,136 @@ function clearCache(moduleId: string): boolean {
   } else {
     return false;
   }
-}
\ No newline at end of file
+}
+
+export interface HotUpdate {
+  changed: string[];
+  removed: string[];
+  boundaries: Record<string, string[][]>;
+  nonScriptUpdates?: Record<string, string>;
+  /** `nodeUpdate.invalidatedModuleIds` of the update result */
+  invalidatedModuleIds: string[];
+}
+
+export interface HotContext {
+  data: any;
+  acceptCallbacks: Array<{ deps: string[]; fn: (mods: any[]) => void }>;
+  disposeCallback?: (data: any) => void | Promise<void>;
+  pruneCallback?: (data: any) => void | Promise<void>;
+  accept(deps?: any, callback?: (mods: any[]) => void): void;
+  dispose(callback: (data: any) => void | Promise<void>): void;
+  prune(callback: (data: any) => void | Promise<void>): void;
+  decline(): void;
+  invalidate(message?: string): void;
+  on(event: string, cb: (payload: any) => void): void;
+  off(event: string, cb: (payload: any) => void): void;
+  send(event: string, data?: any): void;
+}
+
+const hotContexts: Record<string, HotContext> = {};
+
+// the same as `hot` of the hmr runtime plugin, but there is no server connection in node
+function createHotContext(id: string): HotContext {
+  const existing = hotContexts[id];
+
+  if (existing) {
+    // clear the accept callbacks when hot reloading
+    existing.acceptCallbacks = [];
+    return existing;
+  }
+
+  const hot: HotContext = (hotContexts[id] = {
+    data: {},
+    acceptCallbacks: [],
+    accept(deps, callback) {
+      if (typeof deps === 'function' || !deps) {
+        hot.acceptCallbacks.push({ deps: [id], fn: ([mod]) => deps?.(mod) });
+      } else if (typeof deps === 'string') {
+        hot.acceptCallbacks.push({
+          deps: [deps],
+          fn: ([mod]) => callback?.(mod)
+        });
+      } else if (Array.isArray(deps)) {
+        hot.acceptCallbacks.push({ deps, fn: callback });
+      } else {
+        throw new Error('invalid hot.accept call');
+      }
+    },
+    dispose(callback) {
+      hot.disposeCallback = callback;
+    },
+    prune(callback) {
+      hot.pruneCallback = callback;
+    },
+    decline() {},
+    invalidate() {},
+    on() {},
+    off() {},
+    send() {}
+  });
+
+  return hot;
+}
+
+/**
+ * Apply the update of node targets, the `code` of the update must be executed before.
+ * Returns false if a changed module does not reach a boundary, the process should be restarted in this case
+ */
+async function applyHotUpdate(update: HotUpdate): Promise<boolean> {
+  if (
+    update.changed.some(
+      (id) => !update.boundaries[id] && !update.nonScriptUpdates?.[id]
+    )
+  ) {
+    return false;
+  }
+
+  for (const id of update.removed) {
+    const hot = hotContexts[id];
+    if (hot?.pruneCallback) await hot.pruneCallback(hot.data);
+
+    delete hotContexts[id];
+  }
+
+  // stale modules are executed again when they are required next time
+  for (const id of update.invalidatedModuleIds) {
+    const hot = hotContexts[id];
+
+    if (hot?.disposeCallback && moduleSystem.c()[id]) {
+      await hot.disposeCallback(hot.data);
+      hot.disposeCallback = undefined;
+    }
+
+    clearCache(id);
+  }
+
+  for (const chains of Object.values(update.boundaries)) {
+    for (const chain of chains) {
+      const boundary = chain[chain.length - 1];
+      const hot = hotContexts[boundary];
+
+      // the boundary module is not executed in this process
+      if (!hot) continue;
+
+      const acceptedDep = chain.length > 1 ? chain[chain.length - 2] : undefined;
+      const depsAcceptedCallbacks = hot.acceptCallbacks.filter(({ deps }) =>
+        deps.includes(acceptedDep)
+      );
+      // when there are both self accept callbacks and deps accept callbacks in a boundary module, only the deps accept callbacks will be called
+      const [acceptedId, acceptedCallbacks] =
+        depsAcceptedCallbacks.length > 0
+          ? [acceptedDep, depsAcceptedCallbacks]
+          : [
+              boundary,
+              hot.acceptCallbacks.filter(({ deps }) => deps.includes(boundary))
+            ];
+      const acceptedExports = await moduleSystem.r(acceptedId);
+
+      for (const { deps, fn } of acceptedCallbacks) {
+        fn(deps.map((dep) => (dep === acceptedId ? acceptedExports : undefined)));
+      }
+    }
+  }
+
+  return true;
+}
@@ -7,6 +7,18 @@ export interface IOptions {
    * exclude the path from the project
    */
   exclude?: string[];
+  /**
+   * fail the build when a module can not be transformed with isolated declarations, the issues are warnings by default
+   */
+  strict?: boolean;
+  /**
+   * bundle the declarations into one `{name}.d.ts` per entry instead of one file per module
+   */
+  bundle?: boolean;
+  /**
+   * emit `.d.ts.map` that maps the declarations back to the source files
+   */
+  declarationMap?: boolean;
 }
 
 declare const binPath: (options?:IOptions)=>[string, IOptions];
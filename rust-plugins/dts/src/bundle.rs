@@ -0,0 +1,1084 @@
+use std::{
+  collections::{HashMap, HashSet, VecDeque},
+  sync::Arc,
+};
+
+use farmfe_core::{
+  cache_item,
+  error::Result,
+  module::{module_graph::ModuleGraph, ModuleId, ModuleMetaData},
+  swc_common::{Globals, SourceMap as SwcSourceMap, SyntaxContext, DUMMY_SP, GLOBALS},
+  swc_ecma_ast::{
+    Atom, ClassDecl, Decl, DefaultDecl, EsVersion, ExportSpecifier, Expr, FnDecl, Ident,
+    ImportSpecifier, Module as SwcModule, ModuleDecl, ModuleExportName, ModuleItem, Stmt, Str,
+    TsModuleName,
+  },
+  swc_ecma_parser::{Syntax, TsSyntax},
+};
+use farmfe_toolkit::{
+  script::{parse_module, swc_try_with::resolve_module_mark},
+  sourcemap::SourceMap,
+  swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith},
+};
+
+use crate::declaration_map::{codegen_with_map, collapse, DeclarationMapBuilder};
+
+/// key of [DtsModule] in the custom meta of script modules
+pub const DTS_MODULE_KEY: &str = "farm_plugin_dts";
+/// name of anonymous default exported declarations, e.g. `export default function(): void`
+const DEFAULT_NAME: &str = "_default";
+
+/// Declarations of a module, kept in the module meta and bundled per entry when the module graph is built
+#[cache_item(farmfe_core)]
+#[derive(Debug, Clone, Default)]
+pub struct DtsModule {
+  pub code: String,
+  /// source map from `code` to the original source, only generated when `declarationMap` is enabled
+  pub source_map: Option<String>,
+  /// sources imported or re-exported by the declarations
+  pub sources: Vec<String>,
+}
+
+/// Bundled declarations of an entry
+pub struct DtsBundle {
+  pub code: String,
+  pub source_map: Option<Vec<u8>>,
+}
+
+/// Sources of the static imports and re-exports of the module
+pub fn collect_sources(ast: &SwcModule) -> Vec<String> {
+  let mut sources = vec![];
+
+  for item in &ast.body {
+    let src = match item {
+      ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => Some(&import.src),
+      ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => export.src.as_ref(),
+      ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => Some(&export.src),
+      _ => None,
+    };
+
+    if let Some(src) = src {
+      let source = str_value(src);
+
+      if !sources.contains(&source) {
+        sources.push(source);
+      }
+    }
+  }
+
+  sources
+}
+
+/// Loads the declarations of a source that is not a dependency in the module graph, e.g. a type only import.
+/// The arguments are the importer and the source
+pub type LoadDeclaration<'a> =
+  dyn FnMut(&ModuleId, &str) -> Option<(ModuleId, Box<DtsModule>)> + 'a;
+
+/// Bundle the declarations of `entry` and the local modules it references into a single declaration file.
+/// Returns `None` if the entry has no declarations
+pub fn bundle_declarations(
+  entry: &ModuleId,
+  dts_path: &str,
+  module_graph: &ModuleGraph,
+  root: &str,
+  output_dir: &str,
+  load_declaration: &mut LoadDeclaration,
+) -> Result<Option<DtsBundle>> {
+  let Some(dts) = get_dts_module(module_graph, entry) else {
+    return Ok(None);
+  };
+
+  let mut bundler = DtsBundler::new(entry, dts, module_graph, load_declaration)?;
+  bundler.tree_shake();
+  bundler.deconflict();
+
+  Ok(Some(bundler.render(dts_path, root, output_dir)))
+}
+
+pub fn get_dts_module(module_graph: &ModuleGraph, module_id: &ModuleId) -> Option<Box<DtsModule>> {
+  let module = module_graph.module(module_id)?;
+
+  if module.external {
+    return None;
+  }
+
+  match &*module.meta {
+    ModuleMetaData::Script(script) => script.custom.get_cache::<DtsModule>(DTS_MODULE_KEY),
+    _ => None,
+  }
+}
+
+fn str_value(s: &Str) -> String {
+  s.value.to_string_lossy().into_owned()
+}
+
+fn export_name(name: &ModuleExportName) -> Atom {
+  match name {
+    ModuleExportName::Ident(ident) => ident.sym.clone(),
+    ModuleExportName::Str(s) => str_value(s).into(),
+  }
+}
+
+fn decl_names(decl: &Decl) -> Vec<Atom> {
+  match decl {
+    Decl::Class(class) => vec![class.ident.sym.clone()],
+    Decl::Fn(func) => vec![func.ident.sym.clone()],
+    Decl::Var(var) => var
+      .decls
+      .iter()
+      .filter_map(|d| d.name.as_ident().map(|i| i.id.sym.clone()))
+      .collect(),
+    Decl::TsInterface(interface) => vec![interface.id.sym.clone()],
+    Decl::TsTypeAlias(alias) => vec![alias.id.sym.clone()],
+    Decl::TsEnum(e) => vec![e.id.sym.clone()],
+    // `declare global {}` and `declare module 'x' {}` are augmentations rather than declarations
+    Decl::TsModule(m) => match &m.id {
+      TsModuleName::Ident(ident) if !m.global => vec![ident.sym.clone()],
+      _ => vec![],
+    },
+    Decl::Using(_) => vec![],
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Imported {
+  Named(Atom),
+  Namespace,
+}
+
+/// A declaration that can be referenced in the bundle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Symbol {
+  /// top level declaration of a bundled module
+  Local(usize, Atom),
+  /// binding imported from a module that is not bundled
+  External(String, Imported),
+  /// namespace object of a bundled module, e.g. `import * as a from './a'`
+  Namespace(usize),
+}
+
+#[derive(Debug, Clone)]
+enum Source {
+  Local(usize),
+  External(String),
+}
+
+enum Export {
+  Local(Atom),
+  ReExport(String, Imported),
+}
+
+#[derive(Debug, PartialEq)]
+enum ItemKind {
+  /// imports and exports, they are replaced by the imports and exports of the bundle
+  Remove,
+  /// declarations which are kept only if they are referenced
+  Decl(Vec<Atom>),
+  /// augmentations which are always kept
+  Keep,
+  /// `export =` and `export as namespace`, kept only in the entry
+  EntryOnly,
+}
+
+struct BundleModule {
+  ast: SwcModule,
+  cm: Arc<SwcSourceMap>,
+  source_map: Option<String>,
+  top_level_ctxt: SyntaxContext,
+  items: Vec<ItemKind>,
+  /// top level identifiers referenced by each item
+  refs: Vec<HashSet<Atom>>,
+  decls: HashMap<Atom, Vec<usize>>,
+  imports: HashMap<Atom, (String, Imported)>,
+  import_order: Vec<Atom>,
+  exports: Vec<(Atom, Export)>,
+  star_exports: Vec<String>,
+  deps: HashMap<String, Source>,
+}
+
+impl BundleModule {
+  fn new(module_id: &ModuleId, dts: DtsModule, globals: &Globals) -> Result<Self> {
+    let parsed = parse_module(
+      module_id,
+      Arc::new(dts.code),
+      Syntax::Typescript(TsSyntax {
+        dts: true,
+        ..Default::default()
+      }),
+      EsVersion::EsNext,
+    )?;
+    let mut ast = parsed.ast;
+    let (_, top_level_mark) = resolve_module_mark(&mut ast, true, globals);
+    let top_level_ctxt = GLOBALS.set(globals, || {
+      SyntaxContext::empty().apply_mark(top_level_mark)
+    });
+
+    let mut module = Self {
+      ast,
+      cm: parsed.source_map,
+      source_map: dts.source_map,
+      top_level_ctxt,
+      items: vec![],
+      refs: vec![],
+      decls: HashMap::new(),
+      imports: HashMap::new(),
+      import_order: vec![],
+      exports: vec![],
+      star_exports: vec![],
+      deps: HashMap::new(),
+    };
+    module.analyze();
+
+    Ok(module)
+  }
+
+  fn analyze(&mut self) {
+    for (i, item) in self.ast.body.iter().enumerate() {
+      let kind = match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+          let source = str_value(&import.src);
+
+          for specifier in &import.specifiers {
+            let (local, imported) = match specifier {
+              ImportSpecifier::Named(s) => (
+                s.local.sym.clone(),
+                Imported::Named(
+                  s.imported
+                    .as_ref()
+                    .map(export_name)
+                    .unwrap_or_else(|| s.local.sym.clone()),
+                ),
+              ),
+              ImportSpecifier::Default(s) => {
+                (s.local.sym.clone(), Imported::Named("default".into()))
+              }
+              ImportSpecifier::Namespace(s) => (s.local.sym.clone(), Imported::Namespace),
+            };
+
+            self.import_order.push(local.clone());
+            self.imports.insert(local, (source.clone(), imported));
+          }
+
+          ItemKind::Remove
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+          let names = decl_names(&export.decl);
+
+          for name in &names {
+            self
+              .exports
+              .push((name.clone(), Export::Local(name.clone())));
+          }
+
+          decl_kind(names)
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => {
+          let source = export.src.as_deref().map(str_value);
+
+          for specifier in &export.specifiers {
+            let (exported, imported) = match specifier {
+              ExportSpecifier::Named(s) => {
+                let orig = export_name(&s.orig);
+                let exported = s
+                  .exported
+                  .as_ref()
+                  .map(export_name)
+                  .unwrap_or_else(|| orig.clone());
+                (exported, Imported::Named(orig))
+              }
+              ExportSpecifier::Namespace(s) => (export_name(&s.name), Imported::Namespace),
+              ExportSpecifier::Default(s) => {
+                (s.exported.sym.clone(), Imported::Named("default".into()))
+              }
+            };
+
+            let export = match (&source, imported) {
+              (Some(source), imported) => Export::ReExport(source.clone(), imported),
+              (None, Imported::Named(local)) => Export::Local(local),
+              (None, Imported::Namespace) => continue,
+            };
+            self.exports.push((exported, export));
+          }
+
+          ItemKind::Remove
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => {
+          self.star_exports.push(str_value(&export.src));
+          ItemKind::Remove
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
+          let name = match &export.decl {
+            DefaultDecl::Class(class) => class.ident.as_ref().map(|i| i.sym.clone()),
+            DefaultDecl::Fn(func) => func.ident.as_ref().map(|i| i.sym.clone()),
+            DefaultDecl::TsInterfaceDecl(interface) => Some(interface.id.sym.clone()),
+          }
+          .unwrap_or_else(|| DEFAULT_NAME.into());
+
+          self
+            .exports
+            .push(("default".into(), Export::Local(name.clone())));
+          ItemKind::Decl(vec![name])
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export)) => {
+          if let Expr::Ident(ident) = &*export.expr {
+            self
+              .exports
+              .push(("default".into(), Export::Local(ident.sym.clone())));
+          }
+
+          ItemKind::Remove
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import)) => {
+          if import.is_export {
+            self
+              .exports
+              .push((import.id.sym.clone(), Export::Local(import.id.sym.clone())));
+          }
+
+          ItemKind::Decl(vec![import.id.sym.clone()])
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(_))
+        | ModuleItem::ModuleDecl(ModuleDecl::TsNamespaceExport(_)) => ItemKind::EntryOnly,
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl_kind(decl_names(decl)),
+        ModuleItem::Stmt(_) => ItemKind::Remove,
+      };
+
+      if let ItemKind::Decl(names) = &kind {
+        for name in names {
+          self.decls.entry(name.clone()).or_default().push(i);
+        }
+      }
+
+      let mut collector = RefsCollector {
+        ctxt: self.top_level_ctxt,
+        refs: HashSet::new(),
+      };
+      item.visit_with(&mut collector);
+
+      self.refs.push(collector.refs);
+      self.items.push(kind);
+    }
+  }
+}
+
+fn import_specifier(imported: &Atom, local: &Atom) -> String {
+  if imported == local {
+    local.to_string()
+  } else {
+    format!("{imported} as {local}")
+  }
+}
+
+fn decl_kind(names: Vec<Atom>) -> ItemKind {
+  if names.is_empty() {
+    ItemKind::Keep
+  } else {
+    ItemKind::Decl(names)
+  }
+}
+
+struct DtsBundler {
+  /// the entry is always the first module
+  modules: Vec<BundleModule>,
+  used: HashSet<Symbol>,
+  names: HashMap<Symbol, Atom>,
+  reserved_names: HashSet<Atom>,
+  /// used external symbols and namespaces in the order they are named
+  externals: Vec<Symbol>,
+  namespaces: Vec<usize>,
+}
+
+impl DtsBundler {
+  fn new(
+    entry: &ModuleId,
+    dts: Box<DtsModule>,
+    module_graph: &ModuleGraph,
+    load_declaration: &mut LoadDeclaration,
+  ) -> Result<Self> {
+    let globals = Globals::new();
+    let mut modules = vec![];
+    let mut indexes = HashMap::from([(entry.clone(), 0)]);
+    let mut queue = VecDeque::from([(entry.clone(), dts)]);
+
+    // modules are pushed in the same order as their indexes are assigned
+    while let Some((module_id, dts)) = queue.pop_front() {
+      let mut module = BundleModule::new(&module_id, *dts, &globals)?;
+
+      for source in collect_sources(&module.ast) {
+        let graph_dep = module_graph
+          .has_module(&module_id)
+          .then(|| module_graph.get_dep_by_source_optional(&module_id, &source, None))
+          .flatten();
+        let dep = match graph_dep {
+          Some(dep) => get_dts_module(module_graph, &dep).map(|dts| (dep, dts)),
+          // type only imports are stripped from the scripts, so they are not dependencies in the module graph
+          None => load_declaration(&module_id, &source),
+        };
+
+        let resolved = match dep {
+          Some((dep, dts)) => {
+            let len = indexes.len();
+            let index = *indexes.entry(dep.clone()).or_insert_with(|| {
+              queue.push_back((dep, dts));
+              len
+            });
+            Source::Local(index)
+          }
+          None => Source::External(source.clone()),
+        };
+
+        module.deps.insert(source, resolved);
+      }
+
+      modules.push(module);
+    }
+
+    Ok(Self {
+      modules,
+      used: HashSet::new(),
+      names: HashMap::new(),
+      reserved_names: HashSet::new(),
+      externals: vec![],
+      namespaces: vec![],
+    })
+  }
+
+  fn resolve_source(&self, m: usize, source: &str) -> Source {
+    self.modules[m]
+      .deps
+      .get(source)
+      .cloned()
+      .unwrap_or_else(|| Source::External(source.to_string()))
+  }
+
+  /// Resolve a top level identifier of module `m` to the declaration it refers to
+  fn resolve_local(
+    &self,
+    m: usize,
+    local: &Atom,
+    visited: &mut HashSet<(usize, Atom)>,
+  ) -> Option<Symbol> {
+    let module = &self.modules[m];
+
+    if module.decls.contains_key(local) {
+      return Some(Symbol::Local(m, local.clone()));
+    }
+
+    let (source, imported) = module.imports.get(local)?;
+    self.resolve_imported(m, source, imported, visited)
+  }
+
+  fn resolve_imported(
+    &self,
+    m: usize,
+    source: &str,
+    imported: &Imported,
+    visited: &mut HashSet<(usize, Atom)>,
+  ) -> Option<Symbol> {
+    match (self.resolve_source(m, source), imported) {
+      (Source::Local(dep), Imported::Named(name)) => self.resolve_export(dep, name, visited),
+      (Source::Local(dep), Imported::Namespace) => Some(Symbol::Namespace(dep)),
+      (Source::External(source), imported) => Some(Symbol::External(source, imported.clone())),
+    }
+  }
+
+  fn resolve_export(
+    &self,
+    m: usize,
+    name: &Atom,
+    visited: &mut HashSet<(usize, Atom)>,
+  ) -> Option<Symbol> {
+    if !visited.insert((m, name.clone())) {
+      return None;
+    }
+
+    let module = &self.modules[m];
+
+    if let Some((_, export)) = module.exports.iter().find(|(exported, _)| exported == name) {
+      return match export {
+        Export::Local(local) => self.resolve_local(m, local, visited),
+        Export::ReExport(source, imported) => self.resolve_imported(m, source, imported, visited),
+      };
+    }
+
+    if name == "default" {
+      return None;
+    }
+
+    module
+      .star_exports
+      .iter()
+      .find_map(|source| match self.resolve_source(m, source) {
+        Source::Local(dep) => self.resolve_export(dep, name, visited),
+        Source::External(_) => None,
+      })
+  }
+
+  fn collect_export_names(
+    &self,
+    m: usize,
+    visited: &mut HashSet<usize>,
+    names: &mut Vec<Atom>,
+    external_stars: &mut Vec<String>,
+  ) {
+    if !visited.insert(m) {
+      return;
+    }
+
+    let module = &self.modules[m];
+
+    for (exported, _) in &module.exports {
+      if !names.contains(exported) {
+        names.push(exported.clone());
+      }
+    }
+
+    for source in &module.star_exports {
+      match self.resolve_source(m, source) {
+        Source::Local(dep) => {
+          let mut star_names = vec![];
+          self.collect_export_names(dep, visited, &mut star_names, external_stars);
+
+          for name in star_names {
+            if name != "default" && !names.contains(&name) {
+              names.push(name);
+            }
+          }
+        }
+        Source::External(source) => {
+          if !external_stars.contains(&source) {
+            external_stars.push(source);
+          }
+        }
+      }
+    }
+  }
+
+  /// Exports of module `m` and the external sources it re-exports with `export *`
+  fn export_entries(&self, m: usize) -> (Vec<(Atom, Symbol)>, Vec<String>) {
+    let mut names = vec![];
+    let mut external_stars = vec![];
+    self.collect_export_names(m, &mut HashSet::new(), &mut names, &mut external_stars);
+
+    let entries = names
+      .into_iter()
+      .filter_map(|name| {
+        let symbol = self.resolve_export(m, &name, &mut HashSet::new())?;
+        Some((name, symbol))
+      })
+      .collect();
+
+    (entries, external_stars)
+  }
+
+  fn item_dependencies(&self, m: usize, i: usize) -> Vec<Symbol> {
+    self.modules[m].refs[i]
+      .iter()
+      .filter_map(|r| self.resolve_local(m, r, &mut HashSet::new()))
+      .collect()
+  }
+
+  /// Mark the exports of the entry, the augmentations and everything they reference as used
+  fn tree_shake(&mut self) {
+    let mut stack: Vec<Symbol> = self
+      .export_entries(0)
+      .0
+      .into_iter()
+      .map(|(_, symbol)| symbol)
+      .collect();
+
+    for (m, module) in self.modules.iter().enumerate() {
+      for (i, kind) in module.items.iter().enumerate() {
+        if *kind == ItemKind::Keep || (*kind == ItemKind::EntryOnly && m == 0) {
+          stack.extend(self.item_dependencies(m, i));
+        }
+      }
+    }
+
+    while let Some(symbol) = stack.pop() {
+      if !self.used.insert(symbol.clone()) {
+        continue;
+      }
+
+      match symbol {
+        Symbol::Local(m, name) => {
+          for &i in &self.modules[m].decls[&name] {
+            stack.extend(self.item_dependencies(m, i));
+          }
+        }
+        Symbol::Namespace(m) => {
+          stack.extend(self.export_entries(m).0.into_iter().map(|(_, s)| s));
+        }
+        Symbol::External(..) => {}
+      }
+    }
+  }
+
+  /// Give every used symbol a unique name in the bundle
+  fn deconflict(&mut self) {
+    for m in 0..self.modules.len() {
+      let module = &self.modules[m];
+      let mut locals = vec![];
+
+      for kind in &module.items {
+        if let ItemKind::Decl(names) = kind {
+          locals.extend(
+            names
+              .iter()
+              .map(|name| (name.clone(), Symbol::Local(m, name.clone()))),
+          );
+        }
+      }
+
+      for local in &module.import_order {
+        if let Some(symbol) = self.resolve_local(m, local, &mut HashSet::new()) {
+          locals.push((local.clone(), symbol));
+        }
+      }
+
+      for (local, symbol) in locals {
+        self.assign_name(&symbol, &local);
+      }
+    }
+
+    // symbols that are only re-exported have not been named yet
+    for (exported, symbol) in self.export_entries(0).0 {
+      self.assign_name(&symbol, &exported);
+    }
+
+    let mut i = 0;
+
+    while i < self.namespaces.len() {
+      for (exported, symbol) in self.export_entries(self.namespaces[i]).0 {
+        self.assign_name(&symbol, &exported);
+      }
+
+      i += 1;
+    }
+  }
+
+  fn assign_name(&mut self, symbol: &Symbol, preferred: &Atom) {
+    if !self.used.contains(symbol) || self.names.contains_key(symbol) {
+      return;
+    }
+
+    let preferred = match symbol {
+      Symbol::Local(_, name) => name.clone(),
+      Symbol::External(_, Imported::Named(name)) if name != "default" => name.clone(),
+      _ if preferred == "default" => DEFAULT_NAME.into(),
+      _ => preferred.clone(),
+    };
+
+    let mut name = preferred.clone();
+    let mut index = 1;
+
+    while self.reserved_names.contains(&name) {
+      name = format!("{preferred}${index}").into();
+      index += 1;
+    }
+
+    match symbol {
+      Symbol::External(..) => self.externals.push(symbol.clone()),
+      Symbol::Namespace(m) => self.namespaces.push(*m),
+      Symbol::Local(..) => {}
+    }
+
+    self.reserved_names.insert(name.clone());
+    self.names.insert(symbol.clone(), name);
+  }
+
+  fn export_specifier(&self, symbol: &Symbol, exported: &Atom) -> Option<String> {
+    let local = self.names.get(symbol)?;
+
+    Some(if local == exported {
+      local.to_string()
+    } else {
+      format!("{local} as {exported}")
+    })
+  }
+
+  fn render_imports(&self) -> String {
+    let mut sources: Vec<&String> = vec![];
+    let mut named: HashMap<&String, Vec<String>> = HashMap::new();
+    let mut namespaces: Vec<(&String, &Atom)> = vec![];
+
+    for symbol in &self.externals {
+      let Symbol::External(source, imported) = symbol else {
+        continue;
+      };
+      let name = &self.names[symbol];
+
+      if !sources.contains(&source) {
+        sources.push(source);
+      }
+
+      match imported {
+        Imported::Named(imported) => named
+          .entry(source)
+          .or_default()
+          .push(import_specifier(imported, name)),
+        Imported::Namespace => namespaces.push((source, name)),
+      }
+    }
+
+    let mut code = String::new();
+
+    for source in sources {
+      if let Some(specifiers) = named.get(source) {
+        code.push_str(&format!(
+          "import {{ {} }} from {source:?};\n",
+          specifiers.join(", ")
+        ));
+      }
+
+      for (_, name) in namespaces.iter().filter(|(s, _)| *s == source) {
+        code.push_str(&format!("import * as {name} from {source:?};\n"));
+      }
+    }
+
+    code
+  }
+
+  fn render_exports(&self) -> String {
+    let mut code = String::new();
+
+    for &m in &self.namespaces {
+      let name = &self.names[&Symbol::Namespace(m)];
+      let specifiers = self
+        .export_entries(m)
+        .0
+        .iter()
+        .filter(|(exported, _)| exported != "default")
+        .filter_map(|(exported, symbol)| self.export_specifier(symbol, exported))
+        .collect::<Vec<_>>();
+
+      code.push_str(&format!(
+        "declare namespace {name} {{\n  export {{ {} }};\n}}\n",
+        specifiers.join(", ")
+      ));
+    }
+
+    let (entries, external_stars) = self.export_entries(0);
+
+    for source in external_stars {
+      code.push_str(&format!("export * from {source:?};\n"));
+    }
+
+    let specifiers = entries
+      .iter()
+      .filter_map(|(exported, symbol)| self.export_specifier(symbol, exported))
+      .collect::<Vec<_>>();
+
+    if specifiers.is_empty() {
+      code.push_str("export {};\n");
+    } else {
+      code.push_str(&format!("export {{ {} }};\n", specifiers.join(", ")));
+    }
+
+    code
+  }
+
+  /// Render the used declarations of module `m` without exports, together with the map to the original source
+  fn render_module(&self, m: usize) -> Option<(String, Option<SourceMap>)> {
+    let module = &self.modules[m];
+    let body = module
+      .ast
+      .body
+      .iter()
+      .zip(&module.items)
+      .filter(|(_, kind)| match kind {
+        ItemKind::Remove => false,
+        ItemKind::Decl(names) => names
+          .iter()
+          .any(|name| self.used.contains(&Symbol::Local(m, name.clone()))),
+        ItemKind::Keep => true,
+        ItemKind::EntryOnly => m == 0,
+      })
+      .map(|(item, _)| strip_export(item.clone(), module.top_level_ctxt))
+      .collect::<Vec<_>>();
+
+    if body.is_empty() {
+      return None;
+    }
+
+    let rename = module
+      .decls
+      .keys()
+      .chain(module.import_order.iter())
+      .filter_map(|local| {
+        let symbol = self.resolve_local(m, local, &mut HashSet::new())?;
+        let name = self.names.get(&symbol)?;
+        (name != local).then(|| (local.clone(), name.clone()))
+      })
+      .collect::<HashMap<_, _>>();
+
+    let mut ast = SwcModule {
+      span: DUMMY_SP,
+      body,
+      shebang: None,
+    };
+    ast.visit_mut_with(&mut Renamer {
+      ctxt: module.top_level_ctxt,
+      rename: &rename,
+    });
+
+    let (code, map) = codegen_with_map(&ast, module.cm.clone());
+    let map = module.source_map.as_ref().and_then(|source_map| {
+      let source_map = SourceMap::from_slice(source_map.as_bytes()).ok()?;
+      Some(collapse(vec![source_map, map]))
+    });
+
+    Some((code, map))
+  }
+
+  fn render(self, dts_path: &str, root: &str, output_dir: &str) -> DtsBundle {
+    let mut code = self.render_imports();
+    let mut map_builder = self.modules[0]
+      .source_map
+      .is_some()
+      .then(|| DeclarationMapBuilder::new(dts_path, root, output_dir));
+
+    // dependencies first, the entry is the last one
+    for m in (0..self.modules.len()).rev() {
+      let Some((module_code, map)) = self.render_module(m) else {
+        continue;
+      };
+
+      if let (Some(builder), Some(map)) = (&mut map_builder, map) {
+        builder.add(&map, code.matches('\n').count() as u32);
+      }
+
+      code.push_str(&module_code);
+
+      if !code.ends_with('\n') {
+        code.push('\n');
+      }
+    }
+
+    code.push_str(&self.render_exports());
+
+    DtsBundle {
+      code,
+      source_map: map_builder.map(|builder| builder.into_bytes()),
+    }
+  }
+}
+
+/// Turn exported declarations into plain declarations, the bundle exports them at the end
+fn strip_export(item: ModuleItem, top_level_ctxt: SyntaxContext) -> ModuleItem {
+  let ModuleItem::ModuleDecl(module_decl) = item else {
+    return item;
+  };
+  let default_ident = || Ident::new(DEFAULT_NAME.into(), DUMMY_SP, top_level_ctxt);
+
+  let decl = match module_decl {
+    ModuleDecl::ExportDecl(export) => export.decl,
+    ModuleDecl::ExportDefaultDecl(export) => match export.decl {
+      DefaultDecl::Class(class) => Decl::Class(ClassDecl {
+        ident: class.ident.unwrap_or_else(default_ident),
+        declare: true,
+        class: class.class,
+      }),
+      DefaultDecl::Fn(func) => Decl::Fn(FnDecl {
+        ident: func.ident.unwrap_or_else(default_ident),
+        declare: true,
+        function: func.function,
+      }),
+      DefaultDecl::TsInterfaceDecl(interface) => Decl::TsInterface(interface),
+    },
+    ModuleDecl::TsImportEquals(mut import) => {
+      import.is_export = false;
+      return ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import));
+    }
+    module_decl => return ModuleItem::ModuleDecl(module_decl),
+  };
+
+  ModuleItem::Stmt(Stmt::Decl(decl))
+}
+
+struct RefsCollector {
+  ctxt: SyntaxContext,
+  refs: HashSet<Atom>,
+}
+
+impl Visit for RefsCollector {
+  fn visit_ident(&mut self, ident: &Ident) {
+    if ident.ctxt == self.ctxt {
+      self.refs.insert(ident.sym.clone());
+    }
+  }
+}
+
+struct Renamer<'a> {
+  ctxt: SyntaxContext,
+  rename: &'a HashMap<Atom, Atom>,
+}
+
+impl VisitMut for Renamer<'_> {
+  fn visit_mut_ident(&mut self, ident: &mut Ident) {
+    if ident.ctxt == self.ctxt
+      && let Some(name) = self.rename.get(&ident.sym)
+    {
+      ident.sym = name.clone();
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use farmfe_core::{
+    module::{
+      meta_data::script::ScriptModuleMetaData,
+      module_graph::{ModuleGraph, ModuleGraphEdgeDataItem},
+      CustomMetaDataMap, Module, ModuleMetaData,
+    },
+    plugin::ResolveKind,
+  };
+
+  use super::{bundle_declarations, DtsModule, DTS_MODULE_KEY};
+
+  fn add_module(module_graph: &mut ModuleGraph, id: &str, code: &str) {
+    let mut custom = CustomMetaDataMap::default();
+    custom.insert(
+      DTS_MODULE_KEY.to_string(),
+      Box::new(DtsModule {
+        code: code.to_string(),
+        ..Default::default()
+      }),
+    );
+
+    let mut module = Module::new(id.into());
+    module.meta = Box::new(ModuleMetaData::Script(Box::new(ScriptModuleMetaData {
+      custom,
+      ..Default::default()
+    })));
+    module_graph.add_module(module);
+  }
+
+  #[test]
+  fn bundle_entry_declarations() {
+    let mut module_graph = ModuleGraph::new();
+    add_module(
+      &mut module_graph,
+      "index.ts",
+      r#"import type { Options } from './options';
+import { Base } from './base';
+export declare function create(options: Options): Base;
+export * from './utils';
+export { default as Foo } from 'ext';"#,
+    );
+    add_module(
+      &mut module_graph,
+      "options.ts",
+      r#"import type { Plugin } from 'vite';
+interface Internal { a: string; }
+export interface Options { plugins: Plugin[]; }
+export interface Unused { b: Internal; }"#,
+    );
+    add_module(
+      &mut module_graph,
+      "base.ts",
+      r#"interface Options { c: number; }
+export declare class Base { options: Options; }"#,
+    );
+    add_module(
+      &mut module_graph,
+      "utils.ts",
+      "export declare const version: string;",
+    );
+
+    for (order, (source, to)) in [
+      ("./options", "options.ts"),
+      ("./base", "base.ts"),
+      ("./utils", "utils.ts"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      module_graph
+        .add_edge_item(
+          &"index.ts".into(),
+          &to.into(),
+          ModuleGraphEdgeDataItem {
+            source: source.to_string(),
+            kind: ResolveKind::Import,
+            order,
+          },
+        )
+        .unwrap();
+    }
+
+    let bundle = bundle_declarations(
+      &"index.ts".into(),
+      "index.d.ts",
+      &module_graph,
+      "/",
+      "dist",
+      &mut |_: &ModuleId, _: &str| None,
+    )
+    .unwrap()
+    .unwrap();
+    let code = bundle.code;
+
+    assert!(code.contains("import { Plugin } from \"vite\";"));
+    assert!(code.contains("import { default as Foo } from \"ext\";"));
+    // local declarations are renamed when their names conflict
+    assert!(code.contains("interface Options$1"));
+    assert!(code.contains("options: Options$1"));
+    assert!(code.contains("plugins: Plugin[]"));
+    // unexported and unreferenced declarations are removed
+    assert!(!code.contains("Unused"));
+    assert!(!code.contains("Internal"));
+    assert!(code.ends_with("export { create, Foo, version };\n"));
+    assert!(bundle.source_map.is_none());
+  }
+
+  #[test]
+  fn bundle_type_only_imports() {
+    let mut module_graph = ModuleGraph::new();
+    add_module(
+      &mut module_graph,
+      "index.ts",
+      r#"import type { Properties } from 'csstype';
+import type { Theme } from './theme';
+export declare function style(theme: Theme): Properties;"#,
+    );
+
+    let mut loaded = vec![];
+    let bundle = bundle_declarations(
+      &"index.ts".into(),
+      "index.d.ts",
+      &module_graph,
+      "/",
+      "dist",
+      &mut |importer: &ModuleId, source: &str| {
+        loaded.push((importer.to_string(), source.to_string()));
+        // csstype only contains declarations, it can not be resolved as a script
+        (source == "./theme").then(|| {
+          (
+            "theme.ts".into(),
+            Box::new(DtsModule {
+              code: "export interface Theme { color: string; }".to_string(),
+              ..Default::default()
+            }),
+          )
+        })
+      },
+    )
+    .unwrap()
+    .unwrap();
+    let code = bundle.code;
+
+    assert_eq!(
+      loaded,
+      vec![
+        ("index.ts".to_string(), "csstype".to_string()),
+        ("index.ts".to_string(), "./theme".to_string()),
+      ]
+    );
+    assert!(code.contains("import { Properties } from \"csstype\";"));
+    assert!(code.contains("interface Theme"));
+    assert!(!code.contains("./theme"));
+    assert!(code.ends_with("export { style };\n"));
+  }
+}
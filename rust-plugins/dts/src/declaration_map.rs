@@ -0,0 +1,115 @@
+use std::{
+  path::{Path, PathBuf},
+  sync::Arc,
+};
+
+use bytes_str::BytesStr;
+use farmfe_core::{swc_common::SourceMap as SwcSourceMap, swc_ecma_ast::Module as SwcModule};
+use farmfe_toolkit::{
+  script::codegen_module,
+  sourcemap::{
+    build_sourcemap, collapse_sourcemap_chain, CollapseSourcemapOptions, SourceMap,
+    SourceMapBuilder,
+  },
+};
+
+/// Generate the code of the declarations together with the source map to the code that `cm` holds
+pub fn codegen_with_map(ast: &SwcModule, cm: Arc<SwcSourceMap>) -> (String, SourceMap) {
+  let mut mappings = vec![];
+  let code = codegen_module(
+    ast,
+    cm.clone(),
+    Some(&mut mappings),
+    Default::default(),
+    None,
+  )
+  .unwrap();
+
+  (
+    String::from_utf8(code).unwrap(),
+    build_sourcemap(cm, &mappings),
+  )
+}
+
+/// Collapse the chain (the first map is the closest to the original source) into a single map.
+/// Source contents are not inlined, declaration maps only need to point to the source files
+pub fn collapse(chain: Vec<SourceMap>) -> SourceMap {
+  collapse_sourcemap_chain(
+    chain,
+    CollapseSourcemapOptions {
+      inline_content: false,
+      remap_source: None,
+    },
+  )
+}
+
+/// Build the `.d.ts.map` of an emitted declaration file.
+/// Sources of the maps are module ids, they are rewritten to paths relative to the declaration map
+pub struct DeclarationMapBuilder {
+  builder: SourceMapBuilder,
+  root: PathBuf,
+  map_dir: PathBuf,
+}
+
+impl DeclarationMapBuilder {
+  /// `dts_path` is the name of the declaration file relative to the output dir
+  pub fn new(dts_path: &str, root: &str, output_dir: &str) -> Self {
+    let root = PathBuf::from(root);
+    let map_path = root.join(output_dir).join(dts_path);
+    let file = map_path
+      .file_name()
+      .map(|f| f.to_string_lossy().to_string())
+      .unwrap_or_default();
+
+    Self {
+      builder: SourceMapBuilder::new(Some(BytesStr::from_string(file))),
+      map_dir: map_path.parent().map(Path::to_path_buf).unwrap_or_default(),
+      root,
+    }
+  }
+
+  /// Add the tokens of the map, `line_offset` is the line where the mapped code starts in the declaration file
+  pub fn add(&mut self, map: &SourceMap, line_offset: u32) {
+    for token in map.tokens() {
+      let src_id = token.get_source().map(|source| {
+        let source = self.relative_source(&source.to_string());
+        self.builder.add_source(BytesStr::from_string(source))
+      });
+
+      self.builder.add_raw(
+        token.get_dst_line() + line_offset,
+        token.get_dst_col(),
+        token.get_src_line(),
+        token.get_src_col(),
+        src_id,
+        None,
+        false,
+      );
+    }
+  }
+
+  pub fn into_bytes(self) -> Vec<u8> {
+    let mut buf = vec![];
+    self.builder.into_sourcemap().to_writer(&mut buf).unwrap();
+    buf
+  }
+
+  fn relative_source(&self, source: &str) -> String {
+    let source = self.root.join(source);
+
+    pathdiff::diff_paths(&source, &self.map_dir)
+      .unwrap_or(source)
+      .to_string_lossy()
+      .replace('\\', "/")
+  }
+}
+
+/// The comment appended to a declaration file that links to its declaration map
+pub fn source_mapping_url_comment(dts_path: &str) -> String {
+  let name = Path::new(dts_path)
+    .file_name()
+    .map(|f| f.to_string_lossy().to_string())
+    .unwrap_or_default();
+
+  format!("//# sourceMappingURL={name}.map\n")
+}
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use farmfe_core::swc_common::{SourceMap as SwcSourceMap, Span};
+use farmfe_toolkit::{
+  sourcemap::{lookup_token, SourceMap},
+  swc_typescript::diagnostic::DtsIssue,
+};
+
+/// Format an isolated declarations issue as `path:line:column: message`.
+/// The position is mapped to the original source through the source map chain of the module
+pub fn format_issue(
+  issue: &DtsIssue,
+  path: &str,
+  cm: &SwcSourceMap,
+  source_map_chain: &[Arc<String>],
+) -> String {
+  let message = &issue.message;
+
+  match locate(issue.range.span, cm, source_map_chain) {
+    Some((line, column)) => format!("{path}:{line}:{column}: {message}"),
+    None => format!("{path}: {message}"),
+  }
+}
+
+/// 1-based line and column of the span in the original source
+fn locate(span: Span, cm: &SwcSourceMap, source_map_chain: &[Arc<String>]) -> Option<(u32, u32)> {
+  if span.is_dummy() {
+    return None;
+  }
+
+  let loc = cm.lookup_char_pos(span.lo);
+  let line = loc.line as u32 - 1;
+  let column = loc.col.0 as u32;
+  // fallback to the position in the transformed code if it can not be traced
+  let (line, column) = trace_position(line, column, source_map_chain).unwrap_or((line, column));
+
+  Some((line + 1, column + 1))
+}
+
+/// Trace the 0-based position back through the source map chain, the last map maps the current code to the previous one
+fn trace_position(
+  mut line: u32,
+  mut column: u32,
+  source_map_chain: &[Arc<String>],
+) -> Option<(u32, u32)> {
+  for map in source_map_chain.iter().rev() {
+    let map = SourceMap::from_slice(map.as_bytes()).ok()?;
+
+    if map.get_token_count() == 0 {
+      continue;
+    }
+
+    let token = lookup_token(&map, line, column)?;
+    line = token.get_src_line();
+    column = token.get_src_col();
+  }
+
+  Some((line, column))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use farmfe_toolkit::sourcemap::SourceMapBuilder;
+
+  use super::trace_position;
+
+  fn map(mappings: &[(u32, u32, u32, u32)]) -> Arc<String> {
+    let mut builder = SourceMapBuilder::new(None);
+    let src_id = builder.add_source("index.ts".into());
+
+    for (dst_line, dst_col, src_line, src_col) in mappings {
+      builder.add_raw(
+        *dst_line,
+        *dst_col,
+        *src_line,
+        *src_col,
+        Some(src_id),
+        None,
+        false,
+      );
+    }
+
+    let mut buf = vec![];
+    builder.into_sourcemap().to_writer(&mut buf).unwrap();
+    Arc::new(String::from_utf8(buf).unwrap())
+  }
+
+  #[test]
+  fn trace_through_chain() {
+    // the first transform inserts a line, the second one indents the code
+    let chain = vec![map(&[(1, 0, 0, 0)]), map(&[(1, 2, 1, 0)])];
+
+    assert_eq!(trace_position(1, 2, &chain), Some((0, 0)));
+    assert_eq!(trace_position(3, 4, &[]), Some((3, 4)));
+  }
+}
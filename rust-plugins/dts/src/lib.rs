@@ -3,28 +3,47 @@ use farmfe_core::{
   config::{config_regex::ConfigRegex, Config, ResolveConfig},
   context::{CompilationContext, EmitFileParams},
   error::CompilationError,
-  plugin::{Plugin, PluginProcessModuleHookParam, ResolveKind},
+  module::{module_graph::ModuleGraph, ModuleId},
+  plugin::{
+    Plugin, PluginHookContext, PluginModuleGraphUpdatedHookParam, PluginProcessModuleHookParam,
+    PluginResolveHookParam, ResolveKind,
+  },
   resource::ResourceType,
   stats::Stats,
-  swc_common::{FileName, Mark},
-  swc_ecma_ast::{ImportDecl, Module as EcmaAstModule, ModuleDecl, ModuleItem, Program},
+  swc_common::{FileName, Globals, Mark, SourceMap as SwcSourceMap, GLOBALS},
+  swc_ecma_ast::{EsVersion, ImportDecl, Module as EcmaAstModule, ModuleDecl, ModuleItem, Program},
+  swc_ecma_parser::{Syntax, TsSyntax},
 };
 use farmfe_plugin_resolve::resolver::{ResolveOptions, Resolver};
 
 use farmfe_toolkit::{
+  fs::read_file_utf8,
   plugin_utils::path_filter::PathFilter,
+  script::{parse_module, swc_try_with::resolve_module_mark},
+  sourcemap::SourceMap,
   swc_ecma_codegen::to_code,
   swc_ecma_visit::{VisitMut, VisitMutWith},
-  swc_typescript::fast_dts::FastDts,
+  swc_typescript::{diagnostic::DtsIssue, fast_dts::FastDts},
 };
 use std::time::Duration;
 use std::{
+  collections::HashMap,
   path::PathBuf,
   sync::{Arc, Mutex},
 };
 
 use farmfe_macro_plugin::farm_plugin;
 
+use bundle::{bundle_declarations, collect_sources, get_dts_module, DtsModule, DTS_MODULE_KEY};
+use declaration_map::{
+  codegen_with_map, collapse, source_mapping_url_comment, DeclarationMapBuilder,
+};
+use issue::format_issue;
+
+mod bundle;
+mod declaration_map;
+mod issue;
+
 #[farm_plugin]
 pub struct FarmPluginDts {
   options: FarmPluginDtsOptions,
@@ -32,9 +51,16 @@ pub struct FarmPluginDts {
 }
 
 #[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase", default)]
 pub struct FarmPluginDtsOptions {
   exclude: Vec<ConfigRegex>,
   include: Vec<ConfigRegex>,
+  /// Fail the build when a module can not be transformed with isolated declarations, the issues are warnings by default
+  strict: bool,
+  /// Bundle the declarations of the modules reachable from each entry into a single `{entry}.d.ts`
+  bundle: bool,
+  /// Generate `.d.ts.map` that maps the declarations back to the source files
+  declaration_map: bool,
 }
 
 impl Default for FarmPluginDtsOptions {
@@ -42,6 +68,9 @@ impl Default for FarmPluginDtsOptions {
     Self {
       exclude: vec![ConfigRegex::new("node_modules/")],
       include: vec![ConfigRegex::new(".(ts|tsx)$")],
+      strict: false,
+      bundle: false,
+      declaration_map: false,
     }
   }
 }
@@ -81,23 +110,65 @@ impl Plugin for FarmPluginDts {
 
     let mut module: EcmaAstModule = meta.ast.clone();
 
-    module.visit_mut_with(&mut ImportPathRewriter {
-      config: (*context.config).clone(),
-      resolver: Resolver::new(),
-    });
+    // the bundled declarations import the modules by their original sources
+    if !self.options.bundle {
+      module.visit_mut_with(&mut ImportPathRewriter {
+        config: (*context.config).clone(),
+        resolver: Resolver::new(),
+      });
+    }
     let filename: Arc<FileName> = Arc::new(FileName::Real(
       param.module_id.relative_path().to_string().into(),
     ));
 
     let unresolved_mark = Mark::from_u32(meta.unresolved_mark);
     let mut checker = FastDts::new(filename.clone(), unresolved_mark, Default::default());
-    module.visit_mut_with(&mut ImportVariableRemover);
+    if !self.options.bundle {
+      module.visit_mut_with(&mut ImportVariableRemover);
+    }
 
     let mut program = Program::Module(module);
     let issues = checker.transform(&mut program);
 
-    for issue in issues {
-      let _range = issue.range;
+    if !issues.is_empty() {
+      let cm = context.meta.get_module_source_map(param.module_id);
+      self.report_issues(&issues, path, &cm, param.source_map_chain, context);
+    }
+
+    let module = program.expect_module();
+    let (dts_code, source_map) = if self.options.declaration_map {
+      let cm = context.meta.get_module_source_map(param.module_id);
+      let (code, map) = codegen_with_map(&module, cm);
+      let mut chain = param
+        .source_map_chain
+        .iter()
+        .filter_map(|map| SourceMap::from_slice(map.as_bytes()).ok())
+        .collect::<Vec<_>>();
+      chain.push(map);
+
+      (code, Some(collapse(chain)))
+    } else {
+      (to_code(&module), None)
+    };
+
+    if self.options.bundle {
+      let source_map = source_map.map(|map| {
+        let mut buf = vec![];
+        map.to_writer(&mut buf).unwrap();
+        String::from_utf8(buf).unwrap()
+      });
+
+      meta.custom.insert(
+        DTS_MODULE_KEY.to_string(),
+        Box::new(DtsModule {
+          sources: collect_sources(&module),
+          code: dts_code,
+          source_map,
+        }),
+      );
+      *self.total_dts_time.lock().unwrap() += start.elapsed();
+
+      return Ok(Some(()));
     }
 
     let dts_path = if path.ends_with(".tsx") {
@@ -106,15 +177,48 @@ impl Plugin for FarmPluginDts {
       path.replace(".ts", ".d.ts")
     };
 
-    let dts_code = to_code(&program.expect_module());
+    let declaration_map = source_map.map(|map| {
+      let mut builder =
+        DeclarationMapBuilder::new(&dts_path, &context.config.root, &context.config.output.path);
+      builder.add(&map, 0);
+      builder.into_bytes()
+    });
     *self.total_dts_time.lock().unwrap() += start.elapsed();
 
-    context.emit_file(EmitFileParams {
-      resolved_path: param.module_id.to_string(),
-      name: dts_path,
-      content: dts_code.as_bytes().to_vec(),
-      resource_type: ResourceType::Custom("d.ts".to_string()),
-    });
+    emit_declaration(
+      &param.module_id.to_string(),
+      dts_path,
+      dts_code,
+      declaration_map,
+      context,
+    );
+    Ok(Some(()))
+  }
+
+  fn module_graph_build_end(
+    &self,
+    module_graph: &mut ModuleGraph,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>, CompilationError> {
+    if !self.options.bundle {
+      return Ok(None);
+    }
+
+    self.emit_bundles(module_graph, context)?;
+    Ok(Some(()))
+  }
+
+  fn module_graph_updated(
+    &self,
+    _param: &PluginModuleGraphUpdatedHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> Result<Option<()>, CompilationError> {
+    if !self.options.bundle {
+      return Ok(None);
+    }
+
+    let module_graph = context.module_graph.read();
+    self.emit_bundles(&module_graph, context)?;
     Ok(Some(()))
   }
 
@@ -132,6 +236,228 @@ impl Plugin for FarmPluginDts {
   }
 }
 
+impl FarmPluginDts {
+  fn report_issues(
+    &self,
+    issues: &[DtsIssue],
+    path: &str,
+    cm: &SwcSourceMap,
+    source_map_chain: &[Arc<String>],
+    context: &Arc<CompilationContext>,
+  ) {
+    let mut log_store = context.log_store.lock();
+
+    for issue in issues {
+      let message = format!(
+        "[{}] {}",
+        self.name(),
+        format_issue(issue, path, cm, source_map_chain)
+      );
+
+      if self.options.strict {
+        log_store.add_error(message);
+      } else {
+        log_store.add_warning(message);
+      }
+    }
+  }
+
+  /// Emit `{name}.d.ts` for each entry and dynamic entry
+  fn emit_bundles(
+    &self,
+    module_graph: &ModuleGraph,
+    context: &Arc<CompilationContext>,
+  ) -> Result<(), CompilationError> {
+    let start = std::time::Instant::now();
+    // declarations of the modules that are not in the module graph, shared by the entries
+    let mut loaded = HashMap::new();
+
+    for (entry, name) in module_graph
+      .entries
+      .iter()
+      .chain(module_graph.dynamic_entries.iter())
+    {
+      let dts_path = format!("{name}.d.ts");
+      let mut watched = vec![];
+      let bundle = bundle_declarations(
+        entry,
+        &dts_path,
+        module_graph,
+        &context.config.root,
+        &context.config.output.path,
+        &mut |importer: &ModuleId, source: &str| {
+          let (module_id, dts) =
+            self.load_declaration(importer, source, module_graph, &mut loaded, context)?;
+
+          if !module_graph.has_module(&module_id) {
+            watched.push(module_id.clone());
+          }
+
+          Some((module_id, dts))
+        },
+      )?;
+
+      // the bundle is emitted again when a module that is only imported by types changes
+      if !watched.is_empty() {
+        context.add_watch_files(entry.clone(), watched)?;
+      }
+
+      let Some(bundle) = bundle else {
+        continue;
+      };
+
+      emit_declaration(
+        &entry.to_string(),
+        dts_path,
+        bundle.code,
+        bundle.source_map,
+        context,
+      );
+    }
+
+    *self.total_dts_time.lock().unwrap() += start.elapsed();
+    Ok(())
+  }
+
+  /// Resolve `source` imported by the declarations of `importer`. Type only imports are stripped from the scripts,
+  /// so the modules they import may not be in the module graph, such modules are loaded and transformed here.
+  /// Returns `None` for sources that can not be resolved, e.g. types only packages like `csstype`, external modules
+  /// and modules that are not processed by the plugin, they are kept as imports of the bundle
+  fn load_declaration(
+    &self,
+    importer: &ModuleId,
+    source: &str,
+    module_graph: &ModuleGraph,
+    loaded: &mut HashMap<ModuleId, Option<Box<DtsModule>>>,
+    context: &Arc<CompilationContext>,
+  ) -> Option<(ModuleId, Box<DtsModule>)> {
+    let resolved = context
+      .plugin_driver
+      .resolve(
+        &PluginResolveHookParam {
+          source: source.to_string(),
+          importer: Some(importer.clone()),
+          kind: ResolveKind::Import,
+        },
+        context,
+        &PluginHookContext {
+          caller: Some(self.name().to_string()),
+          meta: Default::default(),
+        },
+      )
+      .ok()
+      .flatten()
+      .filter(|resolved| !resolved.external)?;
+    let module_id = ModuleId::new(&resolved.resolved_path, "", &context.config.root);
+
+    if module_graph.has_module(&module_id) {
+      return get_dts_module(module_graph, &module_id).map(|dts| (module_id, dts));
+    }
+
+    let dts = loaded
+      .entry(module_id.clone())
+      .or_insert_with(|| self.transform_declaration(&module_id, &resolved.resolved_path, context))
+      .clone()?;
+
+    Some((module_id, dts))
+  }
+
+  /// Generate the declarations of a module that is not in the module graph
+  fn transform_declaration(
+    &self,
+    module_id: &ModuleId,
+    resolved_path: &str,
+    context: &Arc<CompilationContext>,
+  ) -> Option<Box<DtsModule>> {
+    let path = module_id.relative_path();
+    let filter = PathFilter::new(&self.options.include, &self.options.exclude);
+
+    if !filter.execute(path) {
+      return None;
+    }
+
+    let content = read_file_utf8(resolved_path).ok()?;
+    let syntax = Syntax::Typescript(TsSyntax {
+      tsx: resolved_path.ends_with(".tsx"),
+      ..Default::default()
+    });
+    let parsed = match parse_module(module_id, Arc::new(content), syntax, EsVersion::EsNext) {
+      Ok(parsed) => parsed,
+      Err(error) => {
+        let message = format!("[{}] {path}: {error}", self.name());
+        let mut log_store = context.log_store.lock();
+
+        if self.options.strict {
+          log_store.add_error(message);
+        } else {
+          log_store.add_warning(message);
+        }
+
+        return None;
+      }
+    };
+
+    let mut ast = parsed.ast;
+    let globals = Globals::new();
+    let (unresolved_mark, _) = resolve_module_mark(&mut ast, true, &globals);
+    let filename = Arc::new(FileName::Real(path.to_string().into()));
+    let mut program = Program::Module(ast);
+    let issues = GLOBALS.set(&globals, || {
+      FastDts::new(filename, unresolved_mark, Default::default()).transform(&mut program)
+    });
+
+    if !issues.is_empty() {
+      self.report_issues(&issues, path, &parsed.source_map, &[], context);
+    }
+
+    let module = program.expect_module();
+    let (code, source_map) = if self.options.declaration_map {
+      let (code, map) = codegen_with_map(&module, parsed.source_map);
+      let mut buf = vec![];
+      map.to_writer(&mut buf).unwrap();
+
+      (code, Some(String::from_utf8(buf).unwrap()))
+    } else {
+      (to_code(&module), None)
+    };
+
+    Some(Box::new(DtsModule {
+      sources: collect_sources(&module),
+      code,
+      source_map,
+    }))
+  }
+}
+
+fn emit_declaration(
+  resolved_path: &str,
+  dts_path: String,
+  mut code: String,
+  declaration_map: Option<Vec<u8>>,
+  context: &Arc<CompilationContext>,
+) {
+  if let Some(declaration_map) = declaration_map {
+    if !code.ends_with('\n') {
+      code.push('\n');
+    }
+    code.push_str(&source_mapping_url_comment(&dts_path));
+
+    context.emit_file(EmitFileParams {
+      resolved_path: resolved_path.to_string(),
+      name: format!("{dts_path}.map"),
+      content: declaration_map,
+      resource_type: ResourceType::SourceMap(dts_path.clone()),
+    });
+  }
+
+  context.emit_file(EmitFileParams {
+    resolved_path: resolved_path.to_string(),
+    name: dts_path,
+    content: code.into_bytes(),
+    resource_type: ResourceType::Custom("d.ts".to_string()),
+  });
+}
+
 struct ImportVariableRemover;
 
 impl VisitMut for ImportVariableRemover {
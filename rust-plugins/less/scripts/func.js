@@ -0,0 +1,3 @@
+import binPath from "./index.js";
+
+export default (options)=>[binPath,options];
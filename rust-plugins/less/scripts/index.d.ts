@@ -0,0 +1,18 @@
+export interface LessOptions {
+  /**
+   * Add extra content to the head of each less file, such as an @import '@/styles/variables.less'; statement.
+   */
+  additionalData?: string;
+  /**
+   * Global variables that override the variables defined in the less files, e.g. `{ 'primary-color': '#1DA57A' }`.
+   */
+  modifyVars?: Record<string, string | number>;
+  /**
+   * Generate source maps that point at the less files and their imports. Default to `true`.
+   * Only takes effect when `compilation.sourcemap` is enabled.
+   */
+  sourceMap?: boolean;
+}
+
+declare const binPath: (options?: LessOptions) => [string, typeof options];
+export default binPath;
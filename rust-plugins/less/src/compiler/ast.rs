@@ -0,0 +1,76 @@
+use std::rc::Rc;
+
+/// Position of a node: the index of the source it is parsed from and the byte offset in that source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+  pub file: usize,
+  pub start: usize,
+}
+
+#[derive(Debug, Clone)]
+pub enum Node {
+  /// `.a, .b when (@mode = dark) { ... }`
+  Rule {
+    selector: String,
+    guard: Option<String>,
+    children: Rc<Vec<Node>>,
+    span: Span,
+  },
+  /// `.mixin(@a; @b: 2px) when (iscolor(@a)) { ... }`
+  MixinDefinition {
+    name: String,
+    params: Vec<MixinParam>,
+    guard: Option<String>,
+    children: Rc<Vec<Node>>,
+    span: Span,
+  },
+  /// `color: @primary !important;`
+  Declaration {
+    name: String,
+    value: String,
+    important: bool,
+    span: Span,
+  },
+  /// `@primary: #1890ff;`
+  Variable {
+    name: String,
+    value: String,
+    span: Span,
+  },
+  /// `#ns > .mixin(1px; red) !important;`
+  MixinCall {
+    path: Vec<String>,
+    args: Vec<MixinArg>,
+    important: bool,
+    span: Span,
+  },
+  /// `@media`, `@font-face`, `@keyframes`, `@charset`, css `@import`...
+  AtRule {
+    name: String,
+    params: String,
+    children: Option<Rc<Vec<Node>>>,
+    span: Span,
+  },
+  /// nodes of a `@import (reference)`, they define variables and mixins but are not output
+  Reference(Rc<Vec<Node>>),
+  /// css that is output as is, e.g. a top level `/* */` comment or a `@import (inline)`
+  Raw { text: String, span: Span },
+}
+
+#[derive(Debug, Clone)]
+pub enum MixinParam {
+  Variable {
+    name: String,
+    default: Option<String>,
+  },
+  /// pattern matching param, e.g. `dark` in `.theme(dark; @color)`
+  Literal(String),
+  /// `...` or `@rest...`
+  Rest(Option<String>),
+}
+
+#[derive(Debug, Clone)]
+pub struct MixinArg {
+  pub name: Option<String>,
+  pub value: String,
+}
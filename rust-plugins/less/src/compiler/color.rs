@@ -0,0 +1,371 @@
+use super::value::format_number;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Color {
+  pub r: f64,
+  pub g: f64,
+  pub b: f64,
+  pub a: f64,
+  /// the color as written in the source, kept when the color is output unchanged
+  pub text: Option<String>,
+}
+
+impl Color {
+  pub fn new(r: f64, g: f64, b: f64, a: f64) -> Self {
+    Self {
+      r: r.clamp(0.0, 255.0),
+      g: g.clamp(0.0, 255.0),
+      b: b.clamp(0.0, 255.0),
+      a: a.clamp(0.0, 1.0),
+      text: None,
+    }
+  }
+
+  pub fn from_hex(hex: &str) -> Option<Self> {
+    let digits = hex.strip_prefix('#')?;
+
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+      return None;
+    }
+
+    let channel = |s: &str| u8::from_str_radix(s, 16).ok().map(f64::from);
+    let (r, g, b, a) = match digits.len() {
+      3 | 4 => {
+        let expanded: String = digits.chars().flat_map(|c| [c, c]).collect();
+        let a = if digits.len() == 4 {
+          channel(&expanded[6..8])? / 255.0
+        } else {
+          1.0
+        };
+        (
+          channel(&expanded[0..2])?,
+          channel(&expanded[2..4])?,
+          channel(&expanded[4..6])?,
+          a,
+        )
+      }
+      6 | 8 => {
+        let a = if digits.len() == 8 {
+          channel(&digits[6..8])? / 255.0
+        } else {
+          1.0
+        };
+        (
+          channel(&digits[0..2])?,
+          channel(&digits[2..4])?,
+          channel(&digits[4..6])?,
+          a,
+        )
+      }
+      _ => return None,
+    };
+
+    Some(Self {
+      text: Some(hex.to_string()),
+      ..Self::new(r, g, b, a)
+    })
+  }
+
+  pub fn from_keyword(keyword: &str) -> Option<Self> {
+    let lower = keyword.to_ascii_lowercase();
+
+    if lower == "transparent" {
+      return Some(Self {
+        text: Some(keyword.to_string()),
+        ..Self::new(0.0, 0.0, 0.0, 0.0)
+      });
+    }
+
+    let index = NAMED_COLORS
+      .binary_search_by(|(name, _)| name.cmp(&lower.as_str()))
+      .ok()?;
+    let rgb = NAMED_COLORS[index].1;
+
+    Some(Self {
+      text: Some(keyword.to_string()),
+      ..Self::new(
+        f64::from((rgb >> 16) & 0xff),
+        f64::from((rgb >> 8) & 0xff),
+        f64::from(rgb & 0xff),
+        1.0,
+      )
+    })
+  }
+
+  pub fn from_hsla(h: f64, s: f64, l: f64, a: f64) -> Self {
+    let h = (h % 360.0 + 360.0) % 360.0 / 360.0;
+    let s = s.clamp(0.0, 1.0);
+    let l = l.clamp(0.0, 1.0);
+    let m2 = if l <= 0.5 {
+      l * (s + 1.0)
+    } else {
+      l + s - l * s
+    };
+    let m1 = l * 2.0 - m2;
+    let hue = |h: f64| {
+      let h = if h < 0.0 {
+        h + 1.0
+      } else if h > 1.0 {
+        h - 1.0
+      } else {
+        h
+      };
+
+      if h * 6.0 < 1.0 {
+        m1 + (m2 - m1) * h * 6.0
+      } else if h * 2.0 < 1.0 {
+        m2
+      } else if h * 3.0 < 2.0 {
+        m1 + (m2 - m1) * (2.0 / 3.0 - h) * 6.0
+      } else {
+        m1
+      }
+    };
+
+    Self::new(
+      hue(h + 1.0 / 3.0) * 255.0,
+      hue(h) * 255.0,
+      hue(h - 1.0 / 3.0) * 255.0,
+      a,
+    )
+  }
+
+  /// Hue in degrees, saturation and lightness in `0..=1`
+  pub fn to_hsl(&self) -> (f64, f64, f64) {
+    let (r, g, b) = (self.r / 255.0, self.g / 255.0, self.b / 255.0);
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+
+    if d == 0.0 {
+      return (0.0, 0.0, l);
+    }
+
+    let s = if l > 0.5 {
+      d / (2.0 - max - min)
+    } else {
+      d / (max + min)
+    };
+    let h = if max == r {
+      (g - b) / d + if g < b { 6.0 } else { 0.0 }
+    } else if max == g {
+      (b - r) / d + 2.0
+    } else {
+      (r - g) / d + 4.0
+    };
+
+    (h * 60.0, s, l)
+  }
+
+  /// Mix two colors the same way as less and sass, `weight` is the proportion of `self` in `0..=1`
+  pub fn mix(&self, other: &Color, weight: f64) -> Color {
+    let w = weight * 2.0 - 1.0;
+    let a = self.a - other.a;
+    let w1 = (if w * a == -1.0 {
+      w
+    } else {
+      (w + a) / (1.0 + w * a)
+    } + 1.0)
+      / 2.0;
+    let w2 = 1.0 - w1;
+
+    Color::new(
+      self.r * w1 + other.r * w2,
+      self.g * w1 + other.g * w2,
+      self.b * w1 + other.b * w2,
+      self.a * weight + other.a * (1.0 - weight),
+    )
+  }
+
+  pub fn to_css(&self) -> String {
+    if let Some(text) = &self.text {
+      return text.clone();
+    }
+
+    let (r, g, b) = (self.r.round(), self.g.round(), self.b.round());
+
+    if self.a < 1.0 {
+      format!("rgba({}, {}, {}, {})", r, g, b, format_number(self.a))
+    } else {
+      format!("#{:02x}{:02x}{:02x}", r as u8, g as u8, b as u8)
+    }
+  }
+}
+
+/// css named colors, sorted by name
+const NAMED_COLORS: &[(&str, u32)] = &[
+  ("aliceblue", 0xf0f8ff),
+  ("antiquewhite", 0xfaebd7),
+  ("aqua", 0x00ffff),
+  ("aquamarine", 0x7fffd4),
+  ("azure", 0xf0ffff),
+  ("beige", 0xf5f5dc),
+  ("bisque", 0xffe4c4),
+  ("black", 0x000000),
+  ("blanchedalmond", 0xffebcd),
+  ("blue", 0x0000ff),
+  ("blueviolet", 0x8a2be2),
+  ("brown", 0xa52a2a),
+  ("burlywood", 0xdeb887),
+  ("cadetblue", 0x5f9ea0),
+  ("chartreuse", 0x7fff00),
+  ("chocolate", 0xd2691e),
+  ("coral", 0xff7f50),
+  ("cornflowerblue", 0x6495ed),
+  ("cornsilk", 0xfff8dc),
+  ("crimson", 0xdc143c),
+  ("cyan", 0x00ffff),
+  ("darkblue", 0x00008b),
+  ("darkcyan", 0x008b8b),
+  ("darkgoldenrod", 0xb8860b),
+  ("darkgray", 0xa9a9a9),
+  ("darkgreen", 0x006400),
+  ("darkgrey", 0xa9a9a9),
+  ("darkkhaki", 0xbdb76b),
+  ("darkmagenta", 0x8b008b),
+  ("darkolivegreen", 0x556b2f),
+  ("darkorange", 0xff8c00),
+  ("darkorchid", 0x9932cc),
+  ("darkred", 0x8b0000),
+  ("darksalmon", 0xe9967a),
+  ("darkseagreen", 0x8fbc8f),
+  ("darkslateblue", 0x483d8b),
+  ("darkslategray", 0x2f4f4f),
+  ("darkslategrey", 0x2f4f4f),
+  ("darkturquoise", 0x00ced1),
+  ("darkviolet", 0x9400d3),
+  ("deeppink", 0xff1493),
+  ("deepskyblue", 0x00bfff),
+  ("dimgray", 0x696969),
+  ("dimgrey", 0x696969),
+  ("dodgerblue", 0x1e90ff),
+  ("firebrick", 0xb22222),
+  ("floralwhite", 0xfffaf0),
+  ("forestgreen", 0x228b22),
+  ("fuchsia", 0xff00ff),
+  ("gainsboro", 0xdcdcdc),
+  ("ghostwhite", 0xf8f8ff),
+  ("gold", 0xffd700),
+  ("goldenrod", 0xdaa520),
+  ("gray", 0x808080),
+  ("green", 0x008000),
+  ("greenyellow", 0xadff2f),
+  ("grey", 0x808080),
+  ("honeydew", 0xf0fff0),
+  ("hotpink", 0xff69b4),
+  ("indianred", 0xcd5c5c),
+  ("indigo", 0x4b0082),
+  ("ivory", 0xfffff0),
+  ("khaki", 0xf0e68c),
+  ("lavender", 0xe6e6fa),
+  ("lavenderblush", 0xfff0f5),
+  ("lawngreen", 0x7cfc00),
+  ("lemonchiffon", 0xfffacd),
+  ("lightblue", 0xadd8e6),
+  ("lightcoral", 0xf08080),
+  ("lightcyan", 0xe0ffff),
+  ("lightgoldenrodyellow", 0xfafad2),
+  ("lightgray", 0xd3d3d3),
+  ("lightgreen", 0x90ee90),
+  ("lightgrey", 0xd3d3d3),
+  ("lightpink", 0xffb6c1),
+  ("lightsalmon", 0xffa07a),
+  ("lightseagreen", 0x20b2aa),
+  ("lightskyblue", 0x87cefa),
+  ("lightslategray", 0x778899),
+  ("lightslategrey", 0x778899),
+  ("lightsteelblue", 0xb0c4de),
+  ("lightyellow", 0xffffe0),
+  ("lime", 0x00ff00),
+  ("limegreen", 0x32cd32),
+  ("linen", 0xfaf0e6),
+  ("magenta", 0xff00ff),
+  ("maroon", 0x800000),
+  ("mediumaquamarine", 0x66cdaa),
+  ("mediumblue", 0x0000cd),
+  ("mediumorchid", 0xba55d3),
+  ("mediumpurple", 0x9370db),
+  ("mediumseagreen", 0x3cb371),
+  ("mediumslateblue", 0x7b68ee),
+  ("mediumspringgreen", 0x00fa9a),
+  ("mediumturquoise", 0x48d1cc),
+  ("mediumvioletred", 0xc71585),
+  ("midnightblue", 0x191970),
+  ("mintcream", 0xf5fffa),
+  ("mistyrose", 0xffe4e1),
+  ("moccasin", 0xffe4b5),
+  ("navajowhite", 0xffdead),
+  ("navy", 0x000080),
+  ("oldlace", 0xfdf5e6),
+  ("olive", 0x808000),
+  ("olivedrab", 0x6b8e23),
+  ("orange", 0xffa500),
+  ("orangered", 0xff4500),
+  ("orchid", 0xda70d6),
+  ("palegoldenrod", 0xeee8aa),
+  ("palegreen", 0x98fb98),
+  ("paleturquoise", 0xafeeee),
+  ("palevioletred", 0xdb7093),
+  ("papayawhip", 0xffefd5),
+  ("peachpuff", 0xffdab9),
+  ("peru", 0xcd853f),
+  ("pink", 0xffc0cb),
+  ("plum", 0xdda0dd),
+  ("powderblue", 0xb0e0e6),
+  ("purple", 0x800080),
+  ("rebeccapurple", 0x663399),
+  ("red", 0xff0000),
+  ("rosybrown", 0xbc8f8f),
+  ("royalblue", 0x4169e1),
+  ("saddlebrown", 0x8b4513),
+  ("salmon", 0xfa8072),
+  ("sandybrown", 0xf4a460),
+  ("seagreen", 0x2e8b57),
+  ("seashell", 0xfff5ee),
+  ("sienna", 0xa0522d),
+  ("silver", 0xc0c0c0),
+  ("skyblue", 0x87ceeb),
+  ("slateblue", 0x6a5acd),
+  ("slategray", 0x708090),
+  ("slategrey", 0x708090),
+  ("snow", 0xfffafa),
+  ("springgreen", 0x00ff7f),
+  ("steelblue", 0x4682b4),
+  ("tan", 0xd2b48c),
+  ("teal", 0x008080),
+  ("thistle", 0xd8bfd8),
+  ("tomato", 0xff6347),
+  ("turquoise", 0x40e0d0),
+  ("violet", 0xee82ee),
+  ("wheat", 0xf5deb3),
+  ("white", 0xffffff),
+  ("whitesmoke", 0xf5f5f5),
+  ("yellow", 0xffff00),
+  ("yellowgreen", 0x9acd32),
+];
+
+#[cfg(test)]
+mod tests {
+  use super::{Color, NAMED_COLORS};
+
+  #[test]
+  fn named_colors_are_sorted() {
+    assert!(NAMED_COLORS.windows(2).all(|w| w[0].0 < w[1].0));
+  }
+
+  #[test]
+  fn hsl_round_trip() {
+    let color = Color::from_hex("#1890ff").unwrap();
+    let (h, s, l) = color.to_hsl();
+    let converted = Color::from_hsla(h, s, l, 1.0);
+
+    assert_eq!(converted.to_css(), "#1890ff");
+    assert_eq!(
+      color
+        .mix(&Color::from_keyword("white").unwrap(), 0.5)
+        .to_css(),
+      "#8cc8ff"
+    );
+  }
+}
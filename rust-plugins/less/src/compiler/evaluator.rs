@@ -0,0 +1,828 @@
+//! Evaluate the parsed nodes to flat css: resolve variables and mixins, join nested selectors and
+//! bubble `@media` out of rules. Variables are lazily evaluated in the scope they are defined in,
+//! the last definition of a scope wins, the same as less.
+
+use std::{collections::HashMap, rc::Rc};
+
+use super::{
+  ast::{MixinArg, MixinParam, Node, Span},
+  parser::{find_top_level, split_top_level},
+  value::{self, substitute_variables, Value, ValueContext},
+  Error, Importer, Source,
+};
+
+/// At-rules whose children are nested in the current selectors
+const CONDITIONAL_AT_RULES: [&str; 8] = [
+  "media",
+  "supports",
+  "container",
+  "layer",
+  "document",
+  "-moz-document",
+  "scope",
+  "starting-style",
+];
+
+const MAX_MIXIN_DEPTH: usize = 128;
+
+#[derive(Debug)]
+pub enum CssNode {
+  Rule {
+    selectors: Vec<String>,
+    declarations: Vec<CssDeclaration>,
+    span: Span,
+  },
+  AtRule {
+    name: String,
+    params: String,
+    declarations: Vec<CssDeclaration>,
+    children: Vec<CssNode>,
+    span: Span,
+  },
+  /// at-rule without block, e.g. `@charset "utf-8";` or a css `@import`
+  Statement {
+    text: String,
+    span: Span,
+  },
+  Raw {
+    text: String,
+    span: Span,
+  },
+}
+
+#[derive(Debug)]
+pub struct CssDeclaration {
+  pub name: String,
+  pub value: String,
+  pub important: bool,
+  pub span: Span,
+}
+
+enum Variable {
+  Raw { value: String, span: Span },
+  Evaluated(Value),
+}
+
+struct Mixin {
+  /// `None` for plain rules like `.a { ... }` that are called without arguments
+  params: Option<Vec<MixinParam>>,
+  guard: Option<String>,
+  children: Rc<Vec<Node>>,
+  span: Span,
+}
+
+/// Variables and mixins defined in a block
+#[derive(Default)]
+struct Frame {
+  variables: HashMap<String, Rc<Variable>>,
+  mixins: HashMap<String, Vec<Rc<Mixin>>>,
+  parent: Option<Rc<Frame>>,
+  /// scope of the caller of a mixin, searched after the scope the mixin is defined in
+  caller: Option<Rc<Frame>>,
+}
+
+impl Frame {
+  fn new(nodes: &[Node], parent: Option<Rc<Frame>>) -> Self {
+    let mut frame = Self {
+      parent,
+      ..Default::default()
+    };
+    frame.collect(nodes);
+    frame
+  }
+
+  fn collect(&mut self, nodes: &[Node]) {
+    for node in nodes {
+      match node {
+        Node::Variable { name, value, span } => {
+          self.variables.insert(
+            name.clone(),
+            Rc::new(Variable::Raw {
+              value: value.clone(),
+              span: *span,
+            }),
+          );
+        }
+        Node::MixinDefinition {
+          name,
+          params,
+          guard,
+          children,
+          span,
+        } => {
+          self
+            .mixins
+            .entry(name.clone())
+            .or_default()
+            .push(Rc::new(Mixin {
+              params: Some(params.clone()),
+              guard: guard.clone(),
+              children: children.clone(),
+              span: *span,
+            }));
+        }
+        Node::Rule {
+          selector,
+          guard,
+          children,
+          span,
+        } => {
+          for name in split_top_level(selector, ",") {
+            let name = name.trim();
+
+            if is_simple_selector(name) {
+              self
+                .mixins
+                .entry(name.to_string())
+                .or_default()
+                .push(Rc::new(Mixin {
+                  params: None,
+                  guard: guard.clone(),
+                  children: children.clone(),
+                  span: *span,
+                }));
+            }
+          }
+        }
+        Node::Reference(nodes) => self.collect(nodes),
+        _ => {}
+      }
+    }
+  }
+
+  fn find_variable(self: &Rc<Self>, name: &str) -> Option<(Rc<Variable>, Rc<Frame>)> {
+    if let Some(variable) = self.variables.get(name) {
+      return Some((variable.clone(), self.clone()));
+    }
+
+    self
+      .parent
+      .as_ref()
+      .and_then(|parent| parent.find_variable(name))
+      .or_else(|| {
+        self
+          .caller
+          .as_ref()
+          .and_then(|caller| caller.find_variable(name))
+      })
+  }
+
+  /// Mixins of the nearest scope that defines `name`, with the frame they are defined in
+  fn find_mixins(self: &Rc<Self>, name: &str) -> Vec<(Rc<Mixin>, Rc<Frame>)> {
+    if let Some(mixins) = self.mixins.get(name) {
+      return mixins
+        .iter()
+        .map(|mixin| (mixin.clone(), self.clone()))
+        .collect();
+    }
+
+    let from_parent = self
+      .parent
+      .as_ref()
+      .map(|parent| parent.find_mixins(name))
+      .unwrap_or_default();
+
+    if !from_parent.is_empty() {
+      return from_parent;
+    }
+
+    self
+      .caller
+      .as_ref()
+      .map(|caller| caller.find_mixins(name))
+      .unwrap_or_default()
+  }
+}
+
+fn is_simple_selector(selector: &str) -> bool {
+  let mut chars = selector.chars();
+
+  matches!(chars.next(), Some('.' | '#'))
+    && selector.len() > 1
+    && chars.all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+enum GuardResult {
+  True,
+  False,
+  /// the guard matches only if no other mixin matches, `default()`
+  Default,
+}
+
+pub struct Evaluator<'a> {
+  sources: &'a [Source],
+  importer: &'a dyn Importer,
+  /// variables being evaluated, to detect recursive definitions
+  evaluating: Vec<String>,
+  mixin_depth: usize,
+}
+
+struct EvalContext<'e, 'a> {
+  evaluator: &'e mut Evaluator<'a>,
+  frame: &'e Rc<Frame>,
+  file: usize,
+}
+
+impl ValueContext for EvalContext<'_, '_> {
+  fn variable(&mut self, name: &str) -> Result<Value, String> {
+    self.evaluator.variable(name, self.frame)
+  }
+
+  fn url(&mut self, url: &str) -> String {
+    let file = &self.evaluator.sources[self.file];
+
+    if file.rebase_urls {
+      self.evaluator.importer.rebase_url(url, &file.path)
+    } else {
+      url.to_string()
+    }
+  }
+}
+
+impl<'a> Evaluator<'a> {
+  pub fn new(sources: &'a [Source], importer: &'a dyn Importer) -> Self {
+    Self {
+      sources,
+      importer,
+      evaluating: vec![],
+      mixin_depth: 0,
+    }
+  }
+
+  pub fn evaluate(&mut self, nodes: &[Node]) -> Result<Vec<CssNode>, Error> {
+    let frame = Rc::new(Frame::new(nodes, None));
+    let mut output = vec![];
+
+    self.eval_block(nodes, &frame, &[], false, None, &mut output)?;
+
+    Ok(output)
+  }
+
+  fn variable(&mut self, name: &str, frame: &Rc<Frame>) -> Result<Value, String> {
+    let (variable, defined_in) = frame
+      .find_variable(name)
+      .ok_or_else(|| format!("variable @{name} is undefined"))?;
+
+    match &*variable {
+      Variable::Evaluated(value) => Ok(value.clone()),
+      Variable::Raw { value, span } => {
+        if self.evaluating.iter().any(|n| n == name) {
+          return Err(format!("recursive variable definition for @{name}"));
+        }
+
+        self.evaluating.push(name.to_string());
+        let result = self
+          .eval_value(value, &defined_in, *span)
+          .map_err(|e| e.message);
+        self.evaluating.pop();
+
+        result
+      }
+    }
+  }
+
+  fn context<'e>(&'e mut self, frame: &'e Rc<Frame>, span: Span) -> EvalContext<'e, 'a> {
+    EvalContext {
+      evaluator: self,
+      frame,
+      file: span.file,
+    }
+  }
+
+  fn eval_value(&mut self, raw: &str, frame: &Rc<Frame>, span: Span) -> Result<Value, Error> {
+    value::evaluate(raw, &mut self.context(frame, span))
+      .map_err(|message| Error::new(message, span))
+  }
+
+  fn substitute(&mut self, raw: &str, frame: &Rc<Frame>, span: Span) -> Result<String, Error> {
+    substitute_variables(raw, &mut self.context(frame, span))
+      .map_err(|message| Error::new(message, span))
+  }
+
+  fn interpolate(&mut self, raw: &str, frame: &Rc<Frame>, span: Span) -> Result<String, Error> {
+    value::interpolate(raw, &mut self.context(frame, span))
+      .map_err(|message| Error::new(message, span))
+  }
+
+  fn eval_block(
+    &mut self,
+    nodes: &[Node],
+    frame: &Rc<Frame>,
+    selectors: &[String],
+    important: bool,
+    mut declarations: Option<&mut Vec<CssDeclaration>>,
+    output: &mut Vec<CssNode>,
+  ) -> Result<(), Error> {
+    for node in nodes {
+      match node {
+        Node::Variable { .. } | Node::MixinDefinition { .. } | Node::Reference(_) => {}
+        Node::Declaration {
+          name,
+          value,
+          important: declaration_important,
+          span,
+        } => {
+          let Some(declarations) = declarations.as_deref_mut() else {
+            return Err(Error::new(
+              "properties must be inside selector blocks",
+              *span,
+            ));
+          };
+          let name = self.interpolate(name, frame, *span)?;
+          // custom properties and IE filters are not less values
+          let value = if name.starts_with("--") || value.starts_with("progid:") {
+            self.interpolate(value, frame, *span)?
+          } else {
+            self.eval_value(value, frame, *span)?.to_string()
+          };
+
+          declarations.push(CssDeclaration {
+            name,
+            value,
+            important: important || *declaration_important,
+            span: *span,
+          });
+        }
+        Node::Raw { text, span } => {
+          if declarations.is_none() {
+            output.push(CssNode::Raw {
+              text: text.clone(),
+              span: *span,
+            });
+          }
+        }
+        Node::Rule {
+          selector,
+          guard,
+          children,
+          span,
+        } => {
+          if let Some(guard) = guard
+            && matches!(self.eval_guard(guard, frame, *span)?, GuardResult::False)
+          {
+            continue;
+          }
+
+          let own = self.interpolate(selector, frame, *span)?;
+          let rule_selectors = join_selectors(selectors, &split_selectors(&own));
+          let child_frame = Rc::new(Frame::new(children, Some(frame.clone())));
+          let mut rule_declarations = vec![];
+          let mut nested = vec![];
+
+          self.eval_block(
+            children,
+            &child_frame,
+            &rule_selectors,
+            important,
+            Some(&mut rule_declarations),
+            &mut nested,
+          )?;
+
+          if !rule_declarations.is_empty() {
+            output.push(CssNode::Rule {
+              selectors: rule_selectors,
+              declarations: rule_declarations,
+              span: *span,
+            });
+          }
+
+          output.extend(nested);
+        }
+        Node::MixinCall {
+          path,
+          args,
+          important: call_important,
+          span,
+        } => {
+          self.call_mixin(
+            path,
+            args,
+            frame,
+            selectors,
+            important || *call_important,
+            declarations.as_deref_mut(),
+            output,
+            *span,
+          )?;
+        }
+        Node::AtRule {
+          name,
+          params,
+          children: None,
+          span,
+        } => {
+          let params = self.substitute(params, frame, *span)?;
+          let text = if params.is_empty() {
+            format!("@{name};")
+          } else {
+            format!("@{name} {params};")
+          };
+
+          output.push(CssNode::Statement { text, span: *span });
+        }
+        Node::AtRule {
+          name,
+          params,
+          children: Some(children),
+          span,
+        } => {
+          let params = self.substitute(params, frame, *span)?;
+          let child_frame = Rc::new(Frame::new(children, Some(frame.clone())));
+          let mut at_rule_declarations = vec![];
+          let mut at_rule_children = vec![];
+
+          if CONDITIONAL_AT_RULES.contains(&name.as_str()) {
+            // `@media` inside a rule is bubbled up, the declarations are wrapped in the current selectors
+            let mut wrapped_declarations = vec![];
+            let mut nested = vec![];
+
+            self.eval_block(
+              children,
+              &child_frame,
+              selectors,
+              important,
+              (!selectors.is_empty()).then_some(&mut wrapped_declarations),
+              &mut nested,
+            )?;
+
+            if !wrapped_declarations.is_empty() {
+              at_rule_children.push(CssNode::Rule {
+                selectors: selectors.to_vec(),
+                declarations: wrapped_declarations,
+                span: *span,
+              });
+            }
+
+            at_rule_children.extend(nested);
+          } else {
+            self.eval_block(
+              children,
+              &child_frame,
+              &[],
+              important,
+              Some(&mut at_rule_declarations),
+              &mut at_rule_children,
+            )?;
+          }
+
+          output.push(CssNode::AtRule {
+            name: name.clone(),
+            params,
+            declarations: at_rule_declarations,
+            children: at_rule_children,
+            span: *span,
+          });
+        }
+      }
+    }
+
+    Ok(())
+  }
+
+  #[allow(clippy::too_many_arguments)]
+  fn call_mixin(
+    &mut self,
+    path: &[String],
+    args: &[MixinArg],
+    frame: &Rc<Frame>,
+    selectors: &[String],
+    important: bool,
+    mut declarations: Option<&mut Vec<CssDeclaration>>,
+    output: &mut Vec<CssNode>,
+    span: Span,
+  ) -> Result<(), Error> {
+    let call = format!("{}()", path.join(" > "));
+    let candidates = find_mixin_path(frame, path);
+
+    if candidates.is_empty() {
+      return Err(Error::new(format!("`{call}` is undefined"), span));
+    }
+
+    let mut values = vec![];
+
+    for arg in args {
+      values.push((arg.name.clone(), self.eval_value(&arg.value, frame, span)?));
+    }
+
+    let mut matched = vec![];
+    let mut defaults = vec![];
+    let mut arguments_matched = false;
+
+    for (mixin, defined_in) in candidates {
+      let Some(variables) = bind_args(&mixin, &values) else {
+        continue;
+      };
+      arguments_matched = true;
+      let mixin_frame = Rc::new(Frame {
+        variables,
+        mixins: HashMap::new(),
+        parent: Some(defined_in),
+        caller: Some(frame.clone()),
+      });
+
+      let guard = match &mixin.guard {
+        Some(guard) => self.eval_guard(guard, &mixin_frame, mixin.span)?,
+        None => GuardResult::True,
+      };
+
+      match guard {
+        GuardResult::True => matched.push((mixin, mixin_frame)),
+        GuardResult::Default => defaults.push((mixin, mixin_frame)),
+        GuardResult::False => {}
+      }
+    }
+
+    if matched.is_empty() {
+      matched = defaults;
+    }
+
+    // the same as less, a call whose arguments match but whose guards are all false outputs nothing,
+    // e.g. the end of a recursive loop
+    if matched.is_empty() && arguments_matched {
+      return Ok(());
+    }
+
+    if matched.is_empty() {
+      return Err(Error::new(
+        format!("no matching definition was found for `{call}`"),
+        span,
+      ));
+    }
+
+    if self.mixin_depth >= MAX_MIXIN_DEPTH {
+      return Err(Error::new(
+        format!("maximum call depth exceeded when calling `{call}`"),
+        span,
+      ));
+    }
+
+    self.mixin_depth += 1;
+
+    for (mixin, mixin_frame) in matched {
+      let body_frame = Rc::new(Frame::new(&mixin.children, Some(mixin_frame)));
+      let result = self.eval_block(
+        &mixin.children,
+        &body_frame,
+        selectors,
+        important,
+        declarations.as_deref_mut(),
+        output,
+      );
+
+      if let Err(error) = result {
+        self.mixin_depth -= 1;
+        return Err(error);
+      }
+    }
+
+    self.mixin_depth -= 1;
+
+    Ok(())
+  }
+
+  /// Evaluate a guard like `(@a > 0) and (iscolor(@c)), (default())`
+  fn eval_guard(
+    &mut self,
+    guard: &str,
+    frame: &Rc<Frame>,
+    span: Span,
+  ) -> Result<GuardResult, Error> {
+    let mut uses_default = false;
+    let mut result = false;
+
+    for condition in split_top_level(guard, ",") {
+      let mut condition_result = true;
+
+      for term in split_top_level(&condition, " and ") {
+        let term = term.trim();
+        let (negated, term) = match term.strip_prefix("not") {
+          Some(rest) if rest.trim_start().starts_with('(') => (true, rest.trim()),
+          _ => (false, term),
+        };
+        let inner = term
+          .strip_prefix('(')
+          .and_then(|t| t.strip_suffix(')'))
+          .ok_or_else(|| Error::new(format!("invalid guard `{guard}`"), span))?
+          .trim();
+
+        if inner == "default()" {
+          if !negated {
+            uses_default = true;
+          }
+          continue;
+        }
+
+        let value = self.eval_condition(inner, frame, span)?;
+
+        if value == negated {
+          condition_result = false;
+        }
+      }
+
+      result = result || condition_result;
+    }
+
+    Ok(match (result, uses_default) {
+      (false, _) => GuardResult::False,
+      (true, true) => GuardResult::Default,
+      (true, false) => GuardResult::True,
+    })
+  }
+
+  fn eval_condition(
+    &mut self,
+    condition: &str,
+    frame: &Rc<Frame>,
+    span: Span,
+  ) -> Result<bool, Error> {
+    for operator in [">=", "=<", "<=", "=>", ">", "<", "="] {
+      let Some(index) = find_top_level(condition, operator) else {
+        continue;
+      };
+      let left = self.eval_value(condition[..index].trim(), frame, span)?;
+      let right = self.eval_value(condition[index + operator.len()..].trim(), frame, span)?;
+
+      if let (Value::Number { value: a, .. }, Value::Number { value: b, .. }) = (&left, &right) {
+        return Ok(match operator {
+          ">=" | "=>" => a >= b,
+          "=<" | "<=" => a <= b,
+          ">" => a > b,
+          "<" => a < b,
+          _ => a == b,
+        });
+      }
+
+      return Ok(operator == "=" && left.unquoted() == right.unquoted());
+    }
+
+    Ok(self.eval_value(condition, frame, span)?.unquoted() == "true")
+  }
+}
+
+fn find_mixin_path(frame: &Rc<Frame>, path: &[String]) -> Vec<(Rc<Mixin>, Rc<Frame>)> {
+  let Some((first, rest)) = path.split_first() else {
+    return vec![];
+  };
+  let found = frame.find_mixins(first);
+
+  if rest.is_empty() {
+    return found;
+  }
+
+  // `#ns > .mixin`: search `.mixin` in the blocks of `#ns`
+  found
+    .into_iter()
+    .flat_map(|(namespace, defined_in)| {
+      let namespace_frame = Rc::new(Frame::new(&namespace.children, Some(defined_in)));
+      find_own_mixin_path(&namespace_frame, rest)
+    })
+    .collect()
+}
+
+fn find_own_mixin_path(frame: &Rc<Frame>, path: &[String]) -> Vec<(Rc<Mixin>, Rc<Frame>)> {
+  match frame.mixins.get(&path[0]) {
+    Some(_) => find_mixin_path(frame, path),
+    None => vec![],
+  }
+}
+
+/// Bind the arguments of a call to the params of the mixin, `None` if the mixin does not match the call
+fn bind_args(
+  mixin: &Mixin,
+  args: &[(Option<String>, Value)],
+) -> Option<HashMap<String, Rc<Variable>>> {
+  let Some(params) = &mixin.params else {
+    return args.is_empty().then(HashMap::new);
+  };
+
+  let positional: Vec<&Value> = args
+    .iter()
+    .filter(|(name, _)| name.is_none())
+    .map(|(_, value)| value)
+    .collect();
+  let named: HashMap<&str, &Value> = args
+    .iter()
+    .filter_map(|(name, value)| name.as_deref().map(|name| (name, value)))
+    .collect();
+  let mut variables = HashMap::new();
+  let mut arguments = vec![];
+  let mut index = 0;
+
+  for param in params {
+    match param {
+      MixinParam::Literal(literal) => {
+        let value = positional.get(index)?;
+
+        if value.unquoted() != *literal {
+          return None;
+        }
+
+        arguments.push(value.to_string());
+        index += 1;
+      }
+      MixinParam::Variable { name, default } => {
+        let variable = if let Some(value) = named.get(name.as_str()) {
+          Variable::Evaluated((*value).clone())
+        } else if let Some(value) = positional.get(index) {
+          index += 1;
+          Variable::Evaluated((*value).clone())
+        } else {
+          Variable::Raw {
+            value: default.clone()?,
+            span: mixin.span,
+          }
+        };
+
+        variables.insert(name.clone(), Rc::new(variable));
+        arguments.push(format!("@{name}"));
+      }
+      MixinParam::Rest(name) => {
+        let rest: Vec<Value> = positional[index.min(positional.len())..]
+          .iter()
+          .map(|value| (*value).clone())
+          .collect();
+        index = positional.len();
+        arguments.extend(rest.iter().map(ToString::to_string));
+
+        if let Some(name) = name {
+          variables.insert(
+            name.clone(),
+            Rc::new(Variable::Evaluated(Value::List {
+              items: rest,
+              comma: false,
+            })),
+          );
+        }
+      }
+    }
+  }
+
+  if index < positional.len() {
+    return None;
+  }
+
+  // `@arguments` is evaluated in the scope of the mixin so defaults are resolved
+  variables.insert(
+    "arguments".to_string(),
+    Rc::new(Variable::Raw {
+      value: arguments.join(" "),
+      span: mixin.span,
+    }),
+  );
+
+  Some(variables)
+}
+
+/// Split a selector list and collapse whitespaces
+fn split_selectors(selector: &str) -> Vec<String> {
+  split_top_level(selector, ",")
+    .into_iter()
+    .map(|s| s.split_whitespace().collect::<Vec<_>>().join(" "))
+    .filter(|s| !s.is_empty())
+    .collect()
+}
+
+/// Join nested selectors with the parent selectors, `&` is replaced with the parent selector
+fn join_selectors(parents: &[String], children: &[String]) -> Vec<String> {
+  let mut joined: Vec<String> = vec![];
+
+  for child in children {
+    if parents.is_empty() {
+      joined.push(child.replace('&', "").trim().to_string());
+      continue;
+    }
+
+    for parent in parents {
+      let selector = if child.contains('&') {
+        child.replace('&', parent)
+      } else {
+        format!("{parent} {child}")
+      };
+
+      if !joined.contains(&selector) {
+        joined.push(selector);
+      }
+    }
+  }
+
+  joined
+}
+
+#[cfg(test)]
+mod tests {
+  use super::join_selectors;
+
+  #[test]
+  fn join_nested_selectors() {
+    let parents = vec![".a".to_string(), ".b".to_string()];
+
+    assert_eq!(
+      join_selectors(&parents, &["&:hover".to_string(), "> .c".to_string()]),
+      vec![".a:hover", ".b:hover", ".a > .c", ".b > .c"]
+    );
+    assert_eq!(
+      join_selectors(&[], &[".a".to_string()]),
+      vec![".a".to_string()]
+    );
+  }
+}
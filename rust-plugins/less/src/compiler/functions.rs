@@ -0,0 +1,305 @@
+//! Builtin less functions, functions that are not listed here are output as css functions
+
+use super::{color::Color, value::Value};
+
+/// Less functions that are not implemented. They have no css meaning, so outputting them as css functions would
+/// silently produce broken css
+const UNSUPPORTED_FUNCTIONS: &[&str] = &[
+  "contrast",
+  "luma",
+  "luminance",
+  "if",
+  "each",
+  "range",
+  "data-uri",
+  "svg-gradient",
+  "image-size",
+  "image-width",
+  "image-height",
+  "get-unit",
+  "extract",
+  "length",
+  "escape",
+  "replace",
+  "convert",
+  "argb",
+  "hsv",
+  "hsva",
+  "hsvhue",
+  "hsvsaturation",
+  "hsvvalue",
+  "multiply",
+  "screen",
+  "overlay",
+  "softlight",
+  "hardlight",
+  "difference",
+  "exclusion",
+  "average",
+  "negation",
+  "isruleset",
+  "isdefined",
+  "boolean",
+];
+
+fn number_arg(name: &str, args: &[Value], index: usize) -> Result<(f64, String), String> {
+  match args.get(index) {
+    Some(Value::Number { value, unit }) => Ok((*value, unit.clone())),
+    Some(value) => Err(format!(
+      "argument {} of `{name}()` must be a number, got `{value}`",
+      index + 1
+    )),
+    None => Err(format!("missing argument {} of `{name}()`", index + 1)),
+  }
+}
+
+fn color_arg(name: &str, args: &[Value], index: usize) -> Result<Color, String> {
+  match args.get(index) {
+    Some(value) => value.as_color().ok_or_else(|| {
+      format!(
+        "argument {} of `{name}()` must be a color, got `{value}`",
+        index + 1
+      )
+    }),
+    None => Err(format!("missing argument {} of `{name}()`", index + 1)),
+  }
+}
+
+/// A percentage or a number in `0..=1` as a ratio
+fn amount_arg(name: &str, args: &[Value], index: usize) -> Result<f64, String> {
+  let (value, unit) = number_arg(name, args, index)?;
+
+  Ok(if unit == "%" { value / 100.0 } else { value })
+}
+
+fn rgb_channel(value: &Value) -> Option<f64> {
+  match value {
+    Value::Number { value, unit } if unit == "%" => Some(value * 2.55),
+    Value::Number { value, .. } => Some(*value),
+    _ => None,
+  }
+}
+
+fn alpha_channel(value: &Value) -> Option<f64> {
+  match value {
+    Value::Number { value, unit } if unit == "%" => Some(value / 100.0),
+    Value::Number { value, .. } => Some(*value),
+    _ => None,
+  }
+}
+
+fn boolean(value: bool) -> Value {
+  Value::keyword(if value { "true" } else { "false" })
+}
+
+fn hsl_adjust(
+  name: &str,
+  args: &[Value],
+  adjust: impl Fn((f64, f64, f64), f64) -> (f64, f64, f64),
+) -> Result<Option<Value>, String> {
+  let color = color_arg(name, args, 0)?;
+  let amount = amount_arg(name, args, 1)?;
+  let (h, s, l) = adjust(color.to_hsl(), amount);
+
+  Ok(Some(Value::Color(Color::from_hsla(h, s, l, color.a))))
+}
+
+fn with_alpha(color: Color, alpha: f64) -> Value {
+  Value::Color(Color::new(color.r, color.g, color.b, alpha))
+}
+
+pub fn call(name: &str, args: &[Value]) -> Result<Option<Value>, String> {
+  let lower = name.to_ascii_lowercase();
+
+  if UNSUPPORTED_FUNCTIONS.contains(&lower.as_str()) {
+    return Err(format!("`{name}()` is not supported"));
+  }
+
+  let value = match lower.as_str() {
+    "rgb" | "rgba" if args.len() == 3 || args.len() == 4 => {
+      let channels: Option<Vec<f64>> = args[..3].iter().map(rgb_channel).collect();
+      let alpha = args.get(3).map_or(Some(1.0), alpha_channel);
+
+      match (channels, alpha) {
+        (Some(c), Some(a)) => Value::Color(Color::new(c[0], c[1], c[2], a)),
+        // modern syntax like `rgb(0 0 0 / 50%)` or css variables are kept as is
+        _ => return Ok(None),
+      }
+    }
+    "hsl" | "hsla" if args.len() == 3 || args.len() == 4 => {
+      if !args.iter().all(|arg| matches!(arg, Value::Number { .. })) {
+        return Ok(None);
+      }
+
+      let (h, _) = number_arg(name, args, 0)?;
+      let s = amount_arg(name, args, 1)?;
+      let l = amount_arg(name, args, 2)?;
+      let a = args.get(3).and_then(alpha_channel).unwrap_or(1.0);
+      Value::Color(Color::from_hsla(h, s, l, a))
+    }
+    "lighten" => return hsl_adjust(name, args, |(h, s, l), amount| (h, s, l + amount)),
+    "darken" => return hsl_adjust(name, args, |(h, s, l), amount| (h, s, l - amount)),
+    "saturate" => return hsl_adjust(name, args, |(h, s, l), amount| (h, s + amount, l)),
+    "desaturate" => return hsl_adjust(name, args, |(h, s, l), amount| (h, s - amount, l)),
+    "greyscale" => {
+      let color = color_arg(name, args, 0)?;
+      let (h, _, l) = color.to_hsl();
+      Value::Color(Color::from_hsla(h, 0.0, l, color.a))
+    }
+    "spin" => {
+      let color = color_arg(name, args, 0)?;
+      let (degrees, _) = number_arg(name, args, 1)?;
+      let (h, s, l) = color.to_hsl();
+      Value::Color(Color::from_hsla(h + degrees, s, l, color.a))
+    }
+    "fade" => with_alpha(color_arg(name, args, 0)?, amount_arg(name, args, 1)?),
+    "fadein" => {
+      let color = color_arg(name, args, 0)?;
+      let alpha = color.a + amount_arg(name, args, 1)?;
+      with_alpha(color, alpha)
+    }
+    "fadeout" => {
+      let color = color_arg(name, args, 0)?;
+      let alpha = color.a - amount_arg(name, args, 1)?;
+      with_alpha(color, alpha)
+    }
+    "mix" => {
+      let weight = if args.len() > 2 {
+        amount_arg(name, args, 2)?
+      } else {
+        0.5
+      };
+      Value::Color(color_arg(name, args, 0)?.mix(&color_arg(name, args, 1)?, weight))
+    }
+    "tint" | "shade" => {
+      let base = if lower == "tint" {
+        Color::new(255.0, 255.0, 255.0, 1.0)
+      } else {
+        Color::new(0.0, 0.0, 0.0, 1.0)
+      };
+      let weight = if args.len() > 1 {
+        amount_arg(name, args, 1)?
+      } else {
+        0.5
+      };
+      Value::Color(base.mix(&color_arg(name, args, 0)?, weight))
+    }
+    "red" => Value::number(color_arg(name, args, 0)?.r, ""),
+    "green" => Value::number(color_arg(name, args, 0)?.g, ""),
+    "blue" => Value::number(color_arg(name, args, 0)?.b, ""),
+    "alpha" if args.first().and_then(Value::as_color).is_some() => {
+      Value::number(color_arg(name, args, 0)?.a, "")
+    }
+    "hue" => Value::number(color_arg(name, args, 0)?.to_hsl().0, ""),
+    "saturation" => Value::number(color_arg(name, args, 0)?.to_hsl().1 * 100.0, "%"),
+    "lightness" => Value::number(color_arg(name, args, 0)?.to_hsl().2 * 100.0, "%"),
+    "percentage" => Value::number(number_arg(name, args, 0)?.0 * 100.0, "%"),
+    "round" => {
+      let (value, unit) = number_arg(name, args, 0)?;
+      let places = if args.len() > 1 {
+        number_arg(name, args, 1)?.0
+      } else {
+        0.0
+      };
+      let factor = 10f64.powi(places as i32);
+      Value::number((value * factor).round() / factor, &unit)
+    }
+    "ceil" | "floor" | "abs" | "sqrt" => {
+      let (value, unit) = number_arg(name, args, 0)?;
+      let result = match lower.as_str() {
+        "ceil" => value.ceil(),
+        "floor" => value.floor(),
+        "abs" => value.abs(),
+        _ => value.sqrt(),
+      };
+      Value::number(result, &unit)
+    }
+    "unit" => {
+      let (value, _) = number_arg(name, args, 0)?;
+      let unit = args.get(1).map(Value::unquoted).unwrap_or_default();
+      Value::number(value, &unit)
+    }
+    "e" => match args.first() {
+      Some(value) => Value::Str {
+        text: value.unquoted(),
+        quote: None,
+      },
+      None => return Err("missing argument 1 of `e()`".to_string()),
+    },
+    "iscolor" => boolean(matches!(args.first(), Some(Value::Color(_)))),
+    "isnumber" => boolean(matches!(args.first(), Some(Value::Number { .. }))),
+    "isstring" => boolean(matches!(
+      args.first(),
+      Some(Value::Str { quote: Some(_), .. })
+    )),
+    "iskeyword" => boolean(matches!(args.first(), Some(Value::Keyword(_)))),
+    "isurl" => boolean(matches!(args.first(), Some(Value::Url(_)))),
+    "ispixel" | "ispercentage" | "isem" => {
+      let expected = match lower.as_str() {
+        "ispixel" => "px",
+        "ispercentage" => "%",
+        _ => "em",
+      };
+      boolean(matches!(args.first(), Some(Value::Number { unit, .. }) if unit == expected))
+    }
+    "isunit" => {
+      let expected = args.get(1).map(Value::unquoted).unwrap_or_default();
+      boolean(matches!(args.first(), Some(Value::Number { unit, .. }) if *unit == expected))
+    }
+    _ => return Ok(None),
+  };
+
+  Ok(Some(value))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::call;
+  use crate::compiler::{color::Color, value::Value};
+
+  fn color(hex: &str) -> Value {
+    Value::Color(Color::from_hex(hex).unwrap())
+  }
+
+  fn eval(name: &str, args: &[Value]) -> String {
+    call(name, args).unwrap().unwrap().to_string()
+  }
+
+  #[test]
+  fn color_functions() {
+    assert_eq!(
+      eval("darken", &[color("#ffffff"), Value::number(10.0, "%")]),
+      "#e6e6e6"
+    );
+    assert_eq!(
+      eval("lighten", &[color("#000000"), Value::number(50.0, "%")]),
+      "#808080"
+    );
+    assert_eq!(
+      eval("fade", &[color("#000"), Value::number(50.0, "%")]),
+      "rgba(0, 0, 0, 0.5)"
+    );
+    assert_eq!(
+      eval("tint", &[color("#000000"), Value::number(50.0, "%")]),
+      "#808080"
+    );
+    assert_eq!(eval("percentage", &[Value::number(0.25, "")]), "25%");
+    assert!(call("translate", &[Value::number(1.0, "px")])
+      .unwrap()
+      .is_none());
+  }
+
+  #[test]
+  fn unsupported_functions() {
+    for name in ["contrast", "luma", "if", "each", "range", "data-uri"] {
+      assert_eq!(
+        call(name, &[color("#000")]).unwrap_err(),
+        format!("`{name}()` is not supported")
+      );
+    }
+    assert_eq!(
+      call("LUMA", &[color("#000")]).unwrap_err(),
+      "`LUMA()` is not supported"
+    );
+  }
+}
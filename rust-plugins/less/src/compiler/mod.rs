@@ -0,0 +1,448 @@
+//! A less compiler that covers the features commonly used by applications and component libraries:
+//! variables, nesting, mixins with guards and namespaces, operations, color and math functions,
+//! `@import` options and `@media` bubbling. `:extend`, detached rulesets, `@plugin`, property
+//! merging and less functions that are not implemented are not supported and reported as errors.
+
+use std::{
+  collections::HashSet,
+  fmt::{Display, Formatter},
+  path::Path,
+  rc::Rc,
+};
+
+use ast::{Node, Span};
+use evaluator::Evaluator;
+use parser::{ImportHandler, Parser};
+
+mod ast;
+mod color;
+mod evaluator;
+mod functions;
+mod parser;
+mod printer;
+mod value;
+
+/// Resolves and reads the files imported by `@import`
+pub trait Importer {
+  /// Resolve `source` imported by the file `importer`, returns the absolute path of the imported file
+  fn resolve(&self, source: &str, importer: &str) -> Option<String>;
+  fn read(&self, path: &str) -> Result<String, String>;
+  /// Rebase `url` written in the imported `file`, so it is relative to the compiled file.
+  /// `url` is the content of `url()`, quotes included
+  fn rebase_url(&self, url: &str, file: &str) -> String;
+}
+
+#[derive(Debug, Default)]
+pub struct CompileOptions {
+  /// prepended to the compiled file
+  pub additional_data: Option<String>,
+  /// variables that override the variables of the compiled file and its imports
+  pub modify_vars: Vec<(String, String)>,
+  pub source_map: bool,
+}
+
+#[derive(Debug)]
+pub struct CompileResult {
+  pub css: String,
+  pub source_map: Option<String>,
+  /// absolute paths of the imported files
+  pub dependencies: Vec<String>,
+}
+
+#[derive(Debug)]
+pub struct LessError {
+  pub message: String,
+  pub file: String,
+  pub line: usize,
+  pub column: usize,
+}
+
+impl Display for LessError {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    write!(
+      f,
+      "{} in {}:{}:{}",
+      self.message, self.file, self.line, self.column
+    )
+  }
+}
+
+/// Error with the position of the node that caused it
+#[derive(Debug)]
+pub struct Error {
+  pub message: String,
+  pub span: Span,
+}
+
+impl Error {
+  pub fn new(message: impl Into<String>, span: Span) -> Self {
+    Self {
+      message: message.into(),
+      span,
+    }
+  }
+}
+
+pub struct Source {
+  pub path: String,
+  pub content: String,
+  /// `additionalData` and `modifyVars` do not exist in the files, they are not mapped
+  pub mapped: bool,
+  /// urls of imported files are rebased to the compiled file
+  pub rebase_urls: bool,
+  line_starts: Vec<usize>,
+}
+
+impl Source {
+  fn new(path: &str, content: &str, mapped: bool, rebase_urls: bool) -> Self {
+    let line_starts = std::iter::once(0)
+      .chain(content.match_indices('\n').map(|(index, _)| index + 1))
+      .collect();
+
+    Self {
+      path: path.to_string(),
+      content: content.to_string(),
+      mapped,
+      rebase_urls,
+      line_starts,
+    }
+  }
+
+  /// 0-based line and column (in utf16 code units) of a byte offset
+  pub fn location(&self, offset: usize) -> (usize, usize) {
+    let line = self
+      .line_starts
+      .partition_point(|start| *start <= offset)
+      .saturating_sub(1);
+    let line_start = self.line_starts[line];
+    let column = self.content[line_start..offset.min(self.content.len())]
+      .chars()
+      .map(char::len_utf16)
+      .sum();
+
+    (line, column)
+  }
+}
+
+struct Loader<'i> {
+  importer: &'i dyn Importer,
+  sources: Vec<Source>,
+  imported: HashSet<String>,
+  dependencies: Vec<String>,
+}
+
+impl Loader<'_> {
+  fn load(
+    &mut self,
+    path: &str,
+    content: String,
+    mapped: bool,
+    rebase_urls: bool,
+  ) -> Result<Vec<Node>, Error> {
+    let file = self.sources.len();
+    self
+      .sources
+      .push(Source::new(path, &content, mapped, rebase_urls));
+
+    Parser::new(content, file, self).parse()
+  }
+}
+
+fn unquote(s: &str) -> Option<&str> {
+  let quote = s.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+
+  s[1..].strip_suffix(quote)
+}
+
+impl ImportHandler for Loader<'_> {
+  fn import(&mut self, params: &str, span: Span) -> Result<Vec<Node>, Error> {
+    let (options, rest) = match params.strip_prefix('(') {
+      Some(rest) => {
+        let end = rest
+          .find(')')
+          .ok_or_else(|| Error::new("missing closing `)` of the import options", span))?;
+        let options: Vec<&str> = rest[..end].split(',').map(str::trim).collect();
+        (options, rest[end + 1..].trim())
+      }
+      None => (vec![], params),
+    };
+    let has_option = |option: &str| options.contains(&option);
+    let css_import = || {
+      vec![Node::AtRule {
+        name: "import".to_string(),
+        params: rest.to_string(),
+        children: None,
+        span,
+      }]
+    };
+
+    // `url(...)` and imports with media queries are kept as css imports
+    let Some(path) = unquote(rest) else {
+      return Ok(css_import());
+    };
+
+    if path.contains("@{") {
+      return Err(Error::new(
+        "variable interpolation in `@import` is not supported",
+        span,
+      ));
+    }
+
+    let is_url =
+      path.starts_with("http://") || path.starts_with("https://") || path.starts_with("//");
+
+    if has_option("css")
+      || (!has_option("less") && !has_option("inline") && (path.ends_with(".css") || is_url))
+    {
+      return Ok(css_import());
+    }
+
+    let candidates = if Path::new(path).extension().is_none() {
+      vec![format!("{path}.less"), path.to_string()]
+    } else {
+      vec![path.to_string()]
+    };
+    let importer = self.sources[span.file].path.clone();
+    let Some(resolved) = candidates
+      .iter()
+      .find_map(|candidate| self.importer.resolve(candidate, &importer))
+    else {
+      if has_option("optional") {
+        return Ok(vec![]);
+      }
+
+      return Err(Error::new(format!("can not resolve `{path}`"), span));
+    };
+
+    if !self.imported.insert(resolved.clone()) && !has_option("multiple") {
+      return Ok(vec![]);
+    }
+
+    if !self.dependencies.contains(&resolved) {
+      self.dependencies.push(resolved.clone());
+    }
+
+    let content = self
+      .importer
+      .read(&resolved)
+      .map_err(|message| Error::new(format!("can not read `{resolved}`: {message}"), span))?;
+
+    if has_option("inline") {
+      return Ok(vec![Node::Raw {
+        text: content,
+        span,
+      }]);
+    }
+
+    let nodes = self.load(&resolved, content, true, true)?;
+
+    if has_option("reference") {
+      return Ok(vec![Node::Reference(Rc::new(nodes))]);
+    }
+
+    Ok(nodes)
+  }
+}
+
+fn compile_nodes(
+  loader: &mut Loader,
+  path: &str,
+  content: &str,
+  options: &CompileOptions,
+) -> Result<(String, Option<String>), Error> {
+  let mut nodes = vec![];
+
+  if let Some(additional_data) = &options.additional_data {
+    nodes.extend(loader.load(path, additional_data.clone(), false, false)?);
+  }
+
+  nodes.extend(loader.load(path, content.to_string(), true, false)?);
+
+  // `modifyVars` are defined last so they win over the definitions of the files
+  let modify_vars_file = loader.sources.len();
+  loader
+    .sources
+    .push(Source::new("modifyVars", "", false, false));
+  nodes.extend(
+    options
+      .modify_vars
+      .iter()
+      .map(|(name, value)| Node::Variable {
+        name: name.trim_start_matches('@').to_string(),
+        value: value.clone(),
+        span: Span {
+          file: modify_vars_file,
+          start: 0,
+        },
+      }),
+  );
+
+  let css_nodes = Evaluator::new(&loader.sources, loader.importer).evaluate(&nodes)?;
+
+  Ok(printer::print(
+    &css_nodes,
+    &loader.sources,
+    options.source_map,
+  ))
+}
+
+/// Compile the less `content` of the file `path`
+pub fn compile(
+  path: &str,
+  content: &str,
+  options: &CompileOptions,
+  importer: &dyn Importer,
+) -> Result<CompileResult, LessError> {
+  let mut loader = Loader {
+    importer,
+    sources: vec![],
+    imported: HashSet::from([path.to_string()]),
+    dependencies: vec![],
+  };
+
+  let result = compile_nodes(&mut loader, path, content, options);
+
+  match result {
+    Ok((css, source_map)) => Ok(CompileResult {
+      css,
+      source_map,
+      dependencies: loader.dependencies,
+    }),
+    Err(Error { message, span }) => {
+      let source = &loader.sources[span.file];
+      let (line, column) = source.location(span.start);
+
+      Err(LessError {
+        message,
+        file: source.path.clone(),
+        line: line + 1,
+        column: column + 1,
+      })
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use std::collections::HashMap;
+
+  use farmfe_toolkit::sourcemap::SourceMap;
+
+  use super::{compile, CompileOptions, Importer};
+
+  struct MemoryImporter(HashMap<&'static str, &'static str>);
+
+  impl Importer for MemoryImporter {
+    fn resolve(&self, source: &str, _importer: &str) -> Option<String> {
+      let path = format!("/src/{}", source.trim_start_matches("./"));
+
+      self.0.contains_key(path.as_str()).then_some(path)
+    }
+
+    fn read(&self, path: &str) -> Result<String, String> {
+      self
+        .0
+        .get(path)
+        .map(ToString::to_string)
+        .ok_or_else(|| format!("{path} not found"))
+    }
+
+    fn rebase_url(&self, url: &str, _file: &str) -> String {
+      format!("./styles/{url}")
+    }
+  }
+
+  fn compile_less(content: &str, options: &CompileOptions) -> String {
+    let importer = MemoryImporter(HashMap::from([(
+      "/src/styles/theme.less",
+      "@primary: #1890ff;\n.bordered(@width: 1px) {\n  border: @width solid @primary;\n}\n.icon { background: url(icon.png); }\n",
+    )]));
+
+    compile("/src/index.less", content, options, &importer)
+      .unwrap()
+      .css
+  }
+
+  #[test]
+  fn compile_variables_mixins_and_nesting() {
+    let css = compile_less(
+      r#"@import "./styles/theme";
+@gap: 8px;
+.card {
+  color: darken(@primary, 10%);
+  padding: (@gap / 2) @gap * 2;
+  .bordered(2px);
+  &:hover { color: @primary; }
+  @media (max-width: 768px) {
+    padding: 0;
+  }
+}
+"#,
+      &CompileOptions::default(),
+    );
+
+    assert_eq!(
+      css,
+      ".icon {\n  background: url(./styles/icon.png);\n}\n.card {\n  color: #0076e4;\n  padding: 4px 16px;\n  border: 2px solid #1890ff;\n}\n.card:hover {\n  color: #1890ff;\n}\n@media (max-width: 768px) {\n  .card {\n    padding: 0;\n  }\n}\n"
+    );
+  }
+
+  #[test]
+  fn modify_vars_and_additional_data() {
+    let css = compile_less(
+      ".a { color: @primary; width: @width; }",
+      &CompileOptions {
+        additional_data: Some("@import './styles/theme.less';\n@width: 10px;".to_string()),
+        modify_vars: vec![("@primary".to_string(), "red".to_string())],
+        source_map: false,
+      },
+    );
+
+    assert_eq!(
+      css,
+      ".icon {\n  background: url(./styles/icon.png);\n}\n.a {\n  color: red;\n  width: 10px;\n}\n"
+    );
+  }
+
+  #[test]
+  fn map_rules_and_declarations() {
+    let importer = MemoryImporter(HashMap::new());
+    let result = compile(
+      "/src/index.less",
+      ".a {\n  .b {\n    color: red;\n  }\n}\n",
+      &CompileOptions {
+        additional_data: Some("@c: red;".to_string()),
+        source_map: true,
+        ..Default::default()
+      },
+      &importer,
+    )
+    .unwrap();
+    let map = SourceMap::from_slice(result.source_map.unwrap().as_bytes()).unwrap();
+    let token = map.lookup_token(1, 2).unwrap();
+
+    assert_eq!(result.css, ".a .b {\n  color: red;\n}\n");
+    assert_eq!(
+      token.get_source().map(|source| source.to_string()),
+      Some("/src/index.less".to_string())
+    );
+    assert_eq!((token.get_src_line(), token.get_src_col()), (2, 4));
+  }
+
+  #[test]
+  fn report_error_location() {
+    let importer = MemoryImporter(HashMap::new());
+    let error = compile(
+      "/src/index.less",
+      ".a {\n  color: @missing;\n}\n",
+      &CompileOptions::default(),
+      &importer,
+    )
+    .unwrap_err();
+
+    assert_eq!(
+      error.to_string(),
+      "variable @missing is undefined in /src/index.less:2:3"
+    );
+  }
+}
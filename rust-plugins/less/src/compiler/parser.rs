@@ -0,0 +1,616 @@
+use std::rc::Rc;
+
+use super::{
+  ast::{MixinArg, MixinParam, Node, Span},
+  Error,
+};
+
+/// Called by the parser for each `@import`, returns the nodes that replace the import
+pub trait ImportHandler {
+  fn import(&mut self, params: &str, span: Span) -> Result<Vec<Node>, Error>;
+}
+
+enum Terminator {
+  /// `{` is consumed
+  Block,
+  /// `;` is consumed
+  Semicolon,
+  /// `}` of the parent block, not consumed
+  BlockEnd,
+  Eof,
+}
+
+pub struct Parser<'h> {
+  input: String,
+  pos: usize,
+  file: usize,
+  handler: &'h mut dyn ImportHandler,
+}
+
+impl<'h> Parser<'h> {
+  pub fn new(input: String, file: usize, handler: &'h mut dyn ImportHandler) -> Self {
+    Self {
+      input,
+      pos: 0,
+      file,
+      handler,
+    }
+  }
+
+  pub fn parse(mut self) -> Result<Vec<Node>, Error> {
+    self.parse_block(false)
+  }
+
+  fn span(&self, start: usize) -> Span {
+    Span {
+      file: self.file,
+      start,
+    }
+  }
+
+  fn error(&self, message: impl Into<String>, start: usize) -> Error {
+    Error::new(message, self.span(start))
+  }
+
+  fn peek_at(&self, pos: usize) -> Option<u8> {
+    self.input.as_bytes().get(pos).copied()
+  }
+
+  fn parse_block(&mut self, nested: bool) -> Result<Vec<Node>, Error> {
+    let block_start = self.pos;
+    let mut nodes = vec![];
+
+    loop {
+      self.skip_whitespace_and_comments(&mut nodes, !nested)?;
+
+      let Some(c) = self.peek_at(self.pos) else {
+        if nested {
+          return Err(self.error("missing closing `}`", block_start.saturating_sub(1)));
+        }
+
+        return Ok(nodes);
+      };
+
+      match c {
+        b'}' if nested => {
+          self.pos += 1;
+          return Ok(nodes);
+        }
+        b'}' => return Err(self.error("unexpected `}`", self.pos)),
+        b';' => {
+          self.pos += 1;
+          continue;
+        }
+        _ => {}
+      }
+
+      let start = self.pos;
+      let (prelude, terminator) = self.read_prelude()?;
+      let prelude = prelude.trim().to_string();
+
+      match terminator {
+        Terminator::Block => {
+          let node = self.parse_block_statement(prelude, start)?;
+          nodes.push(node);
+        }
+        Terminator::Semicolon | Terminator::BlockEnd | Terminator::Eof => {
+          self.parse_statement(&prelude, start, &mut nodes)?;
+        }
+      }
+    }
+  }
+
+  /// Skip whitespaces and comments, top level block comments are kept in the output
+  fn skip_whitespace_and_comments(
+    &mut self,
+    nodes: &mut Vec<Node>,
+    keep_comments: bool,
+  ) -> Result<(), Error> {
+    loop {
+      match self.peek_at(self.pos) {
+        Some(c) if c.is_ascii_whitespace() => self.pos += 1,
+        Some(b'/') if self.peek_at(self.pos + 1) == Some(b'/') => self.skip_line_comment(),
+        Some(b'/') if self.peek_at(self.pos + 1) == Some(b'*') => {
+          let start = self.pos;
+          self.skip_block_comment()?;
+
+          if keep_comments {
+            nodes.push(Node::Raw {
+              text: self.input[start..self.pos].to_string(),
+              span: self.span(start),
+            });
+          }
+        }
+        _ => return Ok(()),
+      }
+    }
+  }
+
+  fn skip_line_comment(&mut self) {
+    match self.input[self.pos..].find('\n') {
+      Some(index) => self.pos += index + 1,
+      None => self.pos = self.input.len(),
+    }
+  }
+
+  fn skip_block_comment(&mut self) -> Result<(), Error> {
+    let start = self.pos;
+
+    match self.input[self.pos + 2..].find("*/") {
+      Some(index) => {
+        self.pos += index + 4;
+        Ok(())
+      }
+      None => Err(self.error("missing closing `*/`", start)),
+    }
+  }
+
+  fn skip_string(&mut self, quote: u8) -> Result<(), Error> {
+    let start = self.pos;
+    self.pos += 1;
+
+    while let Some(c) = self.peek_at(self.pos) {
+      self.pos += 1;
+
+      if c == b'\\' {
+        self.pos += 1;
+      } else if c == quote {
+        return Ok(());
+      }
+    }
+
+    Err(self.error("unclosed string", start))
+  }
+
+  /// Read a selector, at-rule prelude or statement until `{`, `;` or `}` outside of strings and parentheses
+  fn read_prelude(&mut self) -> Result<(String, Terminator), Error> {
+    let mut prelude = String::new();
+    let mut depth = 0usize;
+
+    loop {
+      let Some(c) = self.peek_at(self.pos) else {
+        return Ok((prelude, Terminator::Eof));
+      };
+
+      match c {
+        b'"' | b'\'' => {
+          let start = self.pos;
+          self.skip_string(c)?;
+          prelude.push_str(&self.input[start..self.pos]);
+          continue;
+        }
+        b'/' if self.peek_at(self.pos + 1) == Some(b'*') => {
+          self.skip_block_comment()?;
+          prelude.push(' ');
+          continue;
+        }
+        b'/' if depth == 0 && self.peek_at(self.pos + 1) == Some(b'/') => {
+          self.skip_line_comment();
+          prelude.push(' ');
+          continue;
+        }
+        b'@' if self.peek_at(self.pos + 1) == Some(b'{') => {
+          // variable interpolation, `@{name}`
+          let start = self.pos;
+          let Some(index) = self.input[self.pos..].find('}') else {
+            return Err(self.error("missing closing `}` of the interpolation", start));
+          };
+          self.pos += index + 1;
+          prelude.push_str(&self.input[start..self.pos]);
+          continue;
+        }
+        b'(' | b'[' => depth += 1,
+        b')' | b']' => depth = depth.saturating_sub(1),
+        b'{' if depth == 0 => {
+          self.pos += 1;
+          return Ok((prelude, Terminator::Block));
+        }
+        b';' if depth == 0 => {
+          self.pos += 1;
+          return Ok((prelude, Terminator::Semicolon));
+        }
+        b'}' if depth == 0 => return Ok((prelude, Terminator::BlockEnd)),
+        _ => {}
+      }
+
+      let ch = self.input[self.pos..].chars().next().unwrap();
+      prelude.push(ch);
+      self.pos += ch.len_utf8();
+    }
+  }
+
+  fn parse_block_statement(&mut self, prelude: String, start: usize) -> Result<Node, Error> {
+    let span = self.span(start);
+
+    if let Some(rest) = prelude.strip_prefix('@') {
+      let name_len = ident_len(rest);
+      let (name, params) = rest.split_at(name_len);
+
+      if params.trim_start().starts_with(':') {
+        return Err(self.error("detached rulesets are not supported", start));
+      }
+
+      if name.is_empty() {
+        return Err(self.error(format!("unrecognised input `{prelude}`"), start));
+      }
+
+      let children = self.parse_block(true)?;
+
+      return Ok(Node::AtRule {
+        name: name.to_string(),
+        params: params.trim().to_string(),
+        children: Some(Rc::new(children)),
+        span,
+      });
+    }
+
+    if prelude.is_empty() {
+      return Err(self.error("missing selector", start));
+    }
+
+    if prelude.contains(":extend(") {
+      return Err(self.error("`:extend` is not supported", start));
+    }
+
+    if let Some((name, params, guard)) = parse_mixin_definition(&prelude) {
+      let children = self.parse_block(true)?;
+
+      return Ok(Node::MixinDefinition {
+        name,
+        params,
+        guard,
+        children: Rc::new(children),
+        span,
+      });
+    }
+
+    let (selector, guard) = match find_top_level(&prelude, " when ") {
+      Some(index) => (
+        prelude[..index].trim().to_string(),
+        Some(prelude[index + 6..].trim().to_string()),
+      ),
+      None => (prelude, None),
+    };
+    let children = self.parse_block(true)?;
+
+    Ok(Node::Rule {
+      selector,
+      guard,
+      children: Rc::new(children),
+      span,
+    })
+  }
+
+  fn parse_statement(
+    &mut self,
+    prelude: &str,
+    start: usize,
+    nodes: &mut Vec<Node>,
+  ) -> Result<(), Error> {
+    if prelude.is_empty() {
+      return Ok(());
+    }
+
+    let span = self.span(start);
+
+    if let Some(rest) = prelude.strip_prefix('@') {
+      let name_len = ident_len(rest);
+      let (name, params) = rest.split_at(name_len);
+
+      if name.is_empty() {
+        return Err(self.error(format!("unrecognised input `{prelude}`"), start));
+      }
+
+      if let Some(value) = params.trim_start().strip_prefix(':') {
+        nodes.push(Node::Variable {
+          name: name.to_string(),
+          value: value.trim().to_string(),
+          span,
+        });
+        return Ok(());
+      }
+
+      match name {
+        "import" => nodes.extend(self.handler.import(params.trim(), span)?),
+        "plugin" => return Err(self.error("`@plugin` is not supported", start)),
+        _ if params.trim_start().starts_with('(') => {
+          return Err(self.error("detached rulesets are not supported", start));
+        }
+        _ => nodes.push(Node::AtRule {
+          name: name.to_string(),
+          params: params.trim().to_string(),
+          children: None,
+          span,
+        }),
+      }
+
+      return Ok(());
+    }
+
+    if prelude.starts_with('.') || prelude.starts_with('#') {
+      let (path, args, important) = parse_mixin_call(prelude);
+
+      if path.is_empty() {
+        return Err(self.error(format!("unrecognised input `{prelude}`"), start));
+      }
+
+      nodes.push(Node::MixinCall {
+        path,
+        args,
+        important,
+        span,
+      });
+      return Ok(());
+    }
+
+    let Some(index) = find_top_level(prelude, ":") else {
+      return Err(self.error(format!("unrecognised input `{prelude}`"), start));
+    };
+    let name = prelude[..index].trim();
+
+    if name.ends_with('+') || name.ends_with("+_") {
+      return Err(self.error("merging properties is not supported", start));
+    }
+
+    let (value, important) = strip_important(prelude[index + 1..].trim());
+
+    nodes.push(Node::Declaration {
+      name: name.to_string(),
+      value: value.to_string(),
+      important,
+      span,
+    });
+
+    Ok(())
+  }
+}
+
+fn ident_len(s: &str) -> usize {
+  s.find(|c: char| !(c.is_alphanumeric() || c == '-' || c == '_'))
+    .unwrap_or(s.len())
+}
+
+/// Strip a trailing `!important`
+pub fn strip_important(value: &str) -> (&str, bool) {
+  if let Some(index) = value.rfind('!') {
+    let rest = value[index + 1..].trim();
+
+    if rest.eq_ignore_ascii_case("important") {
+      return (value[..index].trim_end(), true);
+    }
+  }
+
+  (value, false)
+}
+
+/// Find `pattern` outside of strings, parentheses and brackets
+pub fn find_top_level(s: &str, pattern: &str) -> Option<usize> {
+  let bytes = s.as_bytes();
+  let mut depth = 0usize;
+  let mut quote = None;
+  let mut i = 0;
+
+  while i < bytes.len() {
+    let c = bytes[i];
+
+    if let Some(q) = quote {
+      if c == b'\\' {
+        i += 1;
+      } else if c == q {
+        quote = None;
+      }
+      i += 1;
+      continue;
+    }
+
+    if depth == 0 && s[i..].starts_with(pattern) {
+      return Some(i);
+    }
+
+    match c {
+      b'"' | b'\'' => quote = Some(c),
+      b'(' | b'[' => depth += 1,
+      b')' | b']' => depth = depth.saturating_sub(1),
+      _ => {}
+    }
+
+    i += 1;
+  }
+
+  None
+}
+
+/// Split `s` by `separator` outside of strings, parentheses and brackets
+pub fn split_top_level(s: &str, separator: &str) -> Vec<String> {
+  let mut parts = vec![];
+  let mut rest = s;
+
+  while let Some(index) = find_top_level(rest, separator) {
+    parts.push(rest[..index].to_string());
+    rest = &rest[index + separator.len()..];
+  }
+
+  parts.push(rest.to_string());
+  parts
+}
+
+/// Index of the `)` matching the `(` at `open`
+fn matching_paren(s: &str, open: usize) -> Option<usize> {
+  find_top_level(&s[open + 1..], ")").map(|index| open + 1 + index)
+}
+
+/// Split mixin params or args by `;`, or by `,` when there is no `;`
+fn split_mixin_args(s: &str) -> Vec<String> {
+  let separator = if find_top_level(s, ";").is_some() {
+    ";"
+  } else {
+    ","
+  };
+
+  split_top_level(s, separator)
+    .into_iter()
+    .map(|part| part.trim().to_string())
+    .filter(|part| !part.is_empty())
+    .collect()
+}
+
+/// Split `@name: value` into the name and the value
+fn split_named(s: &str) -> Option<(String, String)> {
+  let rest = s.strip_prefix('@')?;
+  let len = ident_len(rest);
+  let value = rest[len..].trim_start().strip_prefix(':')?;
+
+  (len > 0).then(|| (rest[..len].to_string(), value.trim().to_string()))
+}
+
+fn parse_mixin_definition(prelude: &str) -> Option<(String, Vec<MixinParam>, Option<String>)> {
+  let first = prelude.chars().next()?;
+
+  if first != '.' && first != '#' {
+    return None;
+  }
+
+  let name_len = 1 + ident_len(&prelude[1..]);
+
+  if name_len == 1 {
+    return None;
+  }
+
+  let open = name_len + (prelude[name_len..].len() - prelude[name_len..].trim_start().len());
+
+  if !prelude[open..].starts_with('(') {
+    return None;
+  }
+
+  let close = matching_paren(prelude, open)?;
+  let rest = prelude[close + 1..].trim();
+  let guard = if rest.is_empty() {
+    None
+  } else {
+    Some(rest.strip_prefix("when")?.trim().to_string())
+  };
+
+  let params = split_mixin_args(&prelude[open + 1..close])
+    .into_iter()
+    .map(|param| {
+      if param == "..." {
+        MixinParam::Rest(None)
+      } else if let Some(name) = param.strip_prefix('@').and_then(|p| p.strip_suffix("...")) {
+        MixinParam::Rest(Some(name.to_string()))
+      } else if let Some((name, default)) = split_named(&param) {
+        MixinParam::Variable {
+          name,
+          default: Some(default),
+        }
+      } else if let Some(name) = param.strip_prefix('@') {
+        MixinParam::Variable {
+          name: name.to_string(),
+          default: None,
+        }
+      } else {
+        MixinParam::Literal(param)
+      }
+    })
+    .collect();
+
+  Some((prelude[..name_len].to_string(), params, guard))
+}
+
+fn parse_mixin_call(prelude: &str) -> (Vec<String>, Vec<MixinArg>, bool) {
+  let (prelude, important) = strip_important(prelude);
+  let (path, args) = match prelude.find('(') {
+    Some(open) => {
+      let close = matching_paren(prelude, open).unwrap_or(prelude.len());
+      let args = split_mixin_args(&prelude[open + 1..close])
+        .into_iter()
+        .map(|arg| match split_named(&arg) {
+          Some((name, value)) => MixinArg {
+            name: Some(name),
+            value,
+          },
+          None => MixinArg {
+            name: None,
+            value: arg,
+          },
+        })
+        .collect();
+
+      (&prelude[..open], args)
+    }
+    None => (prelude, vec![]),
+  };
+
+  // `#ns > .mixin`, `#ns.mixin` and `#ns .mixin` all point to `.mixin` in `#ns`
+  let mut segments: Vec<String> = vec![];
+
+  for c in path.chars() {
+    match c {
+      '.' | '#' => segments.push(c.to_string()),
+      c if c.is_whitespace() || c == '>' => {}
+      c => match segments.last_mut() {
+        Some(segment) => segment.push(c),
+        None => return (vec![], vec![], important),
+      },
+    }
+  }
+
+  (segments, args, important)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{ImportHandler, Parser};
+  use crate::compiler::{
+    ast::{MixinParam, Node, Span},
+    Error,
+  };
+
+  struct NoImport;
+
+  impl ImportHandler for NoImport {
+    fn import(&mut self, _params: &str, span: Span) -> Result<Vec<Node>, Error> {
+      Err(Error::new("unexpected import", span))
+    }
+  }
+
+  #[test]
+  fn parse_rules_and_mixins() {
+    let mut handler = NoImport;
+    let nodes = Parser::new(
+      "@a: 1px; // comment\n.m(@x; @y: 2px) when (@x > 0) { width: @x; }\n.b { .m(1px) !important; color: red }"
+        .to_string(),
+      0,
+      &mut handler,
+    )
+    .parse()
+    .unwrap();
+
+    assert_eq!(nodes.len(), 3);
+    assert!(
+      matches!(&nodes[0], Node::Variable { name, value, .. } if name == "a" && value == "1px")
+    );
+    let Node::MixinDefinition {
+      name,
+      params,
+      guard,
+      ..
+    } = &nodes[1]
+    else {
+      panic!("expected a mixin definition");
+    };
+    assert_eq!(name, ".m");
+    assert!(
+      matches!(&params[1], MixinParam::Variable { name, default: Some(d) } if name == "y" && d == "2px")
+    );
+    assert_eq!(guard.as_deref(), Some("(@x > 0)"));
+    let Node::Rule { children, .. } = &nodes[2] else {
+      panic!("expected a rule");
+    };
+    assert!(
+      matches!(&children[0], Node::MixinCall { path, important: true, .. } if path == &[".m"])
+    );
+    assert!(
+      matches!(&children[1], Node::Declaration { name, value, .. } if name == "color" && value == "red")
+    );
+  }
+}
@@ -0,0 +1,182 @@
+use std::collections::HashMap;
+
+use farmfe_toolkit::sourcemap::SourceMapBuilder;
+
+use super::{
+  ast::Span,
+  evaluator::{CssDeclaration, CssNode},
+  Source,
+};
+
+const INDENT: &str = "  ";
+
+/// Print the evaluated css in the expanded style, every rule, at-rule and declaration is mapped to
+/// the position it is written at in the less sources
+pub fn print(nodes: &[CssNode], sources: &[Source], source_map: bool) -> (String, Option<String>) {
+  let mut printer = Printer {
+    css: String::new(),
+    line: 0,
+    col: 0,
+    sources,
+    builder: source_map.then(|| SourceMapBuilder::new(None)),
+    source_ids: HashMap::new(),
+  };
+
+  // `@charset` and `@import` must be the first statements of the css
+  let is_hoisted = |node: &CssNode, prefix: &str| {
+    matches!(node, CssNode::Statement { text, .. } if text.starts_with(prefix))
+  };
+  let charsets = nodes.iter().filter(|node| is_hoisted(node, "@charset"));
+  let imports = nodes.iter().filter(|node| is_hoisted(node, "@import"));
+  let rest = nodes
+    .iter()
+    .filter(|node| !is_hoisted(node, "@charset") && !is_hoisted(node, "@import"));
+
+  for node in charsets.take(1).chain(imports).chain(rest) {
+    printer.print_node(node, 0);
+  }
+
+  let source_map = printer.builder.take().and_then(|builder| {
+    let map = builder.into_sourcemap();
+
+    if map.get_token_count() == 0 {
+      return None;
+    }
+
+    let mut buf = vec![];
+    map.to_writer(&mut buf).ok()?;
+    String::from_utf8(buf).ok()
+  });
+
+  (printer.css, source_map)
+}
+
+struct Printer<'a> {
+  css: String,
+  line: u32,
+  col: u32,
+  sources: &'a [Source],
+  builder: Option<SourceMapBuilder>,
+  source_ids: HashMap<usize, u32>,
+}
+
+impl Printer<'_> {
+  fn write(&mut self, text: &str) {
+    for c in text.chars() {
+      if c == '\n' {
+        self.line += 1;
+        self.col = 0;
+      } else {
+        self.col += c.len_utf16() as u32;
+      }
+    }
+
+    self.css.push_str(text);
+  }
+
+  fn write_indent(&mut self, depth: usize) {
+    self.write(&INDENT.repeat(depth));
+  }
+
+  fn add_mapping(&mut self, span: Span) {
+    let Some(builder) = &mut self.builder else {
+      return;
+    };
+    let source = &self.sources[span.file];
+
+    if !source.mapped {
+      return;
+    }
+
+    let src_id = *self.source_ids.entry(span.file).or_insert_with(|| {
+      let src_id = builder.add_source(source.path.clone().into());
+      builder.set_source_contents(src_id, Some(source.content.clone().into()));
+      src_id
+    });
+    let (src_line, src_col) = source.location(span.start);
+
+    builder.add_raw(
+      self.line,
+      self.col,
+      src_line as u32,
+      src_col as u32,
+      Some(src_id),
+      None,
+      false,
+    );
+  }
+
+  fn print_node(&mut self, node: &CssNode, depth: usize) {
+    match node {
+      CssNode::Rule {
+        selectors,
+        declarations,
+        span,
+      } => {
+        self.write_indent(depth);
+        self.add_mapping(*span);
+        let separator = format!(",\n{}", INDENT.repeat(depth));
+        self.write(&selectors.join(&separator));
+        self.write(" {\n");
+        self.print_declarations(declarations, depth + 1);
+        self.write_indent(depth);
+        self.write("}\n");
+      }
+      CssNode::AtRule {
+        name,
+        params,
+        declarations,
+        children,
+        span,
+      } => {
+        if declarations.is_empty() && children.is_empty() {
+          return;
+        }
+
+        self.write_indent(depth);
+        self.add_mapping(*span);
+        self.write(&format!("@{name}"));
+
+        if !params.is_empty() {
+          self.write(&format!(" {params}"));
+        }
+
+        self.write(" {\n");
+        self.print_declarations(declarations, depth + 1);
+
+        for child in children {
+          self.print_node(child, depth + 1);
+        }
+
+        self.write_indent(depth);
+        self.write("}\n");
+      }
+      CssNode::Statement { text, span } => {
+        self.write_indent(depth);
+        self.add_mapping(*span);
+        self.write(text);
+        self.write("\n");
+      }
+      CssNode::Raw { text, span } => {
+        self.write_indent(depth);
+        self.add_mapping(*span);
+        self.write(text);
+        self.write("\n");
+      }
+    }
+  }
+
+  fn print_declarations(&mut self, declarations: &[CssDeclaration], depth: usize) {
+    for declaration in declarations {
+      self.write_indent(depth);
+      self.add_mapping(declaration.span);
+      self.write(&format!("{}: {}", declaration.name, declaration.value));
+
+      if declaration.important {
+        self.write(" !important");
+      }
+
+      self.write(";\n");
+    }
+  }
+}
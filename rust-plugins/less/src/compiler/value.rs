@@ -0,0 +1,791 @@
+use std::fmt::{Display, Formatter};
+
+use super::{color::Color, functions};
+
+/// css functions whose arguments are kept as written, only variables are replaced
+const RAW_FUNCTIONS: [&str; 10] = [
+  "calc",
+  "-webkit-calc",
+  "-moz-calc",
+  "var",
+  "env",
+  "clamp",
+  "min",
+  "max",
+  "attr",
+  "expression",
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+  Number {
+    value: f64,
+    unit: String,
+  },
+  Color(Color),
+  /// a quoted string, `quote` is `None` for escaped strings like `~"..."` which are output without quotes
+  Str {
+    text: String,
+    quote: Option<char>,
+  },
+  Keyword(String),
+  Url(String),
+  /// a css function that is not evaluated, e.g. `translate(10px, 20px)`
+  Function {
+    name: String,
+    args: Vec<Value>,
+  },
+  List {
+    items: Vec<Value>,
+    comma: bool,
+  },
+  /// `/` outside of parentheses is not a division, e.g. `12px/1.5`
+  Slash {
+    left: Box<Value>,
+    right: Box<Value>,
+    spaced: bool,
+  },
+}
+
+impl Value {
+  pub fn number(value: f64, unit: &str) -> Self {
+    Self::Number {
+      value,
+      unit: unit.to_string(),
+    }
+  }
+
+  pub fn keyword(keyword: &str) -> Self {
+    Self::Keyword(keyword.to_string())
+  }
+
+  /// The value as it is printed, without the quotes of strings
+  pub fn unquoted(&self) -> String {
+    match self {
+      Value::Str { text, .. } => text.clone(),
+      _ => self.to_string(),
+    }
+  }
+
+  pub fn as_color(&self) -> Option<Color> {
+    match self {
+      Value::Color(color) => Some(color.clone()),
+      Value::Keyword(keyword) => Color::from_keyword(keyword),
+      _ => None,
+    }
+  }
+}
+
+impl Display for Value {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Value::Number { value, unit } => write!(f, "{}{unit}", format_number(*value)),
+      Value::Color(color) => write!(f, "{}", color.to_css()),
+      Value::Str {
+        text,
+        quote: Some(quote),
+      } => write!(f, "{quote}{text}{quote}"),
+      Value::Str { text, quote: None } => write!(f, "{text}"),
+      Value::Keyword(keyword) => write!(f, "{keyword}"),
+      Value::Url(url) => write!(f, "url({url})"),
+      Value::Function { name, args } => write!(f, "{name}({})", join(args, ", ")),
+      Value::List { items, comma } => write!(f, "{}", join(items, if *comma { ", " } else { " " })),
+      Value::Slash {
+        left,
+        right,
+        spaced,
+      } => {
+        if *spaced {
+          write!(f, "{left} / {right}")
+        } else {
+          write!(f, "{left}/{right}")
+        }
+      }
+    }
+  }
+}
+
+fn join(values: &[Value], separator: &str) -> String {
+  values
+    .iter()
+    .map(ToString::to_string)
+    .collect::<Vec<_>>()
+    .join(separator)
+}
+
+/// Numbers are rounded to 8 decimals like less does
+pub fn format_number(value: f64) -> String {
+  let rounded = (value * 1e8).round() / 1e8;
+
+  if rounded == 0.0 {
+    return "0".to_string();
+  }
+
+  format!("{rounded}")
+}
+
+/// Lookup of the variables and rebasing of urls while evaluating a value
+pub trait ValueContext {
+  fn variable(&mut self, name: &str) -> Result<Value, String>;
+  /// `url` is the content of `url()`, quotes included
+  fn url(&mut self, url: &str) -> String;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenKind {
+  Number(f64, String),
+  Color(String),
+  Str(String, Option<char>),
+  /// `@name`, or `@@name` stored as `@name`
+  Variable(String),
+  Ident(String),
+  Function(String),
+  Url(String),
+  RawFunction(String, String),
+  LParen,
+  RParen,
+  Comma,
+  Op(char),
+  Other(char),
+}
+
+#[derive(Debug)]
+struct Token {
+  kind: TokenKind,
+  space_before: bool,
+}
+
+fn is_ident_char(c: char) -> bool {
+  c.is_alphanumeric() || c == '-' || c == '_' || c == '\\' || !c.is_ascii()
+}
+
+fn read_ident(chars: &[char], mut i: usize) -> (String, usize) {
+  let mut ident = String::new();
+
+  while let Some(&c) = chars.get(i) {
+    if c == '\\' {
+      ident.push(c);
+      if let Some(&escaped) = chars.get(i + 1) {
+        ident.push(escaped);
+      }
+      i += 2;
+      continue;
+    }
+
+    if !is_ident_char(c) {
+      break;
+    }
+
+    ident.push(c);
+    i += 1;
+  }
+
+  (ident, i)
+}
+
+/// Read a quoted string starting at `i`, returns the content without the quotes
+fn read_string(chars: &[char], i: usize) -> Result<(String, usize), String> {
+  let quote = chars[i];
+  let mut text = String::new();
+  let mut i = i + 1;
+
+  while let Some(&c) = chars.get(i) {
+    if c == '\\' {
+      text.push(c);
+      if let Some(&escaped) = chars.get(i + 1) {
+        text.push(escaped);
+      }
+      i += 2;
+      continue;
+    }
+
+    if c == quote {
+      return Ok((text, i + 1));
+    }
+
+    text.push(c);
+    i += 1;
+  }
+
+  Err("unclosed string".to_string())
+}
+
+/// Read the content of a function call until the matching `)`, `i` points after the `(`
+fn read_raw_args(chars: &[char], mut i: usize) -> Result<(String, usize), String> {
+  let mut depth = 0usize;
+  let mut raw = String::new();
+
+  while let Some(&c) = chars.get(i) {
+    match c {
+      '"' | '\'' => {
+        let (text, next) = read_string(chars, i)?;
+        raw.push(c);
+        raw.push_str(&text);
+        raw.push(c);
+        i = next;
+        continue;
+      }
+      '(' => depth += 1,
+      ')' if depth == 0 => return Ok((raw, i + 1)),
+      ')' => depth -= 1,
+      _ => {}
+    }
+
+    raw.push(c);
+    i += 1;
+  }
+
+  Err("missing closing `)`".to_string())
+}
+
+fn read_number(chars: &[char], mut i: usize) -> (f64, String, usize) {
+  let start = i;
+
+  if matches!(chars[i], '+' | '-') {
+    i += 1;
+  }
+
+  while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+    i += 1;
+  }
+
+  if chars.get(i) == Some(&'.') && chars.get(i + 1).is_some_and(|c| c.is_ascii_digit()) {
+    i += 1;
+    while chars.get(i).is_some_and(|c| c.is_ascii_digit()) {
+      i += 1;
+    }
+  }
+
+  let number: String = chars[start..i].iter().collect();
+  let unit_start = i;
+
+  if chars.get(i) == Some(&'%') {
+    i += 1;
+  } else {
+    while chars.get(i).is_some_and(|c| c.is_ascii_alphabetic()) {
+      i += 1;
+    }
+  }
+
+  (
+    number.parse().unwrap_or(0.0),
+    chars[unit_start..i].iter().collect(),
+    i,
+  )
+}
+
+fn starts_number(chars: &[char], i: usize) -> bool {
+  let digit_at = |i: usize| chars.get(i).is_some_and(|c| c.is_ascii_digit());
+
+  match chars[i] {
+    '0'..='9' => true,
+    '.' => digit_at(i + 1),
+    '+' | '-' => digit_at(i + 1) || (chars.get(i + 1) == Some(&'.') && digit_at(i + 2)),
+    _ => false,
+  }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+  let chars: Vec<char> = input.chars().collect();
+  let mut tokens: Vec<Token> = vec![];
+  let mut space_before = false;
+  let mut i = 0;
+
+  while i < chars.len() {
+    let c = chars[i];
+
+    if c.is_whitespace() {
+      space_before = true;
+      i += 1;
+      continue;
+    }
+
+    let next_is_space = chars.get(i + 1).is_none_or(|c| c.is_whitespace());
+    // `1px -2px` is a list of two numbers while `1px - 2px` and `1px-2px` are operations
+    let signed_number_allowed = match tokens.last() {
+      None => true,
+      Some(token) => {
+        matches!(
+          token.kind,
+          TokenKind::Op(_) | TokenKind::LParen | TokenKind::Comma | TokenKind::Function(_)
+        ) || space_before
+      }
+    };
+
+    let kind = match c {
+      '"' | '\'' => {
+        let (text, next) = read_string(&chars, i)?;
+        i = next;
+        TokenKind::Str(text, Some(c))
+      }
+      '~' if matches!(chars.get(i + 1), Some('"' | '\'')) => {
+        let (text, next) = read_string(&chars, i + 1)?;
+        i = next;
+        TokenKind::Str(text, None)
+      }
+      '@' => {
+        let (name, next) = match chars.get(i + 1) {
+          Some('{') => {
+            let (name, next) = read_ident(&chars, i + 2);
+            if chars.get(next) != Some(&'}') {
+              return Err("missing closing `}` of the interpolation".to_string());
+            }
+            (name, next + 1)
+          }
+          Some('@') => {
+            let (name, next) = read_ident(&chars, i + 2);
+            (format!("@{name}"), next)
+          }
+          _ => read_ident(&chars, i + 1),
+        };
+
+        if name.is_empty() || name == "@" {
+          return Err("expected a variable name after `@`".to_string());
+        }
+
+        i = next;
+        TokenKind::Variable(name)
+      }
+      '#' => {
+        let (ident, next) = read_ident(&chars, i + 1);
+        let text = format!("#{ident}");
+        i = next;
+
+        if Color::from_hex(&text).is_some() {
+          TokenKind::Color(text)
+        } else {
+          TokenKind::Ident(text)
+        }
+      }
+      '(' => {
+        i += 1;
+        TokenKind::LParen
+      }
+      ')' => {
+        i += 1;
+        TokenKind::RParen
+      }
+      ',' => {
+        i += 1;
+        TokenKind::Comma
+      }
+      '*' | '/' => {
+        i += 1;
+        TokenKind::Op(c)
+      }
+      '+' | '-' | '.' | '0'..='9'
+        if starts_number(&chars, i)
+          && (c.is_ascii_digit() || c == '.' || signed_number_allowed) =>
+      {
+        let (value, unit, next) = read_number(&chars, i);
+        i = next;
+        TokenKind::Number(value, unit)
+      }
+      '+' | '-'
+        if next_is_space
+          || starts_number(&chars, i)
+          || !chars.get(i + 1).is_some_and(|c| is_ident_char(*c)) =>
+      {
+        i += 1;
+        TokenKind::Op(c)
+      }
+      '!' if chars.get(i + 1).is_some_and(|c| c.is_alphabetic()) => {
+        let (ident, next) = read_ident(&chars, i + 1);
+        i = next;
+        TokenKind::Ident(format!("!{ident}"))
+      }
+      c if is_ident_char(c) && !c.is_ascii_digit() => {
+        let (ident, next) = read_ident(&chars, i);
+        i = next;
+
+        if chars.get(i) == Some(&'(') {
+          let lower = ident.to_ascii_lowercase();
+
+          if lower == "url" {
+            let (raw, next) = read_raw_args(&chars, i + 1)?;
+            i = next;
+            TokenKind::Url(raw.trim().to_string())
+          } else if RAW_FUNCTIONS.contains(&lower.as_str()) {
+            let (raw, next) = read_raw_args(&chars, i + 1)?;
+            i = next;
+            TokenKind::RawFunction(ident, raw)
+          } else {
+            i += 1;
+            TokenKind::Function(ident)
+          }
+        } else {
+          TokenKind::Ident(ident)
+        }
+      }
+      _ => {
+        i += 1;
+        TokenKind::Other(c)
+      }
+    };
+
+    tokens.push(Token { kind, space_before });
+    space_before = false;
+  }
+
+  Ok(tokens)
+}
+
+/// Replace `@{name}` in a string with the value of the variable
+pub fn interpolate(text: &str, ctx: &mut dyn ValueContext) -> Result<String, String> {
+  let mut result = String::new();
+  let mut rest = text;
+
+  while let Some(index) = rest.find("@{") {
+    let Some(end) = rest[index..].find('}') else {
+      break;
+    };
+    result.push_str(&rest[..index]);
+    result.push_str(&ctx.variable(&rest[index + 2..index + end])?.unquoted());
+    rest = &rest[index + end + 1..];
+  }
+
+  result.push_str(rest);
+  Ok(result)
+}
+
+/// Replace `@{name}` and `@name` in raw css, e.g. the params of `@media` or the content of `calc()`
+pub fn substitute_variables(text: &str, ctx: &mut dyn ValueContext) -> Result<String, String> {
+  let text = interpolate(text, ctx)?;
+  let chars: Vec<char> = text.chars().collect();
+  let mut result = String::new();
+  let mut i = 0;
+
+  while i < chars.len() {
+    if chars[i] == '@' {
+      let (name, next) = read_ident(&chars, i + 1);
+
+      if !name.is_empty() {
+        result.push_str(&ctx.variable(&name)?.unquoted());
+        i = next;
+        continue;
+      }
+    }
+
+    result.push(chars[i]);
+    i += 1;
+  }
+
+  Ok(result)
+}
+
+/// Evaluate a property or variable value
+pub fn evaluate(input: &str, ctx: &mut dyn ValueContext) -> Result<Value, String> {
+  let tokens = tokenize(input)?;
+
+  if tokens.is_empty() {
+    return Ok(Value::keyword(""));
+  }
+
+  let mut parser = ExprParser {
+    tokens,
+    pos: 0,
+    parens: 0,
+    ctx,
+  };
+  let value = parser.parse_list()?;
+
+  if parser.pos < parser.tokens.len() {
+    return Err(format!("unexpected `)` in `{input}`"));
+  }
+
+  Ok(value)
+}
+
+struct ExprParser<'c> {
+  tokens: Vec<Token>,
+  pos: usize,
+  /// depth of parentheses, `/` is a division only inside parentheses
+  parens: usize,
+  ctx: &'c mut dyn ValueContext,
+}
+
+impl ExprParser<'_> {
+  fn peek(&self) -> Option<&TokenKind> {
+    self.tokens.get(self.pos).map(|token| &token.kind)
+  }
+
+  fn parse_list(&mut self) -> Result<Value, String> {
+    let mut items = vec![self.parse_space_list()?];
+
+    while self.peek() == Some(&TokenKind::Comma) {
+      self.pos += 1;
+      items.push(self.parse_space_list()?);
+    }
+
+    Ok(if items.len() == 1 {
+      items.pop().unwrap()
+    } else {
+      Value::List { items, comma: true }
+    })
+  }
+
+  fn parse_space_list(&mut self) -> Result<Value, String> {
+    let mut items = vec![];
+
+    while let Some(kind) = self.peek() {
+      if matches!(kind, TokenKind::Comma | TokenKind::RParen) {
+        break;
+      }
+
+      items.push(self.parse_additive()?);
+    }
+
+    match items.len() {
+      0 => Err("expected a value".to_string()),
+      1 => Ok(items.pop().unwrap()),
+      _ => Ok(Value::List {
+        items,
+        comma: false,
+      }),
+    }
+  }
+
+  fn parse_additive(&mut self) -> Result<Value, String> {
+    let mut left = self.parse_multiplicative()?;
+
+    while let Some(TokenKind::Op(op @ ('+' | '-'))) = self.peek() {
+      let op = *op;
+      let space_before = self.tokens[self.pos].space_before;
+      let Some(next) = self.tokens.get(self.pos + 1) else {
+        break;
+      };
+
+      // `a -b` starts a new item of the list
+      if space_before && !next.space_before {
+        break;
+      }
+
+      self.pos += 1;
+      let right = self.parse_multiplicative()?;
+      left = operate(op, left, right)?;
+    }
+
+    Ok(left)
+  }
+
+  fn parse_multiplicative(&mut self) -> Result<Value, String> {
+    let mut left = self.parse_unary()?;
+
+    while let Some(TokenKind::Op(op @ ('*' | '/'))) = self.peek() {
+      let op = *op;
+      let spaced = self.tokens[self.pos].space_before;
+      self.pos += 1;
+      let right = self.parse_unary()?;
+
+      left = if op == '/' && self.parens == 0 {
+        Value::Slash {
+          left: Box::new(left),
+          right: Box::new(right),
+          spaced,
+        }
+      } else {
+        operate(op, left, right)?
+      };
+    }
+
+    Ok(left)
+  }
+
+  fn parse_unary(&mut self) -> Result<Value, String> {
+    if self.peek() == Some(&TokenKind::Op('-')) {
+      self.pos += 1;
+
+      return Ok(match self.parse_unary()? {
+        Value::Number { value, unit } => Value::Number {
+          value: -value,
+          unit,
+        },
+        value => Value::Keyword(format!("-{value}")),
+      });
+    }
+
+    self.parse_primary()
+  }
+
+  fn parse_primary(&mut self) -> Result<Value, String> {
+    let Some(token) = self.tokens.get(self.pos) else {
+      return Err("expected a value".to_string());
+    };
+    let kind = token.kind.clone();
+    self.pos += 1;
+
+    match kind {
+      TokenKind::Number(value, unit) => Ok(Value::Number { value, unit }),
+      TokenKind::Color(text) => Ok(Value::Color(Color::from_hex(&text).unwrap())),
+      TokenKind::Str(text, quote) => Ok(Value::Str {
+        text: interpolate(&text, self.ctx)?,
+        quote,
+      }),
+      TokenKind::Variable(name) => match name.strip_prefix('@') {
+        Some(inner) => {
+          let name = self.ctx.variable(inner)?.unquoted();
+          self.ctx.variable(&name)
+        }
+        None => self.ctx.variable(&name),
+      },
+      TokenKind::Ident(ident) => Ok(Value::Keyword(ident)),
+      TokenKind::Url(raw) => {
+        let url = if raw.starts_with('@') && !raw.starts_with("@{") {
+          self.ctx.variable(&raw[1..])?.to_string()
+        } else {
+          interpolate(&raw, self.ctx)?
+        };
+
+        Ok(Value::Url(self.ctx.url(&url)))
+      }
+      TokenKind::RawFunction(name, raw) => Ok(Value::Keyword(format!(
+        "{name}({})",
+        substitute_variables(&raw, self.ctx)?
+      ))),
+      TokenKind::Function(name) => {
+        let mut args = vec![];
+
+        if self.peek() == Some(&TokenKind::RParen) {
+          self.pos += 1;
+        } else {
+          loop {
+            args.push(self.parse_space_list()?);
+
+            match self.peek() {
+              Some(TokenKind::Comma) => self.pos += 1,
+              Some(TokenKind::RParen) => {
+                self.pos += 1;
+                break;
+              }
+              _ => return Err(format!("missing closing `)` of `{name}()`")),
+            }
+          }
+        }
+
+        match functions::call(&name, &args)? {
+          Some(value) => Ok(value),
+          None => Ok(Value::Function { name, args }),
+        }
+      }
+      TokenKind::LParen => {
+        self.parens += 1;
+        let value = self.parse_list()?;
+        self.parens -= 1;
+
+        if self.peek() != Some(&TokenKind::RParen) {
+          return Err("missing closing `)`".to_string());
+        }
+
+        self.pos += 1;
+        Ok(value)
+      }
+      TokenKind::Op(c) | TokenKind::Other(c) => Ok(Value::Keyword(c.to_string())),
+      TokenKind::Comma | TokenKind::RParen => Err("unexpected token".to_string()),
+    }
+  }
+}
+
+fn apply(op: char, a: f64, b: f64) -> Result<f64, String> {
+  match op {
+    '+' => Ok(a + b),
+    '-' => Ok(a - b),
+    '*' => Ok(a * b),
+    '/' if b == 0.0 => Err("division by zero".to_string()),
+    '/' => Ok(a / b),
+    _ => Err(format!("unknown operator `{op}`")),
+  }
+}
+
+pub fn operate(op: char, left: Value, right: Value) -> Result<Value, String> {
+  if let (
+    Value::Number {
+      value: a,
+      unit: left_unit,
+    },
+    Value::Number {
+      value: b,
+      unit: right_unit,
+    },
+  ) = (&left, &right)
+  {
+    let unit = if left_unit.is_empty() {
+      right_unit
+    } else {
+      left_unit
+    };
+
+    return Ok(Value::number(apply(op, *a, *b)?, unit));
+  }
+
+  let channels = |color: &Color| [color.r, color.g, color.b];
+  let (left_channels, right_channels, alpha) = match (&left, &right) {
+    (Value::Number { value, .. }, other) => {
+      let color = other
+        .as_color()
+        .ok_or_else(|| format!("cannot operate on `{left}` and `{right}`"))?;
+      ([*value; 3], channels(&color), color.a)
+    }
+    (other, Value::Number { value, .. }) => {
+      let color = other
+        .as_color()
+        .ok_or_else(|| format!("cannot operate on `{left}` and `{right}`"))?;
+      (channels(&color), [*value; 3], color.a)
+    }
+    _ => match (left.as_color(), right.as_color()) {
+      (Some(a), Some(b)) => (channels(&a), channels(&b), a.a),
+      _ => return Err(format!("cannot operate on `{left}` and `{right}`")),
+    },
+  };
+
+  Ok(Value::Color(Color::new(
+    apply(op, left_channels[0], right_channels[0])?,
+    apply(op, left_channels[1], right_channels[1])?,
+    apply(op, left_channels[2], right_channels[2])?,
+    alpha,
+  )))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::{evaluate, Value, ValueContext};
+
+  struct Vars;
+
+  impl ValueContext for Vars {
+    fn variable(&mut self, name: &str) -> Result<Value, String> {
+      match name {
+        "width" => Ok(Value::number(10.0, "px")),
+        "name" => Ok(Value::Str {
+          text: "width".to_string(),
+          quote: Some('"'),
+        }),
+        _ => Err(format!("variable @{name} is undefined")),
+      }
+    }
+
+    fn url(&mut self, url: &str) -> String {
+      url.to_string()
+    }
+  }
+
+  fn eval(input: &str) -> String {
+    evaluate(input, &mut Vars).unwrap().to_string()
+  }
+
+  #[test]
+  fn evaluate_operations() {
+    assert_eq!(eval("@width * 2 + 1"), "21px");
+    assert_eq!(eval("(@width / 4)"), "2.5px");
+    assert_eq!(
+      eval("12px/1.5 Arial, sans-serif"),
+      "12px/1.5 Arial, sans-serif"
+    );
+    assert_eq!(eval("0 -@width"), "0 -10px");
+    assert_eq!(eval("@width -2px"), "10px -2px");
+    assert_eq!(eval("@@name"), "10px");
+    assert_eq!(eval("#111 + #222"), "#333333");
+    assert_eq!(eval("calc(100% - @width)"), "calc(100% - 10px)");
+    assert_eq!(eval("~\"w-@{width}\""), "w-10px");
+    assert_eq!(eval("translate(@width,0)"), "translate(10px, 0)");
+  }
+}
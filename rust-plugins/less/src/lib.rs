@@ -0,0 +1,257 @@
+#![deny(clippy::all)]
+#![allow(clippy::result_large_err)]
+
+use std::{
+  collections::BTreeMap,
+  fmt::{Debug, Formatter},
+  path::Path,
+  sync::Arc,
+};
+
+use compiler::{compile, CompileOptions, Importer};
+use farmfe_core::{
+  config::Config,
+  context::CompilationContext,
+  error::CompilationError,
+  module::{ModuleId, ModuleType},
+  plugin::{
+    Plugin, PluginHookContext, PluginLoadHookParam, PluginLoadHookResult, PluginResolveHookParam,
+    PluginTransformHookParam, PluginTransformHookResult, ResolveKind,
+  },
+  serde_json::{self, Value},
+};
+use farmfe_macro_plugin::farm_plugin;
+use farmfe_toolkit::{fs::read_file_utf8, regex::Regex};
+use farmfe_utils::relative;
+use serde::Deserialize;
+
+mod compiler;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct LessOptions {
+  /// prepended to each less file, e.g. `@import '@/styles/variables.less';`
+  additional_data: Option<String>,
+  /// global variables that override the variables of the less files
+  modify_vars: BTreeMap<String, Value>,
+  source_map: Option<bool>,
+}
+
+#[farm_plugin]
+pub struct FarmPluginLess {
+  less_options: LessOptions,
+  regex: Regex,
+}
+
+impl FarmPluginLess {
+  pub fn new(_config: &Config, options: String) -> Self {
+    Self {
+      less_options: serde_json::from_str(&options).unwrap_or_default(),
+      regex: Regex::new(r#"\.less$"#).unwrap(),
+    }
+  }
+
+  fn compile_options(&self, source_map: bool) -> CompileOptions {
+    CompileOptions {
+      additional_data: self.less_options.additional_data.clone(),
+      modify_vars: self
+        .less_options
+        .modify_vars
+        .iter()
+        .map(|(name, value)| {
+          let value = match value {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+          };
+          (name.clone(), value)
+        })
+        .collect(),
+      source_map: self.less_options.source_map.unwrap_or(true) && source_map,
+    }
+  }
+}
+
+impl Debug for FarmPluginLess {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    f.debug_struct("FarmPluginLess").finish()
+  }
+}
+
+/// Resolve `@import` through the resolver of Farm, so aliases and packages work the same as in scripts
+struct FarmImporter<'a> {
+  root_file: &'a str,
+  root_importer: ModuleId,
+  context: &'a Arc<CompilationContext>,
+}
+
+impl FarmImporter<'_> {
+  fn resolve_with_farm(&self, source: &str, importer: &str) -> Option<String> {
+    let importer = if importer == self.root_file {
+      self.root_importer.clone()
+    } else {
+      ModuleId::new(importer, "", &self.context.config.root)
+    };
+
+    self
+      .context
+      .plugin_driver
+      .resolve(
+        &PluginResolveHookParam {
+          source: source.to_string(),
+          importer: Some(importer),
+          kind: ResolveKind::CssAtImport,
+        },
+        self.context,
+        &PluginHookContext::default(),
+      )
+      .ok()
+      .flatten()
+      .map(|result| result.resolved_path)
+  }
+}
+
+impl Importer for FarmImporter<'_> {
+  fn resolve(&self, source: &str, importer: &str) -> Option<String> {
+    // `~` is the prefix of packages in node_modules
+    if let Some(package) = source.strip_prefix('~') {
+      return self.resolve_with_farm(package.trim_start_matches('/'), importer);
+    }
+
+    let relative_path = Path::new(importer).parent()?.join(source);
+
+    if relative_path.is_file() {
+      return Some(relative_path.to_string_lossy().to_string());
+    }
+
+    self.resolve_with_farm(source, importer)
+  }
+
+  fn read(&self, path: &str) -> Result<String, String> {
+    read_file_utf8(path).map_err(|error| error.to_string())
+  }
+
+  fn rebase_url(&self, url: &str, file: &str) -> String {
+    let (quote, raw) = match url.chars().next() {
+      Some(quote @ ('"' | '\'')) => (quote.to_string(), url.trim_matches(quote)),
+      _ => (String::new(), url),
+    };
+
+    if raw.is_empty()
+      || raw.starts_with(['/', '#', '@', '~'])
+      || raw.starts_with("data:")
+      || raw.contains("://")
+    {
+      return url.to_string();
+    }
+
+    let (Some(file_dir), Some(root_dir)) =
+      (Path::new(file).parent(), Path::new(self.root_file).parent())
+    else {
+      return url.to_string();
+    };
+
+    if file_dir == root_dir {
+      return url.to_string();
+    }
+
+    let rebased = relative(
+      &root_dir.to_string_lossy(),
+      &file_dir.join(raw).to_string_lossy(),
+    );
+    let rebased = if rebased.starts_with('.') {
+      rebased
+    } else {
+      format!("./{rebased}")
+    };
+
+    format!("{quote}{rebased}{quote}")
+  }
+}
+
+fn is_less_module_type(module_type: &ModuleType) -> bool {
+  matches!(module_type, ModuleType::Custom(t) if t == "less")
+}
+
+impl Plugin for FarmPluginLess {
+  fn name(&self) -> &str {
+    "FarmPluginLess"
+  }
+
+  // this plugin should be executed before internal plugins
+  fn priority(&self) -> i32 {
+    101
+  }
+
+  fn config(&self, config: &mut Config) -> farmfe_core::error::Result<Option<()>> {
+    if config.resolve.extensions.iter().all(|e| e != "less") {
+      config.resolve.extensions.push("less".to_string());
+    }
+
+    Ok(Some(()))
+  }
+
+  fn load(
+    &self,
+    param: &PluginLoadHookParam,
+    _context: &Arc<CompilationContext>,
+    _hook_context: &PluginHookContext,
+  ) -> farmfe_core::error::Result<Option<PluginLoadHookResult>> {
+    if param.query.is_empty() && self.regex.is_match(param.resolved_path) {
+      let content = read_file_utf8(param.resolved_path);
+
+      if let Ok(content) = content {
+        return Ok(Some(PluginLoadHookResult {
+          content,
+          module_type: ModuleType::Custom(String::from("less")),
+          source_map: None,
+        }));
+      }
+    }
+
+    Ok(None)
+  }
+
+  fn transform(
+    &self,
+    param: &PluginTransformHookParam,
+    context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<PluginTransformHookResult>> {
+    if !is_less_module_type(&param.module_type) {
+      return Ok(None);
+    }
+
+    let options = self.compile_options(context.sourcemap_enabled(&param.module_id));
+    let importer = FarmImporter {
+      root_file: param.resolved_path,
+      root_importer: param.module_id.clone().into(),
+      context,
+    };
+    let result =
+      compile(param.resolved_path, &param.content, &options, &importer).map_err(|e| {
+        CompilationError::TransformError {
+          resolved_path: param.resolved_path.to_string(),
+          msg: e.to_string(),
+        }
+      })?;
+
+    let watched_files = result
+      .dependencies
+      .iter()
+      .map(|path| ModuleId::new(path, "", &context.config.root))
+      .collect();
+
+    context
+      .add_watch_files(
+        ModuleId::new(param.resolved_path, "", &context.config.root),
+        watched_files,
+      )
+      .expect("cannot add file to watch graph");
+
+    Ok(Some(PluginTransformHookResult {
+      content: result.css,
+      source_map: result.source_map,
+      module_type: Some(ModuleType::Css),
+      ignore_previous_source_map: false,
+    }))
+  }
+}
@@ -0,0 +1,109 @@
+use std::sync::Arc;
+
+use farmfe_core::{
+  config::Config,
+  context::CompilationContext,
+  module::ModuleType,
+  plugin::{Plugin, PluginTransformHookParam},
+  HashMap,
+};
+use farmfe_plugin_less::FarmPluginLess;
+use farmfe_testing_helpers::fixture;
+use farmfe_toolkit::fs::read_file_utf8;
+
+fn transform(resolved_path: &str, content: String, options: &str) -> String {
+  let config = Config::default();
+  let plugin = Arc::new(FarmPluginLess::new(&config, options.to_string()));
+  let context = Arc::new(CompilationContext::new(config, vec![plugin.clone()]).unwrap());
+  let transformed = plugin
+    .transform(
+      &PluginTransformHookParam {
+        resolved_path,
+        content,
+        module_type: ModuleType::Custom(String::from("less")),
+        query: vec![],
+        meta: HashMap::from_iter([]),
+        module_id: resolved_path.to_string(),
+        source_map_chain: vec![],
+      },
+      &context,
+    )
+    .unwrap()
+    .unwrap();
+
+  assert_eq!(transformed.module_type, Some(ModuleType::Css));
+  transformed.content
+}
+
+#[test]
+fn unsupported_functions_are_errors() {
+  fixture!("tests/fixtures/basic/index.less", |file, _cwd| {
+    let resolved_path = file.to_string_lossy().to_string();
+    let config = Config::default();
+    let plugin = Arc::new(FarmPluginLess::new(&config, "{}".to_string()));
+    let context = Arc::new(CompilationContext::new(config, vec![plugin.clone()]).unwrap());
+    let result = plugin.transform(
+      &PluginTransformHookParam {
+        resolved_path: &resolved_path,
+        content: ".a { color: contrast(#000); }".to_string(),
+        module_type: ModuleType::Custom(String::from("less")),
+        query: vec![],
+        meta: HashMap::from_iter([]),
+        module_id: resolved_path.clone(),
+        source_map_chain: vec![],
+      },
+      &context,
+    );
+
+    assert!(result.is_err());
+  });
+}
+
+#[test]
+fn compile_imports_and_rebase_urls() {
+  fixture!("tests/fixtures/basic/index.less", |file, _cwd| {
+    let resolved_path = file.to_string_lossy().to_string();
+    let content = read_file_utf8(&resolved_path).unwrap();
+    let css = transform(&resolved_path, content, "{}");
+
+    assert_eq!(
+      css.replace("\r\n", "\n"),
+      ".logo {\n  background: url(./partials/logo.png);\n}\n.button {\n  padding: 8px 16px;\n  color: #1890ff;\n  background: url(./icon.png);\n}\n.button:hover {\n  color: #4ba9ff;\n}\n"
+    );
+  });
+}
+
+#[test]
+fn modify_vars_override_imported_variables() {
+  fixture!("tests/fixtures/basic/index.less", |file, _cwd| {
+    let resolved_path = file.to_string_lossy().to_string();
+    let css = transform(
+      &resolved_path,
+      ".a { color: @primary; margin: @gap; }".to_string(),
+      r#"{
+        "additionalData": "@import './partials/theme';",
+        "modifyVars": { "primary": "red", "@gap": 4 }
+      }"#,
+    );
+
+    assert!(css.contains(".a {\n  color: red;\n  margin: 4;\n}\n"));
+  });
+}
+
+/// The fixtures in `tests/fixtures/real-world` are taken from real-world stylesheets, their `expected.css` is the
+/// reviewed output of this compiler rather than the output of `lessc`, so they only guard against regressions
+#[test]
+fn compile_real_world_stylesheets() {
+  fixture!("tests/fixtures/real-world/**/input.less", |file, _cwd| {
+    let resolved_path = file.to_string_lossy().to_string();
+    let content = read_file_utf8(&resolved_path).unwrap();
+    let expected = read_file_utf8(&file.with_file_name("expected.css").to_string_lossy()).unwrap();
+    let css = transform(&resolved_path, content, "{}");
+
+    assert_eq!(
+      css.replace("\r\n", "\n"),
+      expected.replace("\r\n", "\n"),
+      "{resolved_path}"
+    );
+  });
+}
@@ -1,6 +1,8 @@
 export interface TailwindCSSOptions {
   /**
-   * Paths or patterns to scan for TailwindCSS candidates.
+   * Globs of files to scan for TailwindCSS candidates, relative to the project root.
+   * Use it for files outside the module graph like server templates or Markdown.
+   * Globs starting with `!` exclude files.
    */
   content?: string[];
   /**
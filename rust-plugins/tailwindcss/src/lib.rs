@@ -1,16 +1,17 @@
 #![deny(clippy::all)]
 
 use std::collections::{HashMap, HashSet};
-use std::path;
 use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use std::{fs, path};
 
 use farmfe_core::{
   config::Config,
   context::CompilationContext,
   module::{ModuleId, ModuleType},
   plugin::{
-    hooks::freeze_module::PluginFreezeModuleHookParam, Plugin, PluginTransformHookParam,
-    PluginTransformHookResult,
+    hooks::freeze_module::PluginFreezeModuleHookParam, Plugin, PluginModuleGraphUpdatedHookParam,
+    PluginTransformHookParam, PluginTransformHookResult,
   },
   serde_json,
 };
@@ -19,9 +20,10 @@ use farmfe_toolkit::css::parse_css_stylesheet;
 use farmfe_toolkit::lazy_static::lazy_static;
 use farmfe_toolkit::regex::Regex;
 
+use farmfe_ecosystem_tailwindcss::design_system::SourceDirective;
 use farmfe_ecosystem_tailwindcss::TailwindConfig;
 use farmfe_ecosystem_tailwindcss_node::compile::{self, CompileOptions};
-use tailwindcss_oxide::{ChangedContent, Scanner};
+use tailwindcss_oxide::{ChangedContent, PublicSourceEntry, Scanner};
 
 const PKG_NAME: &str = "@farmfe/plugin-tailwindcss";
 
@@ -39,9 +41,9 @@ lazy_static! {
 #[derive(serde::Deserialize, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 struct TailwindCSSOptions {
-  /// Extra paths / globs to scan for candidates.
+  /// Extra paths / globs to scan for candidates, relative to the project root.
+  /// Globs starting with `!` exclude files.
   #[serde(default)]
-  #[allow(dead_code)]
   content: Vec<String>,
   /// Optional Tailwind config payload passed through directly to the Rust core crate.
   #[serde(default)]
@@ -64,13 +66,25 @@ fn has_tailwind_directive(content: &str) -> bool {
   content.contains("@import \"tailwindcss\"") || content.contains("@import 'tailwindcss'")
 }
 
+/// Candidates of a file matched by the `content` option or `@source` directives.
+#[derive(Default)]
+struct ScannedFile {
+  /// Modified time when the file was scanned, the file is rescanned when it changes.
+  modified: Option<SystemTime>,
+  candidates: HashSet<String>,
+}
+
 #[farm_plugin]
 pub struct FarmPluginTailwindCSS {
-  _options: TailwindCSSOptions,
-  /// Set of all candidates collected from scanned source files.
-  candidates: Arc<Mutex<HashSet<String>>>,
-  /// Set of module IDs that contributed candidates.
-  candidate_sources: Arc<Mutex<HashSet<String>>>,
+  options: TailwindCSSOptions,
+  root: String,
+  /// Candidates of each transformed module, keyed by module id. The value
+  /// is the resolved path of the module and its candidates, replaced every
+  /// time the module is transformed again so removed classes are dropped.
+  module_candidates: Arc<Mutex<HashMap<String, (String, HashSet<String>)>>>,
+  /// Files scanned for each CSS root by `content` and `@source`, keyed by
+  /// the resolved path of the CSS root.
+  content_files: Arc<Mutex<HashMap<String, HashMap<String, ScannedFile>>>>,
   /// Original (untransformed) CSS content for CSS root files that need
   /// deferred TailwindCSS compilation. The actual compile-and-inline pass
   /// runs in `freeze_module`, by which time the candidate set is complete.
@@ -78,13 +92,14 @@ pub struct FarmPluginTailwindCSS {
 }
 
 impl FarmPluginTailwindCSS {
-  pub fn new(_config: &Config, options: String) -> Self {
+  pub fn new(config: &Config, options: String) -> Self {
     let parsed: TailwindCSSOptions = serde_json::from_str(&options).unwrap_or_default();
 
     Self {
-      _options: parsed,
-      candidates: Arc::new(Mutex::new(HashSet::new())),
-      candidate_sources: Arc::new(Mutex::new(HashSet::new())),
+      options: parsed,
+      root: config.root.clone(),
+      module_candidates: Arc::new(Mutex::new(HashMap::new())),
+      content_files: Arc::new(Mutex::new(HashMap::new())),
       pending_css: Arc::new(Mutex::new(HashMap::new())),
     }
   }
@@ -98,6 +113,125 @@ impl FarmPluginTailwindCSS {
     )])
   }
 
+  /// Replace the candidates of a transformed module.
+  fn set_module_candidates(&self, module_id: &str, resolved_path: &str, candidates: Vec<String>) {
+    let mut module_candidates = self.module_candidates.lock().unwrap();
+
+    if candidates.is_empty() {
+      module_candidates.remove(module_id);
+    } else {
+      module_candidates.insert(
+        module_id.to_string(),
+        (resolved_path.to_string(), candidates.into_iter().collect()),
+      );
+    }
+  }
+
+  /// Globs of the `content` option and the `@source` directives of a CSS root.
+  /// `content` globs are relative to the project root, `@source` globs are
+  /// relative to the CSS file.
+  fn content_sources(
+    &self,
+    css_base: &str,
+    directives: &[SourceDirective],
+  ) -> Vec<PublicSourceEntry> {
+    let mut sources: Vec<PublicSourceEntry> = self
+      .options
+      .content
+      .iter()
+      .map(|pattern| create_source_entry(&self.root, pattern, false))
+      .collect();
+
+    for directive in directives {
+      match directive {
+        SourceDirective::Include(pattern) => {
+          sources.push(create_source_entry(css_base, pattern, false))
+        }
+        SourceDirective::Exclude(pattern) => {
+          sources.push(create_source_entry(css_base, pattern, true))
+        }
+        SourceDirective::Inline(_) | SourceDirective::NotInline(_) => {}
+      }
+    }
+
+    sources
+  }
+
+  /// Scan the files matched by `sources` for the CSS root. Files not changed
+  /// since the last scan reuse their candidates, files no longer matched are
+  /// dropped. Returns the matched files.
+  fn scan_content_files(&self, css_root: &str, sources: Vec<PublicSourceEntry>) -> Vec<String> {
+    let files = if sources.iter().any(|source| !source.negated) {
+      Scanner::new(sources).get_files()
+    } else {
+      vec![]
+    };
+
+    let mut content_files = self.content_files.lock().unwrap();
+    let mut previous = content_files.remove(css_root).unwrap_or_default();
+    let mut scanned = HashMap::new();
+
+    for file in &files {
+      let modified = fs::metadata(file).and_then(|meta| meta.modified()).ok();
+      let scanned_file = match previous.remove(file) {
+        Some(scanned_file) if modified.is_some() && scanned_file.modified == modified => {
+          scanned_file
+        }
+        _ => ScannedFile {
+          modified,
+          candidates: fs::read_to_string(file)
+            .map(|content| self.scan_candidates(&content, Self::get_extension(file)))
+            .unwrap_or_default()
+            .into_iter()
+            .collect(),
+        },
+      };
+
+      scanned.insert(file.clone(), scanned_file);
+    }
+
+    content_files.insert(css_root.to_string(), scanned);
+
+    files
+  }
+
+  /// All candidates of the CSS root: candidates of transformed modules,
+  /// files matched by `content` / `@source` and `@source inline(...)`,
+  /// without the ones excluded by `@source not inline(...)`.
+  fn collect_candidates(&self, css_root: &str, directives: &[SourceDirective]) -> Vec<String> {
+    let mut candidates: HashSet<String> = self
+      .module_candidates
+      .lock()
+      .unwrap()
+      .values()
+      .flat_map(|(_, candidates)| candidates.iter().cloned())
+      .collect();
+
+    if let Some(files) = self.content_files.lock().unwrap().get(css_root) {
+      candidates.extend(
+        files
+          .values()
+          .flat_map(|file| file.candidates.iter().cloned()),
+      );
+    }
+
+    for directive in directives {
+      match directive {
+        SourceDirective::Inline(list) => {
+          candidates.extend(list.split_whitespace().map(|c| c.to_string()))
+        }
+        SourceDirective::NotInline(list) => {
+          for candidate in list.split_whitespace() {
+            candidates.remove(candidate);
+          }
+        }
+        SourceDirective::Include(_) | SourceDirective::Exclude(_) => {}
+      }
+    }
+
+    candidates.into_iter().collect()
+  }
+
   /// Check whether `id` looks like a CSS root file that should trigger
   /// TailwindCSS generation.
   fn is_css_root_file(id: &str) -> bool {
@@ -145,20 +279,7 @@ impl Plugin for FarmPluginTailwindCSS {
     if Self::is_candidate_source_file(resolved_path) {
       let extension = Self::get_extension(resolved_path);
       let new_candidates = self.scan_candidates(&param.content, extension);
-
-      if !new_candidates.is_empty() {
-        let mut candidates = self.candidates.lock().unwrap();
-        for c in new_candidates {
-          candidates.insert(c);
-        }
-        drop(candidates);
-
-        self
-          .candidate_sources
-          .lock()
-          .unwrap()
-          .insert(resolved_path.to_string());
-      }
+      self.set_module_candidates(&param.module_id, resolved_path, new_candidates);
 
       // Pass through — we don't modify the source.
       return Ok(None);
@@ -188,11 +309,21 @@ impl Plugin for FarmPluginTailwindCSS {
     // Also scan the CSS itself for candidates inside string literals / @apply
     // values — Tailwind's official scanner picks these up.
     let new_candidates = self.scan_candidates(&param.content, "css");
-    if !new_candidates.is_empty() {
-      let mut candidates = self.candidates.lock().unwrap();
-      for c in new_candidates {
-        candidates.insert(c);
-      }
+    self.set_module_candidates(&param.module_id, resolved_path, new_candidates);
+
+    Ok(None)
+  }
+
+  fn module_graph_updated(
+    &self,
+    param: &PluginModuleGraphUpdatedHookParam,
+    _context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<()>> {
+    // Drop candidates of removed modules so they no longer generate utilities
+    let mut module_candidates = self.module_candidates.lock().unwrap();
+
+    for removed in &param.removed_modules_ids {
+      module_candidates.remove(&removed.to_string());
     }
 
     Ok(None)
@@ -238,7 +369,7 @@ impl Plugin for FarmPluginTailwindCSS {
         },
         should_rewrite_urls: true,
         config: self
-          ._options
+          .options
           .config
           .as_ref()
           .cloned()
@@ -262,7 +393,9 @@ impl Plugin for FarmPluginTailwindCSS {
       return Ok(None);
     }
 
-    let candidates: Vec<String> = self.candidates.lock().unwrap().iter().cloned().collect();
+    let sources = self.content_sources(&base, compiler.sources());
+    let content_files = self.scan_content_files(&resolved_path, sources);
+    let candidates = self.collect_candidates(&resolved_path, compiler.sources());
 
     let css = compiler.build(&candidates);
 
@@ -276,21 +409,26 @@ impl Plugin for FarmPluginTailwindCSS {
       }
     };
 
-    // Register watch edges so changes to candidate sources invalidate the
-    // CSS root module on subsequent rebuilds.
-    let sources: Vec<String> = self
-      .candidate_sources
+    // Register watch edges so changes to candidate sources and files matched
+    // by `content` / `@source` invalidate the CSS root module on subsequent
+    // rebuilds.
+    let mut sources: HashSet<String> = self
+      .module_candidates
       .lock()
       .unwrap()
-      .iter()
-      .cloned()
+      .values()
+      .map(|(resolved_path, _)| resolved_path.clone())
       .collect();
-    for source in &sources {
-      let _ = context.add_watch_files(
-        param.module.id.clone(),
-        vec![ModuleId::new(source, "", &context.config.root)],
-      );
-    }
+    sources.extend(content_files);
+    sources.remove(&resolved_path);
+
+    let _ = context.add_watch_files(
+      param.module.id.clone(),
+      sources
+        .iter()
+        .map(|source| ModuleId::new(source, "", &context.config.root))
+        .collect(),
+    );
 
     param.module.meta.as_css_mut().set_ast(parsed.ast);
     param.module.content = Arc::new(css);
@@ -299,6 +437,19 @@ impl Plugin for FarmPluginTailwindCSS {
   }
 }
 
+fn create_source_entry(base: &str, pattern: &str, negated: bool) -> PublicSourceEntry {
+  let (pattern, negated) = match pattern.strip_prefix('!') {
+    Some(pattern) => (pattern, !negated),
+    None => (pattern, negated),
+  };
+
+  PublicSourceEntry {
+    base: base.to_string(),
+    pattern: pattern.to_string(),
+    negated,
+  }
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -308,9 +459,10 @@ mod tests {
   /// fields.
   fn make_plugin() -> FarmPluginTailwindCSS {
     FarmPluginTailwindCSS {
-      _options: TailwindCSSOptions::default(),
-      candidates: Arc::new(Mutex::new(HashSet::new())),
-      candidate_sources: Arc::new(Mutex::new(HashSet::new())),
+      options: TailwindCSSOptions::default(),
+      root: env!("CARGO_MANIFEST_DIR").to_string(),
+      module_candidates: Arc::new(Mutex::new(HashMap::new())),
+      content_files: Arc::new(Mutex::new(HashMap::new())),
       pending_css: Arc::new(Mutex::new(HashMap::new())),
     }
   }
@@ -338,6 +490,66 @@ mod tests {
     }
   }
 
+  #[test]
+  fn module_candidates_are_replaced_on_rescan() {
+    let plugin = make_plugin();
+
+    plugin.set_module_candidates("/src/a.tsx", "/src/a.tsx", vec!["flex".to_string()]);
+    plugin.set_module_candidates("/src/a.tsx", "/src/a.tsx", vec!["grid".to_string()]);
+    assert_eq!(plugin.collect_candidates("/src/app.css", &[]), vec!["grid"]);
+
+    plugin.set_module_candidates("/src/a.tsx", "/src/a.tsx", vec![]);
+    assert!(plugin.collect_candidates("/src/app.css", &[]).is_empty());
+  }
+
+  #[test]
+  fn collect_candidates_with_inline_sources() {
+    let plugin = make_plugin();
+
+    plugin.set_module_candidates(
+      "/src/a.tsx",
+      "/src/a.tsx",
+      vec!["flex".to_string(), "unused".to_string()],
+    );
+    let mut candidates = plugin.collect_candidates(
+      "/src/app.css",
+      &[
+        SourceDirective::Inline("block grid".to_string()),
+        SourceDirective::NotInline("unused".to_string()),
+      ],
+    );
+    candidates.sort();
+    assert_eq!(candidates, vec!["block", "flex", "grid"]);
+  }
+
+  #[test]
+  fn scan_content_files_works() {
+    let mut plugin = make_plugin();
+    plugin.options.content = vec![
+      "tests/fixtures/content/templates/**/*".to_string(),
+      "!tests/fixtures/content/templates/ignored.html".to_string(),
+    ];
+
+    let sources = plugin.content_sources(&plugin.root, &[]);
+    let files = plugin.scan_content_files("/src/app.css", sources);
+    assert_eq!(files.len(), 1);
+    assert!(files[0].ends_with("page.md"));
+
+    let candidates = plugin.collect_candidates("/src/app.css", &[]);
+    assert!(candidates.iter().any(|c| c == "underline"));
+    assert!(!candidates.iter().any(|c| c == "line-through"));
+
+    // files no longer matched are dropped
+    let files = plugin.scan_content_files("/src/app.css", vec![]);
+    assert!(files.is_empty());
+    assert!(
+      !plugin
+        .collect_candidates("/src/app.css", &[])
+        .iter()
+        .any(|c| c == "underline")
+    );
+  }
+
   #[test]
   fn is_css_root_file_works() {
     assert!(FarmPluginTailwindCSS::is_css_root_file("/src/app.css"));
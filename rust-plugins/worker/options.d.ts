@@ -2,4 +2,12 @@ import type { UserConfig } from "@farmfe/core"
 export interface IPluginOptions {
   isBuild?: boolean
   compilerConfig?: UserConfig['compilation']
+  /**
+   * Default output format of the workers imported with query suffix, default to `classic`
+   */
+  format?: 'classic' | 'module'
+  /**
+   * Conditions used when resolving the modules imported by workers, default to `['worker']`
+   */
+  conditions?: string[]
 }
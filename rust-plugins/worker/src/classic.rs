@@ -0,0 +1,220 @@
+use farmfe_core::{
+  swc_common::{SyntaxContext, DUMMY_SP},
+  swc_ecma_ast::{
+    CallExpr, Callee, ClassDecl, Decl, DefaultDecl, Expr, ExprOrSpread, ExprStmt, FnDecl, Ident,
+    IdentName, KeyValueProp, Lit, MemberExpr, MemberProp, MetaPropKind, Module as SwcModule,
+    ModuleDecl, ModuleItem, ObjectLit, Prop, PropName, PropOrSpread, Stmt, Str,
+  },
+};
+use farmfe_toolkit::swc_ecma_visit::{VisitMut, VisitMutWith};
+
+/// Transform a rendered worker to a classic script, which can not contain `import`/`export` statements:
+/// * `import 'external'` and `export * from 'external'` are loaded by `importScripts('external')`
+/// * exports of the worker entry are removed, a worker communicates by `postMessage`
+/// * `import.meta.url` is replaced by `self.location.href`
+///
+/// Bindings imported from external modules are not available in classic workers, an error is returned for them.
+pub fn transform_to_classic_worker(ast: &mut SwcModule) -> Result<(), String> {
+  let mut scripts = vec![];
+  let mut body = vec![];
+
+  for item in ast.body.drain(..) {
+    let ModuleItem::ModuleDecl(module_decl) = item else {
+      body.push(item);
+      continue;
+    };
+
+    match module_decl {
+      ModuleDecl::Import(import) if import.type_only => {}
+      ModuleDecl::Import(import) => {
+        let src = import.src.value.to_string_lossy().into_owned();
+
+        if !import.specifiers.is_empty() {
+          return Err(format!(
+            "bindings imported from external module `{src}` are not available in classic workers, use a module worker instead"
+          ));
+        }
+
+        scripts.push(src);
+      }
+      ModuleDecl::ExportAll(export_all) => {
+        scripts.push(export_all.src.value.to_string_lossy().into_owned());
+      }
+      ModuleDecl::ExportNamed(named_export) => {
+        if let Some(src) = named_export.src {
+          scripts.push(src.value.to_string_lossy().into_owned());
+        }
+      }
+      ModuleDecl::ExportDecl(export_decl) => {
+        body.push(ModuleItem::Stmt(Stmt::Decl(export_decl.decl)));
+      }
+      ModuleDecl::ExportDefaultDecl(export_default_decl) => match export_default_decl.decl {
+        DefaultDecl::Fn(fn_expr) => body.push(ModuleItem::Stmt(match fn_expr.ident {
+          Some(ident) => Stmt::Decl(Decl::Fn(FnDecl {
+            ident,
+            declare: false,
+            function: fn_expr.function,
+          })),
+          None => expr_stmt(Expr::Fn(fn_expr)),
+        })),
+        DefaultDecl::Class(class_expr) => body.push(ModuleItem::Stmt(match class_expr.ident {
+          Some(ident) => Stmt::Decl(Decl::Class(ClassDecl {
+            ident,
+            declare: false,
+            class: class_expr.class,
+          })),
+          None => expr_stmt(Expr::Class(class_expr)),
+        })),
+        DefaultDecl::TsInterfaceDecl(_) => {}
+      },
+      ModuleDecl::ExportDefaultExpr(export_default_expr) => {
+        body.push(ModuleItem::Stmt(expr_stmt(*export_default_expr.expr)));
+      }
+      module_decl => body.push(ModuleItem::ModuleDecl(module_decl)),
+    }
+  }
+
+  if !scripts.is_empty() {
+    scripts.dedup();
+    body.insert(0, ModuleItem::Stmt(import_scripts_stmt(scripts)));
+  }
+
+  ast.body = body;
+  ast.visit_mut_with(&mut ClassicImportMetaVisitor);
+
+  Ok(())
+}
+
+/// `import.meta.url` => `self.location.href`, `import.meta` => `{ url: self.location.href }`
+struct ClassicImportMetaVisitor;
+
+impl VisitMut for ClassicImportMetaVisitor {
+  fn visit_mut_expr(&mut self, expr: &mut Expr) {
+    match expr {
+      Expr::Member(MemberExpr {
+        obj,
+        prop: MemberProp::Ident(IdentName { sym, .. }),
+        ..
+      }) if sym == "url" && is_import_meta(obj) => {
+        *expr = location_href_expr();
+      }
+      Expr::MetaProp(_) if is_import_meta(expr) => {
+        *expr = Expr::Object(ObjectLit {
+          span: DUMMY_SP,
+          props: vec![PropOrSpread::Prop(Box::new(Prop::KeyValue(KeyValueProp {
+            key: PropName::Ident(IdentName::new("url".into(), DUMMY_SP)),
+            value: Box::new(location_href_expr()),
+          })))],
+        });
+      }
+      _ => expr.visit_mut_children_with(self),
+    }
+  }
+}
+
+fn is_import_meta(expr: &Expr) -> bool {
+  matches!(expr, Expr::MetaProp(meta_prop) if matches!(meta_prop.kind, MetaPropKind::ImportMeta))
+}
+
+fn ident(sym: &str) -> Ident {
+  Ident::new(sym.into(), DUMMY_SP, SyntaxContext::empty())
+}
+
+fn member_expr(obj: Expr, prop: &str) -> Expr {
+  Expr::Member(MemberExpr {
+    span: DUMMY_SP,
+    obj: Box::new(obj),
+    prop: MemberProp::Ident(IdentName::new(prop.into(), DUMMY_SP)),
+  })
+}
+
+fn location_href_expr() -> Expr {
+  member_expr(member_expr(Expr::Ident(ident("self")), "location"), "href")
+}
+
+fn expr_stmt(expr: Expr) -> Stmt {
+  Stmt::Expr(ExprStmt {
+    span: DUMMY_SP,
+    expr: Box::new(expr),
+  })
+}
+
+/// `importScripts("a", "b");`
+fn import_scripts_stmt(scripts: Vec<String>) -> Stmt {
+  expr_stmt(Expr::Call(CallExpr {
+    span: DUMMY_SP,
+    ctxt: SyntaxContext::empty(),
+    callee: Callee::Expr(Box::new(Expr::Ident(ident("importScripts")))),
+    args: scripts
+      .into_iter()
+      .map(|script| ExprOrSpread {
+        spread: None,
+        expr: Box::new(Expr::Lit(Lit::Str(Str {
+          span: DUMMY_SP,
+          value: script.as_str().into(),
+          raw: None,
+        }))),
+      })
+      .collect(),
+    type_args: None,
+  }))
+}
+
+#[cfg(test)]
+mod tests {
+  use std::sync::Arc;
+
+  use farmfe_core::{swc_ecma_ast::EsVersion, swc_ecma_parser::Syntax};
+  use farmfe_toolkit::script::{codegen_module, parse_module, ParseScriptModuleResult};
+
+  use super::transform_to_classic_worker;
+
+  fn transform(code: &str) -> Result<String, String> {
+    let ParseScriptModuleResult {
+      mut ast,
+      source_map,
+      ..
+    } = parse_module(
+      &"worker.js".into(),
+      Arc::new(code.to_string()),
+      Syntax::Es(Default::default()),
+      EsVersion::EsNext,
+    )
+    .unwrap();
+
+    transform_to_classic_worker(&mut ast)?;
+
+    let code = codegen_module(&ast, source_map, None, Default::default(), None).unwrap();
+    Ok(String::from_utf8(code).unwrap())
+  }
+
+  #[test]
+  fn classic_worker() {
+    let code = transform(
+      r#"import 'https://cdn.example.com/lib.js';
+export * from './polyfill.js';
+const url = new URL('./a.wasm', import.meta.url);
+export const a = 1;
+export default function onMessage() {}
+self.onmessage = onMessage;
+export { url };
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+      code,
+      r#"importScripts("https://cdn.example.com/lib.js", "./polyfill.js");
+const url = new URL('./a.wasm', self.location.href);
+const a = 1;
+function onMessage() {}
+self.onmessage = onMessage;
+"#
+    );
+  }
+
+  #[test]
+  fn classic_worker_imported_bindings() {
+    assert!(transform("import { a } from 'external';\nconsole.log(a);").is_err());
+  }
+}
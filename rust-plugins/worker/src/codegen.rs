@@ -6,7 +6,7 @@ use farmfe_toolkit::fs::{
 };
 use regress::Regex as JsRegex;
 
-use crate::constants::WORKER_OR_SHARED_WORKER_RE;
+use crate::{constants::WORKER_OR_SHARED_WORKER_RE, options::WorkerFormat};
 
 // Placeholder embedded in the inline worker module.  It is replaced with the
 // actual base64-encoded bundle bytes in the `generate_end` hook after Farm has
@@ -97,13 +97,26 @@ fn worker_constructor(module_id: &str) -> &'static str {
   }
 }
 
+/// Worker constructor options, module workers are always created with `{ type: "module" }`.
+fn worker_options(format: WorkerFormat) -> &'static str {
+  match format {
+    WorkerFormat::Classic => "options",
+    WorkerFormat::Module => r#"{ type: "module", ...options }"#,
+  }
+}
+
 /// Generate the placeholder wrapper module for an inline (`?worker&inline`) worker.
 ///
 /// The placeholder string `__FARM_INLINE_WORKER:<entry_name>:END__` is replaced
 /// with the actual base64-encoded, self-contained worker bundle in the
 /// `generate_end` hook after Farm has compiled the DynamicEntry resource.
-pub fn get_inline_worker_placeholder_code(module_id: &str, entry_name: &str) -> String {
+pub fn get_inline_worker_placeholder_code(
+  module_id: &str,
+  entry_name: &str,
+  format: WorkerFormat,
+) -> String {
   let constructor = worker_constructor(module_id);
+  let options = worker_options(format);
   let placeholder = format!("{INLINE_PLACEHOLDER_PREFIX}{entry_name}{INLINE_PLACEHOLDER_SUFFIX}");
   let encoded_decl = format!(r#"const encodedJs = "{placeholder}";"#);
 
@@ -118,13 +131,13 @@ export default function WorkerWrapper(options) {{
   try {{
     objURL = blob && (self.URL || self.webkitURL).createObjectURL(blob);
     if (!objURL) throw '';
-    const worker = new {constructor}(objURL, options);
+    const worker = new {constructor}(objURL, {options});
     worker.addEventListener("error", () => {{
       (self.URL || self.webkitURL).revokeObjectURL(objURL);
     }});
     return worker;
   }} catch(e) {{
-    return new {constructor}("data:text/javascript;base64," + encodedJs, options);
+    return new {constructor}("data:text/javascript;base64," + encodedJs, {options});
   }} finally {{
     objURL && (self.URL || self.webkitURL).revokeObjectURL(objURL);
   }}
@@ -134,7 +147,7 @@ export default function WorkerWrapper(options) {{
     format!(
       r#"{encoded_decl}
 export default function WorkerWrapper(options) {{
-  return new {constructor}("data:text/javascript;base64," + encodedJs, options);
+  return new {constructor}("data:text/javascript;base64," + encodedJs, {options});
 }}"#
     )
   }
@@ -142,19 +155,24 @@ export default function WorkerWrapper(options) {{
 
 /// Generate the JS wrapper module for a non-inline, URL-based worker.
 ///
-/// Worker constructor options are forwarded as-is, so callers can choose
-/// `{ type: "module" }` when the worker output contains ESM imports.
+/// Worker constructor options are forwarded, `{ type: "module" }` is added for module workers.
 ///
 /// - With `?url`: exports the URL string directly.
 /// - Otherwise: exports a `WorkerWrapper` factory function.
-pub fn get_worker_module_code(module_id: &str, worker_url: &str, is_url: bool) -> String {
+pub fn get_worker_module_code(
+  module_id: &str,
+  worker_url: &str,
+  is_url: bool,
+  format: WorkerFormat,
+) -> String {
   let constructor = worker_constructor(module_id);
+  let options = worker_options(format);
   if is_url {
     return format!(r#"export default "{worker_url}""#);
   }
   format!(
     r#"export default function WorkerWrapper(options) {{
-  return new {constructor}("{worker_url}", options);
+  return new {constructor}("{worker_url}", {options});
 }}"#
   )
 }
@@ -163,7 +181,8 @@ pub fn get_worker_module_code(module_id: &str, worker_url: &str, is_url: bool) -
 mod tests {
   use farmfe_core::config::Config;
 
-  use super::compute_worker_entry_name;
+  use super::{compute_worker_entry_name, get_worker_module_code};
+  use crate::options::WorkerFormat;
 
   #[test]
   fn worker_entry_name_is_stable_for_same_source_with_different_queries() {
@@ -200,4 +219,23 @@ mod tests {
 
     assert_eq!(backslash_entry, slash_entry);
   }
+
+  #[test]
+  fn module_worker_is_created_with_module_type() {
+    let classic = get_worker_module_code(
+      "src/worker.ts?worker",
+      "/worker.js",
+      false,
+      WorkerFormat::Classic,
+    );
+    let module = get_worker_module_code(
+      "src/worker.ts?worker&module",
+      "/worker.js",
+      false,
+      WorkerFormat::Module,
+    );
+
+    assert!(classic.contains(r#"new Worker("/worker.js", options)"#));
+    assert!(module.contains(r#"new Worker("/worker.js", { type: "module", ...options })"#));
+  }
 }
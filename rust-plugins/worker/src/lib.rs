@@ -1,33 +1,42 @@
 #![deny(clippy::all)]
 
+mod classic;
 mod codegen;
 mod constants;
 mod options;
 mod resource_patch;
 
-use std::sync::{Arc, Mutex};
+use std::{
+  collections::HashMap,
+  sync::{Arc, Mutex},
+};
 
 use crate::{
+  classic::transform_to_classic_worker,
   codegen::{
     compute_worker_entry_name, compute_worker_url, get_inline_worker_placeholder_code,
     get_worker_module_code,
   },
   constants::{WORKER_IMPORT_META_URL_RE, WORKER_OR_SHARED_WORKER_RE},
-  options::Options,
+  options::{Options, WorkerFormat, WORKER_CONDITION, WORKER_ENV_QUERY},
   resource_patch::patch_inline_worker_resources,
 };
 use farmfe_core::{
   config::Config,
   context::CompilationContext,
+  error::CompilationError,
   module::{ModuleId, ModuleType},
   plugin::{
     Plugin, PluginAnalyzeDepsHookParam, PluginAnalyzeDepsHookResultEntry, PluginHookContext,
-    PluginLoadHookResult, PluginResolveHookParam, PluginTransformHookResult, ResolveKind,
+    PluginLoadHookResult, PluginResolveHookParam, PluginResolveHookResult,
+    PluginTransformHookResult, ResolveKind,
   },
+  resource::{meta_data::ResourcePotMetaData, resource_pot::ResourcePot},
   serde_json::{self, Map, Value},
 };
 use farmfe_macro_plugin::farm_plugin;
-use farmfe_utils::relative;
+use farmfe_toolkit::resolve::ADDITIONAL_RESOLVE_CONDITIONS;
+use farmfe_utils::{parse_query, relative};
 use regress::{Match, Regex as JsRegex};
 
 // ---------------------------------------------------------------------------
@@ -39,10 +48,12 @@ pub struct FarmfePluginWorker {
   /// Workers discovered in `transform()` for `new Worker(new URL(…))` patterns.
   /// Importer module_id → Vec<(resolved_path, entry_name)>.
   transform_worker_deps: Mutex<std::collections::HashMap<String, Vec<(String, String)>>>,
+  /// Output format of each worker, entry_name → format. The first usage of a worker source decides its format.
+  worker_formats: Mutex<HashMap<String, WorkerFormat>>,
 }
 
 impl FarmfePluginWorker {
-  fn new(_config: &Config, options: String) -> Self {
+  pub fn new(_config: &Config, options: String) -> Self {
     let options: Value = serde_json::from_str(&options).unwrap_or(Value::Object(Map::new()));
     let mut compiler_config = options
       .get("compilerConfig")
@@ -53,13 +64,42 @@ impl FarmfePluginWorker {
     {
       map.insert("presetEnv".to_string(), Value::Bool(true));
     }
+    let format = options
+      .get("format")
+      .and_then(|format| serde_json::from_value(format.clone()).ok())
+      .unwrap_or_default();
+    let conditions = options
+      .get("conditions")
+      .and_then(|conditions| serde_json::from_value(conditions.clone()).ok())
+      .unwrap_or_else(|| vec![WORKER_CONDITION.to_string()]);
     Self {
       options: Options {
         compiler_config: serde_json::from_value(compiler_config).ok(),
+        format,
+        conditions,
       },
       transform_worker_deps: Mutex::new(std::collections::HashMap::new()),
+      worker_formats: Mutex::new(HashMap::new()),
     }
   }
+
+  /// Record the output format of a worker entry, returns the format that is actually used.
+  fn record_worker_format(&self, entry_name: &str, format: WorkerFormat) -> WorkerFormat {
+    *self
+      .worker_formats
+      .lock()
+      .unwrap()
+      .entry(entry_name.to_string())
+      .or_insert(format)
+  }
+
+  fn is_worker_entry(&self, kind: &ResolveKind) -> bool {
+    let ResolveKind::DynamicEntry { name, .. } = kind else {
+      return false;
+    };
+
+    self.worker_formats.lock().unwrap().contains_key(name)
+  }
 }
 
 // ---------------------------------------------------------------------------
@@ -71,6 +111,59 @@ impl Plugin for FarmfePluginWorker {
     "FarmfePluginWorker"
   }
 
+  /// Resolve worker entries and their dependencies with the worker conditions, e.g. `worker` in `exports` of package.json.
+  /// The resolved modules are marked by the [WORKER_ENV_QUERY] query, so the modules imported by both the page and a worker
+  /// are compiled twice and the modules of the page are never resolved with the worker conditions.
+  fn resolve(
+    &self,
+    param: &PluginResolveHookParam,
+    context: &Arc<CompilationContext>,
+    hook_context: &PluginHookContext,
+  ) -> farmfe_core::error::Result<Option<PluginResolveHookResult>> {
+    if hook_context.contain_caller(self.name()) || self.options.conditions.is_empty() {
+      return Ok(None);
+    }
+
+    let is_worker_importer = param.importer.as_ref().is_some_and(|importer| {
+      parse_query(importer.query_string())
+        .iter()
+        .any(|(k, _)| k == WORKER_ENV_QUERY)
+    });
+
+    if !is_worker_importer && !self.is_worker_entry(&param.kind) {
+      return Ok(None);
+    }
+
+    let mut meta = hook_context.meta.clone();
+    meta.insert(
+      ADDITIONAL_RESOLVE_CONDITIONS.to_string(),
+      serde_json::to_string(&self.options.conditions).unwrap(),
+    );
+    let mut result = context.plugin_driver.resolve(
+      param,
+      context,
+      &PluginHookContext {
+        caller: hook_context.add_caller(self.name()),
+        meta,
+      },
+    )?;
+
+    // `?worker` imports of a worker are the constructors of nested workers, whose entries are resolved separately
+    if let Some(result) = &mut result
+      && !result.external
+      && !result
+        .query
+        .iter()
+        .any(|(k, _)| k == WORKER_ENV_QUERY || k == "worker" || k == "sharedworker")
+    {
+      result
+        .query
+        .push((WORKER_ENV_QUERY.to_string(), String::new()));
+    }
+
+    Ok(result)
+  }
+
   fn priority(&self) -> i32 {
     105
   }
@@ -100,6 +193,12 @@ impl Plugin for FarmfePluginWorker {
     let is_inline = param.query.iter().any(|(k, _)| k == "inline");
     let is_url = param.query.iter().any(|(k, _)| k == "url");
     let compiler_config = self.options.compiler_config.as_ref().unwrap();
+    let (entry_name, _) =
+      worker_output_name(param.resolved_path, &param.module_id, compiler_config);
+    let format = self.record_worker_format(
+      &entry_name,
+      WorkerFormat::from_query(&param.query, self.options.format),
+    );
 
     if is_inline {
       // Don't compile now (build_worker would run on a Rayon thread — unsafe).
@@ -107,9 +206,7 @@ impl Plugin for FarmfePluginWorker {
       // dep so Farm's pipeline compiles the worker source, and `generate_end()` /
       // `update_finished()` base64-encodes the worker chunk and replaces the
       // placeholder in the bundled resources.
-      let (entry_name, _) =
-        worker_output_name(param.resolved_path, &param.module_id, compiler_config);
-      let content = get_inline_worker_placeholder_code(&param.module_id, &entry_name);
+      let content = get_inline_worker_placeholder_code(&param.module_id, &entry_name, format);
       return Ok(Some(PluginLoadHookResult {
         content,
         module_type: ModuleType::Js,
@@ -119,10 +216,8 @@ impl Plugin for FarmfePluginWorker {
 
     // Non-inline: return URL wrapper; Farm's DynamicEntry pipeline (via analyze_deps)
     // compiles the worker source and emits it as-is.
-    let (entry_name, _) =
-      worker_output_name(param.resolved_path, &param.module_id, compiler_config);
     let worker_url = compute_worker_url(&entry_name, compiler_config);
-    let content = get_worker_module_code(&param.module_id, &worker_url, is_url);
+    let content = get_worker_module_code(&param.module_id, &worker_url, is_url, format);
     Ok(Some(PluginLoadHookResult {
       content,
       module_type: ModuleType::Js,
@@ -187,6 +282,16 @@ impl Plugin for FarmfePluginWorker {
       let virtual_id = format!("{rel_path}?worker");
       let (entry_name, _) = worker_output_name(&full_path, &virtual_id, compiler_config);
       let worker_url = compute_worker_url(&entry_name, compiler_config);
+      // The format is decided by the constructor options: `{ type: 'module' }` creates a module
+      // worker, otherwise the browser loads the worker as a classic script.
+      let rest = &param.content[args.end..];
+      let options_code = &rest[..rest.find(')').unwrap_or(rest.len())];
+      let format = if options_code.contains("'module'") || options_code.contains("\"module\"") {
+        WorkerFormat::Module
+      } else {
+        WorkerFormat::Classic
+      };
+      self.record_worker_format(&entry_name, format);
 
       self
         .transform_worker_deps
@@ -197,8 +302,6 @@ impl Plugin for FarmfePluginWorker {
         .push((full_path.clone(), entry_name.clone()));
       output.push_str(&param.content[last_end..args.start]);
       // Replace the `new URL(...)` arg with the deterministic URL string.
-      // The worker script is emitted as a classic script, so it does not need
-      // `{ type: 'module' }`.
       output.push_str(&arg_code.replace(url_code, &format!(r#""{worker_url}""#)));
       last_end = args.end;
       found_workers = true;
@@ -280,6 +383,31 @@ impl Plugin for FarmfePluginWorker {
     Ok(None)
   }
 
+  /// Emit classic workers as plain scripts: the rendered worker entry can not contain
+  /// `import`/`export` statements, external scripts are loaded by `importScripts`.
+  fn process_rendered_resource_pot(
+    &self,
+    resource_pot: &mut ResourcePot,
+    _context: &Arc<CompilationContext>,
+  ) -> farmfe_core::error::Result<Option<()>> {
+    if !resource_pot.is_dynamic_entry
+      || self.worker_formats.lock().unwrap().get(&resource_pot.name) != Some(&WorkerFormat::Classic)
+    {
+      return Ok(None);
+    }
+
+    let name = resource_pot.name.clone();
+    let ResourcePotMetaData::Js(meta) = &mut resource_pot.meta else {
+      return Ok(None);
+    };
+
+    transform_to_classic_worker(&mut meta.ast).map_err(|e| {
+      CompilationError::GenericError(format!("Failed to emit classic worker `{name}`: {e}"))
+    })?;
+
+    Ok(Some(()))
+  }
+
   /// Patch worker resources after Farm's generate phase.
   ///
   /// Inline workers: base64-encoded worker chunk replaces the placeholder string
@@ -1,7 +1,41 @@
 use farmfe_core::config::Config;
 
+/// Resolve condition of the modules imported by web workers
+pub const WORKER_CONDITION: &str = "worker";
+
+/// Query of the modules resolved for workers, the same file imported by the page and by a worker are different modules
+pub const WORKER_ENV_QUERY: &str = "farm_worker_env";
+
+/// Output format of a worker entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkerFormat {
+  /// a classic script, external scripts are loaded by `importScripts`
+  #[default]
+  Classic,
+  /// an es module, the worker must be created with `{ type: 'module' }`
+  Module,
+}
+
+impl WorkerFormat {
+  /// `?worker&module` and `?worker&classic` override the default format
+  pub fn from_query(query: &[(String, String)], default: WorkerFormat) -> Self {
+    if query.iter().any(|(k, _)| k == "module") {
+      WorkerFormat::Module
+    } else if query.iter().any(|(k, _)| k == "classic") {
+      WorkerFormat::Classic
+    } else {
+      default
+    }
+  }
+}
+
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Options {
   pub compiler_config: Option<Config>,
+  /// default output format of the workers, default to [WorkerFormat::Classic]
+  pub format: WorkerFormat,
+  /// conditions used together with `resolve.conditions` when resolving the modules imported by workers, default to `["worker"]`
+  pub conditions: Vec<String>,
 }
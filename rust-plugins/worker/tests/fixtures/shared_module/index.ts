@@ -0,0 +1,4 @@
+import { add } from './shared';
+
+const worker = new Worker(new URL('./worker.ts', import.meta.url));
+worker.postMessage(add(1, 2));
@@ -0,0 +1,5 @@
+import { add } from './shared';
+
+self.onmessage = (e: MessageEvent<number>) => {
+  self.postMessage(add(e.data, 1));
+};
@@ -1,3 +1,17 @@
+use std::{path::PathBuf, sync::Arc};
+
+use farmfe_compiler::Compiler;
+use farmfe_core::{
+  config::{
+    bool_or_obj::BoolOrObj, persistent_cache::PersistentCacheConfig, preset_env::PresetEnvConfig,
+    Config, Mode, OutputConfig, RuntimeConfig, SourcemapConfig,
+  },
+  resource::ResourceType,
+  serde_json::{self, json},
+  HashMap,
+};
+use farmfe_plugin_worker::FarmfePluginWorker;
+use farmfe_testing_helpers::fixture;
 use regress::{Match, Regex};
 const WORKER_OR_SHARED_WORKER_RE: &str = r#"(?:\?|&)(worker|sharedworker)(?:&|$)"#;
 const WORKER_IMPORT_META_URL_RE: &str = r#"\bnew\s+(?:Worker|SharedWorker)\s*\(\s*(new\s+URL\s*\(\s*('[^']+'|"[^"]+"|`[^`]+`)\s*,\s*import\.meta\.url[^)]*\))"#;
@@ -97,3 +111,82 @@ export const createDefaultWorkspaceFile = (
     println!("worker_url:{}", &test_str[worker_url.start..worker_url.end])
   });
 }
+
+fn generate_runtime(crate_path: PathBuf) -> Box<RuntimeConfig> {
+  let internal = crate_path.join("../../crates/compiler/tests/fixtures/_internal");
+
+  Box::new(RuntimeConfig {
+    path: internal.join("runtime").to_string_lossy().to_string(),
+    plugins: vec![],
+    swc_helpers_path: internal.join("swc_helpers").to_string_lossy().to_string(),
+    ..Default::default()
+  })
+}
+
+/// A module imported by both the page and a classic worker is compiled as two modules,
+/// and the classic worker is emitted without `import` / `export` statements
+#[test]
+fn shared_module_of_classic_worker() {
+  fixture!(
+    "tests/fixtures/shared_module/index.ts",
+    |file, crate_path| {
+      let cwd = file.parent().unwrap();
+      let config = Config {
+        input: HashMap::from_iter([("index".to_string(), file.to_string_lossy().to_string())]),
+        root: cwd.to_string_lossy().to_string(),
+        runtime: generate_runtime(crate_path),
+        mode: Mode::Production,
+        output: Box::new(OutputConfig {
+          filename: "[resourceName].[ext]".to_string(),
+          entry_filename: "[entryName].[ext]".to_string(),
+          ..Default::default()
+        }),
+        sourcemap: Box::new(SourcemapConfig::Bool(false)),
+        preset_env: Box::new(PresetEnvConfig::Bool(false)),
+        minify: Box::new(BoolOrObj::from(false)),
+        tree_shaking: Box::new(BoolOrObj::Bool(false)),
+        persistent_cache: Box::new(PersistentCacheConfig::Bool(false)),
+        progress: false,
+        ..Default::default()
+      };
+      let plugin = FarmfePluginWorker::new(
+        &config,
+        json!({ "compilerConfig": serde_json::to_value(&config).unwrap() }).to_string(),
+      );
+      let compiler = Compiler::new(config, vec![Arc::new(plugin) as _]).unwrap();
+      compiler.compile().unwrap();
+
+      let context = compiler.context();
+      let module_graph = context.module_graph.read();
+      let mut shared_modules = module_graph
+        .modules()
+        .into_iter()
+        .filter(|m| m.id.relative_path() == "shared.ts")
+        .map(|m| m.id.to_string())
+        .collect::<Vec<_>>();
+      shared_modules.sort();
+      assert_eq!(shared_modules.len(), 2, "{shared_modules:?}");
+      assert!(!shared_modules[0].contains("farm_worker_env"));
+      assert!(shared_modules[1].contains("farm_worker_env"));
+
+      let resources_map = context.resources_map.lock();
+      let workers = resources_map
+        .values()
+        .filter(|r| {
+          !r.emitted && matches!(r.resource_type, ResourceType::Js) && r.name != "index.js"
+        })
+        .collect::<Vec<_>>();
+      assert_eq!(workers.len(), 1);
+
+      let code = String::from_utf8_lossy(&workers[0].bytes);
+      assert!(code.contains("a + b"));
+      assert!(
+        !code
+          .lines()
+          .any(|line| line.trim_start().starts_with("import ")
+            || line.trim_start().starts_with("export ")),
+        "{code}"
+      );
+    }
+  );
+}
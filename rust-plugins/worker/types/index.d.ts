@@ -35,3 +35,17 @@ declare module '*?sharedworker&url' {
   const src: string
   export default src
 }
+
+declare module '*?worker&module' {
+  const workerConstructor: {
+    new(options?: { name?: string }): Worker
+  }
+  export default workerConstructor
+}
+
+declare module '*?worker&classic' {
+  const workerConstructor: {
+    new(options?: { name?: string }): Worker
+  }
+  export default workerConstructor
+}
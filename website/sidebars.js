@@ -150,6 +150,7 @@ const sidebars = {
             "plugins/official-plugins/react",
             "plugins/official-plugins/vue",
             "plugins/official-plugins/sass",
+            "plugins/official-plugins/less",
             "plugins/official-plugins/auto-import",
             "plugins/official-plugins/tailwindcss",
             "plugins/official-plugins/svgr",